@@ -0,0 +1,161 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use crate::index::relative_path;
+use crate::store::Store;
+use crate::{Entries, Metadata};
+
+/// Store that redirects virtual paths to real paths of another store.
+///
+/// Aliases can point to files or to directories, in which case every path
+/// below the virtual directory is redirected too. They take precedence over
+/// the files of the wrapped store, and can point to other aliases, so assets
+/// that were renamed several times keep resolving from all their old paths:
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{Aliases, Zip};
+///
+/// let assets = Aliases::new(Zip::open("assets.zip")?)
+///     .alias("sfx/boom.wav", "sfx/explosion.wav")
+///     .alias("textures/old", "textures/legacy");
+///
+/// let boom = assets.open("sfx/boom.wav")?;
+/// let brick = assets.open("textures/old/brick.png")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Aliases can also be loaded from a [`manifest`](#method.manifest).
+///
+/// # Remarks
+///
+/// Opening files, reading metadata and listing directories follow aliases, but
+/// aliases aren't listed themselves, and creating, removing or renaming files
+/// use the requested paths as they are.
+pub struct Aliases<S> {
+    store: S,
+    aliases: HashMap<PathBuf, PathBuf>,
+}
+
+impl<S> Aliases<S> {
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            aliases: HashMap::new(),
+        }
+    }
+
+    /// Redirect `from`, and every path below it, to `to`.
+    pub fn alias<P: AsRef<Path>, Q: AsRef<Path>>(mut self, from: P, to: Q) -> Self {
+        self.aliases
+            .insert(relative_path(from.as_ref()), relative_path(to.as_ref()));
+        self
+    }
+
+    /// Read aliases from a manifest, with one `from = to` pair per line.
+    ///
+    /// Empty lines and lines starting with `#` are ignored:
+    ///
+    /// ```text
+    /// # renamed in 1.2
+    /// sfx/boom.wav = sfx/explosion.wav
+    /// textures/old = textures/legacy
+    /// ```
+    ///
+    /// Returns an error of kind `InvalidData` if a line isn't a valid pair.
+    pub fn manifest<R: Read>(mut self, manifest: R) -> io::Result<Self> {
+        for (i, line) in BufReader::new(manifest).lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.split_once('=') {
+                Some((from, to)) if !from.trim().is_empty() && !to.trim().is_empty() => {
+                    self = self.alias(from.trim(), to.trim());
+                }
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("Invalid alias on line {} of the manifest.", i + 1),
+                    ))
+                }
+            }
+        }
+        Ok(self)
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.store
+    }
+
+    pub fn into_inner(self) -> S {
+        self.store
+    }
+
+    // Real path of `path`. Chains can't be longer than the number of aliases,
+    // so stopping there keeps cycles from looping forever.
+    fn resolve<'a>(&self, path: &'a Path) -> Cow<'a, Path> {
+        let mut path = Cow::Borrowed(path);
+        for _ in 0..self.aliases.len() {
+            match self.lookup(&path) {
+                Some(real) => path = Cow::Owned(real),
+                None => break,
+            }
+        }
+        path
+    }
+
+    // Applies the alias of the longest prefix of `path`.
+    fn lookup(&self, path: &Path) -> Option<PathBuf> {
+        let path = relative_path(path);
+        let mut prefix = path.as_path();
+        loop {
+            if let Some(real) = self.aliases.get(prefix) {
+                let rest = path.strip_prefix(prefix).unwrap();
+                return Some(real.join(rest));
+            }
+            prefix = prefix.parent()?;
+        }
+    }
+}
+
+impl<S: Store> Store for Aliases<S> {
+    type File = S::File;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        self.store.open_path(&self.resolve(path))
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        self.store.entries_path(&self.resolve(path))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        self.store.metadata_path(&self.resolve(path))
+    }
+
+    #[inline]
+    fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+        self.store.create_path(path)
+    }
+
+    #[inline]
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        self.store.remove_path(path)
+    }
+
+    #[inline]
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.store.rename_path(from, to)
+    }
+
+    #[inline]
+    fn is_read_only(&self) -> bool {
+        self.store.is_read_only()
+    }
+}
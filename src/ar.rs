@@ -0,0 +1,249 @@
+use std::cell::RefCell;
+use std::fs;
+use std::io::{self, BufRead, Cursor, ErrorKind, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time::{Duration, UNIX_EPOCH};
+
+use crate::index::Index;
+use crate::store::Store;
+#[cfg(feature = "tar")]
+use crate::tar::Tar;
+use crate::{Entries, Entry, EntryKind, Metadata};
+
+const MAGIC: &[u8] = b"!<arch>\n";
+// Size of a member header.
+const HEADER: usize = 60;
+
+/// Unix `ar` archive store.
+///
+/// Reads the common, GNU and BSD variants of the format, which is used by
+/// static libraries and as the outer layer of Debian (`.deb`) packages. The
+/// archive is indexed when the store is created. Symbol tables are skipped.
+///
+/// With the `tar` feature, the tar archives contained in a package can be read
+/// as stores themselves, see [`Ar::deb_data`].
+///
+/// [`Ar::deb_data`]: #method.deb_data
+///
+/// # Remarks
+///
+/// When used with a `std::fs::File`, the file will remain open for the lifetime
+/// of the Ar.
+pub struct Ar<T: Read + Seek> {
+    inner: RefCell<T>,
+    index: Index<ArIndexEntry>,
+}
+
+struct ArIndexEntry {
+    // position of the member data within the archive
+    pos: u64,
+    meta: Metadata,
+}
+
+/// Entry in the ar archive.
+pub struct ArEntry {
+    inner: Cursor<Box<[u8]>>,
+}
+
+impl ArEntry {
+    pub(crate) fn len(&self) -> u64 {
+        self.inner.get_ref().len() as u64
+    }
+}
+
+impl Read for ArEntry {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl BufRead for ArEntry {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+impl Seek for ArEntry {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl Ar<fs::File> {
+    /// Open a file from the native filesystem.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = fs::OpenOptions::new()
+            .read(true)
+            .write(false)
+            .create(false)
+            .open(path)?;
+        Self::new(file)
+    }
+}
+
+impl<T: Read + Seek> Ar<T> {
+    /// Read the member headers of the archive and index its contents.
+    pub fn new(mut inner: T) -> io::Result<Self> {
+        let len = inner.seek(SeekFrom::End(0))?;
+        inner.seek(SeekFrom::Start(0))?;
+        let mut magic = [0; MAGIC.len()];
+        if inner.read_exact(&mut magic).is_err() || magic != MAGIC {
+            return Err(invalid("Not an ar archive."));
+        }
+
+        let mut index = Index::new();
+        // GNU archives keep long names in a member of their own.
+        let mut names = Vec::new();
+        let mut pos = MAGIC.len() as u64;
+        let mut header = [0; HEADER];
+        while pos < len {
+            inner.seek(SeekFrom::Start(pos))?;
+            inner.read_exact(&mut header)?;
+            if &header[58..] != b"`\n" {
+                return Err(invalid("Invalid ar member header."));
+            }
+            let size = field(&header[48..58], 10)?;
+            let mut data = pos + HEADER as u64;
+            let next = data + size + size % 2;
+
+            let name = trim(&header[..16]);
+            let name = match name {
+                // symbol tables
+                b"/" | b"/SYM64/" | b"__.SYMDEF" | b"__.SYMDEF SORTED" => None,
+                b"//" => {
+                    names.clear();
+                    (&mut inner).take(size).read_to_end(&mut names)?;
+                    None
+                }
+                _ if name.starts_with(b"#1/") => {
+                    // BSD names follow the header, and count as member data.
+                    let len = field(&name[3..], 10)?;
+                    let mut name = Vec::new();
+                    (&mut inner).take(len).read_to_end(&mut name)?;
+                    data += len;
+                    while name.last() == Some(&0) {
+                        name.pop();
+                    }
+                    Some(name)
+                }
+                _ if name.starts_with(b"/") => {
+                    let offset = field(&name[1..], 10)? as usize;
+                    let name = names
+                        .get(offset..)
+                        .and_then(|name| name.split(|b| *b == b'\n').next())
+                        .ok_or_else(|| invalid("Invalid ar long name."))?;
+                    Some(name.strip_suffix(b"/").unwrap_or(name).to_vec())
+                }
+                _ => Some(name.strip_suffix(b"/").unwrap_or(name).to_vec()),
+            };
+
+            if let Some(name) = name {
+                let meta = Metadata {
+                    kind: EntryKind::File,
+                    len: (next - size % 2).saturating_sub(data),
+                    modified: field(&header[16..28], 10)
+                        .ok()
+                        .map(|secs| UNIX_EPOCH + Duration::from_secs(secs)),
+                };
+                let name = String::from_utf8_lossy(&name).into_owned();
+                index.insert(name, ArIndexEntry { pos: data, meta });
+            }
+            pos = next;
+        }
+
+        Ok(Self {
+            inner: RefCell::new(inner),
+            index,
+        })
+    }
+
+    /// Read a tar archive contained in the archive.
+    ///
+    /// The tar archive is indexed, so its entries can be listed right away.
+    /// Compressed archives are detected like in [`Tar::new`].
+    ///
+    /// [`Tar::new`]: ../tar/struct.Tar.html#method.new
+    #[cfg(feature = "tar")]
+    pub fn tar<P: AsRef<Path>>(&self, path: P) -> io::Result<Tar<ArEntry>> {
+        Tar::new(self.open_path(path.as_ref())?).index()
+    }
+
+    /// Read the data archive (`data.tar.*`) of a Debian package.
+    ///
+    /// `data.tar.xz`, `data.tar.zst` and `data.tar.bz2` require the `xz`, `zstd`
+    /// and `bzip2` features.
+    #[cfg(feature = "tar")]
+    pub fn deb_data(&self) -> io::Result<Tar<ArEntry>> {
+        let name = self
+            .index
+            .entries(".")
+            .map(|ent| ent.name)
+            .find(|name| {
+                let name = name.to_string_lossy();
+                name == "data.tar" || name.starts_with("data.tar.")
+            })
+            .ok_or_else(|| io::Error::from(ErrorKind::NotFound))?;
+        self.tar(name)
+    }
+}
+
+impl<T: Read + Seek> Store for Ar<T> {
+    type File = ArEntry;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        let entry = match self.index.get(path) {
+            Some(entry) => entry,
+            None => return Err(io::Error::from(ErrorKind::NotFound)),
+        };
+        let mut file = self.inner.borrow_mut();
+        file.seek(SeekFrom::Start(entry.pos))?;
+        let mut data = Vec::new();
+        (&mut *file).take(entry.meta.len).read_to_end(&mut data)?;
+        Ok(ArEntry {
+            inner: Cursor::new(data.into()),
+        })
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        Ok(Entries::new(self.index.entries(path).map(|ent| {
+            let name = ent.name.to_os_string();
+            let kind = ent.kind;
+            Ok(Entry { name, kind })
+        })))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        if let Some(entry) = self.index.get(path) {
+            Ok(entry.meta.clone())
+        } else if path.iter().next().is_none() {
+            Ok(Metadata::dir())
+        } else {
+            Err(io::Error::from(ErrorKind::NotFound))
+        }
+    }
+}
+
+// Header fields are padded with spaces.
+fn trim(field: &[u8]) -> &[u8] {
+    let len = field.iter().rposition(|b| *b != b' ').map_or(0, |i| i + 1);
+    &field[..len]
+}
+
+fn field(field: &[u8], radix: u32) -> io::Result<u64> {
+    std::str::from_utf8(trim(field))
+        .ok()
+        .and_then(|field| u64::from_str_radix(field, radix).ok())
+        .ok_or_else(|| invalid("Invalid ar member header."))
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg)
+}
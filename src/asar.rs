@@ -0,0 +1,218 @@
+use std::cell::RefCell;
+use std::fs;
+use std::io::{self, BufRead, Cursor, ErrorKind, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+
+use crate::bytes::le32;
+use crate::index::Index;
+use crate::store::Store;
+use crate::{Entries, Entry, EntryKind, Metadata};
+
+/// Electron asar archive store.
+///
+/// The JSON header of the archive is indexed when the store is created. Files
+/// are read from their offset in the archive. Symlinks are skipped.
+///
+/// # Remarks
+///
+/// Files marked as unpacked are stored next to the archive, in the
+/// `<archive>.unpacked` directory. They can only be read from stores created
+/// with [`Asar::open`].
+///
+/// When used with a `std::fs::File`, the file will remain open for the lifetime
+/// of the Asar.
+///
+/// [`Asar::open`]: #method.open
+pub struct Asar<T: Read + Seek> {
+    inner: RefCell<T>,
+    index: Index<AsarIndexEntry>,
+    // directory of the unpacked files
+    unpacked: Option<PathBuf>,
+}
+
+struct AsarIndexEntry {
+    // position of the file data within the archive, `None` if unpacked
+    pos: Option<u64>,
+    meta: Metadata,
+}
+
+/// Entry in the asar archive.
+pub struct AsarEntry {
+    inner: Cursor<Box<[u8]>>,
+}
+
+impl AsarEntry {
+    pub(crate) fn len(&self) -> u64 {
+        self.inner.get_ref().len() as u64
+    }
+}
+
+impl Read for AsarEntry {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl BufRead for AsarEntry {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+impl Seek for AsarEntry {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl Asar<fs::File> {
+    /// Open a file from the native filesystem.
+    ///
+    /// Unpacked files are read from the `<path>.unpacked` directory.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let file = fs::OpenOptions::new()
+            .read(true)
+            .write(false)
+            .create(false)
+            .open(path)?;
+        let mut asar = Self::new(file)?;
+        let mut unpacked = path.as_os_str().to_os_string();
+        unpacked.push(".unpacked");
+        asar.unpacked = Some(unpacked.into());
+        Ok(asar)
+    }
+}
+
+impl<T: Read + Seek> Asar<T> {
+    /// Read the header of the archive and index its contents.
+    pub fn new(mut inner: T) -> io::Result<Self> {
+        // The header is a Chromium pickle holding the size of a second pickle,
+        // which holds the JSON string.
+        let mut sizes = [0; 16];
+        inner.seek(SeekFrom::Start(0))?;
+        if inner.read_exact(&mut sizes).is_err() || le32(&sizes[..4])? != 4 {
+            return Err(invalid("Not an asar archive."));
+        }
+        let base = 8 + u64::from(le32(&sizes[4..8])?);
+        let len = u64::from(le32(&sizes[12..16])?);
+        let mut json = Vec::new();
+        (&mut inner).take(len).read_to_end(&mut json)?;
+        let header: Value =
+            serde_json::from_slice(&json).map_err(|_| invalid("Invalid asar header."))?;
+
+        let mut index = Index::new();
+        match header.get("files").and_then(Value::as_object) {
+            Some(files) => index_dir(&mut index, PathBuf::new(), files, base)?,
+            None => return Err(invalid("Invalid asar header.")),
+        }
+
+        Ok(Self {
+            inner: RefCell::new(inner),
+            index,
+            unpacked: None,
+        })
+    }
+}
+
+impl<T: Read + Seek> Store for Asar<T> {
+    type File = AsarEntry;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        let entry = match self.index.get(path) {
+            Some(entry) => entry,
+            None => return Err(io::Error::from(ErrorKind::NotFound)),
+        };
+        let mut data = Vec::new();
+        match (entry.pos, &self.unpacked) {
+            (Some(pos), _) => {
+                let mut file = self.inner.borrow_mut();
+                file.seek(SeekFrom::Start(pos))?;
+                (&mut *file).take(entry.meta.len).read_to_end(&mut data)?;
+            }
+            (None, Some(unpacked)) => {
+                let path = crate::index::normalize_path(path);
+                data = fs::read(unpacked.join(path.strip_prefix("/").unwrap_or(&path)))?;
+            }
+            (None, None) => {
+                return Err(io::Error::new(
+                    ErrorKind::Unsupported,
+                    "Unpacked files can only be read from archives opened with `Asar::open`.",
+                ))
+            }
+        }
+        Ok(AsarEntry {
+            inner: Cursor::new(data.into()),
+        })
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        Ok(Entries::new(self.index.entries(path).map(|ent| {
+            let name = ent.name.to_os_string();
+            let kind = ent.kind;
+            Ok(Entry { name, kind })
+        })))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        if let Some(entry) = self.index.get(path) {
+            Ok(entry.meta.clone())
+        } else if self.index.contains_dir(path) {
+            Ok(Metadata::dir())
+        } else {
+            Err(io::Error::from(ErrorKind::NotFound))
+        }
+    }
+}
+
+// Directories have a "files" object, files have a "size", and symlinks have a
+// "link".
+fn index_dir(
+    index: &mut Index<AsarIndexEntry>,
+    path: PathBuf,
+    files: &Map<String, Value>,
+    base: u64,
+) -> io::Result<()> {
+    index.insert_dir(path.clone());
+    for (name, node) in files {
+        let path = path.join(name);
+        if let Some(files) = node.get("files").and_then(Value::as_object) {
+            index_dir(index, path, files, base)?;
+        } else if let Some(len) = node.get("size").and_then(Value::as_u64) {
+            let unpacked = node.get("unpacked").and_then(Value::as_bool) == Some(true);
+            let pos = if unpacked {
+                None
+            } else {
+                // Offsets are strings, as they might not fit in a JavaScript number.
+                let offset = node
+                    .get("offset")
+                    .and_then(Value::as_str)
+                    .and_then(|offset| offset.parse::<u64>().ok())
+                    .ok_or_else(|| invalid("Invalid asar header."))?;
+                let pos = base.checked_add(offset);
+                Some(pos.ok_or_else(|| invalid("Invalid asar header."))?)
+            };
+            let meta = Metadata {
+                kind: EntryKind::File,
+                len,
+                modified: None,
+            };
+            index.insert(path, AsarIndexEntry { pos, meta });
+        }
+    }
+    Ok(())
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg)
+}
@@ -0,0 +1,342 @@
+use std::future::Future;
+use std::io::{self, Read};
+use std::path::Path;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use futures_io::AsyncRead;
+
+use crate::store::Store;
+use crate::{Entries, Entry, File, MiniFs, Ram};
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+use crate::{EntryKind, Local};
+
+/// Asynchronous file storage.
+///
+/// Counterpart of [`Store`] for non-blocking code. The trait itself doesn't
+/// depend on any runtime; implementations doing native IO are enabled with the
+/// `tokio` and `async-std` features.
+///
+/// [`Store`]: ../trait.Store.html
+pub trait AsyncStore {
+    type File;
+
+    fn open_path(&self, path: &Path) -> impl Future<Output = io::Result<Self::File>>;
+
+    /// Stream the entries of a directory.
+    ///
+    /// Same as [`Store::entries_path`], but entries may be yielded as they are
+    /// read instead of all at once. Returns an empty stream by default.
+    ///
+    /// [`Store::entries_path`]: ../trait.Store.html#method.entries_path
+    fn entries_path(&self, _path: &Path) -> impl Future<Output = io::Result<AsyncEntries<'_>>> {
+        async { Ok(AsyncEntries::new(Entries::empty())) }
+    }
+}
+
+/// Convenient methods on top of AsyncStore.
+pub trait AsyncStoreExt: AsyncStore {
+    fn open<P: AsRef<Path>>(&self, path: P) -> impl Future<Output = io::Result<Self::File>> {
+        let path = crate::index::normalize_path(path.as_ref()).into_owned();
+        async move { self.open_path(&path).await }
+    }
+
+    fn entries<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> impl Future<Output = io::Result<AsyncEntries<'_>>> {
+        let path = crate::index::normalize_path(path.as_ref()).into_owned();
+        async move { self.entries_path(&path).await }
+    }
+}
+
+impl<T: AsyncStore> AsyncStoreExt for T {}
+
+/// Stream over the entries of a directory.
+///
+/// Returned by [`AsyncStoreExt::entries`].
+///
+/// [`AsyncStoreExt::entries`]: ./trait.AsyncStoreExt.html#method.entries
+pub struct AsyncEntries<'a> {
+    inner: Pin<Box<dyn Stream<Item = io::Result<Entry>> + 'a>>,
+}
+
+impl<'a> AsyncEntries<'a> {
+    pub fn new<S>(stream: S) -> Self
+    where
+        S: Stream<Item = io::Result<Entry>> + 'a,
+    {
+        Self {
+            inner: Box::pin(stream),
+        }
+    }
+}
+
+impl Stream for AsyncEntries<'_> {
+    type Item = io::Result<Entry>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+// Synchronous listings are always ready.
+impl Stream for Entries<'_> {
+    type Item = io::Result<Entry>;
+
+    fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Poll::Ready(self.get_mut().next())
+    }
+}
+
+// Stream that awaits `f` on the current state to get the next item and state.
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+struct Unfold<T, F, Fut> {
+    state: Option<T>,
+    f: F,
+    fut: Option<Pin<Box<Fut>>>,
+}
+
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+fn unfold<T, F, Fut, I>(state: T, f: F) -> Unfold<T, F, Fut>
+where
+    F: FnMut(T) -> Fut,
+    Fut: Future<Output = Option<(I, T)>>,
+{
+    Unfold {
+        state: Some(state),
+        f,
+        fut: None,
+    }
+}
+
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+impl<T, F, Fut, I> Stream for Unfold<T, F, Fut>
+where
+    T: Unpin,
+    F: FnMut(T) -> Fut + Unpin,
+    Fut: Future<Output = Option<(I, T)>>,
+{
+    type Item = I;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<I>> {
+        let this = self.get_mut();
+        if let Some(state) = this.state.take() {
+            this.fut = Some(Box::pin((this.f)(state)));
+        }
+        let fut = match this.fut.as_mut() {
+            Some(fut) => fut,
+            None => return Poll::Ready(None),
+        };
+        match fut.as_mut().poll(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(next) => {
+                this.fut = None;
+                Poll::Ready(next.map(|(item, state)| {
+                    this.state = Some(state);
+                    item
+                }))
+            }
+        }
+    }
+}
+
+// Entry names are relative to the root, like in the synchronous listing.
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+fn local_entry(root: &Path, path: &Path, is_dir: bool) -> Entry {
+    let name = path
+        .strip_prefix(root)
+        .map(Path::to_path_buf)
+        .expect("Error striping path suffix.");
+    Entry {
+        name: name.into_os_string(),
+        kind: if is_dir {
+            EntryKind::Dir
+        } else {
+            EntryKind::File
+        },
+    }
+}
+
+/// File you can read from asynchronously.
+///
+/// Implements the `AsyncRead` trait from `futures`, and the one from tokio when
+/// the `tokio` feature is enabled.
+pub enum AsyncFile {
+    #[cfg(feature = "tokio")]
+    Tokio(tokio_::fs::File),
+    #[cfg(feature = "async-std")]
+    AsyncStd(async_std_::fs::File),
+    /// Any other file, read synchronously.
+    ///
+    /// Files from in-memory stores and from archives are already loaded into
+    /// memory, so reading them never blocks. Native files end up here when no
+    /// runtime feature is enabled.
+    Sync(File),
+}
+
+// Native files are handed to the runtime. tokio is preferred when both runtime
+// features are enabled.
+impl From<File> for AsyncFile {
+    fn from(file: File) -> Self {
+        match file {
+            #[cfg(feature = "tokio")]
+            File::Local(file) => AsyncFile::Tokio(tokio_::fs::File::from_std(file.into_inner())),
+            #[cfg(all(feature = "async-std", not(feature = "tokio")))]
+            File::Local(file) => AsyncFile::AsyncStd(file.into_inner().into()),
+            file => AsyncFile::Sync(file),
+        }
+    }
+}
+
+impl AsyncRead for AsyncFile {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        #[cfg_attr(
+            not(any(feature = "tokio", feature = "async-std")),
+            allow(unused_variables)
+        )]
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            #[cfg(feature = "tokio")]
+            AsyncFile::Tokio(file) => {
+                let mut buf = tokio_::io::ReadBuf::new(buf);
+                match tokio_::io::AsyncRead::poll_read(Pin::new(file), cx, &mut buf) {
+                    Poll::Ready(Ok(())) => Poll::Ready(Ok(buf.filled().len())),
+                    Poll::Ready(Err(err)) => Poll::Ready(Err(err)),
+                    Poll::Pending => Poll::Pending,
+                }
+            }
+            #[cfg(feature = "async-std")]
+            AsyncFile::AsyncStd(file) => Pin::new(file).poll_read(cx, buf),
+            AsyncFile::Sync(file) => Poll::Ready(file.read(buf)),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio_::io::AsyncRead for AsyncFile {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio_::io::ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            AsyncFile::Tokio(file) => Pin::new(file).poll_read(cx, buf),
+            file => match AsyncRead::poll_read(Pin::new(file), cx, buf.initialize_unfilled()) {
+                Poll::Ready(Ok(n)) => {
+                    buf.advance(n);
+                    Poll::Ready(Ok(()))
+                }
+                Poll::Ready(Err(err)) => Poll::Ready(Err(err)),
+                Poll::Pending => Poll::Pending,
+            },
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncStore for Local {
+    type File = AsyncFile;
+
+    fn open_path(&self, path: &Path) -> impl Future<Output = io::Result<AsyncFile>> {
+        let path = self.native_path(path);
+        async move { tokio_::fs::File::open(path?).await.map(AsyncFile::Tokio) }
+    }
+
+    fn entries_path(&self, path: &Path) -> impl Future<Output = io::Result<AsyncEntries<'_>>> {
+        let path = self.native_path(path);
+        async move {
+            let read_dir = tokio_::fs::read_dir(path?).await?;
+            // The listing ends after the first error.
+            let entries = unfold(Some(read_dir), move |read_dir| async move {
+                let mut read_dir = read_dir?;
+                let entry = match read_dir.next_entry().await {
+                    Ok(Some(entry)) => entry,
+                    Ok(None) => return None,
+                    Err(err) => return Some((Err(err), None)),
+                };
+                match entry.file_type().await {
+                    Ok(ty) => Some((
+                        Ok(local_entry(&self.root, &entry.path(), ty.is_dir())),
+                        Some(read_dir),
+                    )),
+                    Err(err) => Some((Err(err), None)),
+                }
+            });
+            Ok(AsyncEntries::new(entries))
+        }
+    }
+}
+
+#[cfg(all(feature = "async-std", not(feature = "tokio")))]
+impl AsyncStore for Local {
+    type File = AsyncFile;
+
+    fn open_path(&self, path: &Path) -> impl Future<Output = io::Result<AsyncFile>> {
+        let path = self.native_path(path);
+        async move {
+            async_std_::fs::File::open(path?)
+                .await
+                .map(AsyncFile::AsyncStd)
+        }
+    }
+
+    fn entries_path(&self, path: &Path) -> impl Future<Output = io::Result<AsyncEntries<'_>>> {
+        use async_std_::stream::StreamExt;
+
+        let path = self.native_path(path);
+        async move {
+            let read_dir = async_std_::fs::read_dir(path?).await?;
+            // The listing ends after the first error.
+            let entries = unfold(Some(read_dir), move |read_dir| async move {
+                let mut read_dir = read_dir?;
+                let entry = match read_dir.next().await? {
+                    Ok(entry) => entry,
+                    Err(err) => return Some((Err(err), None)),
+                };
+                match entry.file_type().await {
+                    Ok(ty) => Some((
+                        Ok(local_entry(&self.root, entry.path().as_ref(), ty.is_dir())),
+                        Some(read_dir),
+                    )),
+                    Err(err) => Some((Err(err), None)),
+                }
+            });
+            Ok(AsyncEntries::new(entries))
+        }
+    }
+}
+
+impl AsyncStore for Ram {
+    type File = AsyncFile;
+
+    fn open_path(&self, path: &Path) -> impl Future<Output = io::Result<AsyncFile>> {
+        let file = Store::open_path(self, path);
+        async move { file.map(|file| AsyncFile::Sync(file.into())) }
+    }
+
+    fn entries_path(&self, path: &Path) -> impl Future<Output = io::Result<AsyncEntries<'_>>> {
+        let entries = Store::entries_path(self, path);
+        async move { entries.map(AsyncEntries::new) }
+    }
+}
+
+// Mounted stores are synchronous, so the lookup blocks, but reading from native
+// files doesn't if a runtime feature is enabled.
+impl AsyncStore for MiniFs {
+    type File = AsyncFile;
+
+    fn open_path(&self, path: &Path) -> impl Future<Output = io::Result<AsyncFile>> {
+        let file = Store::open_path(self, path);
+        async move { file.map(AsyncFile::from) }
+    }
+
+    fn entries_path(&self, path: &Path) -> impl Future<Output = io::Result<AsyncEntries<'_>>> {
+        let entries = Store::entries_path(self, path);
+        async move { entries.map(AsyncEntries::new) }
+    }
+}
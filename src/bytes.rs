@@ -0,0 +1,35 @@
+use std::convert::TryInto;
+use std::io::{self, ErrorKind};
+
+// Little-endian integers at the start of `data`, as found in the headers and
+// tables of archives. Data that is too short is invalid, so truncated or
+// hostile archives return an error instead of panicking.
+
+#[cfg(any(feature = "minipak", feature = "mpq", feature = "vpk", feature = "zip"))]
+pub(crate) fn le16(data: &[u8]) -> io::Result<u16> {
+    array(data).map(u16::from_le_bytes)
+}
+
+#[cfg(any(
+    feature = "asar",
+    feature = "grf",
+    feature = "iso",
+    feature = "minipak",
+    feature = "mpq",
+    feature = "vpk",
+    feature = "wad"
+))]
+pub(crate) fn le32(data: &[u8]) -> io::Result<u32> {
+    array(data).map(u32::from_le_bytes)
+}
+
+#[cfg(any(feature = "minipak", feature = "mpq"))]
+pub(crate) fn le64(data: &[u8]) -> io::Result<u64> {
+    array(data).map(u64::from_le_bytes)
+}
+
+fn array<const N: usize>(data: &[u8]) -> io::Result<[u8; N]> {
+    data.get(..N)
+        .and_then(|data| data.try_into().ok())
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "Unexpected end of data."))
+}
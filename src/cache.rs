@@ -0,0 +1,233 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::index::relative_path;
+use crate::store::Store;
+use crate::{Entries, File, Metadata};
+
+/// Store that keeps the contents of recently opened files in memory.
+///
+/// Files are read completely the first time they are opened, and later opens
+/// of the same path are served from memory, without touching the wrapped
+/// store. The least recently used files are evicted once the cached contents
+/// exceed a budget in bytes:
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{Cache, Zip};
+///
+/// // keep up to 64 MiB of decompressed entries
+/// let assets = Cache::new(Zip::open("assets.zip")?, 64 << 20);
+///
+/// loop {
+///     let file = assets.open("shaders/main.glsl")?;
+///     # break;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Cached files never expire by default, which suits local stores and
+/// archives. Stores over the network can set a [`ttl`](#method.ttl) instead,
+/// so files are fetched again once they get too old:
+///
+/// ```no_run
+/// # #[cfg(feature = "ureq")]
+/// # fn main() {
+/// use mini_fs::{Cache, Http};
+/// use std::time::Duration;
+///
+/// let remote = Http::new("https://example.com/assets");
+/// let assets = Cache::new(remote, 16 << 20).ttl(Duration::from_secs(60));
+/// # }
+/// # #[cfg(not(feature = "ureq"))]
+/// # fn main() {}
+/// ```
+///
+/// # Remarks
+///
+/// Files larger than the budget aren't cached. Creating, removing or renaming
+/// files through the cache drops them from it, but changes made to the wrapped
+/// store directly can only be seen after calling [`clear`](#method.clear).
+/// Files opened while they're still being written may be cached with partial
+/// contents.
+pub struct Cache<S> {
+    store: S,
+    budget: usize,
+    ttl: Option<Duration>,
+    lru: RefCell<Lru>,
+}
+
+#[derive(Default)]
+struct Lru {
+    files: HashMap<PathBuf, Cached>,
+    // Paths by last use, so the first one is the next to be evicted.
+    uses: BTreeMap<u64, PathBuf>,
+    tick: u64,
+    size: usize,
+}
+
+struct Cached {
+    data: Arc<[u8]>,
+    used: u64,
+    fetched: Instant,
+}
+
+impl Lru {
+    fn get(&mut self, path: &Path, ttl: Option<Duration>) -> Option<Arc<[u8]>> {
+        let file = self.files.get_mut(path)?;
+        if ttl.is_some_and(|ttl| file.fetched.elapsed() >= ttl) {
+            self.remove(path);
+            return None;
+        }
+        self.tick += 1;
+        let path = self.uses.remove(&file.used)?;
+        file.used = self.tick;
+        self.uses.insert(self.tick, path);
+        Some(Arc::clone(&file.data))
+    }
+
+    fn insert(&mut self, path: PathBuf, data: Arc<[u8]>, budget: usize) {
+        self.remove(&path);
+        if data.len() > budget {
+            return;
+        }
+        while self.size + data.len() > budget {
+            let oldest = match self.uses.keys().next() {
+                Some(&used) => self.uses.remove(&used).unwrap(),
+                None => break,
+            };
+            if let Some(file) = self.files.remove(&oldest) {
+                self.size -= file.data.len();
+            }
+        }
+        self.tick += 1;
+        self.size += data.len();
+        self.uses.insert(self.tick, path.clone());
+        self.files.insert(
+            path,
+            Cached {
+                data,
+                used: self.tick,
+                fetched: Instant::now(),
+            },
+        );
+    }
+
+    fn remove(&mut self, path: &Path) {
+        if let Some(file) = self.files.remove(path) {
+            self.uses.remove(&file.used);
+            self.size -= file.data.len();
+        }
+    }
+
+    // Evicts the file at `path`, or the files below it if it's a directory.
+    fn remove_all(&mut self, path: &Path) {
+        let paths: Vec<_> = self
+            .files
+            .keys()
+            .filter(|file| file.starts_with(path))
+            .cloned()
+            .collect();
+        for path in paths {
+            self.remove(&path);
+        }
+    }
+}
+
+impl<S> Cache<S> {
+    /// Cache up to `budget` bytes of the files of `store`.
+    pub fn new(store: S, budget: usize) -> Self {
+        Self {
+            store,
+            budget,
+            ttl: None,
+            lru: RefCell::new(Lru::default()),
+        }
+    }
+
+    /// Fetch files again from the wrapped store once they have been cached for
+    /// `ttl`.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Drop all the cached files.
+    pub fn clear(&self) {
+        *self.lru.borrow_mut() = Lru::default();
+    }
+
+    /// Size in bytes of the cached files.
+    pub fn size(&self) -> usize {
+        self.lru.borrow().size
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.store
+    }
+
+    pub fn into_inner(self) -> S {
+        self.store
+    }
+}
+
+impl<S> Store for Cache<S>
+where
+    S: Store,
+    S::File: Read + Into<File>,
+{
+    type File = File;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        let key = relative_path(path);
+        if let Some(data) = self.lru.borrow_mut().get(&key, self.ttl) {
+            return Ok(data.into());
+        }
+        let mut data = Vec::new();
+        self.store.open_path(path)?.read_to_end(&mut data)?;
+        let data: Arc<[u8]> = data.into();
+        self.lru
+            .borrow_mut()
+            .insert(key, Arc::clone(&data), self.budget);
+        Ok(data.into())
+    }
+
+    #[inline]
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        self.store.entries_path(path)
+    }
+
+    #[inline]
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        self.store.metadata_path(path)
+    }
+
+    fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+        self.lru.borrow_mut().remove(&relative_path(path));
+        self.store.create_path(path).map(Into::into)
+    }
+
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        self.lru.borrow_mut().remove_all(&relative_path(path));
+        self.store.remove_path(path)
+    }
+
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let mut lru = self.lru.borrow_mut();
+        lru.remove_all(&relative_path(from));
+        lru.remove_all(&relative_path(to));
+        drop(lru);
+        self.store.rename_path(from, to)
+    }
+
+    #[inline]
+    fn is_read_only(&self) -> bool {
+        self.store.is_read_only()
+    }
+}
@@ -0,0 +1,161 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+
+use crate::store::Store;
+use crate::{Entries, Metadata};
+
+/// Store that resolves paths ignoring ASCII case.
+///
+/// Each component of a path is looked up in the entries of its parent
+/// directory. An exact match wins, otherwise the first name that only differs
+/// in ASCII case is used, and components that match nothing are passed as they
+/// are, so new files keep the case they were created with:
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{CaseInsensitive, Local};
+///
+/// // authored on Windows as "Textures/Wall.PNG"
+/// let assets = CaseInsensitive::new(Local::new("./assets"));
+/// let file = assets.open("textures/wall.png")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Remarks
+///
+/// Directory entries are listed once and kept in memory. Creating, removing
+/// or renaming files through the wrapper drops them, but changes made to the
+/// wrapped store directly can only be seen after calling
+/// [`clear`](#method.clear). Stores that can't list their entries are used as
+/// they are.
+pub struct CaseInsensitive<S> {
+    store: S,
+    // Names in the directories listed so far.
+    dirs: RefCell<HashMap<PathBuf, Rc<[OsString]>>>,
+}
+
+impl<S> CaseInsensitive<S> {
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            dirs: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Drop the listed directory entries.
+    pub fn clear(&self) {
+        self.dirs.borrow_mut().clear();
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.store
+    }
+
+    pub fn into_inner(self) -> S {
+        self.store
+    }
+}
+
+impl<S: Store> CaseInsensitive<S> {
+    // Path with the case used by the wrapped store.
+    fn resolve(&self, path: &Path) -> PathBuf {
+        let mut resolved = PathBuf::new();
+        // Nothing is found under directories that don't exist.
+        let mut found = true;
+        for comp in path.components() {
+            let name = match comp {
+                Component::Normal(name) => name,
+                comp => {
+                    resolved.push(comp);
+                    continue;
+                }
+            };
+            let real = if found {
+                self.find(&resolved, name)
+            } else {
+                None
+            };
+            found = real.is_some();
+            resolved.push(real.as_deref().unwrap_or(name));
+        }
+        resolved
+    }
+
+    fn find(&self, dir: &Path, name: &OsStr) -> Option<OsString> {
+        let names = self.names(dir)?;
+        if names.iter().any(|other| other == name) {
+            return Some(name.to_os_string());
+        }
+        names
+            .iter()
+            .find(|other| other.eq_ignore_ascii_case(name))
+            .cloned()
+    }
+
+    fn names(&self, dir: &Path) -> Option<Rc<[OsString]>> {
+        if let Some(names) = self.dirs.borrow().get(dir) {
+            return Some(Rc::clone(names));
+        }
+        // Some stores return names relative to their root rather than to the
+        // listed directory, so only the last component is used.
+        let names: Rc<[OsString]> = self
+            .store
+            .entries_path(dir)
+            .ok()?
+            .filter_map(Result::ok)
+            .map(|entry| {
+                let name = Path::new(&entry.name);
+                name.file_name().unwrap_or(name.as_os_str()).to_os_string()
+            })
+            .collect();
+        self.dirs
+            .borrow_mut()
+            .insert(dir.to_path_buf(), Rc::clone(&names));
+        Some(names)
+    }
+}
+
+impl<S: Store> Store for CaseInsensitive<S> {
+    type File = S::File;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        self.store.open_path(&self.resolve(path))
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        self.store.entries_path(&self.resolve(path))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        self.store.metadata_path(&self.resolve(path))
+    }
+
+    fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+        let path = self.resolve(path);
+        self.clear();
+        self.store.create_path(&path)
+    }
+
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        let path = self.resolve(path);
+        self.clear();
+        self.store.remove_path(&path)
+    }
+
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let (from, to) = (self.resolve(from), self.resolve(to));
+        self.clear();
+        self.store.rename_path(&from, &to)
+    }
+
+    #[inline]
+    fn is_read_only(&self) -> bool {
+        self.store.is_read_only()
+    }
+}
@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, BufRead, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use crate::index::relative_path;
+use crate::store::Store;
+use crate::{Entries, File, Metadata};
+
+/// Store checking the SHA-256 hash of the files of another store while they
+/// are read.
+///
+/// Files are hashed as the caller reads them, without reading them ahead, and
+/// reaching the end of a file whose hash doesn't match fails with an
+/// [`IntegrityError`]:
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{Checksummed, IntegrityError, Local};
+/// use std::io::Read;
+///
+/// let manifest = std::fs::read("./content/SHA256SUMS")?;
+/// let content = Checksummed::with_manifest(Local::new("./content"), &manifest)?;
+///
+/// let mut video = Vec::new();
+/// match content.open("intro.webm")?.read_to_end(&mut video) {
+///     Err(err) if IntegrityError::from_io(&err).is_some() => {
+///         eprintln!("corrupted download: {}", err);
+///     }
+///     res => {
+///         res?;
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Remarks
+///
+/// Files that aren't listed can't be opened, and fail with an error of kind
+/// `PermissionDenied`. Seeking back to the start of a file restarts the hash,
+/// but reads after seeking anywhere else aren't checked.
+///
+/// Listing and metadata aren't checked, and the store is read-only, since
+/// writes would invalidate the hashes.
+///
+/// [`IntegrityError`]: ./struct.IntegrityError.html
+pub struct Checksummed<S> {
+    store: S,
+    hashes: HashMap<PathBuf, [u8; 32]>,
+}
+
+impl<S> Checksummed<S> {
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            hashes: HashMap::new(),
+        }
+    }
+
+    /// Check the files of `store` with a `manifest` in the format of
+    /// `sha256sum`, with paths relative to the root of the store.
+    ///
+    /// Returns an error of kind `InvalidData` if the manifest is malformed.
+    pub fn with_manifest(store: S, manifest: &[u8]) -> io::Result<Self> {
+        let hashes = parse_manifest(manifest)?;
+        Ok(Self { store, hashes })
+    }
+
+    /// Check the file at `path` against `hash`.
+    pub fn checksum<P: AsRef<Path>>(mut self, path: P, hash: [u8; 32]) -> Self {
+        self.hashes.insert(relative_path(path.as_ref()), hash);
+        self
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.store
+    }
+
+    pub fn into_inner(self) -> S {
+        self.store
+    }
+}
+
+pub(crate) fn invalid_data<E>(err: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+// Lines of `sha256sum`: a hex hash, whitespace and a path, starting with `*`
+// for files hashed in binary mode.
+pub(crate) fn parse_manifest(manifest: &[u8]) -> io::Result<HashMap<PathBuf, [u8; 32]>> {
+    let manifest = std::str::from_utf8(manifest).map_err(invalid_data)?;
+    let mut hashes = HashMap::new();
+    for line in manifest.lines().filter(|line| !line.trim().is_empty()) {
+        let malformed = || invalid_data(format!("malformed manifest line: {}", line));
+        let (hex, path) = line.split_once(char::is_whitespace).ok_or_else(malformed)?;
+        let path = path.trim_start().trim_start_matches('*');
+        // from_str_radix alone would accept signs like `+f`
+        if hex.len() != 64 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) || path.is_empty() {
+            return Err(malformed());
+        }
+        let mut hash = [0; 32];
+        for (byte, pair) in hash.iter_mut().zip(hex.as_bytes().chunks(2)) {
+            let pair = std::str::from_utf8(pair).map_err(|_| malformed())?;
+            *byte = u8::from_str_radix(pair, 16).map_err(|_| malformed())?;
+        }
+        hashes.insert(relative_path(Path::new(path)), hash);
+    }
+    Ok(hashes)
+}
+
+/// Error of a file whose hash doesn't match the expected one.
+///
+/// It is returned inside an `io::Error` of kind `InvalidData`, so reading
+/// methods can return it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntegrityError {
+    /// Path of the file, as it was opened.
+    pub path: PathBuf,
+    pub expected: [u8; 32],
+    pub actual: [u8; 32],
+}
+
+impl IntegrityError {
+    /// Integrity error inside `err`, if there is one.
+    pub fn from_io(err: &io::Error) -> Option<&Self> {
+        err.get_ref()?.downcast_ref()
+    }
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: SHA-256 mismatch, expected ", self.path.display())?;
+        self.expected
+            .iter()
+            .try_for_each(|b| write!(f, "{:02x}", b))?;
+        write!(f, ", got ")?;
+        self.actual.iter().try_for_each(|b| write!(f, "{:02x}", b))
+    }
+}
+
+impl std::error::Error for IntegrityError {}
+
+enum State {
+    Hashing(Sha256),
+    Matched,
+    Mismatched([u8; 32]),
+    Unchecked,
+}
+
+/// File from a Checksummed store.
+pub struct ChecksumFile {
+    inner: Box<File>,
+    path: PathBuf,
+    expected: [u8; 32],
+    pos: u64,
+    state: State,
+}
+
+impl ChecksumFile {
+    pub(crate) fn len(&mut self) -> io::Result<u64> {
+        self.inner.len()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        self.pos += data.len() as u64;
+        if let State::Hashing(hasher) = &mut self.state {
+            hasher.update(data);
+        }
+    }
+
+    // Called when the end of the file is reached.
+    fn finish(&mut self) -> io::Result<()> {
+        if let State::Hashing(hasher) = &mut self.state {
+            let actual: [u8; 32] = hasher.finalize_reset().into();
+            self.state = if actual == self.expected {
+                State::Matched
+            } else {
+                State::Mismatched(actual)
+            };
+        }
+        match self.state {
+            State::Mismatched(actual) => Err(invalid_data(IntegrityError {
+                path: self.path.clone(),
+                expected: self.expected,
+                actual,
+            })),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Read for ChecksumFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        if n == 0 && !buf.is_empty() {
+            self.finish()?;
+        }
+        self.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+impl BufRead for ChecksumFile {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.inner.fill_buf()?.is_empty() {
+            self.finish()?;
+        }
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        // The data is already buffered, so this doesn't read.
+        if let Ok(data) = self.inner.fill_buf() {
+            let data = &data[..amt.min(data.len())];
+            self.pos += data.len() as u64;
+            if let State::Hashing(hasher) = &mut self.state {
+                hasher.update(data);
+            }
+        }
+        self.inner.consume(amt)
+    }
+}
+
+impl Seek for ChecksumFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let pos = self.inner.seek(pos)?;
+        if pos == 0 {
+            self.state = State::Hashing(Sha256::new());
+        } else if pos != self.pos {
+            self.state = State::Unchecked;
+        }
+        self.pos = pos;
+        Ok(pos)
+    }
+}
+
+impl<S> Store for Checksummed<S>
+where
+    S: Store,
+    S::File: Into<File>,
+{
+    type File = File;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        let expected = match self.hashes.get(&relative_path(path)) {
+            Some(hash) => *hash,
+            None => return Err(io::Error::from(io::ErrorKind::PermissionDenied)),
+        };
+        let inner = self.store.open_path(path)?.into();
+        Ok(File::Checksum(ChecksumFile {
+            inner: Box::new(inner),
+            path: path.to_path_buf(),
+            expected,
+            pos: 0,
+            state: State::Hashing(Sha256::new()),
+        }))
+    }
+
+    #[inline]
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        self.store.entries_path(path)
+    }
+
+    #[inline]
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        self.store.metadata_path(path)
+    }
+}
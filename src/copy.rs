@@ -0,0 +1,135 @@
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::store::{Store, StoreExt};
+
+/// What [`copy`] does with files that already exist in the destination.
+///
+/// [`copy`]: ./fn.copy.html
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Overwrite {
+    /// Replace them.
+    Always,
+    /// Keep them.
+    Never,
+    /// Replace them when the source file was modified more recently, or, if
+    /// either store doesn't keep track of modification times, when their
+    /// lengths are different.
+    IfNewer,
+}
+
+/// Options of [`copy`].
+///
+/// [`copy`]: ./fn.copy.html
+#[derive(Debug, Clone)]
+pub struct CopyOptions {
+    overwrite: Overwrite,
+    dry_run: bool,
+}
+
+impl Default for CopyOptions {
+    fn default() -> Self {
+        Self {
+            overwrite: Overwrite::Always,
+            dry_run: false,
+        }
+    }
+}
+
+impl CopyOptions {
+    /// Options replacing every existing file.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn overwrite(mut self, overwrite: Overwrite) -> Self {
+        self.overwrite = overwrite;
+        self
+    }
+
+    /// Only report the files that would be copied, without writing them.
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+}
+
+/// Copy every file of `src` to the same path in `dst`.
+///
+/// Returns the paths of the copied files, or of the files that would be
+/// copied in a dry run:
+///
+/// ```no_run
+/// # #[cfg(feature = "ureq")]
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::{copy, CopyOptions, Http, Local, Overwrite};
+///
+/// let remote = Http::new("https://example.com/assets");
+/// let cache = Local::new("./cache");
+///
+/// let options = CopyOptions::new().overwrite(Overwrite::IfNewer);
+/// for path in copy(&remote, &cache, &options)? {
+///     println!("updated {}", path.display());
+/// }
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "ureq"))]
+/// # fn main() {}
+/// ```
+///
+/// Paths are the ones listed by `src`, so use [`Rewrite`] to copy a subtree or
+/// to copy to a different directory.
+///
+/// [`Rewrite`]: ./struct.Rewrite.html
+pub fn copy<S, D>(src: &S, dst: &D, options: &CopyOptions) -> io::Result<Vec<PathBuf>>
+where
+    S: Store,
+    S::File: Read,
+    D: Store,
+    D::File: Write,
+{
+    let mut copied = Vec::new();
+    for path in src.walk("") {
+        let path = path?;
+        if !should_copy(src, dst, &path, options.overwrite)? {
+            continue;
+        }
+        if !options.dry_run {
+            let mut file = dst.create_path(&path)?;
+            io::copy(&mut src.open_path(&path)?, &mut file)?;
+            file.flush()?;
+        }
+        copied.push(path);
+    }
+    Ok(copied)
+}
+
+fn should_copy<S: Store, D: Store>(
+    src: &S,
+    dst: &D,
+    path: &Path,
+    overwrite: Overwrite,
+) -> io::Result<bool> {
+    if overwrite == Overwrite::Always {
+        return Ok(true);
+    }
+    let dst_meta = match dst.metadata_path(path) {
+        Ok(meta) => meta,
+        Err(ref err) if err.kind() == io::ErrorKind::NotFound => return Ok(true),
+        // Without metadata, the destination can still tell if it has the file.
+        Err(ref err) if err.kind() == io::ErrorKind::Unsupported => return Ok(!dst.is_file(path)),
+        Err(err) => return Err(err),
+    };
+    if overwrite == Overwrite::Never {
+        return Ok(false);
+    }
+    let src_meta = match src.metadata_path(path) {
+        Ok(meta) => meta,
+        Err(ref err) if err.kind() == io::ErrorKind::Unsupported => return Ok(true),
+        Err(err) => return Err(err),
+    };
+    Ok(match (src_meta.modified, dst_meta.modified) {
+        (Some(src), Some(dst)) => src > dst,
+        _ => src_meta.len != dst_meta.len,
+    })
+}
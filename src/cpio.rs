@@ -0,0 +1,303 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Cursor, ErrorKind, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+use crate::index::Index;
+use crate::store::Store;
+use crate::{Entries, Entry, EntryKind, Metadata};
+
+// Size of a newc header: the magic followed by 13 fields of 8 hex digits.
+const HEADER: usize = 110;
+
+/// Cpio archive store.
+///
+/// Reads archives in the newc format (with or without checksums), which is the
+/// format of Linux initramfs images. Concatenated archives are read as a whole,
+/// and hard links share the data of the linked file. The archive is indexed
+/// when the store is created.
+///
+/// # Remarks
+///
+/// Compressed images have to be decompressed first. The old binary and odc
+/// formats are not supported. Symlinks and special files are skipped.
+///
+/// When used with a `std::fs::File`, the file will remain open for the lifetime
+/// of the Cpio.
+pub struct Cpio<T: Read + Seek> {
+    inner: RefCell<T>,
+    index: Index<CpioIndexEntry>,
+}
+
+#[derive(Clone)]
+struct CpioIndexEntry {
+    // position of the file data within the archive
+    pos: u64,
+    meta: Metadata,
+}
+
+/// Entry in the cpio archive.
+pub struct CpioEntry {
+    inner: Cursor<Box<[u8]>>,
+}
+
+impl CpioEntry {
+    pub(crate) fn len(&self) -> u64 {
+        self.inner.get_ref().len() as u64
+    }
+}
+
+impl Read for CpioEntry {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl BufRead for CpioEntry {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+impl Seek for CpioEntry {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl Cpio<fs::File> {
+    /// Open a file from the native filesystem.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = fs::OpenOptions::new()
+            .read(true)
+            .write(false)
+            .create(false)
+            .open(path)?;
+        Self::new(file)
+    }
+}
+
+impl<T: Read + Seek> Cpio<T> {
+    /// Read the headers of the archive and index its contents.
+    pub fn new(mut inner: T) -> io::Result<Self> {
+        inner.seek(SeekFrom::Start(0))?;
+        let mut reader = Reader {
+            inner: BufReader::new(&mut inner),
+            pos: 0,
+        };
+
+        let mut index = Index::new();
+        // Hard links are identified by device and inode. Only one of the links
+        // carries the data, usually the last one.
+        let mut linked = HashMap::new();
+        let mut pending: HashMap<_, Vec<PathBuf>> = HashMap::new();
+        loop {
+            loop {
+                let header = match reader.header()? {
+                    Some(header) => header,
+                    None => return Err(invalid("Not a cpio archive.")),
+                };
+                let name = reader.name(header.namesize)?;
+                if name == "TRAILER!!!" {
+                    break;
+                }
+
+                let pos = reader.pos;
+                reader.skip(align(u64::from(header.filesize)))?;
+                let path = PathBuf::from(name.trim_start_matches('/'));
+                match header.mode & 0o170_000 {
+                    0o040_000 => index.insert_dir(path),
+                    0o100_000 => {
+                        let entry = CpioIndexEntry {
+                            pos,
+                            meta: Metadata {
+                                kind: EntryKind::File,
+                                len: u64::from(header.filesize),
+                                modified: Some(
+                                    UNIX_EPOCH + Duration::from_secs(u64::from(header.mtime)),
+                                ),
+                            },
+                        };
+                        if header.nlink <= 1 {
+                            index.insert(path, entry);
+                            continue;
+                        }
+                        let key = (header.devmajor, header.devminor, header.ino);
+                        if header.filesize > 0 {
+                            for path in pending.remove(&key).unwrap_or_default() {
+                                index.insert(path, entry.clone());
+                            }
+                            linked.insert(key, entry.clone());
+                            index.insert(path, entry);
+                        } else if let Some(entry) = linked.get(&key) {
+                            index.insert(path, entry.clone());
+                        } else {
+                            pending.entry(key).or_default().push(path.clone());
+                            index.insert(path, entry);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            if !reader.skip_padding()? {
+                break;
+            }
+        }
+
+        Ok(Self {
+            inner: RefCell::new(inner),
+            index,
+        })
+    }
+}
+
+impl<T: Read + Seek> Store for Cpio<T> {
+    type File = CpioEntry;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        let entry = match self.index.get(path) {
+            Some(entry) => entry,
+            None => return Err(io::Error::from(ErrorKind::NotFound)),
+        };
+        let mut file = self.inner.borrow_mut();
+        file.seek(SeekFrom::Start(entry.pos))?;
+        let mut data = Vec::new();
+        (&mut *file).take(entry.meta.len).read_to_end(&mut data)?;
+        Ok(CpioEntry {
+            inner: Cursor::new(data.into()),
+        })
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        Ok(Entries::new(self.index.entries(path).map(|ent| {
+            let name = ent.name.to_os_string();
+            let kind = ent.kind;
+            Ok(Entry { name, kind })
+        })))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        if let Some(entry) = self.index.get(path) {
+            Ok(entry.meta.clone())
+        } else if self.index.contains_dir(path) {
+            Ok(Metadata::dir())
+        } else {
+            Err(io::Error::from(ErrorKind::NotFound))
+        }
+    }
+}
+
+// Header fields used by the store (newc header, in order of appearance).
+struct Header {
+    ino: u32,
+    mode: u32,
+    nlink: u32,
+    mtime: u32,
+    filesize: u32,
+    devmajor: u32,
+    devminor: u32,
+    namesize: u32,
+}
+
+// Sequential reader keeping track of the position within the archive.
+struct Reader<R> {
+    inner: BufReader<R>,
+    pos: u64,
+}
+
+impl<R: Read> Reader<R> {
+    // Returns None if there is no valid header at the current position.
+    fn header(&mut self) -> io::Result<Option<Header>> {
+        let mut header = [0; HEADER];
+        match self.inner.read_exact(&mut header) {
+            Err(ref err) if err.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            res => res?,
+        }
+        self.pos += HEADER as u64;
+        if &header[..6] != b"070701" && &header[..6] != b"070702" {
+            return Ok(None);
+        }
+
+        let mut fields = [0; 13];
+        for (i, field) in fields.iter_mut().enumerate() {
+            let hex = &header[6 + i * 8..14 + i * 8];
+            *field = std::str::from_utf8(hex)
+                .ok()
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| invalid("Invalid cpio header."))?;
+        }
+        Ok(Some(Header {
+            ino: fields[0],
+            mode: fields[1],
+            nlink: fields[4],
+            mtime: fields[5],
+            filesize: fields[6],
+            devmajor: fields[7],
+            devminor: fields[8],
+            namesize: fields[11],
+        }))
+    }
+
+    // The name is NUL terminated, and padded along with the header.
+    fn name(&mut self, size: u32) -> io::Result<String> {
+        let size = u64::from(size);
+        let mut name = Vec::new();
+        (&mut self.inner).take(size).read_to_end(&mut name)?;
+        if name.len() as u64 != size {
+            return Err(io::Error::from(ErrorKind::UnexpectedEof));
+        }
+        self.pos += size;
+        self.skip(align(self.pos) - self.pos)?;
+
+        if name.last() == Some(&0) {
+            name.pop();
+        }
+        Ok(String::from_utf8_lossy(&name).into_owned())
+    }
+
+    fn skip(&mut self, len: u64) -> io::Result<()> {
+        let skipped = io::copy(&mut (&mut self.inner).take(len), &mut io::sink())?;
+        if skipped != len {
+            return Err(io::Error::from(ErrorKind::UnexpectedEof));
+        }
+        self.pos += len;
+        Ok(())
+    }
+
+    // Skips the NUL padding after an archive. Returns false at the end of the
+    // input.
+    fn skip_padding(&mut self) -> io::Result<bool> {
+        loop {
+            let buf = self.inner.fill_buf()?;
+            if buf.is_empty() {
+                return Ok(false);
+            }
+            let zeros = buf.iter().take_while(|b| **b == 0).count();
+            let more = zeros < buf.len();
+            self.inner.consume(zeros);
+            self.pos += zeros as u64;
+            if more {
+                return Ok(true);
+            }
+        }
+    }
+}
+
+// Headers, names and data are aligned to 4 bytes.
+fn align(pos: u64) -> u64 {
+    (pos + 3) & !3
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg)
+}
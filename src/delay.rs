@@ -0,0 +1,129 @@
+use std::io;
+use std::path::Path;
+use std::sync::{Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+use crate::store::Store;
+use crate::{Entries, Metadata};
+
+/// Store adding latency to the files opened from another store, for tests.
+///
+/// Every open sleeps for a fixed delay, plus a random one when [`jitter`] is
+/// set. The random delays come from a seed, so a test sees the same delays
+/// every time it runs:
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{Delay, MiniFs, Ram};
+/// use std::time::{Duration, Instant};
+///
+/// let mut ram = Ram::new();
+/// ram.touch("level.dat", &b"level"[..]);
+/// let slow = Delay::new(ram, Duration::from_millis(20)).jitter(Duration::from_millis(10), 42);
+/// let fs = MiniFs::new().mount("/data", slow);
+///
+/// let start = Instant::now();
+/// fs.open("/data/level.dat")?;
+/// assert!(start.elapsed() >= Duration::from_millis(20));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Remarks
+///
+/// The delay is added to opens only, whether they succeed or not. Listing,
+/// metadata and writes are forwarded as they are.
+///
+/// [`jitter`]: #method.jitter
+pub struct Delay<S> {
+    store: S,
+    delay: Duration,
+    jitter: Duration,
+    // State of a xorshift generator, never zero.
+    rng: Mutex<u64>,
+}
+
+impl<S> Delay<S> {
+    /// Delay every open of `store` by `delay`.
+    pub fn new(store: S, delay: Duration) -> Self {
+        Self {
+            store,
+            delay,
+            jitter: Duration::ZERO,
+            rng: Mutex::new(1),
+        }
+    }
+
+    /// Add a random delay of up to `max` to every open, generated from `seed`.
+    pub fn jitter(mut self, max: Duration, seed: u64) -> Self {
+        self.jitter = max;
+        // splitmix64, so close seeds give unrelated delays
+        let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        self.rng = Mutex::new((z ^ (z >> 31)).max(1));
+        self
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.store
+    }
+
+    pub fn into_inner(self) -> S {
+        self.store
+    }
+
+    fn sleep(&self) {
+        let mut delay = self.delay;
+        if self.jitter > Duration::ZERO {
+            let mut rng = self.rng.lock().unwrap_or_else(PoisonError::into_inner);
+            *rng ^= *rng << 13;
+            *rng ^= *rng >> 7;
+            *rng ^= *rng << 17;
+            let nanos = self.jitter.as_nanos().min(u128::from(u64::MAX)) as u64;
+            delay += Duration::from_nanos(*rng % nanos.max(1));
+        }
+        thread::sleep(delay);
+    }
+}
+
+impl<S: Store> Store for Delay<S> {
+    type File = S::File;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        self.sleep();
+        self.store.open_path(path)
+    }
+
+    #[inline]
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        self.store.entries_path(path)
+    }
+
+    #[inline]
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        self.store.metadata_path(path)
+    }
+
+    #[inline]
+    fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+        self.store.create_path(path)
+    }
+
+    #[inline]
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        self.store.remove_path(path)
+    }
+
+    #[inline]
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.store.rename_path(from, to)
+    }
+
+    #[inline]
+    fn is_read_only(&self) -> bool {
+        self.store.is_read_only()
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeSet;
+use std::hash::Hasher;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use crate::store::{Store, StoreExt};
+
+/// Differences between the files of two stores.
+///
+/// Returned by [`diff`](./fn.diff.html). Paths are sorted.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Diff {
+    /// Files only in the new store.
+    pub added: Vec<PathBuf>,
+    /// Files only in the old store.
+    pub removed: Vec<PathBuf>,
+    /// Files in both stores, with different contents.
+    pub changed: Vec<PathBuf>,
+}
+
+impl Diff {
+    /// Returns `true` if both stores have the same files.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compare the files of an `old` and a `new` store.
+///
+/// Files found in both stores changed if their lengths are different, or
+/// otherwise if the hashes of their contents are, so only files of the same
+/// length are read:
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::{diff, Local, Zip};
+///
+/// let shipped = Zip::open("content-1.0.zip")?;
+/// let current = Local::new("./content");
+///
+/// let patch = diff(&shipped, &current)?;
+/// for path in patch.added.iter().chain(&patch.changed) {
+///     println!("ship {}", path.display());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn diff<A, B>(old: &A, new: &B) -> io::Result<Diff>
+where
+    A: Store,
+    A::File: Read,
+    B: Store,
+    B::File: Read,
+{
+    let old_paths = old.walk("").collect::<io::Result<BTreeSet<_>>>()?;
+    let new_paths = new.walk("").collect::<io::Result<BTreeSet<_>>>()?;
+    let mut diff = Diff {
+        added: new_paths.difference(&old_paths).cloned().collect(),
+        removed: old_paths.difference(&new_paths).cloned().collect(),
+        changed: Vec::new(),
+    };
+    for path in old_paths.intersection(&new_paths) {
+        if changed(old, new, path)? {
+            diff.changed.push(path.clone());
+        }
+    }
+    Ok(diff)
+}
+
+fn changed<A, B>(old: &A, new: &B, path: &Path) -> io::Result<bool>
+where
+    A: Store,
+    A::File: Read,
+    B: Store,
+    B::File: Read,
+{
+    // Stores without metadata always have their files hashed.
+    let old_len = old.metadata_path(path).ok().map(|meta| meta.len);
+    let new_len = new.metadata_path(path).ok().map(|meta| meta.len);
+    if let (Some(old_len), Some(new_len)) = (old_len, new_len) {
+        if old_len != new_len {
+            return Ok(true);
+        }
+    }
+    Ok(hash(old.open_path(path)?)? != hash(new.open_path(path)?)?)
+}
+
+fn hash<R: Read>(mut file: R) -> io::Result<(u64, u64)> {
+    let mut hasher = DefaultHasher::new();
+    let mut buf = [0; 8 * 1024];
+    let mut len = 0;
+    loop {
+        match file.read(&mut buf) {
+            Ok(0) => return Ok((len, hasher.finish())),
+            Ok(n) => {
+                hasher.write(&buf[..n]);
+                len += n as u64;
+            }
+            Err(ref err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+}
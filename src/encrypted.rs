@@ -0,0 +1,214 @@
+use std::io::{self, BufRead, Cursor, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+
+use crate::store::{EntryKind, Store};
+use crate::{Entries, File, Metadata};
+
+const MAGIC: &[u8; 4] = b"MFSE";
+const VERSION: u8 = 1;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+const HEADER_LEN: usize = MAGIC.len() + 1 + NONCE_LEN;
+
+/// Store encrypting the files of another store with AES-256-GCM.
+///
+/// Files are decrypted when they are opened and encrypted when they are
+/// written, with a key provided by the caller, so assets can be protected at
+/// rest in any store:
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{Encrypted, Local, MiniFs};
+/// use std::io::Write;
+///
+/// let key = [0x42; 32]; // load it from somewhere safe
+/// let assets = Encrypted::new(Local::new("./assets"), &key);
+///
+/// let fs = MiniFs::new().mount("/assets", assets);
+/// fs.create("/assets/level1.dat")?.write_all(b"licensed content")?;
+/// assert_eq!(b"licensed content", &fs.read("/assets/level1.dat")?[..]);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Remarks
+///
+/// Each file starts with a header holding a random nonce, which is replaced
+/// every time the file is written. Files that aren't encrypted with the same
+/// key, or were tampered with, fail to open with an error of kind
+/// `InvalidData`.
+///
+/// Decrypted files are kept in memory, and files being written are encrypted
+/// and written to the wrapped store when they are flushed or dropped. Paths
+/// and metadata aren't encrypted.
+pub struct Encrypted<S> {
+    store: S,
+    cipher: Arc<Aes256Gcm>,
+}
+
+impl<S> Encrypted<S> {
+    /// Encrypt the files of `store` with a 256-bit `key`.
+    pub fn new(store: S, key: &[u8; 32]) -> Self {
+        Self {
+            store,
+            cipher: Arc::new(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key))),
+        }
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.store
+    }
+
+    pub fn into_inner(self) -> S {
+        self.store
+    }
+}
+
+fn invalid_data(path: &Path) -> io::Error {
+    let msg = format!("{}: not encrypted with this key", path.display());
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// File being written to an Encrypted store.
+pub struct EncryptedFile {
+    inner: Cursor<Vec<u8>>,
+    file: Box<File>,
+    cipher: Arc<Aes256Gcm>,
+    dirty: bool,
+}
+
+impl EncryptedFile {
+    pub(crate) fn len(&self) -> u64 {
+        self.inner.get_ref().len() as u64
+    }
+}
+
+impl Read for EncryptedFile {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl BufRead for EncryptedFile {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+impl Seek for EncryptedFile {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl Write for EncryptedFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.dirty = true;
+        self.inner.write(buf)
+    }
+
+    // The whole file is encrypted again with a new nonce, so it never gets
+    // shorter and can be written over the previous version.
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let data = self
+            .cipher
+            .encrypt(&nonce, &self.inner.get_ref()[..])
+            .map_err(|_| io::Error::other("encryption failed"))?;
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.write_all(MAGIC)?;
+        self.file.write_all(&[VERSION])?;
+        self.file.write_all(&nonce)?;
+        self.file.write_all(&data)?;
+        self.file.flush()?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+impl Drop for EncryptedFile {
+    fn drop(&mut self) {
+        let _ = Write::flush(self);
+    }
+}
+
+impl<S> Store for Encrypted<S>
+where
+    S: Store,
+    S::File: Read + Into<File>,
+{
+    type File = File;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        let mut data = Vec::new();
+        self.store.open_path(path)?.read_to_end(&mut data)?;
+        if data.len() < HEADER_LEN + TAG_LEN
+            || &data[..MAGIC.len()] != MAGIC
+            || data[MAGIC.len()] != VERSION
+        {
+            return Err(invalid_data(path));
+        }
+        let nonce = Nonce::from_slice(&data[MAGIC.len() + 1..HEADER_LEN]);
+        let data: Arc<[u8]> = self
+            .cipher
+            .decrypt(nonce, &data[HEADER_LEN..])
+            .map_err(|_| invalid_data(path))?
+            .into();
+        Ok(data.into())
+    }
+
+    #[inline]
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        self.store.entries_path(path)
+    }
+
+    // The length of files is the length of their decrypted contents.
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        let mut meta = self.store.metadata_path(path)?;
+        if meta.kind == EntryKind::File {
+            meta.len = meta.len.saturating_sub((HEADER_LEN + TAG_LEN) as u64);
+        }
+        Ok(meta)
+    }
+
+    fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+        let file = self.store.create_path(path)?.into();
+        Ok(File::Encrypted(EncryptedFile {
+            inner: Cursor::new(Vec::new()),
+            file: Box::new(file),
+            cipher: Arc::clone(&self.cipher),
+            dirty: true,
+        }))
+    }
+
+    #[inline]
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        self.store.remove_path(path)
+    }
+
+    #[inline]
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.store.rename_path(from, to)
+    }
+
+    #[inline]
+    fn is_read_only(&self) -> bool {
+        self.store.is_read_only()
+    }
+}
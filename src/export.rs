@@ -0,0 +1,177 @@
+#[cfg(feature = "zip")]
+use std::collections::HashSet;
+#[cfg(feature = "zip")]
+use std::convert::TryFrom;
+#[cfg(feature = "zip")]
+use std::io::Seek;
+use std::io::{self, Read, Write};
+use std::path::Path;
+#[cfg(feature = "tar")]
+use std::time::UNIX_EPOCH;
+
+use crate::index::{normalize_path, relative_path};
+use crate::store::{Store, StoreExt};
+
+/// Write every file reachable from `prefix` in `store` to a tar archive.
+///
+/// Files are added with their path relative to `prefix`, so the subtree
+/// becomes the root of the archive. Returns `writer` once the archive is
+/// finished:
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{export, Local, Tar};
+/// use std::io::Cursor;
+///
+/// let local = Local::new("./tests");
+/// let tar = export::to_tar(&local, "local", Cursor::new(Vec::new()))?;
+///
+/// let tar = Tar::new(Cursor::new(tar.into_inner())).index()?;
+/// assert!(tar.is_file("baz/foobar"));
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "tar")]
+pub fn to_tar<S, P, W>(store: &S, prefix: P, writer: W) -> io::Result<W>
+where
+    S: Store,
+    S::File: Read,
+    P: AsRef<Path>,
+    W: Write,
+{
+    let root = normalize_path(prefix.as_ref()).into_owned();
+    let mut builder = tar_::Builder::new(writer);
+    for path in store.walk(&root) {
+        let path = path?;
+        // The size goes in the header, before the data.
+        let mut data = Vec::new();
+        store.open_path(&path)?.read_to_end(&mut data)?;
+        let mut header = tar_::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        let modified = store
+            .metadata_path(&path)
+            .ok()
+            .and_then(|meta| meta.modified);
+        if let Some(time) = modified.and_then(|time| time.duration_since(UNIX_EPOCH).ok()) {
+            header.set_mtime(time.as_secs());
+        }
+        let name = relative_path(path.strip_prefix(&root).unwrap_or(&path));
+        builder.append_data(&mut header, name, &data[..])?;
+    }
+    builder.into_inner()
+}
+
+/// Compression options of [`to_zip`](fn.to_zip.html).
+///
+/// Files are compressed with deflate by default, but files with one of the
+/// [`stored`](#method.stored) extensions, which are usually compressed
+/// already, are stored as they are.
+#[cfg(feature = "zip")]
+#[derive(Debug, Clone, Default)]
+pub struct ZipOptions {
+    stored: HashSet<String>,
+}
+
+#[cfg(feature = "zip")]
+impl ZipOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store files with any of `extensions` without compressing them.
+    /// Extensions are matched ignoring ASCII case, with or without the leading
+    /// dot.
+    pub fn stored<I>(mut self, extensions: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        for ext in extensions {
+            let ext = ext.as_ref().trim_start_matches('.');
+            self.stored.insert(ext.to_ascii_lowercase());
+        }
+        self
+    }
+
+    fn method(&self, path: &Path) -> zip_::CompressionMethod {
+        let ext = path.extension().and_then(|ext| ext.to_str());
+        match ext {
+            Some(ext) if self.stored.contains(&ext.to_ascii_lowercase()) => {
+                zip_::CompressionMethod::Stored
+            }
+            _ => zip_::CompressionMethod::Deflated,
+        }
+    }
+}
+
+/// Write every file reachable from `prefix` in `store` to a zip archive.
+///
+/// Files are added with their path relative to `prefix`, like in
+/// [`to_tar`](fn.to_tar.html), and compressed according to `options`.
+/// Returns `writer` once the archive is finished:
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::export::{self, ZipOptions};
+/// use mini_fs::prelude::*;
+/// use mini_fs::{Local, Zip};
+/// use std::io::Cursor;
+///
+/// let options = ZipOptions::new().stored(&["png", "jpg", "ogg"]);
+/// let local = Local::new("./tests");
+/// let zip = export::to_zip(&local, "local", Cursor::new(Vec::new()), &options)?;
+///
+/// let zip = Zip::new(Cursor::new(zip.into_inner())).index()?;
+/// assert!(zip.is_file("baz/foobar"));
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "zip")]
+pub fn to_zip<S, P, W>(store: &S, prefix: P, writer: W, options: &ZipOptions) -> io::Result<W>
+where
+    S: Store,
+    S::File: Read,
+    P: AsRef<Path>,
+    W: Write + Seek,
+{
+    let root = normalize_path(prefix.as_ref()).into_owned();
+    let mut zip = zip_::ZipWriter::new(writer);
+    for path in store.walk(&root) {
+        let path = path?;
+        let name = relative_path(path.strip_prefix(&root).unwrap_or(&path));
+        let name = name
+            .to_str()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Non UTF-8 zip path."))?
+            .replace('\\', "/");
+        let mut file_options = zip_::write::FileOptions::default()
+            .compression_method(options.method(Path::new(&name)))
+            .unix_permissions(0o644);
+        let modified = store
+            .metadata_path(&path)
+            .ok()
+            .and_then(|meta| meta.modified);
+        if let Some(time) = modified.and_then(dos_time) {
+            file_options = file_options.last_modified_time(time);
+        }
+        zip.start_file(name, file_options)?;
+        io::copy(&mut store.open_path(&path)?, &mut zip)?;
+    }
+    Ok(zip.finish()?)
+}
+
+// Zip timestamps can't be older than 1980.
+#[cfg(feature = "zip")]
+fn dos_time(time: std::time::SystemTime) -> Option<zip_::DateTime> {
+    let (year, month, day, secs) = crate::time::to_civil(time);
+    zip_::DateTime::from_date_and_time(
+        u16::try_from(year).ok()?,
+        month as u8,
+        day as u8,
+        (secs / 3600) as u8,
+        (secs / 60 % 60) as u8,
+        (secs % 60) as u8,
+    )
+    .ok()
+}
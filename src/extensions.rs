@@ -0,0 +1,124 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::store::Store;
+use crate::{Entries, Metadata};
+
+/// Store that retries missing files with alternate extensions.
+///
+/// When a file can't be found, it's looked up again with each of the
+/// configured extensions, in order, replacing the extension of the requested
+/// path if there's one, so assets can be referenced without knowing the format
+/// they were exported to:
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{Extensions, Local};
+///
+/// let textures = Extensions::new(Local::new("./textures"), &["ktx2", "png", "jpg"]);
+///
+/// // opens `wall.ktx2`, `wall.png` or `wall.jpg`, whichever exists first
+/// let wall = textures.open("wall")?;
+/// // same, unless `wall.dds` exists
+/// let wall = textures.open("wall.dds")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Remarks
+///
+/// Only opening files and reading their metadata fall back to other
+/// extensions. Listing, creating, removing and renaming use the requested
+/// paths as they are.
+pub struct Extensions<S> {
+    store: S,
+    extensions: Vec<String>,
+}
+
+impl<S> Extensions<S> {
+    /// Retry missing files of `store` with each of `extensions`, with or
+    /// without the leading dot.
+    pub fn new<I>(store: S, extensions: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let extensions = extensions
+            .into_iter()
+            .map(|ext| ext.as_ref().trim_start_matches('.').to_string())
+            .collect();
+        Self { store, extensions }
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.store
+    }
+
+    pub fn into_inner(self) -> S {
+        self.store
+    }
+
+    // Calls `f` with `path` and then with each alternate path, until it finds
+    // something.
+    fn fallback<T, F>(&self, path: &Path, f: F) -> io::Result<T>
+    where
+        F: Fn(&Path) -> io::Result<T>,
+    {
+        let err = match f(path) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => err,
+            res => return res,
+        };
+        if path.file_name().is_none() {
+            return Err(err);
+        }
+        for ext in &self.extensions {
+            let alt: PathBuf = path.with_extension(ext);
+            if alt == path {
+                continue;
+            }
+            match f(&alt) {
+                Err(ref err) if err.kind() == io::ErrorKind::NotFound => {}
+                res => return res,
+            }
+        }
+        Err(err)
+    }
+}
+
+impl<S: Store> Store for Extensions<S> {
+    type File = S::File;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        self.fallback(path, |path| self.store.open_path(path))
+    }
+
+    #[inline]
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        self.store.entries_path(path)
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        self.fallback(path, |path| self.store.metadata_path(path))
+    }
+
+    #[inline]
+    fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+        self.store.create_path(path)
+    }
+
+    #[inline]
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        self.store.remove_path(path)
+    }
+
+    #[inline]
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.store.rename_path(from, to)
+    }
+
+    #[inline]
+    fn is_read_only(&self) -> bool {
+        self.store.is_read_only()
+    }
+}
@@ -0,0 +1,318 @@
+use std::ffi::OsStr;
+use std::io;
+#[cfg(feature = "regex")]
+use std::path::Component;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "regex")]
+use regex_::Regex;
+
+use crate::index::relative_path;
+use crate::store::{EntryKind, Store};
+use crate::{Entries, Metadata};
+
+/// Store exposing only the files of another store whose path matches a set of
+/// regular expressions.
+///
+/// Paths are matched relative to the root of the wrapped store, with `/` as
+/// separator and no leading slash. A file is visible when it matches any of
+/// the [`include`](#method.include) patterns (or there are none), and none of
+/// the [`exclude`](#method.exclude) ones:
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{Filtered, MiniFs, Zip};
+///
+/// let scripts = Filtered::new(Zip::open("mod.zip")?)
+///     .include(r"\.lua$")?
+///     .exclude(r"^debug/")?;
+///
+/// let fs = MiniFs::new().mount("/scripts", scripts);
+/// assert!(fs.open("/scripts/main.lua").is_ok());
+/// assert!(fs.open("/scripts/payload.exe").is_err());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Remarks
+///
+/// Hidden files can't be opened, listed, removed or renamed, as if they didn't
+/// exist, and creating them fails with an error of kind `PermissionDenied`.
+/// Exclude patterns also apply to directories, matched with a trailing `/`,
+/// but include patterns don't, so directories without visible files are still
+/// listed.
+#[cfg(feature = "regex")]
+pub struct Filtered<S> {
+    store: S,
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+}
+
+#[cfg(feature = "regex")]
+impl<S> Filtered<S> {
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            include: Vec::new(),
+            exclude: Vec::new(),
+        }
+    }
+
+    /// Expose the files matching `pattern`, in addition to the ones matching
+    /// previous include patterns.
+    ///
+    /// Returns an error of kind `InvalidInput` if `pattern` isn't a valid
+    /// regular expression.
+    pub fn include(mut self, pattern: &str) -> io::Result<Self> {
+        self.include.push(regex(pattern)?);
+        Ok(self)
+    }
+
+    /// Hide the files and directories matching `pattern`.
+    ///
+    /// Returns an error of kind `InvalidInput` if `pattern` isn't a valid
+    /// regular expression.
+    pub fn exclude(mut self, pattern: &str) -> io::Result<Self> {
+        self.exclude.push(regex(pattern)?);
+        Ok(self)
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.store
+    }
+
+    pub fn into_inner(self) -> S {
+        self.store
+    }
+
+    fn is_visible(&self, path: &Path, kind: EntryKind) -> bool {
+        let mut key = key(path);
+        if key.is_empty() {
+            return true;
+        }
+        match kind {
+            EntryKind::File => {
+                (self.include.is_empty() || self.include.iter().any(|re| re.is_match(&key)))
+                    && !self.exclude.iter().any(|re| re.is_match(&key))
+            }
+            EntryKind::Dir => {
+                key.push('/');
+                !self.exclude.iter().any(|re| re.is_match(&key))
+            }
+        }
+    }
+
+    fn check(&self, path: &Path, kind: EntryKind) -> io::Result<()> {
+        if self.is_visible(path, kind) {
+            Ok(())
+        } else {
+            Err(io::Error::from(io::ErrorKind::NotFound))
+        }
+    }
+}
+
+#[cfg(feature = "regex")]
+impl<S: Store> Store for Filtered<S> {
+    type File = S::File;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        self.check(path, EntryKind::File)?;
+        self.store.open_path(path)
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        self.check(path, EntryKind::Dir)?;
+        let entries = self.store.entries_path(path)?;
+        let dir = path.to_path_buf();
+        Ok(Entries::new(entries.filter(move |entry| match entry {
+            Ok(entry) => self.is_visible(&entry_path(&dir, &entry.name), entry.kind),
+            Err(_) => true,
+        })))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        let meta = self.store.metadata_path(path)?;
+        self.check(path, meta.kind)?;
+        Ok(meta)
+    }
+
+    fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+        if !self.is_visible(path, EntryKind::File) {
+            return Err(io::Error::from(io::ErrorKind::PermissionDenied));
+        }
+        self.store.create_path(path)
+    }
+
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        self.check(path, EntryKind::File)?;
+        self.store.remove_path(path)
+    }
+
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.check(from, EntryKind::File)?;
+        if !self.is_visible(to, EntryKind::File) {
+            return Err(io::Error::from(io::ErrorKind::PermissionDenied));
+        }
+        self.store.rename_path(from, to)
+    }
+
+    #[inline]
+    fn is_read_only(&self) -> bool {
+        self.store.is_read_only()
+    }
+}
+
+/// Store exposing only the files of another store accepted by a closure.
+///
+/// The closure is called with the path of every file that is opened, listed
+/// or written, relative to the root of the wrapped store, so it can decide
+/// based on state that changes at runtime:
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{FilterFn, Local, MiniFs};
+/// use std::cell::Cell;
+/// use std::rc::Rc;
+///
+/// let owned = Rc::new(Cell::new(false));
+/// let dlc = Rc::clone(&owned);
+/// let content = FilterFn::new(Local::new("./content"), move |path| {
+///     !path.starts_with("dlc") || dlc.get()
+/// });
+///
+/// let fs = MiniFs::new().mount("/content", content);
+/// assert!(fs.open("/content/dlc/map.bsp").is_err());
+/// owned.set(true);
+/// assert!(fs.open("/content/dlc/map.bsp").is_ok());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Remarks
+///
+/// Rejected files behave like the ones hidden by [`Filtered`], but the
+/// closure is only called for files, so directories are always listed.
+///
+/// [`Filtered`]: ./struct.Filtered.html
+pub struct FilterFn<S, F> {
+    store: S,
+    filter: F,
+}
+
+impl<S, F> FilterFn<S, F> {
+    pub fn new(store: S, filter: F) -> Self
+    where
+        F: Fn(&Path) -> bool,
+    {
+        Self { store, filter }
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.store
+    }
+
+    pub fn into_inner(self) -> S {
+        self.store
+    }
+}
+
+impl<S, F: Fn(&Path) -> bool> FilterFn<S, F> {
+    fn is_visible(&self, path: &Path) -> bool {
+        (self.filter)(&relative_path(path))
+    }
+
+    fn check(&self, path: &Path) -> io::Result<()> {
+        if self.is_visible(path) {
+            Ok(())
+        } else {
+            Err(io::Error::from(io::ErrorKind::NotFound))
+        }
+    }
+}
+
+impl<S, F> Store for FilterFn<S, F>
+where
+    S: Store,
+    F: Fn(&Path) -> bool,
+{
+    type File = S::File;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        self.check(path)?;
+        self.store.open_path(path)
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        let entries = self.store.entries_path(path)?;
+        let dir = path.to_path_buf();
+        Ok(Entries::new(entries.filter(move |entry| match entry {
+            Ok(entry) if entry.kind == EntryKind::File => {
+                self.is_visible(&entry_path(&dir, &entry.name))
+            }
+            _ => true,
+        })))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        let meta = self.store.metadata_path(path)?;
+        if meta.kind == EntryKind::File {
+            self.check(path)?;
+        }
+        Ok(meta)
+    }
+
+    fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+        if !self.is_visible(path) {
+            return Err(io::Error::from(io::ErrorKind::PermissionDenied));
+        }
+        self.store.create_path(path)
+    }
+
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        self.check(path)?;
+        self.store.remove_path(path)
+    }
+
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.check(from)?;
+        if !self.is_visible(to) {
+            return Err(io::Error::from(io::ErrorKind::PermissionDenied));
+        }
+        self.store.rename_path(from, to)
+    }
+
+    #[inline]
+    fn is_read_only(&self) -> bool {
+        self.store.is_read_only()
+    }
+}
+
+// Some stores return names relative to their root rather than to the listed
+// directory, so only the last component is used.
+fn entry_path(dir: &Path, name: &OsStr) -> PathBuf {
+    let name = Path::new(name);
+    dir.join(name.file_name().unwrap_or(name.as_os_str()))
+}
+
+#[cfg(feature = "regex")]
+fn regex(pattern: &str) -> io::Result<Regex> {
+    Regex::new(pattern).map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))
+}
+
+// Path matched by the patterns, relative and with forward slashes on every
+// platform.
+#[cfg(feature = "regex")]
+fn key(path: &Path) -> String {
+    let mut key = String::new();
+    for comp in relative_path(path).components() {
+        if let Component::Normal(name) = comp {
+            if !key.is_empty() {
+                key.push('/');
+            }
+            key.push_str(&name.to_string_lossy());
+        }
+    }
+    key
+}
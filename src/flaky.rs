@@ -0,0 +1,151 @@
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::index::relative_path;
+use crate::store::Store;
+use crate::{Entries, Metadata};
+
+/// Store failing opens of another store on a schedule, for tests.
+///
+/// Failures are added with rules, which are checked in the order they were
+/// added, so retry and fallback code can be tested without a real faulty
+/// store:
+///
+/// ```
+/// use mini_fs::prelude::*;
+/// use mini_fs::{Flaky, MiniFs, Ram};
+/// use std::io::ErrorKind;
+///
+/// let mut ram = Ram::new();
+/// ram.touch("a.txt", &b"a"[..]);
+/// ram.touch("b.txt", &b"b"[..]);
+/// let flaky = Flaky::new(ram)
+///     .fail_path("b.txt", ErrorKind::PermissionDenied)
+///     .fail_every(3, ErrorKind::TimedOut);
+/// let fs = MiniFs::new().mount("/data", flaky);
+///
+/// assert!(fs.open("/data/a.txt").is_ok());
+/// assert!(fs.open("/data/a.txt").is_ok());
+/// let err = fs.open("/data/a.txt").err().unwrap();
+/// assert_eq!(ErrorKind::TimedOut, err.kind());
+///
+/// let err = fs.open("/data/b.txt").err().unwrap();
+/// assert_eq!(ErrorKind::PermissionDenied, err.kind());
+/// ```
+///
+/// # Remarks
+///
+/// Only opens fail. Every open is counted, including the ones that fail, and
+/// the count starts at one. Listing, metadata and writes are forwarded as they
+/// are.
+pub struct Flaky<S> {
+    store: S,
+    rules: Vec<Rule>,
+    calls: AtomicU64,
+}
+
+enum Rule {
+    Every(u64, ErrorKind),
+    First(u64, ErrorKind),
+    Path(PathBuf, ErrorKind),
+}
+
+impl<S> Flaky<S> {
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            rules: Vec::new(),
+            calls: AtomicU64::new(0),
+        }
+    }
+
+    /// Fail every `n`th open with an error of kind `kind`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    pub fn fail_every(mut self, n: u64, kind: ErrorKind) -> Self {
+        assert!(n > 0, "can't fail every 0th open");
+        self.rules.push(Rule::Every(n, kind));
+        self
+    }
+
+    /// Fail the first `n` opens with an error of kind `kind`.
+    pub fn fail_first(mut self, n: u64, kind: ErrorKind) -> Self {
+        self.rules.push(Rule::First(n, kind));
+        self
+    }
+
+    /// Fail every open of `path` with an error of kind `kind`.
+    pub fn fail_path<P: AsRef<Path>>(mut self, path: P, kind: ErrorKind) -> Self {
+        self.rules
+            .push(Rule::Path(relative_path(path.as_ref()), kind));
+        self
+    }
+
+    /// Number of opens so far.
+    pub fn calls(&self) -> u64 {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.store
+    }
+
+    pub fn into_inner(self) -> S {
+        self.store
+    }
+
+    fn failure(&self, path: &Path) -> Option<ErrorKind> {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        let path = relative_path(path);
+        self.rules.iter().find_map(|rule| match *rule {
+            Rule::Every(n, kind) if call.is_multiple_of(n) => Some(kind),
+            Rule::First(n, kind) if call <= n => Some(kind),
+            Rule::Path(ref p, kind) if *p == path => Some(kind),
+            _ => None,
+        })
+    }
+}
+
+impl<S: Store> Store for Flaky<S> {
+    type File = S::File;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        match self.failure(path) {
+            Some(kind) => Err(io::Error::new(kind, "Injected failure.")),
+            None => self.store.open_path(path),
+        }
+    }
+
+    #[inline]
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        self.store.entries_path(path)
+    }
+
+    #[inline]
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        self.store.metadata_path(path)
+    }
+
+    #[inline]
+    fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+        self.store.create_path(path)
+    }
+
+    #[inline]
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        self.store.remove_path(path)
+    }
+
+    #[inline]
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.store.rename_path(from, to)
+    }
+
+    #[inline]
+    fn is_read_only(&self) -> bool {
+        self.store.is_read_only()
+    }
+}
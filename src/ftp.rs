@@ -0,0 +1,407 @@
+use std::cell::RefCell;
+use std::io::{self, BufRead, BufReader, Cursor, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::ops::Range;
+use std::path::{Component, Path};
+use std::time::SystemTime;
+
+use crate::index::normalize_path;
+use crate::store::{Entries, Entry, Store};
+use crate::time;
+use crate::{EntryKind, Metadata};
+
+/// Read-only FTP store.
+///
+/// Files are downloaded with RETR commands and directories are listed with
+/// LIST commands, in passive mode:
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{Ftp, MiniFs};
+///
+/// let ftp = Ftp::new("ftp.example.com:21")
+///     .login("user", "password")
+///     .root("/pub/assets");
+/// let fs = MiniFs::new().mount("/assets", ftp);
+///
+/// let file = fs.open("/assets/textures/wall.png")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Remarks
+///
+/// The control connection is opened on first use and kept open for the
+/// lifetime of the Ftp. It's opened again if it's lost.
+///
+/// Only Unix and MS-DOS style listings are understood.
+pub struct Ftp {
+    addr: String,
+    user: String,
+    password: String,
+    root: String,
+    conn: RefCell<Option<Control>>,
+}
+
+/// File downloaded from an Ftp store.
+pub struct FtpEntry {
+    inner: Cursor<Box<[u8]>>,
+}
+
+impl FtpEntry {
+    pub(crate) fn len(&self) -> u64 {
+        self.inner.get_ref().len() as u64
+    }
+}
+
+impl Read for FtpEntry {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl BufRead for FtpEntry {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+impl Seek for FtpEntry {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl Ftp {
+    /// Create a store for the server at `addr` (`host:port`), logging in
+    /// anonymously.
+    pub fn new<A: Into<String>>(addr: A) -> Self {
+        Self {
+            addr: addr.into(),
+            user: "anonymous".to_string(),
+            password: "anonymous@".to_string(),
+            root: String::new(),
+            conn: RefCell::new(None),
+        }
+    }
+
+    /// Log in with a user and a password.
+    pub fn login<U, P>(mut self, user: U, password: P) -> Self
+    where
+        U: Into<String>,
+        P: Into<String>,
+    {
+        self.user = user.into();
+        self.password = password.into();
+        self
+    }
+
+    /// Serve the files under a directory of the server.
+    pub fn root<R: Into<String>>(mut self, root: R) -> Self {
+        self.root = root.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    // Absolute path of a file on the server.
+    fn remote(&self, path: &Path) -> io::Result<String> {
+        let mut remote = self.root.clone();
+        for comp in normalize_path(path).components() {
+            match comp {
+                Component::Normal(name) => {
+                    let name = name.to_str().ok_or_else(|| {
+                        io::Error::new(ErrorKind::InvalidInput, "Utf8 path conversion error.")
+                    })?;
+                    if name.contains(['\r', '\n']) {
+                        return Err(io::Error::from(ErrorKind::NotFound));
+                    }
+                    remote.push('/');
+                    remote.push_str(name);
+                }
+                Component::RootDir | Component::CurDir => {}
+                _ => return Err(io::Error::from(ErrorKind::NotFound)),
+            }
+        }
+        if remote.is_empty() {
+            remote.push('/');
+        }
+        Ok(remote)
+    }
+
+    // Runs `f` on the control connection, which is opened if needed and
+    // dropped on IO errors so the next command reconnects.
+    fn with_conn<T, F>(&self, f: F) -> io::Result<T>
+    where
+        F: FnOnce(&mut Control) -> io::Result<T>,
+    {
+        let mut conn = self.conn.borrow_mut();
+        let control = match &mut *conn {
+            Some(control) => control,
+            None => conn.insert(Control::connect(&self.addr, &self.user, &self.password)?),
+        };
+        let result = f(control);
+        if let Err(err) = &result {
+            if !err.get_ref().is_some_and(|inner| inner.is::<Reply>()) {
+                *conn = None;
+            }
+        }
+        result
+    }
+}
+
+impl Store for Ftp {
+    type File = FtpEntry;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        let remote = self.remote(path)?;
+        let data = self.with_conn(|control| control.transfer("RETR", &remote))?;
+        Ok(FtpEntry {
+            inner: Cursor::new(data.into()),
+        })
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        let remote = self.remote(path)?;
+        let listing = self.with_conn(|control| {
+            // LIST of a missing path may succeed with an empty listing
+            control.command(&format!("CWD {}", remote), 250)?;
+            control.transfer("LIST", &remote)
+        })?;
+        let entries: Vec<_> = String::from_utf8_lossy(&listing)
+            .lines()
+            .filter_map(parse_list_line)
+            .filter(|entry| entry.name != "." && entry.name != "..")
+            .map(Ok)
+            .collect();
+        Ok(Entries::new(entries))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        let remote = self.remote(path)?;
+        self.with_conn(
+            |control| match control.command(&format!("SIZE {}", remote), 213) {
+                Ok(size) => {
+                    let modified = control
+                        .command(&format!("MDTM {}", remote), 213)
+                        .ok()
+                        .and_then(|time| parse_mdtm(&time));
+                    Ok(Metadata {
+                        kind: EntryKind::File,
+                        len: size
+                            .trim()
+                            .parse()
+                            .map_err(|_| invalid("Invalid SIZE reply."))?,
+                        modified,
+                    })
+                }
+                Err(err) if err.kind() == ErrorKind::NotFound => {
+                    control.command(&format!("CWD {}", remote), 250)?;
+                    Ok(Metadata::dir())
+                }
+                Err(err) => Err(err),
+            },
+        )
+    }
+}
+
+// Control connection to the server.
+struct Control {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Control {
+    fn connect(addr: &str, user: &str, password: &str) -> io::Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        let mut control = Control {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+        };
+        control.reply(220)?;
+        control.send(&format!("USER {}", user))?;
+        match control.read_reply()? {
+            // logged in without a password
+            (230, _) => {}
+            (331, _) => {
+                control.command(&format!("PASS {}", password), 230)?;
+            }
+            (code, text) => return Err(Reply { code, text }.into()),
+        }
+        control.command("TYPE I", 200)?;
+        Ok(control)
+    }
+
+    fn send(&mut self, command: &str) -> io::Result<()> {
+        self.writer
+            .write_all(format!("{}\r\n", command).as_bytes())?;
+        self.writer.flush()
+    }
+
+    // Reads the code and the text of a reply.
+    fn read_reply(&mut self) -> io::Result<(u32, String)> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(io::Error::from(ErrorKind::UnexpectedEof));
+        }
+        let code = line
+            .get(..3)
+            .and_then(|code| code.parse().ok())
+            .ok_or_else(|| invalid("Invalid FTP reply."))?;
+        // multi-line replies end with a line starting with the code and a space
+        if line.as_bytes().get(3) == Some(&b'-') {
+            let end = format!("{} ", code);
+            loop {
+                let mut next = String::new();
+                if self.reader.read_line(&mut next)? == 0 {
+                    return Err(io::Error::from(ErrorKind::UnexpectedEof));
+                }
+                if next.starts_with(&end) {
+                    break;
+                }
+            }
+        }
+        let text = line.get(4..).unwrap_or_default().trim_end().to_string();
+        Ok((code, text))
+    }
+
+    // Reads a reply, failing if its code isn't `expected`. Single digits only
+    // check the class of the code.
+    fn reply(&mut self, expected: u32) -> io::Result<String> {
+        let (code, text) = self.read_reply()?;
+        if code == expected || (expected < 10 && code / 100 == expected) {
+            Ok(text)
+        } else {
+            Err(Reply { code, text }.into())
+        }
+    }
+
+    fn command(&mut self, command: &str, expected: u32) -> io::Result<String> {
+        self.send(command)?;
+        self.reply(expected)
+    }
+
+    // Runs a command over a passive data connection and returns the data.
+    fn transfer(&mut self, command: &str, path: &str) -> io::Result<Vec<u8>> {
+        let addr = self.passive()?;
+        let mut data = TcpStream::connect(addr)?;
+        self.command(&format!("{} {}", command, path), 1)?;
+        let mut buf = Vec::new();
+        data.read_to_end(&mut buf)?;
+        drop(data);
+        self.reply(2)?;
+        Ok(buf)
+    }
+
+    // Address of the data connection from a PASV reply, like
+    // `227 Entering Passive Mode (127,0,0,1,195,80)`.
+    //
+    // The host of the reply is ignored in favor of the one of the control
+    // connection, since servers behind NAT often reply with a private address.
+    fn passive(&mut self) -> io::Result<SocketAddr> {
+        let text = self.command("PASV", 227)?;
+        let start = text
+            .find('(')
+            .ok_or_else(|| invalid("Invalid PASV reply."))?;
+        let end = text
+            .rfind(')')
+            .ok_or_else(|| invalid("Invalid PASV reply."))?;
+        let numbers: Vec<u8> = text
+            .get(start + 1..end)
+            .ok_or_else(|| invalid("Invalid PASV reply."))?
+            .split(',')
+            .map(|n| n.trim().parse())
+            .collect::<Result<_, _>>()
+            .map_err(|_| invalid("Invalid PASV reply."))?;
+        if numbers.len() != 6 {
+            return Err(invalid("Invalid PASV reply."));
+        }
+        let mut addr = self.writer.peer_addr()?;
+        addr.set_port(u16::from(numbers[4]) << 8 | u16::from(numbers[5]));
+        Ok(addr)
+    }
+}
+
+// Unexpected reply from the server.
+#[derive(Debug)]
+struct Reply {
+    code: u32,
+    text: String,
+}
+
+impl std::fmt::Display for Reply {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} {}", self.code, self.text)
+    }
+}
+
+impl std::error::Error for Reply {}
+
+impl From<Reply> for io::Error {
+    fn from(reply: Reply) -> Self {
+        let kind = match reply.code {
+            450 | 550 => ErrorKind::NotFound,
+            530 => ErrorKind::PermissionDenied,
+            _ => ErrorKind::Other,
+        };
+        io::Error::new(kind, reply)
+    }
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg)
+}
+
+// Parses a line of a Unix (`drwxr-xr-x 2 user group 4096 Jan 01 12:00 name`)
+// or MS-DOS (`01-01-20  12:00PM  <DIR>  name`) listing.
+fn parse_list_line(line: &str) -> Option<Entry> {
+    let (kind, fields) = match line.chars().next()? {
+        'd' => (EntryKind::Dir, 8),
+        '-' | 'l' => (EntryKind::File, 8),
+        '0'..='9' => {
+            let is_dir = line.split_whitespace().nth(2)? == "<DIR>";
+            (
+                if is_dir {
+                    EntryKind::Dir
+                } else {
+                    EntryKind::File
+                },
+                3,
+            )
+        }
+        _ => return None,
+    };
+    // the name is what follows the other fields, and may contain spaces
+    let mut rest = line;
+    for _ in 0..fields {
+        rest = rest.trim_start();
+        rest = &rest[rest.find(char::is_whitespace)?..];
+    }
+    let mut name = rest.trim_start();
+    if line.starts_with('l') {
+        name = name.split(" -> ").next()?;
+    }
+    if name.is_empty() {
+        return None;
+    }
+    Some(Entry {
+        name: name.into(),
+        kind,
+    })
+}
+
+// Parses the `YYYYMMDDHHMMSS` time of an MDTM reply.
+fn parse_mdtm(reply: &str) -> Option<SystemTime> {
+    let reply = reply.trim();
+    let field = |range: Range<usize>| reply.get(range)?.parse::<i64>().ok();
+    let (year, month, day) = (field(0..4)?, field(4..6)?, field(6..8)?);
+    let (hour, min, sec) = (field(8..10)?, field(10..12)?, field(12..14)?);
+    time::from_civil(year, month, day, hour * 3600 + min * 60 + sec)
+}
@@ -0,0 +1,211 @@
+use std::error::Error;
+use std::io::{self, BufRead, Cursor, ErrorKind, Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use gix::{ObjectId, Repository};
+
+use crate::index::normalize_path;
+use crate::store::Store;
+use crate::{Entries, Entry, EntryKind, Metadata};
+
+/// Files from a revision of a Git repository, without a checkout.
+///
+/// The revision is anything `git rev-parse` understands, like a branch, a tag
+/// or a commit hash:
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{GitStore, Local, MiniFs};
+///
+/// let release = GitStore::open("config", "v1.2.0")?;
+/// let fs = MiniFs::new()
+///     .mount("/config", (Local::new("./config"), release));
+///
+/// let file = fs.open("/config/server.toml")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Remarks
+///
+/// The revision is resolved once, when the store is created, so commits made
+/// to a branch afterwards aren't visible through the store.
+///
+/// Git doesn't record modification times, so all files report the time of the
+/// commit instead. Submodules and symbolic links aren't listed.
+pub struct GitStore {
+    repo: Repository,
+    tree: ObjectId,
+    time: Option<SystemTime>,
+}
+
+/// File from a GitStore.
+pub struct GitEntry {
+    inner: Cursor<Box<[u8]>>,
+}
+
+impl GitEntry {
+    pub(crate) fn len(&self) -> u64 {
+        self.inner.get_ref().len() as u64
+    }
+}
+
+impl Read for GitEntry {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl BufRead for GitEntry {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+impl Seek for GitEntry {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl GitStore {
+    /// Open the repository at `path`, or at any of its parent directories.
+    pub fn open<P: AsRef<Path>>(path: P, rev: &str) -> io::Result<Self> {
+        let repo = gix::discover(path).map_err(|err| match err {
+            gix::discover::Error::Discover(_) => io::Error::new(ErrorKind::NotFound, err),
+            err => git_error(err),
+        })?;
+        Self::new(repo, rev)
+    }
+
+    pub fn new(repo: Repository, rev: &str) -> io::Result<Self> {
+        let (tree, time) = {
+            let object = repo
+                .rev_parse_single(rev)
+                .map_err(|err| io::Error::new(ErrorKind::NotFound, err))?
+                .object()
+                .map_err(git_error)?;
+            let time = match object.clone().peel_to_commit() {
+                Ok(commit) => {
+                    let secs = commit.time().map_err(git_error)?.seconds.max(0) as u64;
+                    Some(UNIX_EPOCH + Duration::from_secs(secs))
+                }
+                Err(_) => None,
+            };
+            (object.peel_to_tree().map_err(git_error)?.id, time)
+        };
+        Ok(Self { repo, tree, time })
+    }
+
+    // Id and kind of the object at `path`.
+    fn object(&self, path: &Path) -> io::Result<(ObjectId, Kind)> {
+        let path = tree_path(path);
+        if path.as_os_str().is_empty() {
+            return Ok((self.tree, Kind::Tree));
+        }
+        let tree = self.repo.find_tree(self.tree).map_err(git_error)?;
+        let entry = tree
+            .lookup_entry_by_path(&path)
+            .map_err(git_error)?
+            .ok_or_else(|| io::Error::from(ErrorKind::NotFound))?;
+        Ok((entry.object_id(), Kind::from(entry.mode())))
+    }
+}
+
+impl Store for GitStore {
+    type File = GitEntry;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        match self.object(path)? {
+            (id, Kind::Blob) => {
+                let blob = self.repo.find_blob(id).map_err(git_error)?;
+                Ok(GitEntry {
+                    inner: Cursor::new(blob.detach().data.into()),
+                })
+            }
+            _ => Err(io::Error::from(ErrorKind::NotFound)),
+        }
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        let tree = match self.object(path)? {
+            (id, Kind::Tree) => self.repo.find_tree(id).map_err(git_error)?,
+            _ => return Err(io::Error::from(ErrorKind::NotFound)),
+        };
+        let mut entries = Vec::new();
+        for entry in tree.iter() {
+            let entry = entry.map_err(git_error)?;
+            let kind = match Kind::from(entry.mode()) {
+                Kind::Blob => EntryKind::File,
+                Kind::Tree => EntryKind::Dir,
+                Kind::Other => continue,
+            };
+            entries.push(Ok(Entry {
+                name: entry.filename().to_string().into(),
+                kind,
+            }));
+        }
+        Ok(Entries::new(entries))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        let (kind, len) = match self.object(path)? {
+            (id, Kind::Blob) => {
+                let header = self.repo.find_header(id).map_err(git_error)?;
+                (EntryKind::File, header.size())
+            }
+            (_, Kind::Tree) => (EntryKind::Dir, 0),
+            _ => return Err(io::Error::from(ErrorKind::NotFound)),
+        };
+        Ok(Metadata {
+            kind,
+            len,
+            modified: self.time,
+        })
+    }
+}
+
+// Kind of a tree entry. Submodules and symbolic links are `Other`.
+#[derive(PartialEq)]
+enum Kind {
+    Blob,
+    Tree,
+    Other,
+}
+
+impl From<gix::object::tree::EntryMode> for Kind {
+    fn from(mode: gix::object::tree::EntryMode) -> Self {
+        if mode.is_tree() {
+            Kind::Tree
+        } else if mode.is_blob() {
+            Kind::Blob
+        } else {
+            Kind::Other
+        }
+    }
+}
+
+// Path relative to the root tree.
+fn tree_path(path: &Path) -> PathBuf {
+    normalize_path(path)
+        .components()
+        .filter_map(|comp| match comp {
+            Component::Normal(name) => Some(name),
+            _ => None,
+        })
+        .collect()
+}
+
+fn git_error<E: Error + Send + Sync + 'static>(err: E) -> io::Error {
+    io::Error::other(err)
+}
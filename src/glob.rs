@@ -0,0 +1,192 @@
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use crate::index::{forward_slashes, normalize_path};
+use crate::store::{Store, StoreExt, Walk};
+
+/// Iterator over the file paths of a Store that match a glob pattern.
+///
+/// Returned by [`StoreExt::glob`].
+///
+/// [`StoreExt::glob`]: ./trait.StoreExt.html#method.glob
+pub struct Glob<'a, S> {
+    walk: Walk<'a, S>,
+    segments: Vec<Segment>,
+}
+
+enum Segment {
+    // `**`, any number of directories
+    Dirs,
+    Name(Vec<Token>),
+}
+
+enum Token {
+    Char(char),
+    // `?`
+    Any,
+    // `*`
+    Star,
+    // `[a-z]` or `[!a-z]`
+    Class {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+}
+
+impl<'a, S: Store> Glob<'a, S> {
+    pub(crate) fn new(store: &'a S, pattern: &str) -> io::Result<Self> {
+        let pattern = forward_slashes(Path::new(pattern));
+        let pattern = normalize_path(&pattern);
+        let mut root = PathBuf::new();
+        let mut segments = Vec::new();
+        for comp in pattern.components() {
+            match comp {
+                Component::Normal(name) => {
+                    let name = name
+                        .to_str()
+                        .ok_or_else(|| invalid("Non UTF-8 glob pattern."))?;
+                    segments.push(segment(name)?);
+                }
+                comp if segments.is_empty() => root.push(comp),
+                _ => {}
+            }
+        }
+        // The walk starts at the deepest directory without wildcards.
+        let literal = segments.iter().take(segments.len().saturating_sub(1));
+        for segment in literal {
+            match segment.literal() {
+                Some(name) => root.push(name),
+                None => break,
+            }
+        }
+        Ok(Self {
+            walk: store.walk(root),
+            segments,
+        })
+    }
+}
+
+impl<S: Store> Iterator for Glob<'_, S> {
+    type Item = io::Result<PathBuf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.walk.next()? {
+                Ok(path) => {
+                    let names: Vec<_> = path
+                        .components()
+                        .filter_map(|comp| match comp {
+                            Component::Normal(name) => Some(name.to_string_lossy()),
+                            _ => None,
+                        })
+                        .collect();
+                    let names: Vec<&str> = names.iter().map(AsRef::as_ref).collect();
+                    if matches(&self.segments, &names) {
+                        return Some(Ok(path));
+                    }
+                }
+                // Missing directories have no matches.
+                Err(ref err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
+impl Segment {
+    fn literal(&self) -> Option<String> {
+        match self {
+            Segment::Dirs => None,
+            Segment::Name(tokens) => tokens
+                .iter()
+                .map(|token| match token {
+                    Token::Char(ch) => Some(ch),
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Token {
+    fn matches(&self, ch: char) -> bool {
+        match self {
+            Token::Char(other) => ch == *other,
+            Token::Any => true,
+            Token::Star => unreachable!(),
+            Token::Class { negated, ranges } => {
+                ranges.iter().any(|&(lo, hi)| lo <= ch && ch <= hi) != *negated
+            }
+        }
+    }
+}
+
+fn segment(name: &str) -> io::Result<Segment> {
+    if name == "**" {
+        return Ok(Segment::Dirs);
+    }
+    let mut tokens = Vec::new();
+    let mut chars = name.chars();
+    while let Some(ch) = chars.next() {
+        tokens.push(match ch {
+            '?' => Token::Any,
+            '*' => Token::Star,
+            '[' => {
+                let mut class: Vec<char> = Vec::new();
+                // `]` right after the opening bracket is part of the class.
+                loop {
+                    match chars.next() {
+                        Some(']') if class.iter().any(|&ch| ch != '!') => break,
+                        Some(ch) => class.push(ch),
+                        None => return Err(invalid("Unclosed character class in glob pattern.")),
+                    }
+                }
+                let negated = class[0] == '!';
+                let class = &class[negated as usize..];
+                let mut ranges = Vec::new();
+                let mut i = 0;
+                while i < class.len() {
+                    if i + 2 < class.len() && class[i + 1] == '-' {
+                        ranges.push((class[i], class[i + 2]));
+                        i += 3;
+                    } else {
+                        ranges.push((class[i], class[i]));
+                        i += 1;
+                    }
+                }
+                Token::Class { negated, ranges }
+            }
+            ch => Token::Char(ch),
+        });
+    }
+    Ok(Segment::Name(tokens))
+}
+
+fn matches(segments: &[Segment], names: &[&str]) -> bool {
+    match segments.split_first() {
+        None => names.is_empty(),
+        Some((Segment::Dirs, rest)) => (0..=names.len()).any(|i| matches(rest, &names[i..])),
+        Some((Segment::Name(tokens), rest)) => match names.split_first() {
+            Some((name, names)) => {
+                let name: Vec<char> = name.chars().collect();
+                matches_name(tokens, &name) && matches(rest, names)
+            }
+            None => false,
+        },
+    }
+}
+
+fn matches_name(tokens: &[Token], name: &[char]) -> bool {
+    match tokens.split_first() {
+        None => name.is_empty(),
+        Some((Token::Star, rest)) => (0..=name.len()).any(|i| matches_name(rest, &name[i..])),
+        Some((token, rest)) => match name.split_first() {
+            Some((&ch, name)) => token.matches(ch) && matches_name(rest, name),
+            None => false,
+        },
+    }
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, msg)
+}
@@ -0,0 +1,399 @@
+use std::cell::RefCell;
+use std::convert::TryInto;
+use std::fs;
+use std::io::{self, BufRead, Cursor, ErrorKind, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use encoding_rs::EUC_KR;
+use flate2::read::ZlibDecoder;
+
+use crate::bytes::le32;
+use crate::index::Index;
+use crate::store::Store;
+use crate::{Entries, Entry, EntryKind, Metadata};
+
+// Size of the header. File positions are relative to its end.
+const HEADER: u64 = 46;
+
+// Entry flags.
+const FILE: u8 = 0x01;
+const ENCRYPT_MIXED: u8 = 0x02;
+const ENCRYPT_HEADER: u8 = 0x04;
+
+/// Ragnarok Online GRF archive store.
+///
+/// Reads version 0x200 archives. The file table is indexed when the store is
+/// created, and files are decrypted and decompressed when they are opened.
+/// Names are decoded from CP949, and backslashes are read as separators.
+///
+/// Patch folders can be layered over an archive with a tuple, e.g.
+/// `(Local::new("data"), grf)`.
+///
+/// # Remarks
+///
+/// Older versions (0x102 and 0x103) are not supported.
+///
+/// When used with a `std::fs::File`, the file will remain open for the lifetime
+/// of the Grf.
+pub struct Grf<T: Read + Seek> {
+    inner: RefCell<T>,
+    index: Index<GrfIndexEntry>,
+}
+
+struct GrfIndexEntry {
+    // position of the file data within the archive
+    pos: u64,
+    packed: u32,
+    packed_aligned: u32,
+    flags: u8,
+    meta: Metadata,
+}
+
+/// Entry in the GRF archive.
+pub struct GrfEntry {
+    inner: Cursor<Box<[u8]>>,
+}
+
+impl GrfEntry {
+    pub(crate) fn len(&self) -> u64 {
+        self.inner.get_ref().len() as u64
+    }
+}
+
+impl Read for GrfEntry {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl BufRead for GrfEntry {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+impl Seek for GrfEntry {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl Grf<fs::File> {
+    /// Open a file from the native filesystem.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = fs::OpenOptions::new()
+            .read(true)
+            .write(false)
+            .create(false)
+            .open(path)?;
+        Self::new(file)
+    }
+}
+
+impl<T: Read + Seek> Grf<T> {
+    /// Read the file table of the archive and index its contents.
+    pub fn new(mut inner: T) -> io::Result<Self> {
+        let mut header = [0; HEADER as usize];
+        inner.seek(SeekFrom::Start(0))?;
+        if inner.read_exact(&mut header).is_err() || &header[..15] != b"Master of Magic" {
+            return Err(invalid("Not a GRF archive."));
+        }
+        if le32(&header[42..])? != 0x200 {
+            return Err(io::Error::new(
+                ErrorKind::Unsupported,
+                "Only version 0x200 GRF archives are supported.",
+            ));
+        }
+        let table_pos = HEADER + u64::from(le32(&header[30..])?);
+        let count = le32(&header[38..])?
+            .wrapping_sub(le32(&header[34..])?)
+            .wrapping_sub(7);
+
+        // The file table is compressed.
+        let mut sizes = [0; 8];
+        inner.seek(SeekFrom::Start(table_pos))?;
+        inner.read_exact(&mut sizes)?;
+        let mut packed = Vec::new();
+        (&mut inner)
+            .take(u64::from(le32(&sizes[..4])?))
+            .read_to_end(&mut packed)?;
+        let len = u64::from(le32(&sizes[4..])?);
+        let mut table = Vec::new();
+        ZlibDecoder::new(&packed[..])
+            .take(len + 1)
+            .read_to_end(&mut table)?;
+        if table.len() as u64 != len {
+            return Err(invalid("Invalid GRF file table."));
+        }
+
+        let mut index = Index::new();
+        let mut table = &table[..];
+        for _ in 0..count {
+            let name_len = table
+                .iter()
+                .position(|b| *b == 0)
+                .ok_or_else(|| invalid("Invalid GRF file table."))?;
+            let (name, rest) = table.split_at(name_len);
+            let entry = rest
+                .get(1..18)
+                .ok_or_else(|| invalid("Invalid GRF file table."))?;
+            table = &rest[18..];
+
+            let flags = entry[12];
+            let (name, _, _) = EUC_KR.decode(name);
+            let path: PathBuf = name.split('\\').collect();
+            if flags & FILE == 0 {
+                index.insert_dir(path);
+                continue;
+            }
+            let meta = Metadata {
+                kind: EntryKind::File,
+                len: u64::from(le32(&entry[8..])?),
+                modified: None,
+            };
+            let entry = GrfIndexEntry {
+                pos: HEADER + u64::from(le32(&entry[13..])?),
+                packed: le32(&entry[..4])?,
+                packed_aligned: le32(&entry[4..])?,
+                flags,
+                meta,
+            };
+            index.insert(path, entry);
+        }
+
+        Ok(Self {
+            inner: RefCell::new(inner),
+            index,
+        })
+    }
+}
+
+impl<T: Read + Seek> Store for Grf<T> {
+    type File = GrfEntry;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        let entry = match self.index.get(path) {
+            Some(entry) => entry,
+            None => return Err(io::Error::from(ErrorKind::NotFound)),
+        };
+        let mut packed = Vec::new();
+        {
+            let mut file = self.inner.borrow_mut();
+            file.seek(SeekFrom::Start(entry.pos))?;
+            (&mut *file)
+                .take(u64::from(entry.packed_aligned))
+                .read_to_end(&mut packed)?;
+        }
+        if entry.flags & ENCRYPT_MIXED != 0 {
+            decode_mixed(&mut packed, entry.packed);
+        } else if entry.flags & ENCRYPT_HEADER != 0 {
+            decode_header(&mut packed);
+        }
+
+        let mut data = Vec::new();
+        let packed = &packed[..packed.len().min(entry.packed as usize)];
+        ZlibDecoder::new(packed)
+            .take(entry.meta.len.saturating_add(1))
+            .read_to_end(&mut data)?;
+        if data.len() as u64 != entry.meta.len {
+            return Err(invalid("Corrupted GRF file."));
+        }
+        Ok(GrfEntry {
+            inner: Cursor::new(data.into()),
+        })
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        Ok(Entries::new(self.index.entries(path).map(|ent| {
+            let name = ent.name.to_os_string();
+            let kind = ent.kind;
+            Ok(Entry { name, kind })
+        })))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        if let Some(entry) = self.index.get(path) {
+            Ok(entry.meta.clone())
+        } else if self.index.contains_dir(path) {
+            Ok(Metadata::dir())
+        } else {
+            Err(io::Error::from(ErrorKind::NotFound))
+        }
+    }
+}
+
+// Only the first 20 blocks are encrypted.
+fn decode_header(data: &mut [u8]) {
+    for block in data.chunks_exact_mut(8).take(20) {
+        des::decrypt_block(block.try_into().unwrap());
+    }
+}
+
+// After the first 20 blocks, one of every `cycle` blocks is encrypted, and one
+// of every 7 other blocks is shuffled. The cycle depends on the number of
+// digits of the packed length.
+fn decode_mixed(data: &mut [u8], packed: u32) {
+    let digits = packed.max(1).to_string().len();
+    let cycle = match digits {
+        0..=2 => 1,
+        3..=4 => digits + 1,
+        5..=6 => digits + 9,
+        _ => digits + 15,
+    };
+
+    let mut plain = 0;
+    for (i, block) in data.chunks_exact_mut(8).enumerate() {
+        let block: &mut [u8; 8] = block.try_into().unwrap();
+        if i < 20 || i % cycle == 0 {
+            des::decrypt_block(block);
+        } else {
+            if plain % 7 == 0 && plain != 0 {
+                unshuffle(block);
+            }
+            plain += 1;
+        }
+    }
+}
+
+fn unshuffle(block: &mut [u8; 8]) {
+    let b = *block;
+    *block = [b[3], b[4], b[6], b[0], b[1], b[2], b[5], substitute(b[7])];
+}
+
+fn substitute(byte: u8) -> u8 {
+    match byte {
+        0x00 => 0x2b,
+        0x2b => 0x00,
+        0x6c => 0x80,
+        0x80 => 0x6c,
+        0x01 => 0x68,
+        0x68 => 0x01,
+        0x48 => 0x77,
+        0x77 => 0x48,
+        0x60 => 0xff,
+        0xff => 0x60,
+        0xb9 => 0xc0,
+        0xc0 => 0xb9,
+        0xfe => 0xeb,
+        0xeb => 0xfe,
+        byte => byte,
+    }
+}
+
+// Gravity's variant of DES: a single round, without a key schedule.
+mod des {
+    const IP: [u8; 64] = [
+        58, 50, 42, 34, 26, 18, 10, 2, 60, 52, 44, 36, 28, 20, 12, 4, 62, 54, 46, 38, 30, 22, 14,
+        6, 64, 56, 48, 40, 32, 24, 16, 8, 57, 49, 41, 33, 25, 17, 9, 1, 59, 51, 43, 35, 27, 19, 11,
+        3, 61, 53, 45, 37, 29, 21, 13, 5, 63, 55, 47, 39, 31, 23, 15, 7,
+    ];
+
+    const FP: [u8; 64] = [
+        40, 8, 48, 16, 56, 24, 64, 32, 39, 7, 47, 15, 55, 23, 63, 31, 38, 6, 46, 14, 54, 22, 62,
+        30, 37, 5, 45, 13, 53, 21, 61, 29, 36, 4, 44, 12, 52, 20, 60, 28, 35, 3, 43, 11, 51, 19,
+        59, 27, 34, 2, 42, 10, 50, 18, 58, 26, 33, 1, 41, 9, 49, 17, 57, 25,
+    ];
+
+    const TP: [u8; 32] = [
+        16, 7, 20, 21, 29, 12, 28, 17, 1, 15, 23, 26, 5, 18, 31, 10, 2, 8, 24, 14, 32, 27, 3, 9,
+        19, 13, 30, 6, 22, 11, 4, 25,
+    ];
+
+    // Each S-box handles two 6-bit groups, the high nibble for the first one
+    // and the low nibble for the second one.
+    const SBOX: [[u8; 64]; 4] = [
+        [
+            0xef, 0x03, 0x41, 0xfd, 0xd8, 0x74, 0x1e, 0x47, 0x26, 0xef, 0xfb, 0x22, 0xb3, 0xd8,
+            0x84, 0x1e, 0x39, 0xac, 0xa7, 0x60, 0x62, 0xc1, 0xcd, 0xba, 0x5c, 0x96, 0x90, 0x59,
+            0x05, 0x3b, 0x7a, 0x85, 0x40, 0xfd, 0x1e, 0xc8, 0xe7, 0x8a, 0x8b, 0x21, 0xda, 0x43,
+            0x64, 0x9f, 0x2d, 0x14, 0xb1, 0x72, 0xf5, 0x5b, 0xc8, 0xb6, 0x9c, 0x37, 0x76, 0xec,
+            0x39, 0xa0, 0xa3, 0x05, 0x52, 0x6e, 0x0f, 0xd9,
+        ],
+        [
+            0xa7, 0xdd, 0x0d, 0x78, 0x9e, 0x0b, 0xe3, 0x95, 0x60, 0x36, 0x36, 0x4f, 0xf9, 0x60,
+            0x5a, 0xa3, 0x11, 0x24, 0xd2, 0x87, 0xc8, 0x52, 0x75, 0xec, 0xbb, 0xc1, 0x4c, 0xba,
+            0x24, 0xfe, 0x8f, 0x19, 0xda, 0x13, 0x66, 0xaf, 0x49, 0xd0, 0x90, 0x06, 0x8c, 0x6a,
+            0xfb, 0x91, 0x37, 0x8d, 0x0d, 0x78, 0xbf, 0x49, 0x11, 0xf4, 0x23, 0xe5, 0xce, 0x3b,
+            0x55, 0xbc, 0xa2, 0x57, 0xe8, 0x22, 0x74, 0xce,
+        ],
+        [
+            0x2c, 0xea, 0xc1, 0xbf, 0x4a, 0x24, 0x1f, 0xc2, 0x79, 0x47, 0xa2, 0x7c, 0xb6, 0xd9,
+            0x68, 0x15, 0x80, 0x56, 0x5d, 0x01, 0x33, 0xfd, 0xf4, 0xae, 0xde, 0x30, 0x07, 0x9b,
+            0xe5, 0x83, 0x9b, 0x68, 0x49, 0xb4, 0x2e, 0x83, 0x1f, 0xc2, 0xb5, 0x7c, 0xa2, 0x19,
+            0xd8, 0xe5, 0x7c, 0x2f, 0x83, 0xda, 0xf7, 0x6b, 0x90, 0xfe, 0xc4, 0x01, 0x5a, 0x97,
+            0x61, 0xa6, 0x3d, 0x40, 0x0b, 0x58, 0xe6, 0x3d,
+        ],
+        [
+            0x4d, 0xd1, 0xb2, 0x0f, 0x28, 0xbd, 0xe4, 0x78, 0xf6, 0x4a, 0x0f, 0x93, 0x8b, 0x17,
+            0xd1, 0xa4, 0x3a, 0xec, 0xc9, 0x35, 0x93, 0x56, 0x7e, 0xcb, 0x55, 0x20, 0xa0, 0xfe,
+            0x6c, 0x89, 0x17, 0x62, 0x17, 0x62, 0x4b, 0xb1, 0xb4, 0xde, 0xd1, 0x87, 0xc9, 0x14,
+            0x3c, 0x4a, 0x7e, 0xa8, 0xe2, 0x7d, 0xa0, 0x9f, 0xf6, 0x5c, 0x6a, 0x09, 0x8d, 0xf0,
+            0x0f, 0xe3, 0x53, 0x25, 0x95, 0x36, 0x28, 0xcb,
+        ],
+    ];
+
+    fn bit(data: &[u8], i: usize) -> bool {
+        data[i >> 3] & (0x80 >> (i & 7)) != 0
+    }
+
+    fn permute(block: &[u8; 8], table: &[u8; 64]) -> [u8; 8] {
+        let mut out = [0; 8];
+        for (i, j) in table.iter().enumerate() {
+            if bit(block, usize::from(*j) - 1) {
+                out[i >> 3] |= 0x80 >> (i & 7);
+            }
+        }
+        out
+    }
+
+    // Expands the right half into eight groups of 6 bits.
+    fn expand(r: &[u8]) -> [u8; 8] {
+        [
+            ((r[3] << 5) | (r[0] >> 3)) & 0x3f,
+            ((r[0] << 1) | (r[1] >> 7)) & 0x3f,
+            ((r[0] << 5) | (r[1] >> 3)) & 0x3f,
+            ((r[1] << 1) | (r[2] >> 7)) & 0x3f,
+            ((r[1] << 5) | (r[2] >> 3)) & 0x3f,
+            ((r[2] << 1) | (r[3] >> 7)) & 0x3f,
+            ((r[2] << 5) | (r[3] >> 3)) & 0x3f,
+            ((r[3] << 1) | (r[0] >> 7)) & 0x3f,
+        ]
+    }
+
+    fn round(block: &mut [u8; 8]) {
+        let e = expand(&block[4..]);
+        let mut s = [0; 4];
+        for (i, s) in s.iter_mut().enumerate() {
+            *s = (SBOX[i][usize::from(e[i * 2])] & 0xf0)
+                | (SBOX[i][usize::from(e[i * 2 + 1])] & 0x0f);
+        }
+        let mut p = [0; 4];
+        for (i, j) in TP.iter().enumerate() {
+            if bit(&s, usize::from(*j) - 1) {
+                p[i >> 3] |= 0x80 >> (i & 7);
+            }
+        }
+        for i in 0..4 {
+            block[i] ^= p[i];
+        }
+    }
+
+    // The cipher is its own inverse.
+    pub(super) fn decrypt_block(block: &mut [u8; 8]) {
+        let mut tmp = permute(block, &IP);
+        round(&mut tmp);
+        *block = permute(&tmp, &FP);
+    }
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg)
+}
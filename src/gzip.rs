@@ -0,0 +1,116 @@
+use std::ffi::OsString;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use flate2::read::MultiGzDecoder;
+
+use crate::store::Store;
+use crate::{Entries, File, Metadata};
+
+/// Store that decompresses gzipped versions of missing files.
+///
+/// When a file can't be found, its gzipped version, named like it with a `.gz`
+/// suffix, is opened and decompressed instead, so assets can be compressed
+/// individually without changing the code that opens them:
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{Gzipped, Local};
+///
+/// let levels = Gzipped::new(Local::new("./levels"));
+///
+/// // opens `e1m1.map`, or decompresses `e1m1.map.gz`
+/// let map = levels.open("e1m1.map")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Remarks
+///
+/// Decompressed files are kept in memory. Their metadata is the one of the
+/// gzipped file, so its length is the compressed length. Listing, creating,
+/// removing and renaming use the requested paths as they are.
+pub struct Gzipped<S> {
+    store: S,
+}
+
+impl<S> Gzipped<S> {
+    pub fn new(store: S) -> Self {
+        Self { store }
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.store
+    }
+
+    pub fn into_inner(self) -> S {
+        self.store
+    }
+}
+
+fn gz_path(path: &Path) -> Option<PathBuf> {
+    path.file_name()?;
+    let mut gz = OsString::from(path.as_os_str());
+    gz.push(".gz");
+    Some(gz.into())
+}
+
+impl<S> Store for Gzipped<S>
+where
+    S: Store,
+    S::File: Read + Into<File>,
+{
+    type File = File;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        let err = match self.store.open_path(path) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => err,
+            res => return res.map(Into::into),
+        };
+        let gz = match gz_path(path) {
+            Some(gz) => gz,
+            None => return Err(err),
+        };
+        let mut data = Vec::new();
+        MultiGzDecoder::new(self.store.open_path(&gz)?).read_to_end(&mut data)?;
+        let data: Arc<[u8]> = data.into();
+        Ok(data.into())
+    }
+
+    #[inline]
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        self.store.entries_path(path)
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        match self.store.metadata_path(path) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => match gz_path(path) {
+                Some(gz) => self.store.metadata_path(&gz),
+                None => Err(err),
+            },
+            res => res,
+        }
+    }
+
+    #[inline]
+    fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+        self.store.create_path(path).map(Into::into)
+    }
+
+    #[inline]
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        self.store.remove_path(path)
+    }
+
+    #[inline]
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.store.rename_path(from, to)
+    }
+
+    #[inline]
+    fn is_read_only(&self) -> bool {
+        self.store.is_read_only()
+    }
+}
@@ -0,0 +1,325 @@
+#[cfg(feature = "async")]
+use std::future::Future;
+use std::io::{self, BufRead, Cursor, ErrorKind, Read, Seek, SeekFrom};
+use std::path::{Component, Path};
+
+#[cfg(feature = "async")]
+use crate::async_store::{AsyncFile, AsyncStore};
+use crate::index::normalize_path;
+use crate::store::Store;
+use crate::{EntryKind, Metadata};
+
+/// Remote store reading files over HTTP.
+///
+/// Opening `textures/a.png` sends a GET request for `<base>/textures/a.png`,
+/// with the path percent-encoded. Missing files (404 and 410 responses) fail
+/// with `ErrorKind::NotFound`, so remote content can be layered over local
+/// files with a tuple:
+///
+/// ```no_run
+/// # #[cfg(feature = "ureq")]
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{Http, Local, MiniFs};
+///
+/// let cdn = Http::new("https://cdn.example.com/assets").header("Authorization", "Bearer token");
+/// let fs = MiniFs::new().mount("/assets", (cdn, Local::new("./assets")));
+///
+/// let file = fs.open("/assets/textures/a.png")?;
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "ureq"))]
+/// # fn main() {}
+/// ```
+///
+/// Requests are sent by an [`HttpClient`] when the store is used as a
+/// [`Store`], and by an [`AsyncHttpClient`] when it's used as an `AsyncStore`.
+/// A client based on ureq is provided with the `ureq` feature.
+///
+/// # Remarks
+///
+/// HTTP has no directory listings, so the store doesn't list any entries.
+///
+/// [`HttpClient`]: trait.HttpClient.html
+/// [`AsyncHttpClient`]: trait.AsyncHttpClient.html
+/// [`Store`]: ../trait.Store.html
+pub struct Http<C> {
+    pub(crate) base: String,
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) client: C,
+}
+
+/// Blocking HTTP client.
+///
+/// Closures taking the URL and the headers of the request are clients too.
+pub trait HttpClient {
+    /// Send a GET request and return the body of the response.
+    ///
+    /// Missing resources must fail with `ErrorKind::NotFound`.
+    fn get(&self, url: &str, headers: &[(String, String)]) -> io::Result<Vec<u8>>;
+
+    /// Send a HEAD request and return the length of the resource, if known.
+    ///
+    /// Sends a GET request by default.
+    fn head(&self, url: &str, headers: &[(String, String)]) -> io::Result<Option<u64>> {
+        self.get(url, headers).map(|body| Some(body.len() as u64))
+    }
+
+    /// Send a request with any other method, like the PROPFIND requests of
+    /// WebDAV, and return the body of the response.
+    ///
+    /// Fails with `ErrorKind::Unsupported` by default.
+    fn request(
+        &self,
+        method: &str,
+        _url: &str,
+        _headers: &[(String, String)],
+        _body: &[u8],
+    ) -> io::Result<Vec<u8>> {
+        Err(io::Error::new(
+            ErrorKind::Unsupported,
+            format!("{} requests are not supported.", method),
+        ))
+    }
+}
+
+impl<F> HttpClient for F
+where
+    F: Fn(&str, &[(String, String)]) -> io::Result<Vec<u8>>,
+{
+    fn get(&self, url: &str, headers: &[(String, String)]) -> io::Result<Vec<u8>> {
+        self(url, headers)
+    }
+}
+
+/// Asynchronous HTTP client.
+#[cfg(feature = "async")]
+pub trait AsyncHttpClient {
+    /// Send a GET request and return the body of the response.
+    ///
+    /// Missing resources must fail with `ErrorKind::NotFound`.
+    fn get(
+        &self,
+        url: &str,
+        headers: &[(String, String)],
+    ) -> impl Future<Output = io::Result<Vec<u8>>>;
+}
+
+/// File downloaded from an Http store.
+pub struct HttpEntry {
+    inner: Cursor<Box<[u8]>>,
+}
+
+impl HttpEntry {
+    pub(crate) fn len(&self) -> u64 {
+        self.inner.get_ref().len() as u64
+    }
+}
+
+impl Read for HttpEntry {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl BufRead for HttpEntry {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+impl Seek for HttpEntry {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+#[cfg(feature = "ureq")]
+impl Http<Ureq> {
+    /// Create a store for the files under the `base` URL.
+    pub fn new<B: Into<String>>(base: B) -> Self {
+        Self::with_client(base, Ureq::default())
+    }
+}
+
+impl<C> Http<C> {
+    /// Create a store sending its requests with `client`.
+    pub fn with_client<B: Into<String>>(base: B, client: C) -> Self {
+        Self {
+            base: base.into(),
+            headers: Vec::new(),
+            client,
+        }
+    }
+
+    /// Add a header to every request.
+    pub fn header<N, V>(mut self, name: N, value: V) -> Self
+    where
+        N: Into<String>,
+        V: Into<String>,
+    {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// URL of the file at `path`.
+    pub fn url(&self, path: &Path) -> io::Result<String> {
+        let mut url = self.base.trim_end_matches('/').to_owned();
+        for comp in normalize_path(path).components() {
+            match comp {
+                Component::Normal(name) => {
+                    let name = name.to_str().ok_or_else(|| {
+                        io::Error::new(ErrorKind::InvalidInput, "Utf8 path conversion error.")
+                    })?;
+                    url.push('/');
+                    encode(&mut url, name);
+                }
+                Component::RootDir | Component::CurDir => {}
+                _ => return Err(io::Error::from(ErrorKind::NotFound)),
+            }
+        }
+        Ok(url)
+    }
+}
+
+impl<C: HttpClient> Store for Http<C> {
+    type File = HttpEntry;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        let body = self.client.get(&self.url(path)?, &self.headers)?;
+        Ok(HttpEntry {
+            inner: Cursor::new(body.into()),
+        })
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        let url = self.url(path)?;
+        let len = match self.client.head(&url, &self.headers)? {
+            Some(len) => len,
+            None => self.client.get(&url, &self.headers)?.len() as u64,
+        };
+        Ok(Metadata {
+            kind: EntryKind::File,
+            len,
+            modified: None,
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+impl<C: AsyncHttpClient> AsyncStore for Http<C> {
+    type File = AsyncFile;
+
+    fn open_path(&self, path: &Path) -> impl Future<Output = io::Result<AsyncFile>> {
+        let url = self.url(path);
+        async move {
+            let body = self.client.get(&url?, &self.headers).await?;
+            let file = HttpEntry {
+                inner: Cursor::new(body.into()),
+            };
+            Ok(AsyncFile::Sync(file.into()))
+        }
+    }
+}
+
+/// HTTP client based on ureq.
+#[cfg(feature = "ureq")]
+pub struct Ureq {
+    agent: ureq_::Agent,
+}
+
+#[cfg(feature = "ureq")]
+impl Ureq {
+    /// Send requests with a configured agent.
+    pub fn new(agent: ureq_::Agent) -> Self {
+        Self { agent }
+    }
+}
+
+#[cfg(feature = "ureq")]
+impl Default for Ureq {
+    fn default() -> Self {
+        Self::new(ureq_::Agent::new())
+    }
+}
+
+#[cfg(feature = "ureq")]
+impl HttpClient for Ureq {
+    fn get(&self, url: &str, headers: &[(String, String)]) -> io::Result<Vec<u8>> {
+        let mut request = self.agent.get(url);
+        for (name, value) in headers {
+            request = request.set(name, value);
+        }
+        let mut body = Vec::new();
+        request
+            .call()
+            .map_err(ureq_error)?
+            .into_reader()
+            .read_to_end(&mut body)?;
+        Ok(body)
+    }
+
+    fn head(&self, url: &str, headers: &[(String, String)]) -> io::Result<Option<u64>> {
+        let mut request = self.agent.head(url);
+        for (name, value) in headers {
+            request = request.set(name, value);
+        }
+        let response = request.call().map_err(ureq_error)?;
+        Ok(response
+            .header("Content-Length")
+            .and_then(|len| len.parse().ok()))
+    }
+
+    fn request(
+        &self,
+        method: &str,
+        url: &str,
+        headers: &[(String, String)],
+        body: &[u8],
+    ) -> io::Result<Vec<u8>> {
+        let mut request = self.agent.request(method, url);
+        for (name, value) in headers {
+            request = request.set(name, value);
+        }
+        let mut response = Vec::new();
+        request
+            .send_bytes(body)
+            .map_err(ureq_error)?
+            .into_reader()
+            .read_to_end(&mut response)?;
+        Ok(response)
+    }
+}
+
+#[cfg(feature = "ureq")]
+fn ureq_error(err: ureq_::Error) -> io::Error {
+    match err {
+        ureq_::Error::Status(404, _) | ureq_::Error::Status(410, _) => {
+            io::Error::from(ErrorKind::NotFound)
+        }
+        ureq_::Error::Status(401, _) | ureq_::Error::Status(403, _) => {
+            io::Error::from(ErrorKind::PermissionDenied)
+        }
+        err => io::Error::other(err),
+    }
+}
+
+// Percent-encodes everything but the unreserved characters of RFC 3986.
+pub(crate) fn encode(url: &mut String, name: &str) {
+    for byte in name.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                url.push(byte as char)
+            }
+            _ => url.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+}
@@ -1,26 +1,115 @@
-use crate::{EntryKind, File};
+use crate::EntryKind;
 
 use std::borrow::Cow;
 use std::collections::btree_map::{BTreeMap, Iter};
 use std::collections::linked_list::LinkedList;
 use std::ffi::{OsStr, OsString};
-use std::path::{Component, Path, PathBuf};
+use std::path::{Path, PathBuf};
 
+#[derive(Clone)]
 struct DirNode<M> {
     files: BTreeMap<OsString, M>,
     dirs: BTreeMap<OsString, DirNode<M>>,
+    // Names of the files and directories by their folded version, when the
+    // index folds them.
+    folded: BTreeMap<OsString, OsString>,
+    fold: Fold,
+}
+
+// How names are folded, so different names find the same entry.
+#[derive(Clone, Copy, Default)]
+struct Fold {
+    case: bool,
+    unicode: bool,
+}
+
+impl Fold {
+    fn key(self, name: &OsStr) -> Option<OsString> {
+        if !self.case && !self.unicode {
+            return None;
+        }
+        let mut key = name.to_os_string();
+        #[cfg(feature = "unicode")]
+        if self.unicode {
+            use unicode_normalization::UnicodeNormalization;
+            if let Some(name) = name.to_str() {
+                key = name.nfc().collect::<String>().into();
+            }
+        }
+        if self.case {
+            key.make_ascii_lowercase();
+        }
+        Some(key)
+    }
 }
 
 impl<M> DirNode<M> {
-    fn new(name: Option<OsString>) -> Self {
+    fn new(fold: Fold) -> Self {
         Self {
             files: BTreeMap::new(),
             dirs: BTreeMap::new(),
+            folded: BTreeMap::new(),
+            fold,
+        }
+    }
+
+    // Name of the entry matching `name`, which is an exact match if there's
+    // one.
+    fn name<'a>(&'a self, name: &'a OsStr) -> &'a OsStr {
+        if self.folded.is_empty() || self.files.contains_key(name) || self.dirs.contains_key(name) {
+            return name;
+        }
+        match self.fold.key(name).and_then(|key| self.folded.get(&key)) {
+            Some(real) => real,
+            None => name,
+        }
+    }
+
+    fn fold(&mut self, name: &OsStr) {
+        if let Some(key) = self.fold.key(name) {
+            self.folded
+                .entry(key)
+                .or_insert_with(|| name.to_os_string());
+        }
+    }
+
+    // Points the folded name of a removed entry to another entry, if any.
+    fn unfold(&mut self, name: &OsStr) {
+        let key = match self.fold.key(name) {
+            Some(key) => key,
+            None => return,
+        };
+        if self.folded.get(&key).map(OsString::as_os_str) != Some(name) {
+            return;
+        }
+        let fold = self.fold;
+        let other = self
+            .files
+            .keys()
+            .chain(self.dirs.keys())
+            .find(|other| fold.key(other).as_ref() == Some(&key));
+        match other {
+            Some(other) => self.folded.insert(key, other.clone()),
+            None => self.folded.remove(&key),
+        };
+    }
+
+    // Folds the names of the whole tree again.
+    fn refold(&mut self, fold: Fold) {
+        self.fold = fold;
+        self.folded.clear();
+        let names: Vec<_> = self.files.keys().chain(self.dirs.keys()).cloned().collect();
+        for name in names {
+            self.fold(&name);
+        }
+        for dir in self.dirs.values_mut() {
+            dir.refold(fold);
         }
     }
 }
 
 /// Directory index.
+#[derive(Clone)]
 pub struct Index<M> {
     root: DirNode<M>,
 }
@@ -49,7 +138,7 @@ impl<'a, M> Entries<'a, M> {
             iter.next().map(|(n, _)| Entry {
                 name: n.as_os_str(),
                 meta: None,
-                kind: EntryKind::File,
+                kind: EntryKind::Dir,
             })
         } else {
             None
@@ -71,35 +160,99 @@ pub struct Entry<'a, M> {
     pub kind: EntryKind,
 }
 
+impl<M> Default for Index<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<M> Index<M> {
     pub fn new() -> Self {
         Self {
-            root: DirNode::new(None),
+            root: DirNode::new(Fold::default()),
         }
     }
 
-    pub fn entries<P>(&self, path: P) -> Entries<M>
+    /// Look up paths ignoring ASCII case.
+    ///
+    /// Entries keep the case they were inserted with, so names that only differ
+    /// in case are different entries. Exact matches win, and otherwise the
+    /// first entry that was inserted is found.
+    pub fn case_insensitive(mut self) -> Self {
+        let fold = Fold {
+            case: true,
+            ..self.root.fold
+        };
+        self.root.refold(fold);
+        self
+    }
+
+    /// Look up paths by their Unicode normalization form C, so names in
+    /// composed and decomposed forms find the same entries.
+    ///
+    /// Like with [`case_insensitive`](#method.case_insensitive), entries keep
+    /// the names they were inserted with, and exact matches win.
+    #[cfg(feature = "unicode")]
+    pub fn unicode_normalized(mut self) -> Self {
+        let fold = Fold {
+            unicode: true,
+            ..self.root.fold
+        };
+        self.root.refold(fold);
+        self
+    }
+
+    pub fn entries<P>(&self, path: P) -> Entries<'_, M>
     where
         P: AsRef<Path>,
     {
-        let path = normalize_path(path.as_ref()).to_path_buf();
-        entries(path.into_iter().collect(), &self.root)
+        let path = key(path.as_ref());
+        entries(path.iter().collect(), &self.root)
     }
 
     pub fn insert<P>(&mut self, path: P, meta: M)
     where
         P: Into<PathBuf>,
     {
-        let path = normalize_path(&path.into()).to_path_buf();
-        insert(path.into_iter().collect(), &mut self.root, meta)
+        let path = key(&path.into());
+        insert(path.iter().collect(), &mut self.root, meta)
+    }
+
+    /// Inserts a (possibly empty) directory.
+    pub fn insert_dir<P>(&mut self, path: P)
+    where
+        P: Into<PathBuf>,
+    {
+        let path = key(&path.into());
+        let mut node = &mut self.root;
+        for dir in path.iter() {
+            node.files.remove(dir);
+            node.fold(dir);
+            let fold = node.fold;
+            node = node
+                .dirs
+                .entry(dir.to_os_string())
+                .or_insert_with(|| DirNode::new(fold));
+        }
     }
 
     pub fn get<P>(&self, path: P) -> Option<&M>
     where
         P: AsRef<Path>,
     {
-        let path = normalize_path(path.as_ref()).to_path_buf();
-        get(path.into_iter().collect(), &self.root)
+        let path = key(path.as_ref());
+        get(path.iter().collect(), &self.root)
+    }
+
+    /// Removes a file from the index, returning its metadata.
+    ///
+    /// Directories left empty are removed as well.
+    pub fn remove<P>(&mut self, path: P) -> Option<M>
+    where
+        P: AsRef<Path>,
+    {
+        let path = key(path.as_ref());
+        remove(path.iter().collect(), &mut self.root)
     }
 
     pub fn contains<P>(&self, path: P) -> bool
@@ -109,9 +262,24 @@ impl<M> Index<M> {
         self.get(path).is_some()
     }
 
+    /// Returns `true` if the path is a directory. The root is always one.
+    pub fn contains_dir<P>(&self, path: P) -> bool
+    where
+        P: AsRef<Path>,
+    {
+        let path = key(path.as_ref());
+        let mut node = &self.root;
+        for dir in path.iter() {
+            match node.dirs.get(node.name(dir)) {
+                Some(next) => node = next,
+                None => return false,
+            }
+        }
+        true
+    }
+
     pub fn clear(&mut self) {
-        self.root.files.clear();
-        self.root.dirs.clear();
+        self.root = DirNode::new(self.root.fold);
     }
 }
 
@@ -123,7 +291,7 @@ fn entries<'a, M>(mut parts: LinkedList<&OsStr>, node: &'a DirNode<M>) -> Entrie
             dirs: Some(node.dirs.iter()),
         },
         (Some(dir), _) => {
-            if let Some(node) = node.dirs.get(dir) {
+            if let Some(node) = node.dirs.get(node.name(dir)) {
                 entries(parts, node)
             } else {
                 Entries {
@@ -140,7 +308,8 @@ fn insert<M>(mut parts: LinkedList<&OsStr>, node: &mut DirNode<M>, meta: M) {
     match (f0, parts.front()) {
         (None, _) => {}
         (Some(file), None) => {
-            if node.dirs.get(file).is_none() {
+            if !node.dirs.contains_key(file) {
+                node.fold(file);
                 node.files.insert(file.to_os_string(), meta);
             }
         }
@@ -150,8 +319,9 @@ fn insert<M>(mut parts: LinkedList<&OsStr>, node: &mut DirNode<M>, meta: M) {
                 insert(parts, dir, meta)
             } else {
                 let name = dir.to_os_string();
-                let mut new_node = DirNode::new(Some(name.clone()));
+                let mut new_node = DirNode::new(node.fold);
                 insert(parts, &mut new_node, meta);
+                node.fold(dir);
                 node.dirs.insert(name, new_node);
             }
         }
@@ -162,9 +332,9 @@ fn get<'a, M>(mut parts: LinkedList<&OsStr>, node: &'a DirNode<M>) -> Option<&'a
     let f0 = parts.pop_front();
     match (f0, parts.front()) {
         (None, _) => None,
-        (Some(file), None) => node.files.get(file),
+        (Some(file), None) => node.files.get(node.name(file)),
         (Some(dir), Some(_)) => {
-            if let Some(dir) = node.dirs.get(dir) {
+            if let Some(dir) = node.dirs.get(node.name(dir)) {
                 get(parts, dir)
             } else {
                 None
@@ -173,6 +343,29 @@ fn get<'a, M>(mut parts: LinkedList<&OsStr>, node: &'a DirNode<M>) -> Option<&'a
     }
 }
 
+fn remove<M>(mut parts: LinkedList<&OsStr>, node: &mut DirNode<M>) -> Option<M> {
+    let f0 = parts.pop_front();
+    match (f0, parts.front()) {
+        (None, _) => None,
+        (Some(file), None) => {
+            let file = node.name(file).to_os_string();
+            let meta = node.files.remove(&file)?;
+            node.unfold(&file);
+            Some(meta)
+        }
+        (Some(dir), Some(_)) => {
+            let dir = node.name(dir).to_os_string();
+            let child = node.dirs.get_mut(&dir)?;
+            let meta = remove(parts, child);
+            if meta.is_some() && child.files.is_empty() && child.dirs.is_empty() {
+                node.dirs.remove(&dir);
+                node.unfold(&dir);
+            }
+            meta
+        }
+    }
+}
+
 /// Normalizes path by removing references to the parent (`..`) and the current
 /// (`.`) directory.
 ///
@@ -184,12 +377,9 @@ fn get<'a, M>(mut parts: LinkedList<&OsStr>, node: &'a DirNode<M>) -> Option<&'a
 /// assert_eq!(Path::new("foo"), normalize_path(Path::new("./foo")),);
 /// ```
 #[doc(hidden)]
-pub fn normalize_path(path: &Path) -> Cow<Path> {
+pub fn normalize_path(path: &Path) -> Cow<'_, Path> {
     use std::path::Component::*;
-    if path.components().any(|c| match c {
-        CurDir | ParentDir => true,
-        _ => false,
-    }) {
+    if path.components().any(|c| matches!(c, CurDir | ParentDir)) {
         let mut normal = PathBuf::new();
         for comp in path.components() {
             match comp {
@@ -207,3 +397,28 @@ pub fn normalize_path(path: &Path) -> Cow<Path> {
         Cow::Borrowed(path)
     }
 }
+
+// Path of an entry of the index. Backslashes are separators too, so paths from
+// Windows find the same entries everywhere.
+fn key(path: &Path) -> PathBuf {
+    normalize_path(&forward_slashes(path)).into_owned()
+}
+
+// Replaces backslashes with slashes, so both are separators on every platform.
+pub(crate) fn forward_slashes(path: &Path) -> Cow<'_, Path> {
+    match path.to_str() {
+        Some(name) if name.contains('\\') => Cow::Owned(name.replace('\\', "/").into()),
+        _ => Cow::Borrowed(path),
+    }
+}
+
+// Normalized path, without the root.
+pub(crate) fn relative_path(path: &Path) -> PathBuf {
+    normalize_path(&forward_slashes(path))
+        .components()
+        .filter_map(|comp| match comp {
+            std::path::Component::Normal(name) => Some(name),
+            _ => None,
+        })
+        .collect()
+}
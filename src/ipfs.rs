@@ -0,0 +1,111 @@
+#[cfg(feature = "async")]
+use std::future::Future;
+use std::io;
+use std::path::Path;
+
+#[cfg(feature = "async")]
+use crate::async_store::{AsyncFile, AsyncStore};
+#[cfg(feature = "async")]
+use crate::http::AsyncHttpClient;
+#[cfg(feature = "ureq")]
+use crate::http::Ureq;
+use crate::http::{Http, HttpClient, HttpEntry};
+use crate::store::Store;
+use crate::Metadata;
+
+/// IPFS content read through an HTTP gateway.
+///
+/// Opening `textures/a.png` sends a GET request for
+/// `<gateway>/ipfs/<cid>/textures/a.png`, so a content-addressed directory can
+/// be mounted like any other store:
+///
+/// ```no_run
+/// # #[cfg(feature = "ureq")]
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{Ipfs, MiniFs};
+///
+/// let release = Ipfs::new("bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi")
+///     .gateway("https://dweb.link");
+/// let fs = MiniFs::new().mount("/assets", release);
+///
+/// let file = fs.open("/assets/textures/a.png")?;
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "ureq"))]
+/// # fn main() {}
+/// ```
+///
+/// The `https://ipfs.io` gateway is used by default. Requests are sent by an
+/// [`HttpClient`], like in the [`Http`] store.
+///
+/// # Remarks
+///
+/// Gateways list directories as HTML pages, so the store doesn't list any
+/// entries.
+///
+/// [`HttpClient`]: ../http/trait.HttpClient.html
+/// [`Http`]: ../http/struct.Http.html
+pub struct Ipfs<C> {
+    cid: String,
+    http: Http<C>,
+}
+
+#[cfg(feature = "ureq")]
+impl Ipfs<Ureq> {
+    /// Create a store for the content under `cid`.
+    pub fn new<I: Into<String>>(cid: I) -> Self {
+        Self::with_client(cid, Ureq::default())
+    }
+}
+
+impl<C> Ipfs<C> {
+    /// Create a store sending its requests with `client`.
+    pub fn with_client<I: Into<String>>(cid: I, client: C) -> Self {
+        let cid = cid.into().trim_matches('/').to_string();
+        let http = Http::with_client(format!("https://ipfs.io/ipfs/{}", cid), client);
+        Self { cid, http }
+    }
+
+    /// Send requests to another gateway.
+    pub fn gateway(mut self, gateway: &str) -> Self {
+        self.http.base = format!("{}/ipfs/{}", gateway.trim_end_matches('/'), self.cid);
+        self
+    }
+
+    /// Add a header to every request.
+    pub fn header<N, V>(mut self, name: N, value: V) -> Self
+    where
+        N: Into<String>,
+        V: Into<String>,
+    {
+        self.http = self.http.header(name, value);
+        self
+    }
+
+    /// URL of the file at `path`.
+    pub fn url(&self, path: &Path) -> io::Result<String> {
+        self.http.url(path)
+    }
+}
+
+impl<C: HttpClient> Store for Ipfs<C> {
+    type File = HttpEntry;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        self.http.open_path(path)
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        self.http.metadata_path(path)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<C: AsyncHttpClient> AsyncStore for Ipfs<C> {
+    type File = AsyncFile;
+
+    fn open_path(&self, path: &Path) -> impl Future<Output = io::Result<AsyncFile>> {
+        AsyncStore::open_path(&self.http, path)
+    }
+}
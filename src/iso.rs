@@ -0,0 +1,429 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, BufRead, Cursor, ErrorKind, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::bytes::le32;
+use crate::index::Index;
+use crate::store::Store;
+use crate::{Entries, Entry, EntryKind, Metadata};
+
+// Size of a sector. Directory records never cross sector boundaries.
+const SECTOR: usize = 2048;
+
+/// ISO 9660 disc image store.
+///
+/// Long file names are read from the Rock Ridge extensions when present, and
+/// from the Joliet directory tree otherwise. The image is indexed when the
+/// store is created.
+///
+/// # Remarks
+///
+/// Images with only a UDF filesystem are not supported. Most DVD images are
+/// UDF bridge images, which contain an ISO 9660 filesystem as well.
+///
+/// When used with a `std::fs::File`, the file will remain open for the lifetime
+/// of the Iso.
+pub struct Iso<T: Read + Seek> {
+    inner: RefCell<T>,
+    index: Index<IsoIndexEntry>,
+}
+
+struct IsoIndexEntry {
+    // position of the file data within the image
+    pos: u64,
+    meta: Metadata,
+}
+
+/// Entry in the disc image.
+pub struct IsoEntry {
+    inner: Cursor<Box<[u8]>>,
+}
+
+impl IsoEntry {
+    pub(crate) fn len(&self) -> u64 {
+        self.inner.get_ref().len() as u64
+    }
+}
+
+impl Read for IsoEntry {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl BufRead for IsoEntry {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+impl Seek for IsoEntry {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl Iso<fs::File> {
+    /// Open a file from the native filesystem.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = fs::OpenOptions::new()
+            .read(true)
+            .write(false)
+            .create(false)
+            .open(path)?;
+        Self::new(file)
+    }
+}
+
+impl<T: Read + Seek> Iso<T> {
+    /// Read the volume descriptors of the image and index its contents.
+    pub fn new(mut inner: T) -> io::Result<Self> {
+        let mut primary = None;
+        let mut joliet = None;
+        let mut sector = [0; SECTOR];
+        // The volume descriptors start after the 16 sectors of the system area.
+        for lba in 16.. {
+            inner.seek(SeekFrom::Start(lba * SECTOR as u64))?;
+            inner.read_exact(&mut sector)?;
+            if &sector[1..6] != b"CD001" {
+                return Err(invalid("Not an ISO 9660 image."));
+            }
+            match sector[0] {
+                1 if primary.is_none() => {
+                    let block = u64::from(u16::from_le_bytes([sector[128], sector[129]]));
+                    primary = Some((block, Record::parse(&sector[156..190])?));
+                }
+                2 if is_joliet(&sector[88..120]) => {
+                    joliet = Some(Record::parse(&sector[156..190])?)
+                }
+                255 => break,
+                _ => {}
+            }
+        }
+        let (block, root) = primary.ok_or_else(|| invalid("Missing primary volume descriptor."))?;
+        let mut image = Image { inner, block };
+
+        // Rock Ridge is announced by a SUSP entry in the first record of the root.
+        let first = image.read_dir(&root)?.into_iter().next();
+        let names = match first.as_ref().and_then(|dot| susp_skip(&dot.system_use)) {
+            Some(skip) => Names::RockRidge(skip),
+            None if joliet.is_some() => Names::Joliet,
+            None => Names::Iso,
+        };
+        let root = match names {
+            Names::Joliet => joliet.unwrap(),
+            _ => root,
+        };
+
+        let index = image.index(root, names)?;
+        Ok(Self {
+            inner: RefCell::new(image.inner),
+            index,
+        })
+    }
+}
+
+impl<T: Read + Seek> Store for Iso<T> {
+    type File = IsoEntry;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        let entry = match self.index.get(path) {
+            Some(entry) => entry,
+            None => return Err(io::Error::from(ErrorKind::NotFound)),
+        };
+        let mut file = self.inner.borrow_mut();
+        file.seek(SeekFrom::Start(entry.pos))?;
+        let mut data = Vec::new();
+        (&mut *file).take(entry.meta.len).read_to_end(&mut data)?;
+        Ok(IsoEntry {
+            inner: Cursor::new(data.into()),
+        })
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        Ok(Entries::new(self.index.entries(path).map(|ent| {
+            let name = ent.name.to_os_string();
+            let kind = ent.kind;
+            Ok(Entry { name, kind })
+        })))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        if let Some(entry) = self.index.get(path) {
+            Ok(entry.meta.clone())
+        } else if self.index.contains_dir(path) {
+            Ok(Metadata::dir())
+        } else {
+            Err(io::Error::from(ErrorKind::NotFound))
+        }
+    }
+}
+
+// Which directory tree is indexed, and how its names are decoded.
+#[derive(Clone, Copy)]
+enum Names {
+    Iso,
+    Joliet,
+    // Bytes to skip at the start of each system use area.
+    RockRidge(usize),
+}
+
+// Directory record (ECMA-119 9.1).
+struct Record {
+    extent: u32,
+    size: u32,
+    dir: bool,
+    associated: bool,
+    modified: Option<SystemTime>,
+    ident: Vec<u8>,
+    system_use: Vec<u8>,
+}
+
+impl Record {
+    fn parse(data: &[u8]) -> io::Result<Self> {
+        if data.len() < 34 {
+            return Err(invalid("Directory record is too short."));
+        }
+        let ident_end = 33 + usize::from(data[32]);
+        if ident_end > data.len() {
+            return Err(invalid("Directory record is too short."));
+        }
+        // A padding byte follows identifiers of even length.
+        let system_use = (ident_end + 1 - usize::from(data[32]) % 2).min(data.len());
+        Ok(Self {
+            extent: le32(&data[2..])?,
+            size: le32(&data[10..])?,
+            dir: data[25] & 0x02 != 0,
+            associated: data[25] & 0x04 != 0,
+            modified: record_time(&data[18..25]),
+            ident: data[33..ident_end].to_vec(),
+            system_use: data[system_use..].to_vec(),
+        })
+    }
+
+    // Records for the directory itself and its parent.
+    fn is_dot(&self) -> bool {
+        self.ident == [0] || self.ident == [1]
+    }
+}
+
+// Rock Ridge data of a directory record.
+#[derive(Default)]
+struct RockRidge {
+    name: Vec<u8>,
+    // directory moved elsewhere in the tree, pointed to by a child link
+    relocated: bool,
+    child_link: Option<u32>,
+    symlink: bool,
+}
+
+struct Image<T> {
+    inner: T,
+    block: u64,
+}
+
+impl<T: Read + Seek> Image<T> {
+    fn read(&mut self, lba: u32, offset: u64, len: u64) -> io::Result<Vec<u8>> {
+        self.inner
+            .seek(SeekFrom::Start(u64::from(lba) * self.block + offset))?;
+        let mut data = Vec::new();
+        (&mut self.inner).take(len).read_to_end(&mut data)?;
+        if (data.len() as u64) < len {
+            return Err(io::Error::from(ErrorKind::UnexpectedEof));
+        }
+        Ok(data)
+    }
+
+    fn read_dir(&mut self, dir: &Record) -> io::Result<Vec<Record>> {
+        let data = self.read(dir.extent, 0, u64::from(dir.size))?;
+        let mut records = Vec::new();
+        let mut pos = 0;
+        while pos < data.len() {
+            let len = usize::from(data[pos]);
+            if len == 0 {
+                // The rest of the sector is padding.
+                pos = (pos / SECTOR + 1) * SECTOR;
+                continue;
+            }
+            let end = (pos + len).min(data.len());
+            records.push(Record::parse(&data[pos..end])?);
+            pos += len;
+        }
+        Ok(records)
+    }
+
+    fn rock_ridge(&mut self, record: &Record, skip: usize) -> io::Result<RockRidge> {
+        let mut rr = RockRidge::default();
+        let mut area = record.system_use.get(skip..).unwrap_or_default().to_vec();
+        // Continuation areas are followed a bounded number of times, in case
+        // they form a loop.
+        for _ in 0..16 {
+            let mut continuation = None;
+            let mut pos = 0;
+            while pos + 4 <= area.len() {
+                let len = usize::from(area[pos + 2]);
+                if len < 4 || pos + len > area.len() {
+                    break;
+                }
+                let data = &area[pos + 4..pos + len];
+                match &area[pos..pos + 2] {
+                    // Name, unless it refers to the current or parent directory.
+                    b"NM" if !data.is_empty() && data[0] & 0x06 == 0 => {
+                        rr.name.extend_from_slice(&data[1..])
+                    }
+                    b"CE" if data.len() >= 24 => {
+                        continuation = Some((le32(data)?, le32(&data[8..])?, le32(&data[16..])?))
+                    }
+                    b"CL" if data.len() >= 4 => rr.child_link = Some(le32(data)?),
+                    b"RE" => rr.relocated = true,
+                    b"PX" if data.len() >= 4 => rr.symlink = le32(data)? & 0o170_000 == 0o120_000,
+                    b"ST" => break,
+                    _ => {}
+                }
+                pos += len;
+            }
+            match continuation {
+                Some((lba, offset, len)) => {
+                    area = self.read(lba, u64::from(offset), u64::from(len))?
+                }
+                None => break,
+            }
+        }
+        Ok(rr)
+    }
+
+    fn index(&mut self, root: Record, names: Names) -> io::Result<Index<IsoIndexEntry>> {
+        let mut index = Index::new();
+        // Extents of the directories already visited, in case the tree has loops.
+        let mut visited = HashSet::new();
+        let mut stack = vec![(PathBuf::new(), root)];
+        while let Some((path, dir)) = stack.pop() {
+            if !visited.insert(dir.extent) {
+                continue;
+            }
+            for mut record in self.read_dir(&dir)? {
+                if record.is_dot() || record.associated {
+                    continue;
+                }
+                let name = match names {
+                    Names::Iso => iso_name(&record.ident, record.dir),
+                    Names::Joliet => joliet_name(&record.ident, record.dir),
+                    Names::RockRidge(skip) => {
+                        let rr = self.rock_ridge(&record, skip)?;
+                        if rr.relocated || rr.symlink {
+                            continue;
+                        }
+                        if let Some(extent) = rr.child_link {
+                            // The size is only known from the record of the moved
+                            // directory itself.
+                            let dot = Record {
+                                extent,
+                                size: SECTOR as u32,
+                                ..record
+                            };
+                            record = match self.read_dir(&dot)?.into_iter().next() {
+                                Some(moved) => Record {
+                                    extent,
+                                    dir: true,
+                                    ..moved
+                                },
+                                None => continue,
+                            };
+                        }
+                        if rr.name.is_empty() {
+                            iso_name(&record.ident, record.dir)
+                        } else {
+                            String::from_utf8_lossy(&rr.name).into_owned()
+                        }
+                    }
+                };
+
+                let path = path.join(name);
+                if record.dir {
+                    index.insert_dir(&path);
+                    stack.push((path, record));
+                } else {
+                    let pos = u64::from(record.extent) * self.block;
+                    let meta = Metadata {
+                        kind: EntryKind::File,
+                        len: u64::from(record.size),
+                        modified: record.modified,
+                    };
+                    index.insert(path, IsoIndexEntry { pos, meta });
+                }
+            }
+        }
+        Ok(index)
+    }
+}
+
+// Escape sequences of the three levels of Joliet.
+fn is_joliet(escape: &[u8]) -> bool {
+    [b"%/@", b"%/C", b"%/E"]
+        .iter()
+        .any(|seq| escape.starts_with(&seq[..]))
+}
+
+// Checks the SUSP indicator, returning the bytes to skip in system use areas.
+fn susp_skip(system_use: &[u8]) -> Option<usize> {
+    match system_use {
+        [b'S', b'P', 7, _, 0xbe, 0xef, skip, ..] => Some(usize::from(*skip)),
+        _ => None,
+    }
+}
+
+fn iso_name(ident: &[u8], dir: bool) -> String {
+    let name = String::from_utf8_lossy(ident);
+    strip_version(&name, dir)
+}
+
+fn joliet_name(ident: &[u8], dir: bool) -> String {
+    let units = ident
+        .chunks_exact(2)
+        .map(|unit| u16::from_be_bytes([unit[0], unit[1]]));
+    let name: String = char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect();
+    strip_version(&name, dir)
+}
+
+// File identifiers end in a version number, and in a dot when there is no
+// extension.
+fn strip_version(name: &str, dir: bool) -> String {
+    if dir {
+        return name.to_string();
+    }
+    let name = match name.rfind(';') {
+        Some(pos) => &name[..pos],
+        None => name,
+    };
+    name.strip_suffix('.').unwrap_or(name).to_string()
+}
+
+// Recording date and time (ECMA-119 9.1.5), with the offset from UTC in 15
+// minute intervals.
+fn record_time(time: &[u8]) -> Option<SystemTime> {
+    let secs = i64::from(time[3]) * 3600 + i64::from(time[4]) * 60 + i64::from(time[5])
+        - i64::from(time[6] as i8) * 15 * 60;
+    crate::time::from_civil(
+        1900 + i64::from(time[0]),
+        i64::from(time[1]),
+        i64::from(time[2]),
+        secs,
+    )
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg)
+}
@@ -0,0 +1,277 @@
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, Cursor, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Component, Path};
+use std::rc::Rc;
+
+use crate::index::normalize_path;
+use crate::store::{read_only, Store};
+use crate::{Entries, Entry, EntryKind, Metadata};
+
+/// Key-value database that can back a [`KvStore`].
+///
+/// Keys are the paths of the files relative to the root of the store, with
+/// components separated by `/`, and values are their contents. Only `get` and
+/// `scan_prefix` are needed for reading; backends that can be written to also
+/// implement `put` and `delete`.
+///
+/// ```
+/// use mini_fs::kv::{KvBackend, KvStore};
+/// use mini_fs::prelude::*;
+/// use std::cell::RefCell;
+/// use std::collections::BTreeMap;
+/// use std::io::{self, Write};
+///
+/// struct Map(RefCell<BTreeMap<String, Vec<u8>>>);
+///
+/// impl KvBackend for Map {
+///     fn get(&self, key: &str) -> io::Result<Option<Vec<u8>>> {
+///         Ok(self.0.borrow().get(key).cloned())
+///     }
+///
+///     fn put(&self, key: &str, value: &[u8]) -> io::Result<()> {
+///         self.0.borrow_mut().insert(key.to_string(), value.to_vec());
+///         Ok(())
+///     }
+///
+///     fn scan_prefix(&self, prefix: &str) -> io::Result<Vec<String>> {
+///         let map = self.0.borrow();
+///         let keys = map.keys().filter(|key| key.starts_with(prefix));
+///         Ok(keys.cloned().collect())
+///     }
+/// }
+///
+/// let store = KvStore::new(Map(RefCell::default()));
+/// store.create("textures/wall.png")?.write_all(b"png")?;
+/// assert!(store.is_dir("textures"));
+/// # Ok::<(), io::Error>(())
+/// ```
+///
+/// [`KvStore`]: ./struct.KvStore.html
+pub trait KvBackend {
+    /// Value of `key`, if it exists.
+    fn get(&self, key: &str) -> io::Result<Option<Vec<u8>>>;
+
+    /// Insert or replace the value of `key`.
+    ///
+    /// Fails with an error of kind `ReadOnlyFilesystem` by default.
+    fn put(&self, _key: &str, _value: &[u8]) -> io::Result<()> {
+        Err(read_only())
+    }
+
+    /// Delete `key`, returning whether it existed.
+    ///
+    /// Fails with an error of kind `ReadOnlyFilesystem` by default.
+    fn delete(&self, _key: &str) -> io::Result<bool> {
+        Err(read_only())
+    }
+
+    /// Keys starting with `prefix`, in any order.
+    fn scan_prefix(&self, prefix: &str) -> io::Result<Vec<String>>;
+}
+
+/// Store over a [`KvBackend`].
+///
+/// Directories aren't stored, they exist as long as they contain files.
+///
+/// # Remarks
+///
+/// Files being written are stored with [`KvBackend::put`] when they are
+/// flushed or dropped. Metadata doesn't include modification times, and the
+/// length of a file is known by reading it.
+///
+/// [`KvBackend`]: ./trait.KvBackend.html
+/// [`KvBackend::put`]: ./trait.KvBackend.html#method.put
+pub struct KvStore<B> {
+    backend: Rc<B>,
+}
+
+/// File from a KvStore.
+pub struct KvFile {
+    inner: Cursor<Vec<u8>>,
+    // Key the contents are written back to.
+    write: Option<(String, Rc<dyn KvBackend>)>,
+}
+
+impl KvFile {
+    pub(crate) fn len(&self) -> u64 {
+        self.inner.get_ref().len() as u64
+    }
+}
+
+impl Read for KvFile {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl BufRead for KvFile {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+impl Seek for KvFile {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl Write for KvFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.write {
+            Some(_) => self.inner.write(buf),
+            None => Err(read_only()),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if let Some((ref key, ref backend)) = self.write {
+            backend.put(key, self.inner.get_ref())?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for KvFile {
+    fn drop(&mut self) {
+        let _ = Write::flush(self);
+    }
+}
+
+impl<B> KvStore<B> {
+    pub fn new(backend: B) -> Self {
+        Self {
+            backend: Rc::new(backend),
+        }
+    }
+
+    /// Backend of the store.
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+}
+
+impl<B: KvBackend> KvStore<B> {
+    fn contains_dir(&self, dir: &str) -> io::Result<bool> {
+        Ok(dir.is_empty() || !self.backend.scan_prefix(&format!("{}/", dir))?.is_empty())
+    }
+}
+
+impl<B: KvBackend + 'static> Store for KvStore<B> {
+    type File = KvFile;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        match self.backend.get(&key(path)?)? {
+            Some(data) => Ok(KvFile {
+                inner: Cursor::new(data),
+                write: None,
+            }),
+            None => Err(io::Error::from(ErrorKind::NotFound)),
+        }
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        let dir = key(path)?;
+        let prefix = if dir.is_empty() {
+            dir
+        } else {
+            format!("{}/", dir)
+        };
+        let keys = self.backend.scan_prefix(&prefix)?;
+        let entries = child_entries(keys.iter().filter_map(|key| key.strip_prefix(&prefix)));
+        Ok(Entries::new(entries.into_iter().map(|(name, kind)| {
+            Ok(Entry {
+                name: name.into(),
+                kind,
+            })
+        })))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        let key = key(path)?;
+        match self.backend.get(&key)? {
+            Some(data) => Ok(Metadata {
+                kind: EntryKind::File,
+                len: data.len() as u64,
+                modified: None,
+            }),
+            None if self.contains_dir(&key)? => Ok(Metadata::dir()),
+            None => Err(io::Error::from(ErrorKind::NotFound)),
+        }
+    }
+
+    fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+        let key = key(path)?;
+        self.backend.put(&key, &[])?;
+        Ok(KvFile {
+            inner: Cursor::new(Vec::new()),
+            write: Some((key, Rc::clone(&self.backend) as Rc<dyn KvBackend>)),
+        })
+    }
+
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        if self.backend.delete(&key(path)?)? {
+            Ok(())
+        } else {
+            Err(io::Error::from(ErrorKind::NotFound))
+        }
+    }
+
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let (from, to) = (key(from)?, key(to)?);
+        let data = self
+            .backend
+            .get(&from)?
+            .ok_or_else(|| io::Error::from(ErrorKind::NotFound))?;
+        self.backend.put(&to, &data)?;
+        if from != to {
+            self.backend.delete(&from)?;
+        }
+        Ok(())
+    }
+
+    fn is_read_only(&self) -> bool {
+        false
+    }
+}
+
+// Key of the file at `path`, shared by the stores over key-value databases.
+pub(crate) fn key(path: &Path) -> io::Result<String> {
+    let mut key = String::new();
+    for comp in normalize_path(path).components() {
+        if let Component::Normal(name) = comp {
+            let name = name
+                .to_str()
+                .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "Non UTF-8 path."))?;
+            if !key.is_empty() {
+                key.push('/');
+            }
+            key.push_str(name);
+        }
+    }
+    Ok(key)
+}
+
+// Direct children of a directory, given the keys under it (without the prefix
+// of the directory). Deeper files show up as a single directory.
+pub(crate) fn child_entries<'a, I>(keys: I) -> BTreeMap<String, EntryKind>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut entries = BTreeMap::new();
+    for key in keys {
+        match key.find('/') {
+            Some(i) => entries.insert(key[..i].to_string(), EntryKind::Dir),
+            None => entries.insert(key.to_string(), EntryKind::File),
+        };
+    }
+    entries
+}
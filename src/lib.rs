@@ -3,15 +3,73 @@
 //! Currently supported features include:
 //!
 //! - Access to the local (native) filesystem.
-//! - In-memory filesystems.
-//! - Read from tar, tar.gz, and zip archives.
-//! - Filesystem overlays.
+//! - In-memory filesystems, which can be serialized (`serde` feature),
+//!   including ones keeping every revision of their files.
+//! - Read from tar, tar.gz, and zip archives, and from tar.xz, tar.zst and
+//!   tar.bz2 (`xz`, `zstd` and `bzip2` features), with limits against
+//!   decompression bombs.
+//! - Read from 7z, RAR, cpio and ar archives, including Debian packages (`7z`,
+//!   `rar`, `cpio` and `ar` features).
+//! - Read from Electron asar archives, Doom WAD files, Valve VPKs, Blizzard
+//!   MPQs and Ragnarok Online GRFs (`asar`, `wad`, `vpk`, `mpq` and `grf`
+//!   features).
+//! - Read from ISO 9660 disc images (`iso` feature) and SquashFS images
+//!   (`squashfs` feature).
+//! - Export any store to a tar or zip archive.
+//! - Read and write MiniPak packages, the native archive format of the crate
+//!   (`minipak` feature).
+//! - Memory-mapped archives (`mmap` feature).
+//! - Remote files over HTTP (`http` feature, with a client based on ureq
+//!   behind the `ureq` feature), objects from Amazon S3 buckets (`s3`
+//!   feature), WebDAV shares (`webdav` feature), FTP servers (`ftp`
+//!   feature) and IPFS gateways (`ipfs` feature).
+//! - Mount any backend of the `object_store` crate (`object_store` feature).
+//! - Read and write files stored in SQLite databases (`sqlite` feature) and
+//!   redb databases (`redb` feature).
+//! - Read files from a commit, branch or tag of a Git repository (`git`
+//!   feature).
+//! - Unicode normalization of the paths of in-memory stores and archives
+//!   (`unicode` feature).
+//! - Plug in any key-value database by implementing a small trait.
+//! - Filesystem overlays, including copy-on-write overlays over read-only
+//!   stores.
+//! - Merge stores in tuples, or in lists of stores chosen at runtime.
+//! - In-memory caching of the files of any store.
+//! - Fall back to alternate extensions when files are missing.
+//! - Decompress gzipped versions of missing files (`gzip` feature).
+//! - Decrypt, decompress or preprocess files on open with chains of closures.
+//! - Expand `${VAR}` placeholders in config templates.
+//! - Look up localized versions of files along a chain of locales.
+//! - Redirect old paths of renamed files with aliases, or rewrite the prefix
+//!   of every path.
+//! - Read-only views of writable stores.
+//! - Encrypt the files of any store with AES-256-GCM (`encryption` feature).
+//! - Verify files against manifests signed with ed25519 (`signatures`
+//!   feature), or check their SHA-256 hashes while they are read (`checksums`
+//!   feature).
+//! - Expose only the files accepted by a closure, or matching regular
+//!   expressions (`regex` feature).
+//! - Select files across all mounts with glob patterns.
+//! - Write files to local and in-memory stores, and copy or compare files
+//!   between stores.
+//! - Limit the size and number of the files written to any store, or the
+//!   rate at which its files are read.
+//! - Add latency to the files opened in tests, or make them fail on a
+//!   schedule.
+//! - Record the files opened from any store to a journal, and replay them
+//!   without the original data.
+//! - Deserialize JSON, TOML and RON files (`json`, `toml` and `ron` features).
+//! - Guess the MIME type of files from their contents and extension.
+//! - Asynchronous reads (`async` feature), with native IO provided by tokio or
+//!   async-std (`tokio` and `async-std` features).
+//! - Virtual filesystems shared between threads, with stores mounted while
+//!   other threads read from them.
 //!
 //! ## Case sensitivity
 //!
 //! All implementations of [`Store`] from this crate use **case sensitive**¹
 //! paths. However, you are free to implement custom stores where paths are case
-//! insensitive.
+//! insensitive, or wrap any store in a [`CaseInsensitive`] one.
 //!
 //! ¹ Except maybe [`Local`], which uses [`std::fs`] internally and is subject
 //! to the underlying OS.
@@ -38,34 +96,256 @@
 //! ## Security
 //!
 //! Don't use this crate in applications where security is a critical factor.
-//! [`Local`] in particular might be vulnerable to [directory traversal
-//! attacks][dir], so it's best not to use it directly in a static file server,
-//! for example.
+//! [`Local`] rejects paths that leave its root, and can reject symbolic links
+//! pointing outside of it, but it might still be vulnerable to [directory
+//! traversal attacks][dir] (if files are swapped while they're being opened,
+//! for example), so it's best not to use it directly in a static file server.
 //!
 //! [`std::fs`]: https://doc.rust-lang.org/std/fs/index.html
 //! [`Store`]: ./trait.Store.html
 //! [`Local`]: ./struct.Local.html
+//! [`CaseInsensitive`]: ./struct.CaseInsensitive.html
 //! [dir]: https://en.wikipedia.org/wiki/Directory_traversal_attack
-use std::collections::{BTreeMap, LinkedList};
-use std::path::{Path, PathBuf};
+use std::cell::RefCell;
+use std::path::{Component, Path, PathBuf};
 use std::rc::Rc;
+use std::sync::Arc;
+use std::time::SystemTime;
 use std::{env, fs, io};
 
+use crate::mount::{MountTable, Order};
+
+pub use aliases::Aliases;
+#[cfg(feature = "ar")]
+pub use ar::Ar;
+#[cfg(feature = "asar")]
+pub use asar::Asar;
+#[cfg(feature = "async")]
+pub use async_store::{AsyncEntries, AsyncFile, AsyncStore, AsyncStoreExt};
+pub use cache::Cache;
+pub use case::CaseInsensitive;
+#[cfg(feature = "checksums")]
+pub use checksum::{Checksummed, IntegrityError};
+pub use copy::{copy, CopyOptions, Overwrite};
+#[cfg(feature = "cpio")]
+pub use cpio::Cpio;
+pub use delay::Delay;
+pub use diff::{diff, Diff};
+#[cfg(feature = "encryption")]
+pub use encrypted::Encrypted;
+pub use extensions::Extensions;
+pub use filter::FilterFn;
+#[cfg(feature = "regex")]
+pub use filter::Filtered;
+pub use flaky::Flaky;
+#[cfg(feature = "ftp")]
+pub use ftp::Ftp;
+#[cfg(feature = "git")]
+pub use git::GitStore;
+pub use glob::Glob;
+#[cfg(feature = "grf")]
+pub use grf::Grf;
+#[cfg(feature = "gzip")]
+pub use gzip::Gzipped;
+#[cfg(feature = "http")]
+pub use http::Http;
 //pub use index::{Index, IndexEntries};
-pub use store::{Entries, Entry, EntryKind, Store, StoreExt};
+#[cfg(feature = "ipfs")]
+pub use ipfs::Ipfs;
+#[cfg(feature = "iso")]
+pub use iso::Iso;
+pub use kv::{KvBackend, KvStore};
+#[cfg(any(feature = "tar", feature = "zip"))]
+pub use limits::Limits;
+pub use localized::Localized;
+pub use merge::MergeDyn;
+#[cfg(feature = "minipak")]
+pub use minipak::{MiniPak, MiniPakWriter};
+#[cfg(feature = "mmap")]
+pub use mmap::Mmap;
+#[cfg(feature = "mpq")]
+pub use mpq::Mpq;
+#[cfg(feature = "object_store")]
+pub use object_store::ObjectStore;
+pub use overlay::Overlay;
+pub use quota::Quota;
+#[cfg(feature = "rar")]
+pub use rar::Rar;
+pub use read_only::ReadOnly;
+#[cfg(feature = "redb")]
+pub use redb::Redb;
+pub use replay::{Journal, Recorder, Replayer};
+pub use rewrite::Rewrite;
+#[cfg(feature = "s3")]
+pub use s3::S3;
+#[cfg(feature = "7z")]
+pub use sevenz::SevenZ;
+#[cfg(feature = "sqlite")]
+pub use sqlite::Sqlite;
+#[cfg(feature = "squashfs")]
+pub use squashfs::SquashFs;
+pub use store::{Entries, Entry, EntryKind, Metadata, Store, StoreExt, Walk};
+pub use sync_fs::SyncMiniFs;
 #[cfg(feature = "tar")]
 pub use tar::Tar;
+pub use template::Templates;
+pub use throttle::Throttle;
+pub use transform::Transform;
+#[cfg(feature = "signatures")]
+pub use verified::Verified;
+pub use versioned::Versioned;
+#[cfg(feature = "vpk")]
+pub use vpk::Vpk;
+#[cfg(feature = "wad")]
+pub use wad::Wad;
+#[cfg(feature = "webdav")]
+pub use webdav::WebDav;
 #[cfg(feature = "zip")]
 pub use zip::Zip;
 
+mod aliases;
+/// Ar file storage.
+#[cfg(feature = "ar")]
+pub mod ar;
+/// Asar file storage.
+#[cfg(feature = "asar")]
+pub mod asar;
+/// Asynchronous file storage.
+#[cfg(feature = "async")]
+pub mod async_store;
+#[cfg(any(
+    feature = "asar",
+    feature = "grf",
+    feature = "iso",
+    feature = "minipak",
+    feature = "mpq",
+    feature = "vpk",
+    feature = "wad",
+    feature = "zip"
+))]
+mod bytes;
+mod cache;
+mod case;
+/// SHA-256 checked storage.
+#[cfg(feature = "checksums")]
+pub mod checksum;
+mod copy;
+/// Cpio file storage.
+#[cfg(feature = "cpio")]
+pub mod cpio;
+mod delay;
+mod diff;
+/// Encrypted storage.
+#[cfg(feature = "encryption")]
+pub mod encrypted;
+/// Export stores to archives.
+#[cfg(any(feature = "tar", feature = "zip"))]
+pub mod export;
+mod extensions;
+mod filter;
+mod flaky;
+/// FTP file storage.
+#[cfg(feature = "ftp")]
+pub mod ftp;
+/// Git repository storage.
+#[cfg(feature = "git")]
+pub mod git;
+mod glob;
+/// GRF file storage.
+#[cfg(feature = "grf")]
+pub mod grf;
+#[cfg(feature = "gzip")]
+mod gzip;
+/// HTTP file storage.
+#[cfg(feature = "http")]
+pub mod http;
 // TODO module is hidden for now.
 /// Directory index.
 #[doc(hidden)]
 pub mod index;
+/// IPFS file storage.
+#[cfg(feature = "ipfs")]
+pub mod ipfs;
+/// Disc image storage.
+#[cfg(feature = "iso")]
+pub mod iso;
+/// Key-value database storage.
+pub mod kv;
+#[cfg(any(feature = "tar", feature = "zip"))]
+mod limits;
+mod localized;
+mod merge;
+/// MIME type detection.
+pub mod mime;
+/// MiniPak file storage.
+#[cfg(feature = "minipak")]
+pub mod minipak;
+/// Memory-mapped files.
+#[cfg(feature = "mmap")]
+pub mod mmap;
+mod mount;
+/// MPQ file storage.
+#[cfg(feature = "mpq")]
+pub mod mpq;
+/// Adapter over the `object_store` crate.
+#[cfg(feature = "object_store")]
+pub mod object_store;
+mod overlay;
+/// Storage with a write quota.
+pub mod quota;
+/// RAR file storage.
+#[cfg(feature = "rar")]
+pub mod rar;
+mod read_only;
+/// redb database storage.
+#[cfg(feature = "redb")]
+pub mod redb;
+mod replay;
+mod rewrite;
+/// Amazon S3 object storage.
+#[cfg(feature = "s3")]
+pub mod s3;
+#[cfg(feature = "serde")]
+mod serialize;
+/// 7-Zip file storage.
+#[cfg(feature = "7z")]
+pub mod sevenz;
+/// SQLite database storage.
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+/// SquashFS image storage.
+#[cfg(feature = "squashfs")]
+pub mod squashfs;
 mod store;
+mod sync_fs;
 /// Tar file storage.
 #[cfg(feature = "tar")]
 pub mod tar;
+mod template;
+/// Storage with a limited read rate.
+pub mod throttle;
+#[cfg(any(
+    feature = "zip",
+    feature = "rar",
+    feature = "iso",
+    feature = "s3",
+    feature = "webdav",
+    feature = "ftp"
+))]
+mod time;
+mod transform;
+#[cfg(feature = "signatures")]
+mod verified;
+mod versioned;
+/// VPK file storage.
+#[cfg(feature = "vpk")]
+pub mod vpk;
+/// WAD file storage.
+#[cfg(feature = "wad")]
+pub mod wad;
+/// WebDAV file storage.
+#[cfg(feature = "webdav")]
+pub mod webdav;
 /// Zip file storage.
 #[cfg(feature = "zip")]
 pub mod zip;
@@ -112,7 +392,7 @@ macro_rules! file {
             }
         }
 
-        impl io::Seek for File {
+        impl io::Seek for $enum_name {
             fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
                 match self {
                     $(
@@ -121,91 +401,465 @@ macro_rules! file {
                     )*
                 }
             }
+
+            fn stream_position(&mut self) -> io::Result<u64> {
+                match self {
+                    $(
+                        $(#[$($var_meta)+])*
+                        $enum_name::$var_name(ref mut file) => file.stream_position(),
+                    )*
+                }
+            }
+        }
+
+        impl io::BufRead for $enum_name {
+            fn fill_buf(&mut self) -> io::Result<&[u8]> {
+                match self {
+                    $(
+                        $(#[$($var_meta)+])*
+                        $enum_name::$var_name(ref mut file) => file.fill_buf(),
+                    )*
+                }
+            }
+
+            fn consume(&mut self, amt: usize) {
+                match self {
+                    $(
+                        $(#[$($var_meta)+])*
+                        $enum_name::$var_name(ref mut file) => file.consume(amt),
+                    )*
+                }
+            }
         }
     }
 }
 
 file! {
     /// File you can seek and read from.
+    ///
+    /// Native and user files are buffered, so `File` implements `BufRead`.
     pub enum File {
-        Local(fs::File),
+        Local(io::BufReader<fs::File>),
         Ram(RamFile),
+        // Shared in-memory data
+        Bytes(io::Cursor<Arc<[u8]>>),
         #[cfg(feature = "zip")]
         Zip(zip::ZipEntry),
         #[cfg(feature = "tar")]
         Tar(tar::TarEntry),
+        #[cfg(feature = "7z")]
+        SevenZ(sevenz::SevenZEntry),
+        #[cfg(feature = "rar")]
+        Rar(rar::RarEntry),
+        #[cfg(feature = "iso")]
+        Iso(iso::IsoEntry),
+        #[cfg(feature = "squashfs")]
+        SquashFs(squashfs::SquashFsEntry),
+        #[cfg(feature = "cpio")]
+        Cpio(cpio::CpioEntry),
+        #[cfg(feature = "ar")]
+        Ar(ar::ArEntry),
+        #[cfg(feature = "asar")]
+        Asar(asar::AsarEntry),
+        #[cfg(feature = "wad")]
+        Wad(wad::WadEntry),
+        #[cfg(feature = "vpk")]
+        Vpk(vpk::VpkEntry),
+        #[cfg(feature = "mpq")]
+        Mpq(mpq::MpqEntry),
+        #[cfg(feature = "grf")]
+        Grf(grf::GrfEntry),
+        #[cfg(feature = "minipak")]
+        MiniPak(minipak::MiniPakEntry),
+        #[cfg(feature = "http")]
+        Http(http::HttpEntry),
+        #[cfg(feature = "s3")]
+        S3(s3::S3Object),
+        #[cfg(feature = "ftp")]
+        Ftp(ftp::FtpEntry),
+        #[cfg(feature = "git")]
+        Git(git::GitEntry),
+        #[cfg(feature = "sqlite")]
+        Sqlite(sqlite::SqliteEntry),
+        #[cfg(feature = "redb")]
+        Redb(redb::RedbEntry),
+        Kv(kv::KvFile),
+        #[cfg(feature = "encryption")]
+        Encrypted(encrypted::EncryptedFile),
+        #[cfg(feature = "checksums")]
+        Checksum(checksum::ChecksumFile),
+        Quota(quota::QuotaFile),
+        Throttle(throttle::ThrottleFile),
         // External types are dynamic
-        User(Box<dyn UserFile>),
+        User(io::BufReader<Box<dyn UserFile>>),
+    }
+}
+
+impl File {
+    /// Size of the file in bytes.
+    ///
+    /// Archive entries report their uncompressed size.
+    pub fn len(&mut self) -> io::Result<u64> {
+        match self {
+            File::Local(file) => file.get_ref().metadata().map(|meta| meta.len()),
+            File::Ram(file) => Ok(file.len()),
+            File::Bytes(file) => Ok(file.get_ref().len() as u64),
+            #[cfg(feature = "zip")]
+            File::Zip(file) => Ok(file.len()),
+            #[cfg(feature = "tar")]
+            File::Tar(file) => Ok(file.len()),
+            #[cfg(feature = "7z")]
+            File::SevenZ(file) => Ok(file.len()),
+            #[cfg(feature = "rar")]
+            File::Rar(file) => Ok(file.len()),
+            #[cfg(feature = "iso")]
+            File::Iso(file) => Ok(file.len()),
+            #[cfg(feature = "squashfs")]
+            File::SquashFs(file) => Ok(file.len()),
+            #[cfg(feature = "cpio")]
+            File::Cpio(file) => Ok(file.len()),
+            #[cfg(feature = "ar")]
+            File::Ar(file) => Ok(file.len()),
+            #[cfg(feature = "asar")]
+            File::Asar(file) => Ok(file.len()),
+            #[cfg(feature = "wad")]
+            File::Wad(file) => Ok(file.len()),
+            #[cfg(feature = "vpk")]
+            File::Vpk(file) => Ok(file.len()),
+            #[cfg(feature = "mpq")]
+            File::Mpq(file) => Ok(file.len()),
+            #[cfg(feature = "grf")]
+            File::Grf(file) => Ok(file.len()),
+            #[cfg(feature = "minipak")]
+            File::MiniPak(file) => Ok(file.len()),
+            #[cfg(feature = "http")]
+            File::Http(file) => Ok(file.len()),
+            #[cfg(feature = "s3")]
+            File::S3(file) => Ok(file.len()),
+            #[cfg(feature = "ftp")]
+            File::Ftp(file) => Ok(file.len()),
+            #[cfg(feature = "git")]
+            File::Git(file) => Ok(file.len()),
+            #[cfg(feature = "sqlite")]
+            File::Sqlite(file) => Ok(file.len()),
+            #[cfg(feature = "redb")]
+            File::Redb(file) => Ok(file.len()),
+            File::Kv(file) => Ok(file.len()),
+            #[cfg(feature = "encryption")]
+            File::Encrypted(file) => Ok(file.len()),
+            #[cfg(feature = "checksums")]
+            File::Checksum(file) => file.len(),
+            File::Quota(file) => file.len(),
+            File::Throttle(file) => file.len(),
+            File::User(file) => {
+                use io::Seek;
+                let pos = file.stream_position()?;
+                let len = file.seek(io::SeekFrom::End(0))?;
+                file.seek(io::SeekFrom::Start(pos))?;
+                Ok(len)
+            }
+        }
+    }
+
+    pub fn is_empty(&mut self) -> io::Result<bool> {
+        self.len().map(|len| len == 0)
+    }
+
+    /// Number of bytes left to read from the current position.
+    pub fn remaining(&mut self) -> io::Result<u64> {
+        let len = self.len()?;
+        let pos = io::Seek::stream_position(self)?;
+        Ok(len.saturating_sub(pos))
+    }
+
+    /// Contents of an in-memory file, without copying them.
+    ///
+    /// Returns `None` for other kinds of files.
+    pub fn as_slice(&self) -> Option<&[u8]> {
+        match self {
+            File::Ram(file) => Some(file.as_slice()),
+            File::Bytes(file) => Some(file.get_ref()),
+            _ => None,
+        }
+    }
+
+    /// Contents of the file.
+    ///
+    /// In-memory files return their buffer, without copying it. Other files are
+    /// read from the start.
+    pub fn into_bytes(self) -> io::Result<Arc<[u8]>> {
+        match self {
+            File::Ram(file) => Ok(file.to_bytes()),
+            File::Bytes(file) => Ok(file.into_inner()),
+            mut file => {
+                use io::{Read, Seek};
+                let mut data = Vec::new();
+                file.seek(io::SeekFrom::Start(0))?;
+                file.read_to_end(&mut data)?;
+                Ok(data.into())
+            }
+        }
+    }
+}
+
+/// In-memory file sharing `data`, which isn't copied.
+impl From<Arc<[u8]>> for File {
+    fn from(data: Arc<[u8]>) -> Self {
+        File::Bytes(io::Cursor::new(data))
     }
 }
 
 /// Custom file type.
-pub trait UserFile: std::any::Any + io::Read + io::Seek + Send {}
+pub trait UserFile: std::any::Any + io::Read + io::Seek + Send {
+    /// Writable view of the file.
+    ///
+    /// Files returned by writable stores should override this method. The
+    /// default returns `None`, which makes any write fail.
+    fn as_write(&mut self) -> Option<&mut dyn io::Write> {
+        None
+    }
+}
 
 impl<T: UserFile> From<T> for File {
     fn from(file: T) -> Self {
-        File::User(Box::new(file))
+        File::User(io::BufReader::new(Box::new(file)))
+    }
+}
+
+impl From<fs::File> for File {
+    fn from(file: fs::File) -> Self {
+        File::Local(io::BufReader::new(file))
     }
 }
 
-struct Mount {
-    path: PathBuf,
-    store: Box<dyn Store<File = File>>,
+impl io::Write for File {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        #[allow(unreachable_patterns)]
+        match self {
+            File::Local(file) => file.get_mut().write(buf),
+            File::Ram(file) => file.write(buf),
+            File::Kv(file) => file.write(buf),
+            #[cfg(feature = "encryption")]
+            File::Encrypted(file) => file.write(buf),
+            File::Quota(file) => file.write(buf),
+            #[cfg(feature = "sqlite")]
+            File::Sqlite(file) => file.write(buf),
+            #[cfg(feature = "redb")]
+            File::Redb(file) => file.write(buf),
+            File::User(file) => match file.get_mut().as_write() {
+                Some(file) => file.write(buf),
+                None => Err(store::read_only()),
+            },
+            _ => Err(store::read_only()),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        #[allow(unreachable_patterns)]
+        match self {
+            File::Local(file) => file.get_mut().flush(),
+            File::Ram(file) => file.flush(),
+            File::Kv(file) => file.flush(),
+            #[cfg(feature = "encryption")]
+            File::Encrypted(file) => file.flush(),
+            File::Quota(file) => file.flush(),
+            #[cfg(feature = "sqlite")]
+            File::Sqlite(file) => file.flush(),
+            #[cfg(feature = "redb")]
+            File::Redb(file) => file.flush(),
+            File::User(file) => match file.get_mut().as_write() {
+                Some(file) => file.flush(),
+                None => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
 }
 
 /// Virtual filesystem.
+///
+/// Paths are normalized before they are matched with the mount points, so
+/// `/res/./ui//main.png` is the same file as `/res/ui/main.png`. Relative
+/// paths start at the root, and backslashes are separators on every platform,
+/// like they are in the paths of `Ram`, `Zip` and `Tar` stores.
+///
+/// Paths are resolved by the mount with the longest mount point containing
+/// them, like in native filesystems, so mounting `/` doesn't shadow a mount at
+/// `/res`, even when it's mounted later. Stores mounted at the same point
+/// shadow the ones mounted before them, unless they are given a lower
+/// [priority](#method.mount_with_priority):
+///
+/// ```
+/// use mini_fs::{Local, MiniFs};
+///
+/// let mods = Local::new("./mods");
+/// let base = Local::new("./base");
+///
+/// // `mods` shadows `base`, even though it's mounted first.
+/// let files = MiniFs::new()
+///     .mount_with_priority("/data", mods, 10)
+///     .mount("/data", base);
+/// ```
 pub struct MiniFs {
-    mount: LinkedList<Mount>,
+    mount: MountTable<dyn Store<File = File>>,
+}
+
+/// Where a [`MiniFs`] finds a file, as returned by
+/// [`resolve`](./struct.MiniFs.html#method.resolve).
+///
+/// [`MiniFs`]: ./struct.MiniFs.html
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Resolved {
+    /// Mount point of the store.
+    pub mount: PathBuf,
+    /// Path of the file inside the mounted store.
+    pub path: PathBuf,
+    /// Type name of the store with the file, which is one of the stores of the
+    /// mounted tuple when stores are merged.
+    pub store: &'static str,
 }
 
 impl Store for MiniFs {
     type File = File;
 
+    #[inline]
     fn open_path(&self, path: &Path) -> io::Result<File> {
-        let next = self.mount.iter().rev().find_map(|mnt| {
-            if let Ok(np) = path.strip_prefix(&mnt.path) {
-                Some((np, &mnt.store))
-            } else {
-                None
-            }
-        });
-        if let Some((np, store)) = next {
-            store.open_path(np)
-        } else {
-            Err(io::Error::from(io::ErrorKind::NotFound))
-        }
+        self.mount.open_path(path)
     }
 
-    fn entries_path(&self, path: &Path) -> io::Result<Entries> {
-        // FIXME creating a new PathBuf because otherwise I'm getting lifetime mismatch
-        // errors.
-        let path = path.to_path_buf();
+    #[inline]
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        self.mount.entries_path(path)
+    }
 
-        Ok(Entries::new(self.mount.iter().flat_map(
-            move |m| match path.strip_prefix(&m.path) {
-                Ok(np) => m.store.entries_path(np).unwrap(),
-                Err(_) => Entries::empty(),
-            },
-        )))
+    #[inline]
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        self.mount.metadata_path(path)
+    }
+
+    #[inline]
+    fn create_path(&self, path: &Path) -> io::Result<File> {
+        self.mount.create_path(path)
+    }
+
+    #[inline]
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        self.mount.remove_path(path)
+    }
+
+    #[inline]
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.mount.rename_path(from, to)
+    }
+
+    #[inline]
+    fn is_read_only(&self) -> bool {
+        self.mount.is_read_only()
+    }
+
+    #[inline]
+    fn resolve_path(&self, path: &Path) -> io::Result<&'static str> {
+        self.mount.resolve_path(path)
+    }
+}
+
+impl Default for MiniFs {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl MiniFs {
     pub fn new() -> Self {
         Self {
-            mount: LinkedList::new(),
+            mount: MountTable::new(),
         }
     }
 
-    pub fn mount<P, S, T>(mut self, path: P, store: S) -> Self
+    pub fn mount<P, S, T>(self, path: P, store: S) -> Self
+    where
+        P: Into<PathBuf>,
+        S: Store<File = T> + 'static,
+        T: Into<File>,
+    {
+        self.mount_with_priority(path, store, 0)
+    }
+
+    /// Like [`mount`](#method.mount), but without consuming the `MiniFs`, so
+    /// stores can be mounted and [unmounted](#method.umount) at any time:
+    ///
+    /// ```
+    /// use mini_fs::{Local, MiniFs};
+    ///
+    /// struct Game {
+    ///     files: MiniFs,
+    /// }
+    ///
+    /// impl Game {
+    ///     fn load_mod(&mut self, name: &str) {
+    ///         let store = Local::new(format!("./mods/{}", name));
+    ///         self.files.mount_mut("/", store);
+    ///     }
+    /// }
+    /// ```
+    pub fn mount_mut<P, S, T>(&mut self, path: P, store: S) -> &mut Self
     where
         P: Into<PathBuf>,
         S: Store<File = T> + 'static,
         T: Into<File>,
     {
-        let path = path.into();
-        let store = Box::new(store::MapFile::new(store, |file: T| file.into()));
-        self.mount.push_back(Mount { path, store });
+        let name = std::any::type_name::<S>();
+        self.mount
+            .insert(&path.into(), name, boxed(store), Order::Priority(0));
+        self
+    }
+
+    /// Mount `store` so it shadows the stores mounted at the same point with a
+    /// lower or equal priority, and is shadowed by the ones with a higher
+    /// priority. [`mount`](#method.mount) uses a priority of zero.
+    pub fn mount_with_priority<P, S, T>(mut self, path: P, store: S, priority: i32) -> Self
+    where
+        P: Into<PathBuf>,
+        S: Store<File = T> + 'static,
+        T: Into<File>,
+    {
+        let name = std::any::type_name::<S>();
+        self.mount
+            .insert(&path.into(), name, boxed(store), Order::Priority(priority));
+        self
+    }
+
+    /// Mount `store` so it shadows every store already mounted at the same
+    /// point, whatever their priority.
+    pub fn mount_before<P, S, T>(mut self, path: P, store: S) -> Self
+    where
+        P: Into<PathBuf>,
+        S: Store<File = T> + 'static,
+        T: Into<File>,
+    {
+        let name = std::any::type_name::<S>();
+        self.mount
+            .insert(&path.into(), name, boxed(store), Order::Before);
+        self
+    }
+
+    /// Mount `store` so it's shadowed by every store already mounted at the
+    /// same point, whatever their priority.
+    ///
+    /// Shadowed stores aren't fallbacks: they can't be reached until the stores
+    /// shadowing them are unmounted, even for the files those don't have. To
+    /// fall back to a store, merge it with a tuple or a [`MergeDyn`] instead.
+    ///
+    /// [`MergeDyn`]: ./struct.MergeDyn.html
+    pub fn mount_after<P, S, T>(mut self, path: P, store: S) -> Self
+    where
+        P: Into<PathBuf>,
+        S: Store<File = T> + 'static,
+        T: Into<File>,
+    {
+        let name = std::any::type_name::<S>();
+        self.mount
+            .insert(&path.into(), name, boxed(store), Order::After);
         self
     }
 
@@ -213,21 +867,93 @@ impl MiniFs {
     where
         P: AsRef<Path>,
     {
-        let path = path.as_ref();
-        if let Some(p) = self.mount.iter().rposition(|p| p.path == path) {
-            let mut tail = self.mount.split_off(p);
-            let fs = tail.pop_front().map(|m| m.store);
-            self.mount.append(&mut tail);
-            fs
-        } else {
-            None
-        }
+        self.mount.umount(path.as_ref())
     }
+
+    /// Replace the store of the mount at `path` that resolves paths first,
+    /// keeping its priority, and return the old store.
+    ///
+    /// If nothing is mounted at `path`, `store` is mounted like with
+    /// [`mount_mut`](#method.mount_mut) and `None` is returned.
+    pub fn remount<P, S, T>(&mut self, path: P, store: S) -> Option<Box<dyn Store<File = File>>>
+    where
+        P: Into<PathBuf>,
+        S: Store<File = T> + 'static,
+        T: Into<File>,
+    {
+        let name = std::any::type_name::<S>();
+        self.mount.remount(&path.into(), name, boxed(store))
+    }
+
+    /// Find the mount, and the store in it, that would open the file at `path`,
+    /// without reading it:
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use mini_fs::{Local, MiniFs, Ram};
+    /// use std::path::Path;
+    ///
+    /// let mut patch = Ram::new();
+    /// patch.touch("bar.txt", vec![]);
+    /// let base = Local::new("./tests/local");
+    ///
+    /// let files = MiniFs::new().mount("/textures", (patch, base));
+    /// let resolved = files.resolve("/textures/baz/foobar")?;
+    /// assert_eq!(Path::new("/textures"), resolved.mount);
+    /// assert_eq!(Path::new("baz/foobar"), resolved.path);
+    /// assert_eq!("mini_fs::Local", resolved.store);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// Returns an error of kind `NotFound` if there is no file at `path`.
+    pub fn resolve<P: AsRef<Path>>(&self, path: P) -> io::Result<Resolved> {
+        self.mount.resolve(path.as_ref())
+    }
+
+    /// Iterate over the mount points, the type names of their stores and
+    /// whether those are read-only, in the order they resolve paths:
+    ///
+    /// ```
+    /// use mini_fs::{Local, MiniFs, ReadOnly};
+    ///
+    /// let files = MiniFs::new()
+    ///     .mount("/", Local::new("./assets"))
+    ///     .mount("/saves", ReadOnly::new(Local::new("./saves")));
+    ///
+    /// for (path, store, read_only) in files.mounts() {
+    ///     println!("{} {} read_only={}", path.display(), store, read_only);
+    /// }
+    /// ```
+    pub fn mounts(&self) -> impl Iterator<Item = (&Path, &'static str, bool)> + '_ {
+        self.mount.mounts()
+    }
+}
+
+fn boxed<S, T>(store: S) -> Box<dyn Store<File = File>>
+where
+    S: Store<File = T> + 'static,
+    T: Into<File>,
+{
+    Box::new(store::MapFile::new(store, |file: T| file.into()))
 }
 
 /// Native file store.
+///
+/// Paths are resolved inside the root of the store: absolute paths are
+/// relative to it, and paths with `..` components that would leave it are
+/// rejected with an error of kind `PermissionDenied`. Stores created with
+/// [`allow_traversal`](#method.allow_traversal) join paths to the root as they
+/// are instead.
+///
+/// Symbolic links are followed wherever they point, unless the store is made
+/// to [`contain_symlinks`](#method.contain_symlinks).
 pub struct Local {
     root: PathBuf,
+    // Whether paths are joined to the root without checking them.
+    traversal: bool,
+    // Whether symbolic links must point inside the root.
+    contain: bool,
 }
 
 impl Store for Local {
@@ -238,14 +964,11 @@ impl Store for Local {
             .create(false)
             .read(true)
             .write(false)
-            .open(self.root.join(path))
+            .open(self.native_path(path)?)
     }
 
-    fn entries_path(&self, path: &Path) -> io::Result<Entries> {
-        // FIXME cloned because lifetimes.
-        let root = self.root.clone();
-
-        let entries = fs::read_dir(self.root.join(path))?.map(move |ent| {
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        let entries = fs::read_dir(self.native_path(path)?)?.map(move |ent| {
             let entry = ent?;
             let path = entry
                 .path()
@@ -257,8 +980,6 @@ impl Store for Local {
             // TODO synlinks
             let kind = if file_type.is_dir() {
                 EntryKind::Dir
-            } else if file_type.is_symlink() {
-                EntryKind::File
             } else {
                 EntryKind::File
             };
@@ -271,36 +992,312 @@ impl Store for Local {
 
         Ok(Entries::new(entries))
     }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        let meta = fs::metadata(self.native_path(path)?)?;
+        Ok(Metadata {
+            kind: if meta.is_dir() {
+                EntryKind::Dir
+            } else {
+                EntryKind::File
+            },
+            len: if meta.is_dir() { 0 } else { meta.len() },
+            modified: meta.modified().ok(),
+        })
+    }
+
+    fn create_path(&self, path: &Path) -> io::Result<fs::File> {
+        let path = self.native_path(path)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .write(true)
+            .open(path)
+    }
+
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        fs::remove_file(self.native_path(path)?)
+    }
+
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let (from, to) = (self.native_path(from)?, self.native_path(to)?);
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(from, to)
+    }
+
+    fn is_read_only(&self) -> bool {
+        false
+    }
 }
 
 impl Local {
     pub fn new<P: Into<PathBuf>>(root: P) -> Self {
-        Self { root: root.into() }
+        Self {
+            root: root.into(),
+            traversal: false,
+            contain: false,
+        }
+    }
+
+    /// Join paths to the root without checking them, so absolute paths and
+    /// `..` components can point anywhere in the native filesystem.
+    pub fn allow_traversal(mut self) -> Self {
+        self.traversal = true;
+        self
+    }
+
+    /// Resolve symbolic links, and reject paths that point outside of the root
+    /// with an error of kind `PermissionDenied`.
+    ///
+    /// Paths are resolved every time they are used, which costs a few system
+    /// calls.
+    pub fn contain_symlinks(mut self) -> Self {
+        self.contain = true;
+        self
     }
 
     /// Point to the current working directory.
     pub fn pwd() -> io::Result<Self> {
         Ok(Self::new(env::current_dir()?))
     }
+
+    // Path of a file of the store in the native filesystem.
+    pub(crate) fn native_path(&self, path: &Path) -> io::Result<PathBuf> {
+        if self.traversal {
+            let native = self.root.join(path);
+            if self.contain {
+                self.check_contained(&native)?;
+            }
+            return Ok(native);
+        }
+        let mut native = self.root.clone();
+        let mut depth = 0;
+        for comp in path.components() {
+            match comp {
+                Component::Normal(name) => {
+                    native.push(name);
+                    depth += 1;
+                }
+                Component::ParentDir if depth > 0 => {
+                    native.pop();
+                    depth -= 1;
+                }
+                Component::ParentDir => return Err(outside_root()),
+                _ => {}
+            }
+        }
+        if self.contain {
+            self.check_contained(&native)?;
+        }
+        Ok(native)
+    }
+
+    fn check_contained(&self, native: &Path) -> io::Result<()> {
+        let root = fs::canonicalize(&self.root)?;
+        // Files that are about to be created don't exist yet, so the closest
+        // existing parent is checked instead. Dangling symbolic links can't be
+        // checked at all.
+        let mut target = native;
+        let resolved = loop {
+            match fs::canonicalize(target) {
+                Ok(resolved) => break resolved,
+                Err(ref err) if err.kind() == io::ErrorKind::NotFound => {
+                    if fs::symlink_metadata(target).is_ok() {
+                        return Err(outside_root());
+                    }
+                    match target.parent() {
+                        Some(parent) => target = parent,
+                        None => return Err(outside_root()),
+                    }
+                }
+                Err(err) => return Err(err),
+            }
+        };
+        if resolved.starts_with(root) {
+            Ok(())
+        } else {
+            Err(outside_root())
+        }
+    }
+}
+
+fn outside_root() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        "Path outside of the root of the store.",
+    )
 }
 
 /// In-memory file storage
+///
+/// File contents are shared, so opening a file doesn't copy it, and neither
+/// does cloning the store. Clones are independent stores: files written to one
+/// of them don't show up in the others.
+///
+/// With the `serde` feature, stores can be serialized, as a map from the paths
+/// of their files to their contents. Contents are serialized as bytes, so
+/// binary formats store them as they are:
+///
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use mini_fs::Ram;
+///
+/// let mut save = Ram::new();
+/// save.touch("player.json", &b"{}"[..]);
+///
+/// let json = serde_json::to_string(&save)?;
+/// let save: Ram = serde_json::from_str(&json)?;
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "serde"))]
+/// # fn main() {}
+/// ```
 pub struct Ram {
-    index: index::Index<Rc<[u8]>>,
+    index: Rc<RefCell<index::Index<RamEntry>>>,
+}
+
+#[derive(Clone)]
+struct RamEntry {
+    data: Arc<[u8]>,
+    modified: SystemTime,
+}
+
+impl RamEntry {
+    fn new(data: Arc<[u8]>) -> Self {
+        Self {
+            data,
+            modified: SystemTime::now(),
+        }
+    }
+}
+
+/// Saved state of a [`Ram`] store.
+///
+/// Returned by [`Ram::snapshot`], and shares the contents of the files with
+/// the store, so snapshots only copy the directory tree.
+///
+/// [`Ram`]: ./struct.Ram.html
+/// [`Ram::snapshot`]: ./struct.Ram.html#method.snapshot
+#[derive(Clone)]
+pub struct RamSnapshot {
+    index: index::Index<RamEntry>,
 }
 
 /// In-memory file.
-pub struct RamFile(io::Cursor<Rc<[u8]>>);
+///
+/// Files returned by [`Ram::create_path`] keep their contents in a buffer that
+/// is written back to the store when the file is flushed or dropped.
+///
+/// [`Ram::create_path`]: ./trait.Store.html#method.create_path
+pub struct RamFile(RamFileInner);
+
+enum RamFileInner {
+    Read(io::Cursor<Arc<[u8]>>),
+    Write {
+        cursor: io::Cursor<Vec<u8>>,
+        // Stores the contents back.
+        commit: Box<dyn Fn(Arc<[u8]>)>,
+    },
+}
+
+impl RamFile {
+    pub(crate) fn reader(data: Arc<[u8]>) -> Self {
+        RamFile(RamFileInner::Read(io::Cursor::new(data)))
+    }
+
+    // The (empty) file is committed right away, like `fs::File::create` does.
+    pub(crate) fn writer<F: Fn(Arc<[u8]>) + 'static>(commit: F) -> Self {
+        let mut file = RamFile(RamFileInner::Write {
+            cursor: io::Cursor::new(Vec::new()),
+            commit: Box::new(commit),
+        });
+        let _ = io::Write::flush(&mut file);
+        file
+    }
+
+    fn len(&self) -> u64 {
+        self.as_slice().len() as u64
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        match self.0 {
+            RamFileInner::Read(ref cursor) => cursor.get_ref(),
+            RamFileInner::Write { ref cursor, .. } => cursor.get_ref(),
+        }
+    }
+
+    // Files being written are copied.
+    fn to_bytes(&self) -> Arc<[u8]> {
+        match self.0 {
+            RamFileInner::Read(ref cursor) => Arc::clone(cursor.get_ref()),
+            RamFileInner::Write { ref cursor, .. } => cursor.get_ref().as_slice().into(),
+        }
+    }
+}
 
 impl io::Read for RamFile {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        self.0.read(buf)
+        match self.0 {
+            RamFileInner::Read(ref mut cursor) => cursor.read(buf),
+            RamFileInner::Write { ref mut cursor, .. } => cursor.read(buf),
+        }
     }
 }
 
 impl io::Seek for RamFile {
     fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
-        self.0.seek(pos)
+        match self.0 {
+            RamFileInner::Read(ref mut cursor) => cursor.seek(pos),
+            RamFileInner::Write { ref mut cursor, .. } => cursor.seek(pos),
+        }
+    }
+}
+
+impl io::BufRead for RamFile {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        match self.0 {
+            RamFileInner::Read(ref mut cursor) => cursor.fill_buf(),
+            RamFileInner::Write { ref mut cursor, .. } => cursor.fill_buf(),
+        }
+    }
+
+    fn consume(&mut self, amt: usize) {
+        match self.0 {
+            RamFileInner::Read(ref mut cursor) => cursor.consume(amt),
+            RamFileInner::Write { ref mut cursor, .. } => cursor.consume(amt),
+        }
+    }
+}
+
+impl io::Write for RamFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.0 {
+            RamFileInner::Read(_) => Err(store::read_only()),
+            RamFileInner::Write { ref mut cursor, .. } => cursor.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if let RamFileInner::Write {
+            ref cursor,
+            ref commit,
+        } = self.0
+        {
+            commit(cursor.get_ref().as_slice().into());
+        }
+        Ok(())
+    }
+}
+
+impl Drop for RamFile {
+    fn drop(&mut self) {
+        let _ = io::Write::flush(self);
     }
 }
 
@@ -308,46 +1305,206 @@ impl Store for Ram {
     type File = RamFile;
 
     fn open_path(&self, path: &Path) -> io::Result<Self::File> {
-        match self.index.get(path) {
-            Some(file) => Ok(RamFile(io::Cursor::new(Rc::clone(file)))),
+        match self.index.borrow().get(path) {
+            Some(file) => Ok(RamFile::reader(Arc::clone(&file.data))),
             None => Err(io::Error::from(io::ErrorKind::NotFound)),
         }
     }
 
-    fn entries_path(&self, path: &Path) -> io::Result<Entries> {
-        Ok(Entries::new(self.index.entries(path).map(|ent| {
-            Ok(Entry {
-                name: ent.name.to_os_string(),
-                kind: ent.kind,
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        // Collected because the index can't stay borrowed.
+        let entries: Vec<_> = self
+            .index
+            .borrow()
+            .entries(path)
+            .map(|ent| {
+                Ok(Entry {
+                    name: ent.name.to_os_string(),
+                    kind: ent.kind,
+                })
+            })
+            .collect();
+        Ok(Entries::new(entries))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        let index = self.index.borrow();
+        if let Some(file) = index.get(path) {
+            Ok(Metadata {
+                kind: EntryKind::File,
+                len: file.data.len() as u64,
+                modified: Some(file.modified),
             })
-        })))
+        } else if index.contains_dir(path) {
+            Ok(Metadata::dir())
+        } else {
+            Err(io::Error::from(io::ErrorKind::NotFound))
+        }
+    }
+
+    fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+        check_file_path(&self.index.borrow(), path)?;
+        let path = path.to_path_buf();
+        let index = Rc::clone(&self.index);
+        Ok(RamFile::writer(move |data| {
+            index.borrow_mut().insert(path.clone(), RamEntry::new(data))
+        }))
+    }
+
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        match self.index.borrow_mut().remove(path) {
+            Some(_) => Ok(()),
+            None => Err(io::Error::from(io::ErrorKind::NotFound)),
+        }
+    }
+
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let mut index = self.index.borrow_mut();
+        if !index.contains(from) {
+            return Err(io::Error::from(io::ErrorKind::NotFound));
+        }
+        check_file_path(&index, to)?;
+        if let Some(file) = index.remove(from) {
+            index.insert(to.to_path_buf(), file);
+        }
+        Ok(())
+    }
+
+    fn is_read_only(&self) -> bool {
+        false
+    }
+}
+
+// Fails if a file can't be stored at `path`, because there's a directory there
+// or a file in place of one of its parents.
+fn check_file_path(index: &index::Index<RamEntry>, path: &Path) -> io::Result<()> {
+    if index.contains_dir(path) {
+        return Err(io::Error::from(io::ErrorKind::IsADirectory));
+    }
+    if path.ancestors().skip(1).any(|dir| index.contains(dir)) {
+        return Err(io::Error::from(io::ErrorKind::NotADirectory));
+    }
+    Ok(())
+}
+
+impl Clone for Ram {
+    fn clone(&self) -> Self {
+        Self {
+            index: Rc::new(RefCell::new(self.index.borrow().clone())),
+        }
+    }
+}
+
+impl Default for Ram {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl Ram {
     pub fn new() -> Self {
         Self {
-            index: index::Index::new(),
+            index: Rc::new(RefCell::new(index::Index::new())),
         }
     }
 
     pub fn clear(&mut self) {
-        self.index.clear();
+        self.index.borrow_mut().clear();
+    }
+
+    /// Save the current files, to [`restore`](#method.restore) them later.
+    pub fn snapshot(&self) -> RamSnapshot {
+        RamSnapshot {
+            index: self.index.borrow().clone(),
+        }
+    }
+
+    /// Replace all the files with the ones of a snapshot.
+    ///
+    /// Files still open for writing are stored again when they are flushed or
+    /// dropped, even if they were created after the snapshot.
+    pub fn restore(&mut self, snapshot: RamSnapshot) {
+        *self.index.borrow_mut() = snapshot.index;
     }
 
     pub fn rm<P: AsRef<Path>>(&mut self, path: P) {
-        // TODO
+        self.index.borrow_mut().remove(path);
     }
 
     pub fn touch<P, F>(&mut self, path: P, file: F)
     where
         P: Into<PathBuf>,
-        F: Into<Rc<[u8]>>,
+        F: Into<Arc<[u8]>>,
     {
-        self.index.insert(path.into(), file.into());
+        self.index
+            .borrow_mut()
+            .insert(path.into(), RamEntry::new(file.into()));
+    }
+
+    /// Load every file of a local directory, recursively.
+    pub fn from_dir<P: Into<PathBuf>>(path: P) -> io::Result<Self> {
+        Self::from_store(&Local::new(path))
+    }
+
+    /// Load the files of a local directory matching a glob `pattern`, as
+    /// understood by [`StoreExt::glob`](./trait.StoreExt.html#method.glob).
+    pub fn from_dir_glob<P: Into<PathBuf>>(path: P, pattern: &str) -> io::Result<Self> {
+        let local = Local::new(path);
+        let paths = local.glob(pattern)?;
+        Self::load(&local, paths)
+    }
+
+    /// Load every file of another store, such as an archive, so later opens
+    /// don't have to read or decompress it again.
+    ///
+    /// Only the files that can be listed are loaded, so archives must have
+    /// been indexed.
+    pub fn from_store<S>(store: &S) -> io::Result<Self>
+    where
+        S: Store,
+        S::File: io::Read,
+    {
+        Self::load(store, store.walk(""))
+    }
+
+    /// Load every file of a tar archive.
+    #[cfg(feature = "tar")]
+    pub fn from_tar<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_store(&Tar::open(path)?.index()?)
+    }
+
+    /// Load every file of a zip archive.
+    #[cfg(feature = "zip")]
+    pub fn from_zip<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_store(&Zip::open(path)?.index()?)
+    }
+
+    fn load<S, I>(store: &S, paths: I) -> io::Result<Self>
+    where
+        S: Store,
+        S::File: io::Read,
+        I: IntoIterator<Item = io::Result<PathBuf>>,
+    {
+        let mut ram = Self::new();
+        for path in paths {
+            let path = path?;
+            let mut data = Vec::new();
+            io::Read::read_to_end(&mut store.open_path(&path)?, &mut data)?;
+            ram.touch(path, data);
+        }
+        Ok(ram)
     }
 
     pub fn index(self) -> Self {
         self
     }
+
+    /// Look up paths by their Unicode normalization form C, so names in
+    /// composed and decomposed forms find the same files.
+    #[cfg(feature = "unicode")]
+    pub fn unicode_normalized(self) -> Self {
+        let index = std::mem::take(&mut *self.index.borrow_mut());
+        *self.index.borrow_mut() = index.unicode_normalized();
+        self
+    }
 }
@@ -0,0 +1,143 @@
+#[cfg(feature = "tar")]
+use std::io::Read;
+use std::io::{self, ErrorKind};
+
+/// Limits on the archives read by [`Zip`] and [`Tar`] stores, so archives from
+/// untrusted sources can't exhaust memory when they are decompressed.
+///
+/// There are no limits by default:
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::{Limits, Zip};
+///
+/// let limits = Limits::new()
+///     .max_file_size(64 << 20)
+///     .max_ratio(100)
+///     .max_entries(10_000)
+///     .max_total_size(1 << 30);
+/// let mods = Zip::open("user-mod.zip")?.limits(limits).index()?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Files and archives exceeding them fail with an error of kind
+/// `FileTooLarge`.
+///
+/// [`Zip`]: ./struct.Zip.html
+/// [`Tar`]: ./struct.Tar.html
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Limits {
+    max_file_size: u64,
+    max_ratio: u64,
+    max_entries: usize,
+    max_total_size: u64,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_file_size: u64::MAX,
+            max_ratio: u64::MAX,
+            max_entries: usize::MAX,
+            max_total_size: u64::MAX,
+        }
+    }
+}
+
+impl Limits {
+    /// No limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maximum uncompressed size of a file, in bytes.
+    pub fn max_file_size(mut self, len: u64) -> Self {
+        self.max_file_size = len;
+        self
+    }
+
+    /// Maximum ratio between the uncompressed and the compressed size of data.
+    ///
+    /// Zip files are compressed one by one, so the ratio applies to each file.
+    /// Compressed tar archives are compressed as a whole, so it applies to the
+    /// archive, which is never decompressed past the ratio.
+    pub fn max_ratio(mut self, ratio: u64) -> Self {
+        self.max_ratio = ratio;
+        self
+    }
+
+    /// Maximum number of files and directories in an indexed archive.
+    pub fn max_entries(mut self, entries: usize) -> Self {
+        self.max_entries = entries;
+        self
+    }
+
+    /// Maximum uncompressed size of all the files in an indexed archive, in
+    /// bytes.
+    pub fn max_total_size(mut self, len: u64) -> Self {
+        self.max_total_size = len;
+        self
+    }
+
+    pub(crate) fn check_file_size(&self, len: u64) -> io::Result<()> {
+        if len > self.max_file_size {
+            return Err(too_large("File is larger than the size limit."));
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "zip")]
+    pub(crate) fn check_ratio(&self, len: u64, compressed: u64) -> io::Result<()> {
+        if len > compressed.saturating_mul(self.max_ratio) {
+            return Err(too_large("File exceeds the compression ratio limit."));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_entries(&self, entries: usize) -> io::Result<()> {
+        if entries > self.max_entries {
+            return Err(too_large("Archive has more entries than the limit."));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_total_size(&self, len: u64) -> io::Result<()> {
+        if len > self.max_total_size {
+            return Err(too_large("Archive is larger than the size limit."));
+        }
+        Ok(())
+    }
+
+    // Decompressed data of an archive of `compressed` bytes, failing once it
+    // exceeds the ratio.
+    #[cfg(feature = "tar")]
+    pub(crate) fn decompressed<R: Read>(&self, inner: R, compressed: u64) -> Decompressed<R> {
+        Decompressed {
+            inner,
+            left: compressed.saturating_mul(self.max_ratio),
+        }
+    }
+}
+
+#[cfg(feature = "tar")]
+pub(crate) struct Decompressed<R> {
+    inner: R,
+    left: u64,
+}
+
+#[cfg(feature = "tar")]
+impl<R: Read> Read for Decompressed<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        match self.left.checked_sub(n as u64) {
+            Some(left) => self.left = left,
+            None => return Err(too_large("Archive exceeds the compression ratio limit.")),
+        }
+        Ok(n)
+    }
+}
+
+fn too_large(msg: &str) -> io::Error {
+    io::Error::new(ErrorKind::FileTooLarge, msg)
+}
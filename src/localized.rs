@@ -0,0 +1,117 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::store::Store;
+use crate::{Entries, Metadata};
+
+/// Store that looks up localized versions of files first.
+///
+/// Files are looked up in a directory named after each locale of a chain, in
+/// order, next to the requested file, and then at the requested path itself:
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{Local, Localized};
+///
+/// let dialog = Localized::new(Local::new("./dialog"), &["pt-BR", "pt", "en"]);
+///
+/// // opens the first of `pt-BR/intro.txt`, `pt/intro.txt`, `en/intro.txt` and
+/// // `intro.txt` that exists
+/// let intro = dialog.open("intro.txt")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Remarks
+///
+/// Only opening files and reading their metadata look for localized versions.
+/// Listing, creating, removing and renaming use the requested paths as they
+/// are.
+pub struct Localized<S> {
+    store: S,
+    locales: Vec<String>,
+}
+
+impl<S> Localized<S> {
+    /// Look up files of `store` in the directories of `locales` first, from the
+    /// most to the least specific.
+    pub fn new<I>(store: S, locales: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let locales = locales
+            .into_iter()
+            .map(|locale| locale.as_ref().to_string())
+            .collect();
+        Self { store, locales }
+    }
+
+    pub fn locales(&self) -> &[String] {
+        &self.locales
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.store
+    }
+
+    pub fn into_inner(self) -> S {
+        self.store
+    }
+
+    // Calls `f` with each localized path and then with `path`, until it finds
+    // something.
+    fn localize<T, F>(&self, path: &Path, f: F) -> io::Result<T>
+    where
+        F: Fn(&Path) -> io::Result<T>,
+    {
+        if let (Some(dir), Some(name)) = (path.parent(), path.file_name()) {
+            for locale in &self.locales {
+                let localized: PathBuf = dir.join(locale).join(name);
+                match f(&localized) {
+                    Err(ref err) if err.kind() == io::ErrorKind::NotFound => {}
+                    res => return res,
+                }
+            }
+        }
+        f(path)
+    }
+}
+
+impl<S: Store> Store for Localized<S> {
+    type File = S::File;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        self.localize(path, |path| self.store.open_path(path))
+    }
+
+    #[inline]
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        self.store.entries_path(path)
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        self.localize(path, |path| self.store.metadata_path(path))
+    }
+
+    #[inline]
+    fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+        self.store.create_path(path)
+    }
+
+    #[inline]
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        self.store.remove_path(path)
+    }
+
+    #[inline]
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.store.rename_path(from, to)
+    }
+
+    #[inline]
+    fn is_read_only(&self) -> bool {
+        self.store.is_read_only()
+    }
+}
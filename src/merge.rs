@@ -0,0 +1,128 @@
+use std::io;
+use std::path::Path;
+
+use crate::store::{MapFile, Store};
+use crate::{Entries, File, Metadata};
+
+/// Merge of stores chosen at runtime.
+///
+/// Works like a tuple of stores, each one shadowing the ones after it, but
+/// stores can be of any type and are added while the program runs, for example
+/// from a list of mods in a config file:
+///
+/// ```no_run
+/// use mini_fs::{Local, MergeDyn, MiniFs, Zip};
+///
+/// let mut data = MergeDyn::new();
+/// data.push_back(Local::new("./data"));
+/// for name in &["hd-textures.zip", "fixes.zip"] {
+///     if let Ok(zip) = Zip::open(format!("./mods/{}", name)) {
+///         data.push_front(zip);
+///     }
+/// }
+///
+/// let files = MiniFs::new().mount("/data", data);
+/// ```
+#[derive(Default)]
+pub struct MergeDyn {
+    stores: Vec<Box<dyn Store<File = File>>>,
+}
+
+impl MergeDyn {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a store shadowing all the others.
+    pub fn push_front<S, T>(&mut self, store: S)
+    where
+        S: Store<File = T> + 'static,
+        T: Into<File>,
+    {
+        self.insert(0, store);
+    }
+
+    /// Add a store shadowed by all the others.
+    pub fn push_back<S, T>(&mut self, store: S)
+    where
+        S: Store<File = T> + 'static,
+        T: Into<File>,
+    {
+        self.insert(self.stores.len(), store);
+    }
+
+    /// Add a store at `index`, shadowing the stores after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of stores.
+    pub fn insert<S, T>(&mut self, index: usize, store: S)
+    where
+        S: Store<File = T> + 'static,
+        T: Into<File>,
+    {
+        let store = MapFile::new(store, |file: T| file.into());
+        self.stores.insert(index, Box::new(store));
+    }
+
+    /// Remove the store at `index`, unlike `remove`, which removes a file.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove_store(&mut self, index: usize) -> Box<dyn Store<File = File>> {
+        self.stores.remove(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.stores.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stores.is_empty()
+    }
+}
+
+impl Store for MergeDyn {
+    type File = File;
+
+    #[inline]
+    fn open_path(&self, path: &Path) -> io::Result<File> {
+        self.stores[..].open_path(path)
+    }
+
+    #[inline]
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        self.stores[..].entries_path(path)
+    }
+
+    #[inline]
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        self.stores[..].metadata_path(path)
+    }
+
+    #[inline]
+    fn create_path(&self, path: &Path) -> io::Result<File> {
+        self.stores[..].create_path(path)
+    }
+
+    #[inline]
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        self.stores[..].remove_path(path)
+    }
+
+    #[inline]
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.stores[..].rename_path(from, to)
+    }
+
+    #[inline]
+    fn is_read_only(&self) -> bool {
+        self.stores[..].is_read_only()
+    }
+
+    #[inline]
+    fn resolve_path(&self, path: &Path) -> io::Result<&'static str> {
+        self.stores[..].resolve_path(path)
+    }
+}
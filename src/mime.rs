@@ -0,0 +1,164 @@
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+// Enough for every signature below, including the one of tar archives.
+const SNIFF_LEN: usize = 512;
+
+/// Guess the MIME type of a file from its contents and its path:
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{mime, MiniFs, Ram};
+///
+/// let mut ram = Ram::new();
+/// ram.touch("logo.png", &b"\x89PNG\r\n\x1a\n"[..]);
+/// ram.touch("style.css", &b"body {}"[..]);
+/// let files = MiniFs::new().mount("/static", ram);
+///
+/// let mut file = files.open("/static/logo.png")?;
+/// assert_eq!("image/png", mime::guess("/static/logo.png", &mut file)?);
+/// let mut file = files.open("/static/style.css")?;
+/// assert_eq!("text/css", mime::guess("/static/style.css", &mut file)?);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// The first bytes of the file are matched with the signatures of common
+/// formats first, so a JPEG saved as `texture.png` is still `image/jpeg`. The
+/// extension is used for formats without a signature, like text formats, and
+/// for formats built on zip archives. Files that match neither are
+/// `text/plain` if their first bytes are UTF-8 text, and
+/// `application/octet-stream` otherwise.
+///
+/// The file is seeked back to where it was, so it can be read afterwards.
+pub fn guess<P, R>(path: P, file: &mut R) -> io::Result<&'static str>
+where
+    P: AsRef<Path>,
+    R: Read + Seek,
+{
+    let start = file.stream_position()?;
+    let mut buf = Vec::with_capacity(SNIFF_LEN);
+    file.by_ref().take(SNIFF_LEN as u64).read_to_end(&mut buf)?;
+    file.seek(SeekFrom::Start(start))?;
+
+    let ext = from_extension(path.as_ref());
+    Ok(match (sniff(&buf), ext) {
+        (Some("application/zip"), Some(ext)) => ext,
+        (Some(mime), _) => mime,
+        (None, Some(ext)) => ext,
+        (None, None) if is_text(&buf) => "text/plain",
+        (None, None) => "application/octet-stream",
+    })
+}
+
+/// Guess the MIME type of a file from the extension of its path only,
+/// ignoring ASCII case.
+pub fn from_extension<P: AsRef<Path>>(path: P) -> Option<&'static str> {
+    let ext = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+    Some(match ext.as_str() {
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "js" | "mjs" => "text/javascript",
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "txt" => "text/plain",
+        "csv" => "text/csv",
+        "md" => "text/markdown",
+        "toml" => "application/toml",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "ico" => "image/vnd.microsoft.icon",
+        "tif" | "tiff" => "image/tiff",
+        "wav" => "audio/wav",
+        "mp3" => "audio/mpeg",
+        "ogg" => "audio/ogg",
+        "flac" => "audio/flac",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "pdf" => "application/pdf",
+        "wasm" => "application/wasm",
+        "zip" => "application/zip",
+        "jar" => "application/java-archive",
+        "epub" => "application/epub+zip",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "gz" => "application/gzip",
+        "tar" => "application/x-tar",
+        "7z" => "application/x-7z-compressed",
+        "xz" => "application/x-xz",
+        "zst" => "application/zstd",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        _ => return None,
+    })
+}
+
+fn sniff(buf: &[u8]) -> Option<&'static str> {
+    let at = |offset: usize, magic: &[u8]| buf.get(offset..offset + magic.len()) == Some(magic);
+    Some(if at(0, b"\x89PNG\r\n\x1a\n") {
+        "image/png"
+    } else if at(0, b"\xff\xd8\xff") {
+        "image/jpeg"
+    } else if at(0, b"GIF87a") || at(0, b"GIF89a") {
+        "image/gif"
+    } else if at(0, b"RIFF") && at(8, b"WEBP") {
+        "image/webp"
+    } else if at(0, b"RIFF") && at(8, b"WAVE") {
+        "audio/wav"
+    } else if at(0, b"BM") && buf.len() >= 14 {
+        "image/bmp"
+    } else if at(0, b"\0\0\x01\0") {
+        "image/vnd.microsoft.icon"
+    } else if at(0, b"II*\0") || at(0, b"MM\0*") {
+        "image/tiff"
+    } else if at(0, b"ID3") || at(0, b"\xff\xfb") {
+        "audio/mpeg"
+    } else if at(0, b"OggS") {
+        "audio/ogg"
+    } else if at(0, b"fLaC") {
+        "audio/flac"
+    } else if at(4, b"ftyp") {
+        "video/mp4"
+    } else if at(0, b"\x1a\x45\xdf\xa3") {
+        "video/webm"
+    } else if at(0, b"%PDF-") {
+        "application/pdf"
+    } else if at(0, b"\0asm") {
+        "application/wasm"
+    } else if at(0, b"PK\x03\x04") || at(0, b"PK\x05\x06") {
+        "application/zip"
+    } else if at(0, b"\x1f\x8b") {
+        "application/gzip"
+    } else if at(257, b"ustar") {
+        "application/x-tar"
+    } else if at(0, b"7z\xbc\xaf\x27\x1c") {
+        "application/x-7z-compressed"
+    } else if at(0, b"\xfd7zXZ\0") {
+        "application/x-xz"
+    } else if at(0, b"\x28\xb5\x2f\xfd") {
+        "application/zstd"
+    } else if at(0, b"wOFF") {
+        "font/woff"
+    } else if at(0, b"wOF2") {
+        "font/woff2"
+    } else {
+        return None;
+    })
+}
+
+// The sniffed bytes may end in the middle of a character.
+fn is_text(buf: &[u8]) -> bool {
+    let valid = match std::str::from_utf8(buf) {
+        Ok(_) => buf,
+        Err(err) if err.error_len().is_none() => &buf[..err.valid_up_to()],
+        Err(_) => return false,
+    };
+    !valid.contains(&0)
+}
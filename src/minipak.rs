@@ -0,0 +1,449 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+use std::fs;
+use std::io::{self, BufRead, Cursor, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Component, Path};
+
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::{Compression, Crc};
+
+use crate::bytes::{le16, le32, le64};
+use crate::index::normalize_path;
+use crate::store::{Store, StoreExt};
+use crate::{Entries, Entry, EntryKind, Metadata};
+
+const MAGIC: &[u8; 8] = b"MINIPAK\0";
+const VERSION: u32 = 1;
+const HEADER: u64 = 24;
+
+const STORED: u8 = 0;
+const DEFLATE: u8 = 1;
+
+/// MiniPak store.
+///
+/// The index of the package is read when the store is created. Files are
+/// looked up with a binary search and their checksum is verified when they are
+/// read. Packages are created with a [`MiniPakWriter`].
+///
+/// # Format
+///
+/// All integers are little endian. A package starts with a 24 byte header: the
+/// `MINIPAK\0` magic, the format version (`u32`, 1), the number of files
+/// (`u32`) and the position of the index (`u64`). The data of the files comes
+/// next, followed by the index.
+///
+/// The index holds one record per file, sorted by path: the length of the path
+/// (`u16`), the path itself (UTF-8, with `/` separators), the compression
+/// method (`u8`, 0 for none and 1 for deflate), the CRC-32 of the uncompressed
+/// data (`u32`), and the position, stored length and uncompressed length of the
+/// data (`u64` each). Directories are not stored, they are implied by the paths
+/// of their files.
+///
+/// # Remarks
+///
+/// When used with a `std::fs::File`, the file will remain open for the lifetime
+/// of the MiniPak.
+///
+/// [`MiniPakWriter`]: struct.MiniPakWriter.html
+pub struct MiniPak<T: Read + Seek> {
+    inner: RefCell<T>,
+    // sorted by path
+    index: Vec<MiniPakIndexEntry>,
+}
+
+struct MiniPakIndexEntry {
+    path: String,
+    compression: u8,
+    crc: u32,
+    pos: u64,
+    stored: u64,
+    meta: Metadata,
+}
+
+/// Entry in the MiniPak.
+pub struct MiniPakEntry {
+    inner: Cursor<Box<[u8]>>,
+}
+
+impl MiniPakEntry {
+    pub(crate) fn len(&self) -> u64 {
+        self.inner.get_ref().len() as u64
+    }
+}
+
+impl Read for MiniPakEntry {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl BufRead for MiniPakEntry {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+impl Seek for MiniPakEntry {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl MiniPak<fs::File> {
+    /// Open a file from the native filesystem.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = fs::OpenOptions::new()
+            .read(true)
+            .write(false)
+            .create(false)
+            .open(path)?;
+        Self::new(file)
+    }
+}
+
+impl<T: Read + Seek> MiniPak<T> {
+    /// Read the index of the package.
+    pub fn new(mut inner: T) -> io::Result<Self> {
+        let mut header = [0; HEADER as usize];
+        inner.seek(SeekFrom::Start(0))?;
+        if inner.read_exact(&mut header).is_err() || &header[..8] != MAGIC {
+            return Err(invalid("Not a MiniPak file."));
+        }
+        if le32(&header[8..12])? != VERSION {
+            return Err(invalid("Unsupported MiniPak version."));
+        }
+        let count = le32(&header[12..16])? as usize;
+        inner.seek(SeekFrom::Start(le64(&header[16..24])?))?;
+        let mut data = Vec::new();
+        inner.read_to_end(&mut data)?;
+
+        let mut data = &data[..];
+        let mut index: Vec<MiniPakIndexEntry> = Vec::with_capacity(count.min(data.len() / 31));
+        for _ in 0..count {
+            let entry = record(&mut data).ok_or_else(|| invalid("Truncated MiniPak index."))?;
+            // lookups rely on the order of the index
+            if let Some(last) = index.last() {
+                if last.path >= entry.path {
+                    return Err(invalid("Unsorted MiniPak index."));
+                }
+            }
+            index.push(entry);
+        }
+
+        Ok(Self {
+            inner: RefCell::new(inner),
+            index,
+        })
+    }
+
+    fn get(&self, path: &Path) -> Option<&MiniPakIndexEntry> {
+        let key = key(path)?;
+        self.index
+            .binary_search_by(|entry| entry.path.as_str().cmp(&key))
+            .ok()
+            .map(|i| &self.index[i])
+    }
+
+    // Records of the files below the directory, which are contiguous in the
+    // index because they all share the same prefix.
+    fn dir(&self, path: &Path) -> Option<(usize, &[MiniPakIndexEntry])> {
+        let mut prefix = key(path)?;
+        if !prefix.is_empty() {
+            prefix.push('/');
+        }
+        let start = self
+            .index
+            .partition_point(|entry| entry.path.as_str() < prefix.as_str());
+        let len = self.index[start..]
+            .iter()
+            .take_while(|entry| entry.path.starts_with(&prefix))
+            .count();
+        if len == 0 && !prefix.is_empty() {
+            return None;
+        }
+        Some((prefix.len(), &self.index[start..start + len]))
+    }
+}
+
+impl<T: Read + Seek> Store for MiniPak<T> {
+    type File = MiniPakEntry;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        let entry = match self.get(path) {
+            Some(entry) => entry,
+            None => return Err(io::Error::from(ErrorKind::NotFound)),
+        };
+        let mut stored = Vec::new();
+        {
+            let mut file = self.inner.borrow_mut();
+            file.seek(SeekFrom::Start(entry.pos))?;
+            (&mut *file).take(entry.stored).read_to_end(&mut stored)?;
+        }
+        let data = match entry.compression {
+            STORED => stored,
+            DEFLATE => {
+                // The length is only trusted once the data is decompressed.
+                let mut data = Vec::new();
+                DeflateDecoder::new(&stored[..])
+                    .take(entry.meta.len.saturating_add(1))
+                    .read_to_end(&mut data)?;
+                if data.len() as u64 != entry.meta.len {
+                    return Err(invalid("MiniPak length mismatch."));
+                }
+                data
+            }
+            _ => {
+                return Err(io::Error::new(
+                    ErrorKind::Unsupported,
+                    "Unsupported MiniPak compression method.",
+                ))
+            }
+        };
+        if data.len() as u64 != entry.meta.len {
+            return Err(io::Error::from(ErrorKind::UnexpectedEof));
+        }
+        let mut crc = Crc::new();
+        crc.update(&data);
+        if crc.sum() != entry.crc {
+            return Err(invalid("MiniPak checksum mismatch."));
+        }
+        Ok(MiniPakEntry {
+            inner: Cursor::new(data.into()),
+        })
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        let (prefix, files) = match self.dir(path) {
+            Some(dir) => dir,
+            None => return Ok(Entries::empty()),
+        };
+        let mut last: Option<&str> = None;
+        Ok(Entries::new(files.iter().filter_map(move |entry| {
+            let rest = &entry.path[prefix..];
+            let (name, kind) = match rest.find('/') {
+                Some(i) => (&rest[..i], EntryKind::Dir),
+                None => (rest, EntryKind::File),
+            };
+            // files of the same subdirectory follow each other
+            if last == Some(name) {
+                return None;
+            }
+            last = Some(name);
+            let name = name.into();
+            Some(Ok(Entry { name, kind }))
+        })))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        if let Some(entry) = self.get(path) {
+            Ok(entry.meta.clone())
+        } else if self.dir(path).is_some() {
+            Ok(Metadata::dir())
+        } else {
+            Err(io::Error::from(ErrorKind::NotFound))
+        }
+    }
+}
+
+/// MiniPak package writer.
+///
+/// File data is written as it is added, and the index when the writer is
+/// finished. Files are compressed with deflate, unless that doesn't make them
+/// smaller.
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{Local, MiniPak, MiniPakWriter};
+/// use std::io::Cursor;
+///
+/// let mut writer = MiniPakWriter::new(Cursor::new(Vec::new()))?;
+/// writer.add_store(&Local::new("./tests/local"), "./")?;
+/// let pak = MiniPak::new(writer.finish()?)?;
+///
+/// assert!(pak.is_file("baz/foobar"));
+/// # Ok(())
+/// # }
+/// ```
+pub struct MiniPakWriter<W: Write + Seek> {
+    inner: W,
+    // records by path, so they are written in order
+    index: BTreeMap<String, Record>,
+    pos: u64,
+}
+
+struct Record {
+    compression: u8,
+    crc: u32,
+    pos: u64,
+    stored: u64,
+    len: u64,
+}
+
+impl MiniPakWriter<fs::File> {
+    /// Create a file in the native filesystem.
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::new(fs::File::create(path)?)
+    }
+}
+
+impl<W: Write + Seek> MiniPakWriter<W> {
+    /// Start a package at the beginning of `inner`.
+    pub fn new(mut inner: W) -> io::Result<Self> {
+        // the header is rewritten when the writer is finished
+        inner.seek(SeekFrom::Start(0))?;
+        inner.write_all(&[0; HEADER as usize])?;
+        Ok(Self {
+            inner,
+            index: BTreeMap::new(),
+            pos: HEADER,
+        })
+    }
+
+    /// Add a file with the contents of `data`.
+    ///
+    /// Fails with `ErrorKind::AlreadyExists` if the path was already added, and
+    /// with `ErrorKind::InvalidInput` if it isn't valid UTF-8 or doesn't name a
+    /// file.
+    pub fn add<P: AsRef<Path>, R: Read>(&mut self, path: P, mut data: R) -> io::Result<()> {
+        let path = match key(path.as_ref()) {
+            Some(path) if !path.is_empty() && path.len() <= usize::from(u16::MAX) => path,
+            _ => {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidInput,
+                    "Invalid MiniPak path.",
+                ))
+            }
+        };
+        if self.index.contains_key(&path) {
+            return Err(io::Error::from(ErrorKind::AlreadyExists));
+        }
+
+        let mut buf = Vec::new();
+        data.read_to_end(&mut buf)?;
+        let len = buf.len() as u64;
+        let mut crc = Crc::new();
+        crc.update(&buf);
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&buf)?;
+        let deflated = encoder.finish()?;
+        let (compression, stored) = if deflated.len() < buf.len() {
+            (DEFLATE, deflated)
+        } else {
+            (STORED, buf)
+        };
+
+        self.inner.seek(SeekFrom::Start(self.pos))?;
+        self.inner.write_all(&stored)?;
+        let record = Record {
+            compression,
+            crc: crc.sum(),
+            pos: self.pos,
+            stored: stored.len() as u64,
+            len,
+        };
+        self.pos += record.stored;
+        self.index.insert(path, record);
+        Ok(())
+    }
+
+    /// Add every file reachable from `root` in `store`.
+    ///
+    /// Files are added with their path relative to `root`.
+    pub fn add_store<S, P>(&mut self, store: &S, root: P) -> io::Result<()>
+    where
+        S: Store,
+        S::File: Read,
+        P: AsRef<Path>,
+    {
+        let root = normalize_path(root.as_ref()).into_owned();
+        for path in store.walk(&root) {
+            let path = path?;
+            let file = store.open_path(&path)?;
+            self.add(path.strip_prefix(&root).unwrap_or(&path), file)?;
+        }
+        Ok(())
+    }
+
+    /// Write the index and the header, and return the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        let mut index = Vec::new();
+        for (path, record) in &self.index {
+            index.extend_from_slice(&(path.len() as u16).to_le_bytes());
+            index.extend_from_slice(path.as_bytes());
+            index.push(record.compression);
+            index.extend_from_slice(&record.crc.to_le_bytes());
+            index.extend_from_slice(&record.pos.to_le_bytes());
+            index.extend_from_slice(&record.stored.to_le_bytes());
+            index.extend_from_slice(&record.len.to_le_bytes());
+        }
+        let count: u32 = self.index.len().try_into().map_err(|_| {
+            io::Error::new(ErrorKind::InvalidInput, "Too many files for a MiniPak.")
+        })?;
+
+        self.inner.seek(SeekFrom::Start(self.pos))?;
+        self.inner.write_all(&index)?;
+        let mut header = Vec::with_capacity(HEADER as usize);
+        header.extend_from_slice(MAGIC);
+        header.extend_from_slice(&VERSION.to_le_bytes());
+        header.extend_from_slice(&count.to_le_bytes());
+        header.extend_from_slice(&self.pos.to_le_bytes());
+        self.inner.seek(SeekFrom::Start(0))?;
+        self.inner.write_all(&header)?;
+        self.inner
+            .seek(SeekFrom::Start(self.pos + index.len() as u64))?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+// Path of an entry in the index, without the root and with `/` separators.
+fn key(path: &Path) -> Option<String> {
+    let mut key = String::new();
+    for comp in normalize_path(path).components() {
+        match comp {
+            Component::Normal(name) => {
+                if !key.is_empty() {
+                    key.push('/');
+                }
+                key.push_str(name.to_str()?);
+            }
+            Component::RootDir | Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    Some(key)
+}
+
+fn record(data: &mut &[u8]) -> Option<MiniPakIndexEntry> {
+    let len = usize::from(le16(data).ok()?);
+    let path = std::str::from_utf8(data.get(2..2 + len)?).ok()?.to_owned();
+    let fields = data.get(2 + len..2 + len + 29)?;
+    *data = &data[2 + len + 29..];
+    Some(MiniPakIndexEntry {
+        path,
+        compression: fields[0],
+        crc: le32(&fields[1..5]).ok()?,
+        pos: le64(&fields[5..13]).ok()?,
+        stored: le64(&fields[13..21]).ok()?,
+        meta: Metadata {
+            kind: EntryKind::File,
+            len: le64(&fields[21..29]).ok()?,
+            modified: None,
+        },
+    })
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg)
+}
@@ -0,0 +1,86 @@
+use std::fs;
+use std::io::{self, BufRead, Cursor, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Memory-mapped file from the native filesystem.
+///
+/// Archive stores can be created from a `Mmap` instead of a `std::fs::File`,
+/// so the archive is read from the page cache of the OS rather than through
+/// read calls:
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::{Mmap, Tar, Zip};
+///
+/// let zip = Zip::mmap("archive.zip")?;
+/// let tar = Tar::new(Mmap::open("archive.tar.gz")?).index()?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Remarks
+///
+/// The file must not be modified while it's mapped. Changes made by other
+/// processes are visible through the map, and truncating the file makes reads
+/// crash the process.
+pub struct Mmap {
+    inner: Cursor<memmap2::Mmap>,
+}
+
+impl Mmap {
+    /// Map a file from the native filesystem.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = fs::OpenOptions::new()
+            .read(true)
+            .write(false)
+            .create(false)
+            .open(path)?;
+        // Safety: the map is read only, and the remarks above document what
+        // happens when the file is modified.
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        Ok(Self {
+            inner: Cursor::new(map),
+        })
+    }
+
+    /// Size of the file in bytes.
+    pub fn len(&self) -> u64 {
+        self.inner.get_ref().len() as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl AsRef<[u8]> for Mmap {
+    fn as_ref(&self) -> &[u8] {
+        self.inner.get_ref()
+    }
+}
+
+impl Read for Mmap {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl BufRead for Mmap {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+impl Seek for Mmap {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
@@ -0,0 +1,267 @@
+use std::collections::LinkedList;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use crate::store::{self, Entry, EntryKind, Store, TryEach};
+use crate::{index, Entries, File, Metadata, Resolved};
+
+// Where a new mount goes among the stores mounted at the same point.
+pub(crate) enum Order {
+    Priority(i32),
+    Before,
+    After,
+}
+
+struct Mount<S: ?Sized> {
+    path: PathBuf,
+    // Type name of the mounted store, for `mounts`.
+    name: &'static str,
+    priority: i32,
+    store: Box<S>,
+}
+
+// Mount points of `MiniFs` and `SyncMiniFs`, which only differ in the trait
+// objects of their stores.
+pub(crate) struct MountTable<S: ?Sized> {
+    // Sorted by the depth of the mount point, then by priority and recency, so
+    // iterating in reverse visits the mounts in the order they resolve paths.
+    mount: LinkedList<Mount<S>>,
+}
+
+impl<S: ?Sized> MountTable<S> {
+    pub(crate) fn new() -> Self {
+        Self {
+            mount: LinkedList::new(),
+        }
+    }
+
+    pub(crate) fn insert(&mut self, path: &Path, name: &'static str, store: Box<S>, order: Order) {
+        let path = mount_path(path);
+        let (priority, shadow) = match order {
+            Order::Priority(priority) => (priority, true),
+            Order::Before => (self.priorities(&path).max().unwrap_or(0), true),
+            Order::After => (self.priorities(&path).min().unwrap_or(0), false),
+        };
+        // A new mount goes after the ones with the same depth and priority if it
+        // shadows them, before otherwise.
+        let key = (path.components().count(), priority);
+        let at = self
+            .mount
+            .iter()
+            .position(|mnt| {
+                let other = (mnt.path.components().count(), mnt.priority);
+                if shadow {
+                    other > key
+                } else {
+                    other >= key
+                }
+            })
+            .unwrap_or(self.mount.len());
+        let mut tail = self.mount.split_off(at);
+        self.mount.push_back(Mount {
+            path,
+            name,
+            priority,
+            store,
+        });
+        self.mount.append(&mut tail);
+    }
+
+    fn priorities<'a>(&'a self, path: &'a Path) -> impl Iterator<Item = i32> + 'a {
+        self.mount
+            .iter()
+            .filter(move |mnt| mnt.path == path)
+            .map(|mnt| mnt.priority)
+    }
+
+    pub(crate) fn umount(&mut self, path: &Path) -> Option<Box<S>> {
+        let path = mount_path(path);
+        if let Some(p) = self.mount.iter().rposition(|p| p.path == path) {
+            let mut tail = self.mount.split_off(p);
+            let fs = tail.pop_front().map(|m| m.store);
+            self.mount.append(&mut tail);
+            fs
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn remount(
+        &mut self,
+        path: &Path,
+        name: &'static str,
+        store: Box<S>,
+    ) -> Option<Box<S>> {
+        let path = mount_path(path);
+        if let Some(mnt) = self.mount.iter_mut().rev().find(|mnt| mnt.path == path) {
+            mnt.name = name;
+            Some(std::mem::replace(&mut mnt.store, store))
+        } else {
+            self.insert(&path, name, store, Order::Priority(0));
+            None
+        }
+    }
+}
+
+impl<S: Store<File = File> + ?Sized> MountTable<S> {
+    pub(crate) fn resolve(&self, path: &Path) -> io::Result<Resolved> {
+        let path = &mount_path(path);
+        for mnt in self.mount.iter().rev() {
+            if let Ok(np) = path.strip_prefix(&mnt.path) {
+                return Ok(Resolved {
+                    mount: mnt.path.clone(),
+                    path: np.to_path_buf(),
+                    store: mnt.store.resolve_path(np)?,
+                });
+            }
+        }
+        Err(io::Error::from(io::ErrorKind::NotFound))
+    }
+
+    pub(crate) fn mounts(&self) -> impl Iterator<Item = (&Path, &'static str, bool)> + '_ {
+        self.mount
+            .iter()
+            .rev()
+            .map(|mnt| (mnt.path.as_path(), mnt.name, mnt.store.is_read_only()))
+    }
+}
+
+impl<S: Store<File = File> + ?Sized> Store for MountTable<S> {
+    type File = File;
+
+    fn open_path(&self, path: &Path) -> io::Result<File> {
+        let path = &mount_path(path);
+        let next = self.mount.iter().rev().find_map(|mnt| {
+            if let Ok(np) = path.strip_prefix(&mnt.path) {
+                Some((np, &mnt.store))
+            } else {
+                None
+            }
+        });
+        if let Some((np, store)) = next {
+            store.open_path(np)
+        } else {
+            Err(io::Error::from(io::ErrorKind::NotFound))
+        }
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        let path = &mount_path(path);
+        // Mounts are visited in the same order as in `open_path`. The first one
+        // containing `path` shadows the rest, but mount points nested below `path`
+        // come before it and show up as directories.
+        let mut lists = Vec::new();
+        for m in self.mount.iter().rev() {
+            if let Ok(np) = path.strip_prefix(&m.path) {
+                lists.push(match m.store.entries_path(np) {
+                    Ok(entries) => entries,
+                    Err(ref err) if err.kind() == io::ErrorKind::NotFound => Entries::empty(),
+                    Err(err) => Entries::new(Some(Err(err))),
+                });
+                break;
+            } else if let Ok(np) = m.path.strip_prefix(path) {
+                if let Some(Component::Normal(name)) = np.components().next() {
+                    lists.push(Entries::new(Some(Ok(Entry {
+                        name: name.to_os_string(),
+                        kind: EntryKind::Dir,
+                    }))));
+                }
+            }
+        }
+
+        Ok(Entries::new(store::TupleEntries::new(
+            lists.into_iter().flatten(),
+        )))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        // Same lookup as `open_path`, but parents of mount points are directories.
+        let path = &mount_path(path);
+        let mut parent = false;
+        for mnt in self.mount.iter().rev() {
+            if let Ok(np) = path.strip_prefix(&mnt.path) {
+                return match mnt.store.metadata_path(np) {
+                    Err(ref err) if parent && err.kind() == io::ErrorKind::NotFound => {
+                        Ok(Metadata::dir())
+                    }
+                    res => res,
+                };
+            } else if mnt.path.starts_with(path) {
+                parent = true;
+            }
+        }
+        if parent {
+            Ok(Metadata::dir())
+        } else {
+            Err(io::Error::from(io::ErrorKind::NotFound))
+        }
+    }
+
+    fn create_path(&self, path: &Path) -> io::Result<File> {
+        // Unlike `open_path`, read-only mounts are skipped so the file is created
+        // in the first writable mount containing the path, in the same order.
+        let path = &mount_path(path);
+        for mnt in self.mount.iter().rev() {
+            if let Ok(np) = path.strip_prefix(&mnt.path) {
+                match mnt.store.create_path(np) {
+                    Err(ref err) if err.kind() == io::ErrorKind::ReadOnlyFilesystem => {}
+                    res => return res,
+                }
+            }
+        }
+        Err(store::read_only())
+    }
+
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        let path = &mount_path(path);
+        let mut each = TryEach::default();
+        for mnt in self.mount.iter().rev() {
+            if let Ok(np) = path.strip_prefix(&mnt.path) {
+                if let Some(res) = each.next(mnt.store.remove_path(np)) {
+                    return res;
+                }
+            }
+        }
+        each.finish()
+    }
+
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let (from, to) = (&mount_path(from), &mount_path(to));
+        // Try to rename within a single mount first.
+        let mut each = TryEach::default();
+        for mnt in self.mount.iter().rev() {
+            if let (Ok(nf), Ok(nt)) = (from.strip_prefix(&mnt.path), to.strip_prefix(&mnt.path)) {
+                if let Some(res) = each.next(mnt.store.rename_path(nf, nt)) {
+                    return res;
+                }
+            }
+        }
+
+        // Otherwise `from` and `to` live in different mounts, so copy the file
+        // over and remove the original.
+        let mut src = Store::open_path(self, from)?;
+        let mut dst = self.create_path(to)?;
+        io::copy(&mut src, &mut dst)?;
+        io::Write::flush(&mut dst)?;
+        drop((src, dst));
+        if let Err(err) = self.remove_path(from) {
+            let _ = self.remove_path(to);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    fn is_read_only(&self) -> bool {
+        self.mount.iter().all(|mnt| mnt.store.is_read_only())
+    }
+
+    fn resolve_path(&self, path: &Path) -> io::Result<&'static str> {
+        self.resolve(path).map(|resolved| resolved.store)
+    }
+}
+
+// Paths in a MiniFs are absolute, with `.` and `..` components resolved, so
+// they can be compared with the mount points.
+fn mount_path(path: &Path) -> PathBuf {
+    Path::new("/").join(index::relative_path(path))
+}
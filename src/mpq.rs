@@ -0,0 +1,504 @@
+use std::cell::RefCell;
+use std::fs;
+use std::io::{self, BufRead, Cursor, ErrorKind, Read, Seek, SeekFrom};
+use std::path::{Component, Path};
+
+use bzip2_::read::BzDecoder;
+use flate2::read::ZlibDecoder;
+
+use crate::bytes::{le16, le32, le64};
+use crate::index::Index;
+use crate::store::Store;
+use crate::{Entries, Entry, EntryKind, Metadata};
+
+// Headers are aligned to 512 bytes, so archives can be appended to other files.
+const HEADER_ALIGN: u64 = 0x200;
+
+// Block flags.
+const FILE_IMPLODE: u32 = 0x0000_0100;
+const FILE_COMPRESS: u32 = 0x0000_0200;
+const FILE_ENCRYPTED: u32 = 0x0001_0000;
+const FILE_FIX_KEY: u32 = 0x0002_0000;
+const FILE_PATCH_FILE: u32 = 0x0010_0000;
+const FILE_SINGLE_UNIT: u32 = 0x0100_0000;
+const FILE_DELETE_MARKER: u32 = 0x0200_0000;
+const FILE_EXISTS: u32 = 0x8000_0000;
+
+// Block indices of unused hash table entries.
+const HASH_FREE: u32 = 0xffff_ffff;
+const HASH_DELETED: u32 = 0xffff_fffe;
+
+// Compression masks of compressed sectors.
+const COMPRESSION_ZLIB: u8 = 0x02;
+const COMPRESSION_PKWARE: u8 = 0x08;
+const COMPRESSION_BZIP2: u8 = 0x10;
+
+// Hash types.
+const HASH_OFFSET: u32 = 0;
+const HASH_A: u32 = 1;
+const HASH_B: u32 = 2;
+const HASH_KEY: u32 = 3;
+
+const CRYPT_TABLE: [u32; 0x500] = crypt_table();
+
+/// Blizzard MPQ archive store.
+///
+/// Files are found through the hash table of the archive, so they can be
+/// opened by name right away (names are not case sensitive, and `\` and `/`
+/// are the same separator). Listing the archive requires a `(listfile)`.
+///
+/// Sectors are decompressed with zlib, bzip2 or PKWARE implode, and encrypted
+/// files are decrypted.
+///
+/// # Remarks
+///
+/// Patch files, and sectors compressed with Huffman, ADPCM, LZMA or sparse
+/// compression are not supported.
+///
+/// When used with a `std::fs::File`, the file will remain open for the lifetime
+/// of the Mpq.
+pub struct Mpq<T: Read + Seek> {
+    inner: RefCell<T>,
+    // position of the header
+    offset: u64,
+    sector_size: usize,
+    hashes: Vec<HashEntry>,
+    blocks: Vec<Block>,
+    // files from the listfile
+    index: Index<Metadata>,
+}
+
+struct HashEntry {
+    a: u32,
+    b: u32,
+    block: u32,
+}
+
+struct Block {
+    // position of the file data, relative to the header
+    pos: u64,
+    packed: u64,
+    len: u32,
+    flags: u32,
+}
+
+/// Entry in the MPQ archive.
+pub struct MpqEntry {
+    inner: Cursor<Box<[u8]>>,
+}
+
+impl MpqEntry {
+    pub(crate) fn len(&self) -> u64 {
+        self.inner.get_ref().len() as u64
+    }
+}
+
+impl Read for MpqEntry {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl BufRead for MpqEntry {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+impl Seek for MpqEntry {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl Mpq<fs::File> {
+    /// Open a file from the native filesystem.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = fs::OpenOptions::new()
+            .read(true)
+            .write(false)
+            .create(false)
+            .open(path)?;
+        Self::new(file)
+    }
+}
+
+impl<T: Read + Seek> Mpq<T> {
+    /// Read the hash and block tables of the archive, and index the files of
+    /// its listfile.
+    pub fn new(mut inner: T) -> io::Result<Self> {
+        let len = inner.seek(SeekFrom::End(0))?;
+        let mut offset = 0;
+        let header = loop {
+            if offset + 32 > len {
+                return Err(invalid("Not an MPQ archive."));
+            }
+            inner.seek(SeekFrom::Start(offset))?;
+            let mut header = Vec::new();
+            (&mut inner).take(44).read_to_end(&mut header)?;
+            match &header[..4] {
+                b"MPQ\x1a" => break header,
+                // user data, followed by the header
+                b"MPQ\x1b" => match le32(&header[8..])? {
+                    0 => offset += HEADER_ALIGN,
+                    skip => offset += u64::from(skip),
+                },
+                _ => offset += HEADER_ALIGN,
+            }
+        };
+
+        let version = le16(&header[12..])?;
+        let sector_size = 512 << le16(&header[14..])?.min(22);
+        let mut hash_pos = u64::from(le32(&header[16..])?);
+        let mut block_pos = u64::from(le32(&header[20..])?);
+        let hash_count = le32(&header[24..])? as usize;
+        let block_count = le32(&header[28..])? as usize;
+        // Version 1 headers add the high bits of 64-bit positions.
+        let mut hi_block_pos = 0;
+        if version >= 1 && header.len() >= 44 {
+            hi_block_pos = le64(&header[32..])?;
+            hash_pos |= u64::from(le16(&header[40..])?) << 32;
+            block_pos |= u64::from(le16(&header[42..])?) << 32;
+        }
+
+        let hashes = read_table(&mut inner, offset + hash_pos, hash_count, "(hash table)")?
+            .chunks(4)
+            .map(|entry| HashEntry {
+                a: entry[0],
+                b: entry[1],
+                block: entry[3],
+            })
+            .collect();
+        let mut blocks: Vec<_> =
+            read_table(&mut inner, offset + block_pos, block_count, "(block table)")?
+                .chunks(4)
+                .map(|entry| Block {
+                    pos: u64::from(entry[0]),
+                    packed: u64::from(entry[1]),
+                    len: entry[2],
+                    flags: entry[3],
+                })
+                .collect();
+        if hi_block_pos != 0 {
+            inner.seek(SeekFrom::Start(offset + hi_block_pos))?;
+            for block in blocks.iter_mut() {
+                let mut hi = [0; 2];
+                inner.read_exact(&mut hi)?;
+                block.pos |= u64::from(le16(&hi)?) << 32;
+            }
+        }
+
+        let mut mpq = Self {
+            inner: RefCell::new(inner),
+            offset,
+            sector_size,
+            hashes,
+            blocks,
+            index: Index::new(),
+        };
+        if let Some(block) = mpq.find("(listfile)") {
+            let listfile = mpq.read_file("(listfile)", block)?;
+            let mut index = Index::new();
+            for name in String::from_utf8_lossy(&listfile).lines() {
+                // entries may be followed by other fields
+                let name = name.split(';').next().unwrap_or_default().trim();
+                if let Some(block) = mpq.find(name) {
+                    index.insert(name.replace('\\', "/"), metadata(block));
+                }
+            }
+            mpq.index = index;
+        }
+        Ok(mpq)
+    }
+
+    fn find(&self, name: &str) -> Option<&Block> {
+        if self.hashes.is_empty() {
+            return None;
+        }
+        let start = hash(name, HASH_OFFSET) as usize % self.hashes.len();
+        let (a, b) = (hash(name, HASH_A), hash(name, HASH_B));
+        for i in 0..self.hashes.len() {
+            let entry = &self.hashes[(start + i) % self.hashes.len()];
+            match entry.block {
+                HASH_FREE => break,
+                HASH_DELETED => {}
+                block if entry.a == a && entry.b == b => {
+                    return self.blocks.get(block as usize).filter(|block| {
+                        block.flags & (FILE_EXISTS | FILE_DELETE_MARKER) == FILE_EXISTS
+                    });
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    fn read_file(&self, name: &str, block: &Block) -> io::Result<Vec<u8>> {
+        if block.flags & FILE_PATCH_FILE != 0 {
+            return Err(io::Error::new(
+                ErrorKind::Unsupported,
+                "MPQ patch files are not supported.",
+            ));
+        }
+        let key = if block.flags & FILE_ENCRYPTED != 0 {
+            let name = name.rsplit(['\\', '/']).next().unwrap_or(name);
+            let key = hash(name, HASH_KEY);
+            if block.flags & FILE_FIX_KEY != 0 {
+                Some(key.wrapping_add(block.pos as u32) ^ block.len)
+            } else {
+                Some(key)
+            }
+        } else {
+            None
+        };
+
+        let mut data = Vec::new();
+        {
+            let mut file = self.inner.borrow_mut();
+            file.seek(SeekFrom::Start(self.offset + block.pos))?;
+            (&mut *file).take(block.packed).read_to_end(&mut data)?;
+        }
+        if data.len() as u64 != block.packed {
+            return Err(io::Error::from(ErrorKind::UnexpectedEof));
+        }
+
+        let len = block.len as usize;
+        let compressed = block.flags & (FILE_COMPRESS | FILE_IMPLODE) != 0;
+        let out = if block.flags & FILE_SINGLE_UNIT != 0 {
+            if let Some(key) = key {
+                decrypt(&mut data, key);
+            }
+            decode_sector(block.flags, data, len)?
+        } else if !compressed {
+            for (i, sector) in data.chunks_mut(self.sector_size).enumerate() {
+                if let Some(key) = key {
+                    decrypt(sector, key.wrapping_add(i as u32));
+                }
+            }
+            data
+        } else {
+            // Compressed files start with the offsets of their sectors.
+            let count = len.div_ceil(self.sector_size);
+            let mut offsets = data
+                .get(..(count + 1) * 4)
+                .ok_or_else(|| invalid("Invalid MPQ sector table."))?
+                .to_vec();
+            if let Some(key) = key {
+                decrypt(&mut offsets, key.wrapping_sub(1));
+            }
+            let offsets = offsets
+                .chunks(4)
+                .map(|o| Ok(le32(o)? as usize))
+                .collect::<io::Result<Vec<_>>>()?;
+
+            let mut out = Vec::new();
+            for i in 0..count {
+                let mut sector = data
+                    .get(offsets[i]..offsets[i + 1])
+                    .ok_or_else(|| invalid("Invalid MPQ sector table."))?
+                    .to_vec();
+                if let Some(key) = key {
+                    decrypt(&mut sector, key.wrapping_add(i as u32));
+                }
+                let sector_len = self.sector_size.min(len - i * self.sector_size);
+                out.extend(decode_sector(block.flags, sector, sector_len)?);
+            }
+            out
+        };
+
+        if out.len() != len {
+            return Err(invalid("Corrupted MPQ file."));
+        }
+        Ok(out)
+    }
+}
+
+impl<T: Read + Seek> Store for Mpq<T> {
+    type File = MpqEntry;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        let name = mpq_name(path)?;
+        let block = self
+            .find(&name)
+            .ok_or_else(|| io::Error::from(ErrorKind::NotFound))?;
+        let data = self.read_file(&name, block)?;
+        Ok(MpqEntry {
+            inner: Cursor::new(data.into()),
+        })
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        Ok(Entries::new(self.index.entries(path).map(|ent| {
+            let name = ent.name.to_os_string();
+            let kind = ent.kind;
+            Ok(Entry { name, kind })
+        })))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        if let Some(block) = self.find(&mpq_name(path)?) {
+            Ok(metadata(block))
+        } else if self.index.contains_dir(path) {
+            Ok(Metadata::dir())
+        } else {
+            Err(io::Error::from(ErrorKind::NotFound))
+        }
+    }
+}
+
+// Names use backslashes as separators.
+fn mpq_name(path: &Path) -> io::Result<String> {
+    let mut name = String::new();
+    for comp in path.components() {
+        if let Component::Normal(comp) = comp {
+            let comp = comp
+                .to_str()
+                .ok_or_else(|| io::Error::from(ErrorKind::NotFound))?;
+            if !name.is_empty() {
+                name.push('\\');
+            }
+            name.push_str(comp);
+        }
+    }
+    Ok(name)
+}
+
+fn metadata(block: &Block) -> Metadata {
+    Metadata {
+        kind: EntryKind::File,
+        len: u64::from(block.len),
+        modified: None,
+    }
+}
+
+// Sectors that didn't shrink are stored as they are.
+fn decode_sector(flags: u32, mut data: Vec<u8>, len: usize) -> io::Result<Vec<u8>> {
+    if data.len() >= len {
+        data.truncate(len);
+        return Ok(data);
+    }
+    if flags & FILE_IMPLODE != 0 {
+        return bounded(explode(&data)?, len);
+    }
+
+    let (mask, mut data) = match data.split_first() {
+        Some((mask, data)) => (*mask, data.to_vec()),
+        None => return Err(invalid("Corrupted MPQ file.")),
+    };
+    if mask & !(COMPRESSION_ZLIB | COMPRESSION_PKWARE | COMPRESSION_BZIP2) != 0 {
+        return Err(io::Error::new(
+            ErrorKind::Unsupported,
+            "Unsupported MPQ compression.",
+        ));
+    }
+    if mask & COMPRESSION_BZIP2 != 0 {
+        data = decompress(BzDecoder::new(&data[..]), len)?;
+    }
+    if mask & COMPRESSION_PKWARE != 0 {
+        data = bounded(explode(&data)?, len)?;
+    }
+    if mask & COMPRESSION_ZLIB != 0 {
+        data = decompress(ZlibDecoder::new(&data[..]), len)?;
+    }
+    Ok(data)
+}
+
+// Sectors never decompress past their length, so a corrupt one can't inflate
+// without limit.
+fn decompress<R: Read>(decoder: R, len: usize) -> io::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    decoder.take(len as u64 + 1).read_to_end(&mut out)?;
+    bounded(out, len)
+}
+
+fn bounded(data: Vec<u8>, len: usize) -> io::Result<Vec<u8>> {
+    if data.len() > len {
+        return Err(invalid("Corrupted MPQ file."));
+    }
+    Ok(data)
+}
+
+fn explode(data: &[u8]) -> io::Result<Vec<u8>> {
+    explode::explode(data).map_err(|err| io::Error::new(ErrorKind::InvalidData, err))
+}
+
+fn read_table<R: Read + Seek>(
+    inner: &mut R,
+    pos: u64,
+    count: usize,
+    name: &str,
+) -> io::Result<Vec<u32>> {
+    inner.seek(SeekFrom::Start(pos))?;
+    let mut data = Vec::new();
+    inner.take(count as u64 * 16).read_to_end(&mut data)?;
+    if data.len() != count * 16 {
+        return Err(invalid("Truncated MPQ table."));
+    }
+    decrypt(&mut data, hash(name, HASH_KEY));
+    data.chunks(4).map(le32).collect()
+}
+
+const fn crypt_table() -> [u32; 0x500] {
+    let mut table = [0; 0x500];
+    let mut seed: u32 = 0x0010_0001;
+    let mut i = 0;
+    while i < 0x100 {
+        let mut j = 0;
+        while j < 5 {
+            seed = (seed * 125 + 3) % 0x002a_aaab;
+            let hi = (seed & 0xffff) << 16;
+            seed = (seed * 125 + 3) % 0x002a_aaab;
+            table[i + j * 0x100] = hi | (seed & 0xffff);
+            j += 1;
+        }
+        i += 1;
+    }
+    table
+}
+
+fn hash(name: &str, kind: u32) -> u32 {
+    let mut seed1: u32 = 0x7fed_7fed;
+    let mut seed2: u32 = 0xeeee_eeee;
+    for ch in name.bytes() {
+        let ch = match ch {
+            b'/' => b'\\',
+            ch => ch.to_ascii_uppercase(),
+        };
+        let ch = u32::from(ch);
+        seed1 = CRYPT_TABLE[(kind * 0x100 + ch) as usize] ^ seed1.wrapping_add(seed2);
+        seed2 = ch
+            .wrapping_add(seed1)
+            .wrapping_add(seed2)
+            .wrapping_add(seed2 << 5)
+            .wrapping_add(3);
+    }
+    seed1
+}
+
+// Only whole 32-bit words are encrypted.
+fn decrypt(data: &mut [u8], mut key: u32) {
+    let mut seed: u32 = 0xeeee_eeee;
+    for word in data.chunks_exact_mut(4) {
+        seed = seed.wrapping_add(CRYPT_TABLE[0x400 + (key & 0xff) as usize]);
+        let value =
+            u32::from_le_bytes([word[0], word[1], word[2], word[3]]) ^ key.wrapping_add(seed);
+        key = ((!key << 21).wrapping_add(0x1111_1111)) | (key >> 11);
+        seed = value
+            .wrapping_add(seed)
+            .wrapping_add(seed << 5)
+            .wrapping_add(3);
+        word.copy_from_slice(&value.to_le_bytes());
+    }
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg)
+}
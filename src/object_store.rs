@@ -0,0 +1,174 @@
+use std::cell::OnceCell;
+use std::future::Future;
+use std::io::{self, ErrorKind};
+use std::path::{Component, Path};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use object_store_::path::{Path as ObjectPath, PathPart};
+use object_store_::ObjectStore as _;
+use tokio_::runtime::{Builder, Runtime};
+
+use crate::async_store::{AsyncEntries, AsyncFile, AsyncStore};
+use crate::store::{Entries, Entry, EntryKind, Metadata, Store};
+use crate::File;
+
+/// Adapter over the stores of the `object_store` crate.
+///
+/// Any backend of `object_store` (Amazon S3, Google Cloud Storage, Azure Blob
+/// Storage, in-memory...) can be mounted through this adapter. The backends
+/// themselves are enabled with the features of `object_store`:
+///
+/// ```no_run
+/// # use object_store_ as object_store;
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{MiniFs, ObjectStore};
+/// use object_store::memory::InMemory;
+///
+/// let fs = MiniFs::new().mount("/data", ObjectStore::new(InMemory::new()));
+/// let file = fs.open("/data/maps/de_dust.bsp")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Objects are mapped to files, and the prefixes of their keys to directories.
+///
+/// # Remarks
+///
+/// `object_store` is asynchronous. When used as an `AsyncStore`, requests run
+/// on the runtime of the caller. When used as a [`Store`], requests run on a
+/// runtime owned by the adapter, so it must not be used from within another
+/// tokio runtime.
+///
+/// [`Store`]: ../trait.Store.html
+pub struct ObjectStore {
+    inner: Arc<dyn object_store_::ObjectStore>,
+    runtime: OnceCell<Runtime>,
+}
+
+impl ObjectStore {
+    pub fn new<S: object_store_::ObjectStore>(store: S) -> Self {
+        Self::from_arc(Arc::new(store))
+    }
+
+    /// Create an adapter sharing a store with other code.
+    pub fn from_arc(store: Arc<dyn object_store_::ObjectStore>) -> Self {
+        Self {
+            inner: store,
+            runtime: OnceCell::new(),
+        }
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> io::Result<F::Output> {
+        let runtime = match self.runtime.get() {
+            Some(runtime) => runtime,
+            None => {
+                let runtime = Builder::new_current_thread().enable_all().build()?;
+                self.runtime.get_or_init(|| runtime)
+            }
+        };
+        Ok(runtime.block_on(future))
+    }
+
+    async fn read(&self, path: &Path) -> io::Result<File> {
+        let result = self.inner.get(&location(path)?).await?;
+        let bytes = result.bytes().await?;
+        Ok(Arc::<[u8]>::from(&bytes[..]).into())
+    }
+
+    async fn list(&self, path: &Path) -> io::Result<Vec<Entry>> {
+        let prefix = location(path)?;
+        let prefix = if prefix.as_ref().is_empty() {
+            None
+        } else {
+            Some(&prefix)
+        };
+        let list = self.inner.list_with_delimiter(prefix).await?;
+        let dirs = list.common_prefixes.iter().map(|dir| (dir, EntryKind::Dir));
+        let files = list
+            .objects
+            .iter()
+            .map(|object| (&object.location, EntryKind::File));
+        Ok(dirs
+            .chain(files)
+            .filter_map(|(location, kind)| {
+                let name = location.filename()?;
+                Some(Entry {
+                    name: name.into(),
+                    kind,
+                })
+            })
+            .collect())
+    }
+
+    async fn stat(&self, path: &Path) -> io::Result<Metadata> {
+        let location = location(path)?;
+        if location.as_ref().is_empty() {
+            return Ok(Metadata::dir());
+        }
+        match self.inner.head(&location).await {
+            Ok(meta) => Ok(Metadata {
+                kind: EntryKind::File,
+                len: meta.size,
+                modified: Some(SystemTime::from(meta.last_modified)),
+            }),
+            Err(object_store_::Error::NotFound { .. }) if !self.list(path).await?.is_empty() => {
+                Ok(Metadata::dir())
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+impl Store for ObjectStore {
+    type File = File;
+
+    fn open_path(&self, path: &Path) -> io::Result<File> {
+        self.block_on(self.read(path))?
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        let entries = self.block_on(self.list(path))??;
+        Ok(Entries::new(entries.into_iter().map(Ok)))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        self.block_on(self.stat(path))?
+    }
+}
+
+impl AsyncStore for ObjectStore {
+    type File = AsyncFile;
+
+    fn open_path(&self, path: &Path) -> impl Future<Output = io::Result<AsyncFile>> {
+        let path = path.to_path_buf();
+        async move { self.read(&path).await.map(AsyncFile::Sync) }
+    }
+
+    fn entries_path(&self, path: &Path) -> impl Future<Output = io::Result<AsyncEntries<'_>>> {
+        let path = path.to_path_buf();
+        async move {
+            let entries = self.list(&path).await?;
+            Ok(AsyncEntries::new(Entries::new(entries.into_iter().map(Ok))))
+        }
+    }
+}
+
+// Object stores have no notion of a root, so keys are relative.
+fn location(path: &Path) -> io::Result<ObjectPath> {
+    let mut parts = Vec::new();
+    for comp in path.components() {
+        match comp {
+            Component::Normal(name) => {
+                let name = name.to_str().ok_or_else(|| {
+                    io::Error::new(ErrorKind::InvalidInput, "Utf8 path conversion error.")
+                })?;
+                parts.push(PathPart::from(name));
+            }
+            Component::RootDir | Component::CurDir => {}
+            _ => return Err(io::Error::from(ErrorKind::NotFound)),
+        }
+    }
+    Ok(parts.into_iter().collect())
+}
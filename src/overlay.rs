@@ -0,0 +1,218 @@
+use std::collections::HashSet;
+use std::ffi::{OsStr, OsString};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::index::relative_path;
+use crate::store::{Store, StoreExt, TupleEntries};
+use crate::{Entries, File, Metadata};
+
+/// Copy-on-write overlay of two stores.
+///
+/// Files are read from `upper` first and then from `base`, but every change
+/// goes to `upper`, so `base` is never modified. Renaming files of `base`
+/// copies them to `upper`, and removing them records a whiteout in `upper`,
+/// an empty file named after the removed one with a `.wh.` prefix, which hides
+/// it from then on:
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{Overlay, Ram, Zip};
+/// use std::io::Write;
+///
+/// let mods = Overlay::new(Zip::open("game.zip")?, Ram::new());
+/// mods.create("levels/1.map")?.write_all(b"...")?;
+/// mods.remove("levels/2.map")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Remarks
+///
+/// Unlike a tuple overlay, files are never created, removed or renamed in
+/// `base`, even when it's writable. Whiteouts are stored like any other file,
+/// so removals persist when `upper` does, but they aren't listed, and files
+/// whose name starts with `.wh.` can't be opened. Directories of `base` are
+/// still listed after all their files are removed.
+pub struct Overlay<B, U> {
+    base: B,
+    upper: U,
+}
+
+const WHITEOUT: &str = ".wh.";
+
+impl<B, U> Overlay<B, U> {
+    pub fn new(base: B, upper: U) -> Self {
+        Self { base, upper }
+    }
+
+    pub fn base(&self) -> &B {
+        &self.base
+    }
+
+    pub fn upper(&self) -> &U {
+        &self.upper
+    }
+
+    pub fn into_inner(self) -> (B, U) {
+        (self.base, self.upper)
+    }
+}
+
+impl<B, U: Store> Overlay<B, U> {
+    // Whiteouts are hidden themselves.
+    fn is_hidden(&self, path: &Path) -> bool {
+        is_whiteout(path) || whiteout(path).is_some_and(|whiteout| self.upper.is_file(whiteout))
+    }
+
+    fn hide(&self, path: &Path) -> io::Result<()> {
+        let whiteout = whiteout(path).ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))?;
+        self.upper.create_path(&whiteout).map(drop)
+    }
+
+    fn unhide(&self, path: &Path) -> io::Result<()> {
+        if let Some(whiteout) = whiteout(path) {
+            match self.upper.remove_path(&whiteout) {
+                Err(ref err) if err.kind() == io::ErrorKind::NotFound => {}
+                res => return res,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<B, U> Store for Overlay<B, U>
+where
+    B: Store,
+    U: Store,
+    B::File: Into<File>,
+    U::File: Into<File>,
+{
+    type File = File;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        if self.is_hidden(path) {
+            return Err(io::Error::from(io::ErrorKind::NotFound));
+        }
+        match self.upper.open_path(path) {
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => {}
+            res => return res.map(Into::into),
+        }
+        self.base.open_path(path).map(Into::into)
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        let upper: Vec<_> = match self.upper.entries_path(path) {
+            Ok(entries) => entries.collect(),
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err),
+        };
+        let base = match self.base.entries_path(path) {
+            Ok(entries) => entries,
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => Entries::empty(),
+            Err(err) => return Err(err),
+        };
+
+        // Names of the whiteouts, which hide both themselves and their files.
+        let mut hidden = HashSet::new();
+        for entry in upper.iter().flatten() {
+            let name = name(&entry.name);
+            if let Some(file) = name.to_str().and_then(|name| name.strip_prefix(WHITEOUT)) {
+                hidden.insert(OsString::from(file));
+                hidden.insert(name.to_os_string());
+            }
+        }
+        let entries = upper
+            .into_iter()
+            .chain(base)
+            .filter(move |entry| match entry {
+                Ok(entry) => !hidden.contains(name(&entry.name)),
+                Err(_) => true,
+            });
+        Ok(Entries::new(TupleEntries::new(entries)))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        if self.is_hidden(path) {
+            return Err(io::Error::from(io::ErrorKind::NotFound));
+        }
+        match self.upper.metadata_path(path) {
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => {}
+            res => return res,
+        }
+        self.base.metadata_path(path)
+    }
+
+    fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+        if is_whiteout(path) {
+            return Err(io::Error::from(io::ErrorKind::InvalidInput));
+        }
+        let file = self.upper.create_path(path)?;
+        self.unhide(path)?;
+        Ok(file.into())
+    }
+
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        if self.is_hidden(path) {
+            return Err(io::Error::from(io::ErrorKind::NotFound));
+        }
+        let in_base = self.base.is_file(path);
+        match self.upper.remove_path(path) {
+            Err(ref err) if in_base && err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+            Ok(()) if !in_base => return Ok(()),
+            Ok(()) => {}
+        }
+        self.hide(path)
+    }
+
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        if self.is_hidden(from) {
+            return Err(io::Error::from(io::ErrorKind::NotFound));
+        }
+        if is_whiteout(to) {
+            return Err(io::Error::from(io::ErrorKind::InvalidInput));
+        }
+        let in_base = self.base.is_file(from);
+        match self.upper.rename_path(from, to) {
+            Err(ref err) if in_base && err.kind() == io::ErrorKind::NotFound => {
+                let mut src: File = self.base.open_path(from)?.into();
+                let mut dst: File = self.upper.create_path(to)?.into();
+                io::copy(&mut src, &mut dst)?;
+                io::Write::flush(&mut dst)?;
+            }
+            res => res?,
+        }
+        self.unhide(to)?;
+        if in_base && relative_path(from) != relative_path(to) {
+            self.hide(from)?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn is_read_only(&self) -> bool {
+        self.upper.is_read_only()
+    }
+}
+
+fn is_whiteout(path: &Path) -> bool {
+    path.file_name()
+        .and_then(OsStr::to_str)
+        .is_some_and(|name| name.starts_with(WHITEOUT))
+}
+
+// Path of the whiteout hiding `path`.
+fn whiteout(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?;
+    let mut whiteout = OsString::from(WHITEOUT);
+    whiteout.push(name);
+    Some(path.with_file_name(whiteout))
+}
+
+// Some stores return names relative to their root rather than to the listed
+// directory.
+fn name(name: &OsStr) -> &OsStr {
+    Path::new(name).file_name().unwrap_or(name)
+}
@@ -0,0 +1,293 @@
+use std::io::{self, BufRead, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::index::normalize_path;
+use crate::store::{EntryKind, Store, StoreExt};
+use crate::{Entries, File, Metadata};
+
+/// Store limiting the total size and number of the files written to another
+/// store.
+///
+/// Writes that would exceed the quota fail with an error of kind
+/// `QuotaExceeded`, so code given a writable store, like scripts running in a
+/// sandbox, can't exhaust memory or disk space:
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{MiniFs, Quota, Ram};
+/// use std::io::{ErrorKind, Write};
+///
+/// let saves = Quota::new(Ram::new())?.max_bytes(1024).max_files(8);
+/// let fs = MiniFs::new().mount("/saves", saves);
+///
+/// let mut save = fs.create("/saves/slot1.sav")?;
+/// save.write_all(&[0; 1000])?;
+/// let err = save.write_all(&[0; 1000]).unwrap_err();
+/// assert_eq!(ErrorKind::QuotaExceeded, err.kind());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Remarks
+///
+/// The files already in the store are measured when the quota is created, so
+/// the store must support listing. Overwritten, removed and renamed files give
+/// their size back to the quota, and so do the files of removed directories,
+/// but changes made to the wrapped store directly aren't seen.
+pub struct Quota<S> {
+    store: S,
+    max_bytes: u64,
+    max_files: u64,
+    usage: Arc<Usage>,
+}
+
+#[derive(Default)]
+struct Usage {
+    bytes: AtomicU64,
+    files: AtomicU64,
+}
+
+impl Usage {
+    // Adds `n` to `counter` if it stays within `max`.
+    fn reserve(counter: &AtomicU64, n: u64, max: u64) -> io::Result<()> {
+        counter
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |used| {
+                used.checked_add(n).filter(|&used| used <= max)
+            })
+            .map(drop)
+            .map_err(|_| io::Error::from(ErrorKind::QuotaExceeded))
+    }
+
+    fn release(counter: &AtomicU64, n: u64) {
+        let _ = counter.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |used| {
+            Some(used.saturating_sub(n))
+        });
+    }
+}
+
+impl<S: Store> Quota<S> {
+    /// Quota over `store`, without limits until they are set.
+    ///
+    /// Returns an error if the files of the store can't be listed.
+    pub fn new(store: S) -> io::Result<Self> {
+        let usage = Usage::default();
+        for path in store.walk("") {
+            let meta = store.metadata_path(&path?)?;
+            usage.bytes.fetch_add(meta.len, Ordering::SeqCst);
+            usage.files.fetch_add(1, Ordering::SeqCst);
+        }
+        Ok(Self {
+            store,
+            max_bytes: u64::MAX,
+            max_files: u64::MAX,
+            usage: Arc::new(usage),
+        })
+    }
+}
+
+impl<S> Quota<S> {
+    /// Maximum total size of the files, in bytes.
+    pub fn max_bytes(mut self, bytes: u64) -> Self {
+        self.max_bytes = bytes;
+        self
+    }
+
+    /// Maximum number of files.
+    pub fn max_files(mut self, files: u64) -> Self {
+        self.max_files = files;
+        self
+    }
+
+    /// Total size of the files, in bytes.
+    pub fn bytes_used(&self) -> u64 {
+        self.usage.bytes.load(Ordering::SeqCst)
+    }
+
+    /// Number of files.
+    pub fn files_used(&self) -> u64 {
+        self.usage.files.load(Ordering::SeqCst)
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.store
+    }
+
+    pub fn into_inner(self) -> S {
+        self.store
+    }
+}
+
+// Length of the file at `path`, if there is one.
+fn file_len<S: Store>(store: &S, path: &Path) -> Option<u64> {
+    match store.metadata_path(path) {
+        Ok(Metadata {
+            kind: EntryKind::File,
+            len,
+            ..
+        }) => Some(len),
+        _ => None,
+    }
+}
+
+// Total size and number of the files at `path`, a file or a directory.
+fn usage<S: Store>(store: &S, path: &Path) -> (u64, u64) {
+    match store.metadata_path(path) {
+        Ok(Metadata {
+            kind: EntryKind::File,
+            len,
+            ..
+        }) => (len, 1),
+        Ok(Metadata {
+            kind: EntryKind::Dir,
+            ..
+        }) => store
+            .walk(path)
+            .filter_map(|file| store.metadata_path(&file.ok()?).ok())
+            .fold((0, 0), |(bytes, files), meta| (bytes + meta.len, files + 1)),
+        Err(_) => (0, 0),
+    }
+}
+
+/// File being written to a Quota store.
+pub struct QuotaFile {
+    inner: Box<File>,
+    usage: Arc<Usage>,
+    max_bytes: u64,
+    pos: u64,
+    len: u64,
+}
+
+impl QuotaFile {
+    pub(crate) fn len(&mut self) -> io::Result<u64> {
+        self.inner.len()
+    }
+}
+
+impl Read for QuotaFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl BufRead for QuotaFile {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos += amt as u64;
+        self.inner.consume(amt)
+    }
+}
+
+impl Seek for QuotaFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.pos = self.inner.seek(pos)?;
+        Ok(self.pos)
+    }
+}
+
+impl Write for QuotaFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let end = self.pos + buf.len() as u64;
+        let grow = end.saturating_sub(self.len);
+        Usage::reserve(&self.usage.bytes, grow, self.max_bytes)?;
+        match self.inner.write(buf) {
+            Ok(n) => {
+                // Give back what wasn't written.
+                let end = self.pos + n as u64;
+                Usage::release(&self.usage.bytes, grow - end.saturating_sub(self.len));
+                self.pos = end;
+                self.len = self.len.max(end);
+                Ok(n)
+            }
+            Err(err) => {
+                Usage::release(&self.usage.bytes, grow);
+                Err(err)
+            }
+        }
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<S> Store for Quota<S>
+where
+    S: Store,
+    S::File: Into<File>,
+{
+    type File = File;
+
+    #[inline]
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        self.store.open_path(path).map(Into::into)
+    }
+
+    #[inline]
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        self.store.entries_path(path)
+    }
+
+    #[inline]
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        self.store.metadata_path(path)
+    }
+
+    fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+        let old = file_len(&self.store, path);
+        if old.is_none() {
+            Usage::reserve(&self.usage.files, 1, self.max_files)?;
+        }
+        let inner = match self.store.create_path(path) {
+            Ok(file) => file.into(),
+            Err(err) => {
+                if old.is_none() {
+                    Usage::release(&self.usage.files, 1);
+                }
+                return Err(err);
+            }
+        };
+        Usage::release(&self.usage.bytes, old.unwrap_or(0));
+        Ok(File::Quota(QuotaFile {
+            inner: Box::new(inner),
+            usage: Arc::clone(&self.usage),
+            max_bytes: self.max_bytes,
+            pos: 0,
+            len: 0,
+        }))
+    }
+
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        let (bytes, files) = usage(&self.store, path);
+        self.store.remove_path(path)?;
+        Usage::release(&self.usage.bytes, bytes);
+        Usage::release(&self.usage.files, files);
+        Ok(())
+    }
+
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        // renaming a file to itself doesn't replace anything
+        if normalize_path(from) == normalize_path(to) {
+            return self.store.rename_path(from, to);
+        }
+        let (bytes, files) = usage(&self.store, to);
+        self.store.rename_path(from, to)?;
+        Usage::release(&self.usage.bytes, bytes);
+        Usage::release(&self.usage.files, files);
+        Ok(())
+    }
+
+    #[inline]
+    fn is_read_only(&self) -> bool {
+        self.store.is_read_only()
+    }
+}
@@ -0,0 +1,137 @@
+use std::io::{self, BufRead, Cursor, ErrorKind, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use unrar::error::UnrarError;
+use unrar::{Archive, FileHeader};
+
+use crate::index::Index;
+use crate::store::Store;
+use crate::{Entries, Entry, EntryKind, Metadata};
+
+/// RAR archive store.
+///
+/// The archive is indexed when the store is created, and entries are
+/// extracted when they are opened.
+///
+/// # Remarks
+///
+/// RAR archives are read through the unrar library, which only reads from the
+/// native filesystem, so the archive is reopened every time an entry is opened.
+/// Multi-volume and encrypted archives are not supported.
+pub struct Rar {
+    path: PathBuf,
+    index: Index<Metadata>,
+}
+
+/// Entry in the RAR archive.
+pub struct RarEntry {
+    inner: Cursor<Box<[u8]>>,
+}
+
+impl RarEntry {
+    pub(crate) fn len(&self) -> u64 {
+        self.inner.get_ref().len() as u64
+    }
+}
+
+impl Read for RarEntry {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl BufRead for RarEntry {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+impl Seek for RarEntry {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl Rar {
+    /// Open and index an archive from the native filesystem.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        // unrar doesn't tell missing archives apart from other errors.
+        std::fs::metadata(&path)?;
+
+        let mut index = Index::new();
+        for header in Archive::new(&path).open_for_listing().map_err(io_error)? {
+            let header = header.map_err(io_error)?;
+            if header.is_directory() {
+                index.insert_dir(header.filename);
+            } else {
+                let meta = metadata(&header);
+                index.insert(header.filename, meta);
+            }
+        }
+        Ok(Self { path, index })
+    }
+}
+
+impl Store for Rar {
+    type File = RarEntry;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        if self.index.get(path).is_none() {
+            return Err(io::Error::from(ErrorKind::NotFound));
+        }
+
+        let mut archive = Archive::new(&self.path)
+            .open_for_processing()
+            .map_err(io_error)?;
+        while let Some(header) = archive.read_header().map_err(io_error)? {
+            if header.entry().is_file() && header.entry().filename == path {
+                let (data, _) = header.read().map_err(io_error)?;
+                return Ok(RarEntry {
+                    inner: Cursor::new(data.into()),
+                });
+            }
+            archive = header.skip().map_err(io_error)?;
+        }
+        Err(io::Error::from(ErrorKind::NotFound))
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        Ok(Entries::new(self.index.entries(path).map(|ent| {
+            let name = ent.name.to_os_string();
+            let kind = ent.kind;
+            Ok(Entry { name, kind })
+        })))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        if let Some(meta) = self.index.get(path) {
+            Ok(meta.clone())
+        } else if self.index.contains_dir(path) {
+            Ok(Metadata::dir())
+        } else {
+            Err(io::Error::from(ErrorKind::NotFound))
+        }
+    }
+}
+
+fn metadata(header: &FileHeader) -> Metadata {
+    let (date, time) = ((header.file_time >> 16) as u16, header.file_time as u16);
+    Metadata {
+        kind: EntryKind::File,
+        len: header.unpacked_size,
+        modified: crate::time::from_dos(date, time),
+    }
+}
+
+fn io_error(err: UnrarError) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, err)
+}
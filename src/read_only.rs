@@ -0,0 +1,76 @@
+use std::io;
+use std::path::Path;
+
+use crate::store::{read_only, Store};
+use crate::{Entries, Metadata};
+
+/// Store that forwards reads to another store and rejects any change.
+///
+/// Creating, removing and renaming files fail with an error of kind
+/// `ReadOnlyFilesystem`, even when the wrapped store is writable, so it can be
+/// handed to code that shouldn't modify it:
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{Local, MiniFs, ReadOnly};
+///
+/// let saves = Local::new("./saves");
+/// let plugins = MiniFs::new().mount("/saves", ReadOnly::new(saves));
+///
+/// assert!(plugins.create("/saves/slot1.sav").is_err());
+/// # Ok(())
+/// # }
+/// ```
+pub struct ReadOnly<S> {
+    store: S,
+}
+
+impl<S> ReadOnly<S> {
+    pub fn new(store: S) -> Self {
+        Self { store }
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.store
+    }
+
+    pub fn into_inner(self) -> S {
+        self.store
+    }
+}
+
+impl<S: Store> Store for ReadOnly<S> {
+    type File = S::File;
+
+    #[inline]
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        self.store.open_path(path)
+    }
+
+    #[inline]
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        self.store.entries_path(path)
+    }
+
+    #[inline]
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        self.store.metadata_path(path)
+    }
+
+    fn create_path(&self, _path: &Path) -> io::Result<Self::File> {
+        Err(read_only())
+    }
+
+    fn remove_path(&self, _path: &Path) -> io::Result<()> {
+        Err(read_only())
+    }
+
+    fn rename_path(&self, _from: &Path, _to: &Path) -> io::Result<()> {
+        Err(read_only())
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+}
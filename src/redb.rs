@@ -0,0 +1,258 @@
+use std::io::{self, BufRead, Cursor, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::rc::Rc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use redb_::{Database, TableDefinition};
+
+use crate::kv::{child_entries, key};
+use crate::store::Store;
+use crate::{Entries, Entry, EntryKind, Metadata};
+
+// Paths to the modification time (in seconds since the Unix epoch) and the
+// contents of the files.
+const FILES: TableDefinition<&str, (u64, &[u8])> = TableDefinition::new("files");
+
+/// Files stored in a [redb] database.
+///
+/// Files are kept in a `files` table, keyed by their path relative to the
+/// root of the store with components separated by `/`. Directories aren't
+/// stored, they exist as long as they contain files.
+///
+/// Every write is committed in its own transaction, so the database stays
+/// consistent if the application crashes, which makes the store a good
+/// persistent cache in front of slower stores:
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{MiniFs, Redb, Tar};
+///
+/// let cache = Redb::open("cache.redb")?;
+/// let fs = MiniFs::new().mount("/assets", (cache, Tar::open("assets.tar.gz")?));
+///
+/// let file = fs.open("/assets/textures/wall.png")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Remarks
+///
+/// Files being written are stored when they are flushed or dropped.
+///
+/// [redb]: https://docs.rs/redb
+pub struct Redb {
+    db: Rc<Database>,
+}
+
+/// File from a Redb store.
+pub struct RedbEntry {
+    inner: Cursor<Vec<u8>>,
+    // Key the contents are written back to.
+    write: Option<(String, Rc<Database>)>,
+}
+
+impl RedbEntry {
+    pub(crate) fn len(&self) -> u64 {
+        self.inner.get_ref().len() as u64
+    }
+}
+
+impl Read for RedbEntry {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl BufRead for RedbEntry {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+impl Seek for RedbEntry {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl Write for RedbEntry {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.write {
+            Some(_) => self.inner.write(buf),
+            None => Err(crate::store::read_only()),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if let Some((ref path, ref db)) = self.write {
+            insert(db, path, self.inner.get_ref())?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for RedbEntry {
+    fn drop(&mut self) {
+        let _ = Write::flush(self);
+    }
+}
+
+impl Redb {
+    /// Open a database, creating it if it doesn't exist.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::new(Database::create(path).map_err(db_error)?)
+    }
+
+    /// Use an open database, creating the `files` table if it doesn't exist.
+    pub fn new(db: Database) -> io::Result<Self> {
+        let tx = db.begin_write().map_err(db_error)?;
+        tx.open_table(FILES).map_err(db_error)?;
+        tx.commit().map_err(db_error)?;
+        Ok(Self { db: Rc::new(db) })
+    }
+
+    // Keys under the directory `dir`, or all of them for the root.
+    fn keys(&self, dir: &str) -> io::Result<Vec<String>> {
+        let prefix = if dir.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", dir)
+        };
+        let tx = self.db.begin_read().map_err(db_error)?;
+        let table = tx.open_table(FILES).map_err(db_error)?;
+        let mut keys = Vec::new();
+        for row in table.range(prefix.as_str()..).map_err(db_error)? {
+            let (key, _) = row.map_err(db_error)?;
+            match key.value().strip_prefix(prefix.as_str()) {
+                Some(rest) => keys.push(rest.to_string()),
+                None => break,
+            }
+        }
+        Ok(keys)
+    }
+}
+
+impl Store for Redb {
+    type File = RedbEntry;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        let tx = self.db.begin_read().map_err(db_error)?;
+        let table = tx.open_table(FILES).map_err(db_error)?;
+        match table.get(key(path)?.as_str()).map_err(db_error)? {
+            Some(file) => Ok(RedbEntry {
+                inner: Cursor::new(file.value().1.to_vec()),
+                write: None,
+            }),
+            None => Err(io::Error::from(ErrorKind::NotFound)),
+        }
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        let keys = self.keys(&key(path)?)?;
+        let entries = child_entries(keys.iter().map(String::as_str));
+        Ok(Entries::new(entries.into_iter().map(|(name, kind)| {
+            Ok(Entry {
+                name: name.into(),
+                kind,
+            })
+        })))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        let key = key(path)?;
+        let file = {
+            let tx = self.db.begin_read().map_err(db_error)?;
+            let table = tx.open_table(FILES).map_err(db_error)?;
+            let file = table.get(key.as_str()).map_err(db_error)?;
+            file.map(|file| {
+                let (mtime, data) = file.value();
+                (mtime, data.len() as u64)
+            })
+        };
+        match file {
+            Some((mtime, len)) => Ok(Metadata {
+                kind: EntryKind::File,
+                len,
+                modified: Some(UNIX_EPOCH + Duration::from_secs(mtime)),
+            }),
+            None if key.is_empty() || !self.keys(&key)?.is_empty() => Ok(Metadata::dir()),
+            None => Err(io::Error::from(ErrorKind::NotFound)),
+        }
+    }
+
+    fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+        let key = key(path)?;
+        insert(&self.db, &key, &[])?;
+        Ok(RedbEntry {
+            inner: Cursor::new(Vec::new()),
+            write: Some((key, Rc::clone(&self.db))),
+        })
+    }
+
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        let tx = self.db.begin_write().map_err(db_error)?;
+        let removed = {
+            let mut table = tx.open_table(FILES).map_err(db_error)?;
+            let removed = table.remove(key(path)?.as_str()).map_err(db_error)?;
+            removed.is_some()
+        };
+        if !removed {
+            return Err(io::Error::from(ErrorKind::NotFound));
+        }
+        tx.commit().map_err(db_error)
+    }
+
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let (from, to) = (key(from)?, key(to)?);
+        let tx = self.db.begin_write().map_err(db_error)?;
+        {
+            let mut table = tx.open_table(FILES).map_err(db_error)?;
+            let (mtime, data) = match table.remove(from.as_str()).map_err(db_error)? {
+                Some(file) => {
+                    let (mtime, data) = file.value();
+                    (mtime, data.to_vec())
+                }
+                None => return Err(io::Error::from(ErrorKind::NotFound)),
+            };
+            table
+                .insert(to.as_str(), (mtime, data.as_slice()))
+                .map_err(db_error)?;
+        }
+        tx.commit().map_err(db_error)
+    }
+
+    fn is_read_only(&self) -> bool {
+        false
+    }
+}
+
+fn insert(db: &Database, path: &str, data: &[u8]) -> io::Result<()> {
+    let mtime = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs());
+    let tx = db.begin_write().map_err(db_error)?;
+    tx.open_table(FILES)
+        .map_err(db_error)?
+        .insert(path, (mtime, data))
+        .map_err(db_error)?;
+    tx.commit().map_err(db_error)
+}
+
+fn db_error<E: Into<redb_::Error>>(err: E) -> io::Error {
+    match err.into() {
+        redb_::Error::Io(err) => err,
+        redb_::Error::DatabaseAlreadyOpen => {
+            io::Error::new(ErrorKind::WouldBlock, redb_::Error::DatabaseAlreadyOpen)
+        }
+        err => io::Error::other(err),
+    }
+}
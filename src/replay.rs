@@ -0,0 +1,399 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fs;
+use std::io::{self, BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::index::normalize_path;
+use crate::store::{EntryKind, Store};
+use crate::{Entries, File, Metadata};
+
+const MAGIC: &[u8; 4] = b"MFSJ";
+const VERSION: u8 = 1;
+
+// Error kinds kept by name in journals. Other kinds are replayed as `Other`.
+const KINDS: &[ErrorKind] = &[
+    ErrorKind::NotFound,
+    ErrorKind::PermissionDenied,
+    ErrorKind::ConnectionRefused,
+    ErrorKind::ConnectionReset,
+    ErrorKind::ConnectionAborted,
+    ErrorKind::NotConnected,
+    ErrorKind::AlreadyExists,
+    ErrorKind::WouldBlock,
+    ErrorKind::NotADirectory,
+    ErrorKind::IsADirectory,
+    ErrorKind::ReadOnlyFilesystem,
+    ErrorKind::InvalidInput,
+    ErrorKind::InvalidData,
+    ErrorKind::TimedOut,
+    ErrorKind::StorageFull,
+    ErrorKind::QuotaExceeded,
+    ErrorKind::FileTooLarge,
+    ErrorKind::Interrupted,
+    ErrorKind::Unsupported,
+    ErrorKind::UnexpectedEof,
+    ErrorKind::OutOfMemory,
+    ErrorKind::Other,
+];
+
+/// Files opened from a [`Recorder`], and the errors their opens failed with,
+/// in the order they were opened.
+///
+/// Journals are saved in a small binary format, and loaded back to replay
+/// them with a [`Replayer`].
+///
+/// [`Recorder`]: ./struct.Recorder.html
+/// [`Replayer`]: ./struct.Replayer.html
+#[derive(Clone, Default)]
+pub struct Journal {
+    records: Vec<Record>,
+}
+
+#[derive(Clone)]
+struct Record {
+    path: PathBuf,
+    response: Response,
+}
+
+#[derive(Clone)]
+enum Response {
+    Data(Arc<[u8]>),
+    Error(ErrorKind, String),
+}
+
+impl Response {
+    fn replay(&self) -> io::Result<File> {
+        match self {
+            Response::Data(data) => Ok(Arc::clone(data).into()),
+            Response::Error(kind, msg) => Err(io::Error::new(*kind, msg.as_str())),
+        }
+    }
+}
+
+impl Journal {
+    /// Empty journal.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of recorded opens.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Load the journal saved in the file at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::read_from(BufReader::new(fs::File::open(path)?))
+    }
+
+    /// Save the journal to the file at `path`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut file = BufWriter::new(fs::File::create(path)?);
+        self.write_to(&mut file)?;
+        file.flush()
+    }
+
+    /// Read a journal written with [`write_to`](#method.write_to).
+    pub fn read_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut header = [0; 5];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != MAGIC {
+            return Err(invalid("Not a journal."));
+        }
+        if header[4] != VERSION {
+            return Err(invalid("Unsupported journal version."));
+        }
+        let mut records = Vec::new();
+        let mut tag = [0];
+        loop {
+            // Journals end after a whole record.
+            if reader.read(&mut tag)? == 0 {
+                break;
+            }
+            let path = string(&mut reader)?.into();
+            let response = match tag[0] {
+                0 => {
+                    let len = u64::from_le_bytes(bytes(&mut reader)?);
+                    let mut data = Vec::new();
+                    (&mut reader).take(len).read_to_end(&mut data)?;
+                    if data.len() as u64 != len {
+                        return Err(ErrorKind::UnexpectedEof.into());
+                    }
+                    Response::Data(data.into())
+                }
+                1 => {
+                    let name = string(&mut reader)?;
+                    let kind = KINDS
+                        .iter()
+                        .copied()
+                        .find(|kind| format!("{:?}", kind) == name)
+                        .unwrap_or(ErrorKind::Other);
+                    Response::Error(kind, string(&mut reader)?)
+                }
+                _ => return Err(invalid("Unknown journal record.")),
+            };
+            records.push(Record { path, response });
+        }
+        Ok(Self { records })
+    }
+
+    /// Write the journal to `writer`.
+    ///
+    /// Returns an error of kind `InvalidInput` if a recorded path isn't valid
+    /// UTF-8.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        for record in &self.records {
+            let path = record
+                .path
+                .to_str()
+                .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "Non UTF-8 path."))?;
+            match &record.response {
+                Response::Data(data) => {
+                    writer.write_all(&[0])?;
+                    write_string(&mut writer, path)?;
+                    writer.write_all(&(data.len() as u64).to_le_bytes())?;
+                    writer.write_all(data)?;
+                }
+                Response::Error(kind, msg) => {
+                    writer.write_all(&[1])?;
+                    write_string(&mut writer, path)?;
+                    write_string(&mut writer, &format!("{:?}", kind))?;
+                    write_string(&mut writer, msg)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn bytes<R: Read, const N: usize>(reader: &mut R) -> io::Result<[u8; N]> {
+    let mut buf = [0; N];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn string<R: Read>(reader: &mut R) -> io::Result<String> {
+    let len = u32::from_le_bytes(bytes(reader)?);
+    // Read as it comes, so a corrupt length can't allocate gigabytes up front.
+    let mut buf = Vec::new();
+    reader.take(u64::from(len)).read_to_end(&mut buf)?;
+    if buf.len() as u64 != u64::from(len) {
+        return Err(ErrorKind::UnexpectedEof.into());
+    }
+    String::from_utf8(buf).map_err(|_| invalid("Non UTF-8 string in journal."))
+}
+
+fn write_string<W: Write>(writer: &mut W, s: &str) -> io::Result<()> {
+    let len = u32::try_from(s.len()).map_err(|_| invalid("String too long for a journal."))?;
+    writer.write_all(&len.to_le_bytes())?;
+    writer.write_all(s.as_bytes())
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg)
+}
+
+/// Store recording every open of another store to a [`Journal`], with the
+/// contents of the file or the error it failed with.
+///
+/// Sessions recorded once against the real data can be replayed later with a
+/// [`Replayer`], in CI for example, where the data isn't available:
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{MiniFs, Ram, Recorder, Replayer};
+///
+/// let mut ram = Ram::new();
+/// ram.touch("level1.dat", &b"level 1"[..]);
+/// let recorder = Recorder::new(ram);
+/// assert_eq!(b"level 1", &recorder.read("level1.dat")?[..]);
+/// assert!(recorder.open("level2.dat").is_err());
+///
+/// let fs = MiniFs::new().mount("/data", Replayer::new(recorder.journal()));
+/// assert_eq!(b"level 1", &fs.read("/data/level1.dat")?[..]);
+/// assert!(fs.open("/data/level2.dat").is_err());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Remarks
+///
+/// Files are read whole when they are opened, so their contents can be
+/// recorded. Only opens are recorded: listing, metadata and writes are
+/// forwarded as they are.
+///
+/// [`Journal`]: ./struct.Journal.html
+/// [`Replayer`]: ./struct.Replayer.html
+pub struct Recorder<S> {
+    store: S,
+    journal: Mutex<Journal>,
+}
+
+impl<S> Recorder<S> {
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            journal: Mutex::new(Journal::new()),
+        }
+    }
+
+    /// Copy of the opens recorded so far.
+    pub fn journal(&self) -> Journal {
+        self.journal
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.store
+    }
+
+    pub fn into_inner(self) -> S {
+        self.store
+    }
+}
+
+impl<S> Store for Recorder<S>
+where
+    S: Store,
+    S::File: Read + Into<File>,
+{
+    type File = File;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        let result = self.store.open_path(path).and_then(|mut file| {
+            let mut data = Vec::new();
+            file.read_to_end(&mut data)?;
+            Ok(data)
+        });
+        let response = match result {
+            Ok(data) => Response::Data(data.into()),
+            Err(err) => Response::Error(err.kind(), err.to_string()),
+        };
+        let file = response.replay();
+        self.journal
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .records
+            .push(Record {
+                path: normalize_path(path).into_owned(),
+                response,
+            });
+        file
+    }
+
+    #[inline]
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        self.store.entries_path(path)
+    }
+
+    #[inline]
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        self.store.metadata_path(path)
+    }
+
+    #[inline]
+    fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+        self.store.create_path(path).map(Into::into)
+    }
+
+    #[inline]
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        self.store.remove_path(path)
+    }
+
+    #[inline]
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.store.rename_path(from, to)
+    }
+
+    #[inline]
+    fn is_read_only(&self) -> bool {
+        self.store.is_read_only()
+    }
+}
+
+/// Read-only store serving the opens recorded in a [`Journal`].
+///
+/// Each open of a path gets the response recorded for the same open, so a
+/// file that failed to open the first time and opened the second time does
+/// the same when replayed. Once the responses recorded for a path run out, the
+/// last one is repeated. Paths that were never opened aren't found.
+///
+/// # Remarks
+///
+/// Metadata is only known for the recorded files that were opened, and
+/// directories can't be listed.
+///
+/// [`Journal`]: ./struct.Journal.html
+pub struct Replayer {
+    paths: HashMap<PathBuf, Responses>,
+}
+
+struct Responses {
+    responses: Vec<Response>,
+    next: AtomicUsize,
+}
+
+impl Replayer {
+    pub fn new(journal: Journal) -> Self {
+        let mut paths: HashMap<_, Responses> = HashMap::new();
+        for record in journal.records {
+            paths
+                .entry(normalize_path(&record.path).into_owned())
+                .or_insert_with(|| Responses {
+                    responses: Vec::new(),
+                    next: AtomicUsize::new(0),
+                })
+                .responses
+                .push(record.response);
+        }
+        Self { paths }
+    }
+
+    /// Replay the journal saved in the file at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Journal::open(path).map(Self::new)
+    }
+}
+
+impl Store for Replayer {
+    type File = File;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        let path = self
+            .paths
+            .get(&*normalize_path(path))
+            .ok_or_else(|| io::Error::from(ErrorKind::NotFound))?;
+        let next = path.next.fetch_add(1, Ordering::SeqCst);
+        path.responses[next.min(path.responses.len() - 1)].replay()
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        let data = self.paths.get(&*normalize_path(path)).and_then(|path| {
+            path.responses.iter().find_map(|response| match response {
+                Response::Data(data) => Some(data),
+                Response::Error(..) => None,
+            })
+        });
+        match data {
+            Some(data) => Ok(Metadata {
+                kind: EntryKind::File,
+                len: data.len() as u64,
+                modified: None,
+            }),
+            None => Err(io::Error::from(ErrorKind::NotFound)),
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::index::relative_path;
+use crate::store::Store;
+use crate::{Entries, Metadata};
+
+/// Store that rewrites the prefix of paths before passing them to another
+/// store.
+///
+/// Paths first lose the prefix set with [`strip_prefix`], and then get the
+/// one set with [`add_prefix`], so an archive whose entries all live in a
+/// directory can be used as if that directory were its root:
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{Local, MiniFs, Rewrite, Zip};
+///
+/// // `/assets/logo.png` opens `data/logo.png` from the archive
+/// let data = Rewrite::new(Zip::open("game.zip")?).add_prefix("data");
+///
+/// // `/legacy/v1/logo.png` opens `logo.png` from the directory
+/// let legacy = Rewrite::new(Local::new("./old")).strip_prefix("v1");
+///
+/// let fs = MiniFs::new()
+///     .mount("/assets", data)
+///     .mount("/legacy", legacy);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Remarks
+///
+/// Paths outside of the stripped prefix aren't found, and creating them fails
+/// with an error of kind `NotFound` too. Listed entries keep the names given
+/// by the wrapped store.
+///
+/// [`strip_prefix`]: #method.strip_prefix
+/// [`add_prefix`]: #method.add_prefix
+pub struct Rewrite<S> {
+    store: S,
+    strip: PathBuf,
+    add: PathBuf,
+}
+
+impl<S> Rewrite<S> {
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            strip: PathBuf::new(),
+            add: PathBuf::new(),
+        }
+    }
+
+    /// Remove `prefix` from the paths, which must start with it.
+    pub fn strip_prefix<P: AsRef<Path>>(mut self, prefix: P) -> Self {
+        self.strip = relative_path(prefix.as_ref());
+        self
+    }
+
+    /// Prepend `prefix` to the paths.
+    pub fn add_prefix<P: AsRef<Path>>(mut self, prefix: P) -> Self {
+        self.add = relative_path(prefix.as_ref());
+        self
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.store
+    }
+
+    pub fn into_inner(self) -> S {
+        self.store
+    }
+
+    fn rewrite(&self, path: &Path) -> io::Result<PathBuf> {
+        let path = relative_path(path);
+        let rest = path
+            .strip_prefix(&self.strip)
+            .map_err(|_| io::Error::from(io::ErrorKind::NotFound))?;
+        Ok(self.add.join(rest))
+    }
+}
+
+impl<S: Store> Store for Rewrite<S> {
+    type File = S::File;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        self.store.open_path(&self.rewrite(path)?)
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        self.store.entries_path(&self.rewrite(path)?)
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        self.store.metadata_path(&self.rewrite(path)?)
+    }
+
+    fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+        self.store.create_path(&self.rewrite(path)?)
+    }
+
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        self.store.remove_path(&self.rewrite(path)?)
+    }
+
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.store
+            .rename_path(&self.rewrite(from)?, &self.rewrite(to)?)
+    }
+
+    #[inline]
+    fn is_read_only(&self) -> bool {
+        self.store.is_read_only()
+    }
+}
@@ -0,0 +1,505 @@
+use std::env;
+use std::fmt::Write as _;
+use std::io::{self, BufRead, ErrorKind, Read, Seek, SeekFrom};
+use std::path::{Component, Path};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use sha2::{Digest, Sha256};
+
+#[cfg(feature = "ureq")]
+use crate::http::Ureq;
+use crate::http::{encode, HttpClient};
+use crate::index::normalize_path;
+use crate::store::Store;
+use crate::time;
+use crate::{EntryKind, Metadata};
+
+// Hash of the empty payload of GET and HEAD requests.
+const EMPTY_SHA256: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+// Objects are downloaded in ranges of at least this many bytes.
+const CHUNK_SIZE: u64 = 1 << 20;
+
+/// Amazon S3 object storage.
+///
+/// Opening `maps/de_dust.bsp` reads the object with the key
+/// `<prefix>maps/de_dust.bsp` from the bucket:
+///
+/// ```no_run
+/// # #[cfg(feature = "ureq")]
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{MiniFs, S3};
+///
+/// let s3 = S3::new("game-data").prefix("release/");
+/// let fs = MiniFs::new().mount("/data", s3);
+///
+/// let file = fs.open("/data/maps/de_dust.bsp")?;
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "ureq"))]
+/// # fn main() {}
+/// ```
+///
+/// Requests are signed with AWS Signature Version 4 when the store has
+/// [`Credentials`], and sent by an [`HttpClient`]. Objects are downloaded
+/// with ranged GET requests as they are read, so seeking within a large object
+/// only downloads the parts that are actually read.
+///
+/// # Remarks
+///
+/// Objects are listed by key only, so the store doesn't list any entries.
+/// Clients must be `Send` and `Sync`, so objects can be read from other
+/// threads.
+///
+/// [`Credentials`]: struct.Credentials.html
+/// [`HttpClient`]: ../http/trait.HttpClient.html
+pub struct S3<C> {
+    bucket: String,
+    prefix: String,
+    region: String,
+    endpoint: Option<String>,
+    credentials: Option<Credentials>,
+    client: Arc<C>,
+}
+
+/// AWS credentials used to sign requests.
+#[derive(Clone)]
+pub struct Credentials {
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+}
+
+impl Credentials {
+    pub fn new<I, S>(access_key_id: I, secret_access_key: S) -> Self
+    where
+        I: Into<String>,
+        S: Into<String>,
+    {
+        Self {
+            access_key_id: access_key_id.into(),
+            secret_access_key: secret_access_key.into(),
+            session_token: None,
+        }
+    }
+
+    /// Read the credentials from the `AWS_ACCESS_KEY_ID`,
+    /// `AWS_SECRET_ACCESS_KEY` and `AWS_SESSION_TOKEN` environment variables.
+    pub fn from_env() -> io::Result<Self> {
+        let var = |name| {
+            env::var(name)
+                .map_err(|_| io::Error::new(ErrorKind::NotFound, format!("{} is not set.", name)))
+        };
+        Ok(Self {
+            access_key_id: var("AWS_ACCESS_KEY_ID")?,
+            secret_access_key: var("AWS_SECRET_ACCESS_KEY")?,
+            session_token: var("AWS_SESSION_TOKEN").ok(),
+        })
+    }
+
+    /// Set the token of temporary credentials.
+    pub fn session_token<T: Into<String>>(mut self, token: T) -> Self {
+        self.session_token = Some(token.into());
+        self
+    }
+}
+
+/// Object opened from an S3 store.
+pub struct S3Object {
+    request: Arc<dyn Fetch + Send + Sync>,
+    len: u64,
+    pos: u64,
+    buf: Vec<u8>,
+    buf_pos: u64,
+}
+
+impl S3Object {
+    pub(crate) fn len(&self) -> u64 {
+        self.len
+    }
+
+    fn buffered(&self) -> &[u8] {
+        if self.pos >= self.buf_pos && self.pos < self.buf_pos + self.buf.len() as u64 {
+            &self.buf[(self.pos - self.buf_pos) as usize..]
+        } else {
+            &[]
+        }
+    }
+}
+
+impl Read for S3Object {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.buffered().is_empty() && buf.len() as u64 >= CHUNK_SIZE {
+            // large reads bypass the buffer
+            let end = self.len.min(self.pos + buf.len() as u64);
+            if self.pos >= end {
+                return Ok(0);
+            }
+            let data = self.request.fetch(self.pos, end)?;
+            buf[..data.len()].copy_from_slice(&data);
+            self.pos += data.len() as u64;
+            return Ok(data.len());
+        }
+        let n = {
+            let data = self.fill_buf()?;
+            let n = data.len().min(buf.len());
+            buf[..n].copy_from_slice(&data[..n]);
+            n
+        };
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl BufRead for S3Object {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.buffered().is_empty() && self.pos < self.len {
+            let end = self.len.min(self.pos + CHUNK_SIZE);
+            self.buf = self.request.fetch(self.pos, end)?;
+            self.buf_pos = self.pos;
+        }
+        Ok(self.buffered())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos += amt as u64;
+    }
+}
+
+impl Seek for S3Object {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(n) => Some(n),
+            SeekFrom::End(n) => self.len.checked_add_signed(n),
+            SeekFrom::Current(n) => self.pos.checked_add_signed(n),
+        };
+        self.pos = pos.ok_or_else(|| {
+            io::Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        Ok(self.pos)
+    }
+}
+
+// Ranged download of a single object.
+trait Fetch {
+    fn fetch(&self, start: u64, end: u64) -> io::Result<Vec<u8>>;
+}
+
+struct Request<C> {
+    url: String,
+    host: String,
+    path: String,
+    region: String,
+    credentials: Option<Credentials>,
+    client: Arc<C>,
+}
+
+impl<C: HttpClient> Request<C> {
+    fn headers(&self, method: &str, range: Option<String>) -> Vec<(String, String)> {
+        let mut headers = Vec::new();
+        if let Some(range) = range {
+            headers.push(("range".to_string(), range));
+        }
+        if let Some(credentials) = &self.credentials {
+            let date = amz_date(SystemTime::now());
+            headers.push(("x-amz-content-sha256".to_string(), EMPTY_SHA256.to_string()));
+            headers.push(("x-amz-date".to_string(), date.clone()));
+            if let Some(token) = &credentials.session_token {
+                headers.push(("x-amz-security-token".to_string(), token.clone()));
+            }
+            let auth = authorization(
+                credentials,
+                &self.region,
+                method,
+                &self.host,
+                &self.path,
+                &headers,
+                &date,
+            );
+            headers.push(("authorization".to_string(), auth));
+        }
+        headers
+    }
+
+    fn len(&self) -> io::Result<u64> {
+        match self.client.head(&self.url, &self.headers("HEAD", None))? {
+            Some(len) => Ok(len),
+            None => Ok(self
+                .client
+                .get(&self.url, &self.headers("GET", None))?
+                .len() as u64),
+        }
+    }
+}
+
+impl<C: HttpClient> Fetch for Request<C> {
+    fn fetch(&self, start: u64, end: u64) -> io::Result<Vec<u8>> {
+        let range = format!("bytes={}-{}", start, end - 1);
+        let mut data = self
+            .client
+            .get(&self.url, &self.headers("GET", Some(range)))?;
+        if data.len() as u64 > end - start {
+            // the range was ignored and the whole object was sent
+            if (data.len() as u64) < end {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    "Response shorter than the object.",
+                ));
+            }
+            data.truncate(end as usize);
+            data.drain(..start as usize);
+        }
+        if data.is_empty() {
+            return Err(io::Error::from(ErrorKind::UnexpectedEof));
+        }
+        Ok(data)
+    }
+}
+
+#[cfg(feature = "ureq")]
+impl S3<Ureq> {
+    /// Create a store for the objects of `bucket`.
+    ///
+    /// The region, the endpoint and the credentials are read from the
+    /// `AWS_REGION` (or `AWS_DEFAULT_REGION`), `AWS_ENDPOINT_URL` and
+    /// [`Credentials::from_env`] environment variables. Requests are anonymous
+    /// when there are no credentials.
+    ///
+    /// [`Credentials::from_env`]: struct.Credentials.html#method.from_env
+    pub fn new<B: Into<String>>(bucket: B) -> Self {
+        let mut s3 = Self::with_client(bucket, Ureq::default());
+        if let Ok(region) = env::var("AWS_REGION").or_else(|_| env::var("AWS_DEFAULT_REGION")) {
+            s3.region = region;
+        }
+        s3.endpoint = env::var("AWS_ENDPOINT_URL").ok();
+        s3.credentials = Credentials::from_env().ok();
+        s3
+    }
+}
+
+impl<C> S3<C> {
+    /// Create an anonymous store in the `us-east-1` region sending its
+    /// requests with `client`.
+    pub fn with_client<B: Into<String>>(bucket: B, client: C) -> Self {
+        Self {
+            bucket: bucket.into(),
+            prefix: String::new(),
+            region: "us-east-1".to_string(),
+            endpoint: None,
+            credentials: None,
+            client: Arc::new(client),
+        }
+    }
+
+    /// Prepend `prefix` to the keys of the objects.
+    pub fn prefix<P: Into<String>>(mut self, prefix: P) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Set the region of the bucket.
+    pub fn region<R: Into<String>>(mut self, region: R) -> Self {
+        self.region = region.into();
+        self
+    }
+
+    /// Send requests to an S3 compatible service instead of AWS.
+    ///
+    /// Buckets are addressed with path-style URLs
+    /// (`<endpoint>/<bucket>/<key>`).
+    pub fn endpoint<E: Into<String>>(mut self, endpoint: E) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// Sign requests with `credentials`.
+    pub fn credentials(mut self, credentials: Credentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// URL of the object at `path`.
+    pub fn url(&self, path: &Path) -> io::Result<String> {
+        let (origin, path) = self.split_url(path)?;
+        Ok(format!("{}{}", origin, path))
+    }
+
+    // Splits the URL of an object into its origin and its encoded path.
+    fn split_url(&self, path: &Path) -> io::Result<(String, String)> {
+        let mut key = self.prefix.trim_start_matches('/').to_string();
+        for comp in normalize_path(path).components() {
+            match comp {
+                Component::Normal(name) => {
+                    let name = name.to_str().ok_or_else(|| {
+                        io::Error::new(ErrorKind::InvalidInput, "Utf8 path conversion error.")
+                    })?;
+                    if !key.is_empty() && !key.ends_with('/') {
+                        key.push('/');
+                    }
+                    key.push_str(name);
+                }
+                Component::RootDir | Component::CurDir => {}
+                _ => return Err(io::Error::from(ErrorKind::NotFound)),
+            }
+        }
+        if key.is_empty() || key.ends_with('/') {
+            return Err(io::Error::from(ErrorKind::NotFound));
+        }
+
+        let (origin, mut encoded) = match &self.endpoint {
+            Some(endpoint) => {
+                let mut path = String::from("/");
+                encode(&mut path, &self.bucket);
+                (endpoint.trim_end_matches('/').to_string(), path)
+            }
+            None => (
+                format!("https://{}.s3.{}.amazonaws.com", self.bucket, self.region),
+                String::new(),
+            ),
+        };
+        for name in key.split('/') {
+            encoded.push('/');
+            encode(&mut encoded, name);
+        }
+        Ok((origin, encoded))
+    }
+
+    fn request(&self, path: &Path) -> io::Result<Request<C>> {
+        let (origin, path) = self.split_url(path)?;
+        let host = origin
+            .split("://")
+            .nth(1)
+            .unwrap_or(&origin)
+            .split('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        Ok(Request {
+            url: format!("{}{}", origin, path),
+            host,
+            path,
+            region: self.region.clone(),
+            credentials: self.credentials.clone(),
+            client: Arc::clone(&self.client),
+        })
+    }
+}
+
+impl<C: HttpClient + Send + Sync + 'static> Store for S3<C> {
+    type File = S3Object;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        let request = self.request(path)?;
+        let len = request.len()?;
+        Ok(S3Object {
+            request: Arc::new(request),
+            len,
+            pos: 0,
+            buf: Vec::new(),
+            buf_pos: 0,
+        })
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        Ok(Metadata {
+            kind: EntryKind::File,
+            len: self.request(path)?.len()?,
+            modified: None,
+        })
+    }
+}
+
+// Authorization header of a request signed with AWS Signature Version 4.
+//
+// `headers` are the lowercase headers to sign besides `host`.
+fn authorization(
+    credentials: &Credentials,
+    region: &str,
+    method: &str,
+    host: &str,
+    path: &str,
+    headers: &[(String, String)],
+    date: &str,
+) -> String {
+    let mut headers: Vec<_> = headers
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.trim()))
+        .chain(Some(("host", host)))
+        .collect();
+    headers.sort();
+    let signed = headers
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(";");
+
+    let mut canonical = format!("{}\n{}\n\n", method, path);
+    for (name, value) in &headers {
+        let _ = writeln!(canonical, "{}:{}", name, value);
+    }
+    let _ = write!(canonical, "\n{}\n{}", signed, EMPTY_SHA256);
+
+    let day = &date[..8];
+    let scope = format!("{}/{}/s3/aws4_request", day, region);
+    let to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        date,
+        scope,
+        hex(&Sha256::digest(canonical.as_bytes()))
+    );
+    let key = format!("AWS4{}", credentials.secret_access_key);
+    let key = hmac(key.as_bytes(), day.as_bytes());
+    let key = hmac(&key, region.as_bytes());
+    let key = hmac(&key, b"s3");
+    let key = hmac(&key, b"aws4_request");
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        credentials.access_key_id,
+        scope,
+        signed,
+        hex(&hmac(&key, to_sign.as_bytes()))
+    )
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut block = [0; 64];
+    if key.len() > block.len() {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let mut inner = Sha256::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    inner.update(data);
+    let mut outer = Sha256::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        let _ = write!(hex, "{:02x}", byte);
+    }
+    hex
+}
+
+// Formats a time as `YYYYMMDDTHHMMSSZ`.
+fn amz_date(time: SystemTime) -> String {
+    let (year, month, day, secs) = time::to_civil(time);
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
@@ -0,0 +1,105 @@
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+use serde_::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde_::ser::{self, Serialize, SerializeMap, Serializer};
+
+use crate::store::StoreExt;
+use crate::{Ram, RamEntry};
+
+struct Bytes<'a>(&'a [u8]);
+
+impl Serialize for Bytes<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+impl Serialize for Ram {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Collected first, because the map needs to know its length.
+        let mut paths = Vec::new();
+        for path in self.walk("") {
+            paths.push(path.map_err(ser::Error::custom)?);
+        }
+        let index = self.index.borrow();
+        let mut map = serializer.serialize_map(Some(paths.len()))?;
+        for path in &paths {
+            let name = path
+                .to_str()
+                .ok_or_else(|| ser::Error::custom("Non UTF-8 path."))?;
+            if let Some(file) = index.get(path) {
+                map.serialize_entry(name, &Bytes(&file.data))?;
+            }
+        }
+        map.end()
+    }
+}
+
+struct BytesBuf(Vec<u8>);
+
+impl<'de> Deserialize<'de> for BytesBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_byte_buf(BytesVisitor)
+    }
+}
+
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = BytesBuf;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("file contents")
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(BytesBuf(v.to_vec()))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(BytesBuf(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(BytesBuf(v.as_bytes().to_vec()))
+    }
+
+    // Formats without a bytes type use sequences of numbers.
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut data = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element()? {
+            data.push(byte);
+        }
+        Ok(BytesBuf(data))
+    }
+}
+
+impl<'de> Deserialize<'de> for Ram {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(RamVisitor)
+    }
+}
+
+struct RamVisitor;
+
+impl<'de> Visitor<'de> for RamVisitor {
+    type Value = Ram;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a map of paths to file contents")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let ram = Ram::new();
+        {
+            let mut index = ram.index.borrow_mut();
+            while let Some((path, data)) = map.next_entry::<String, BytesBuf>()? {
+                let data: Arc<[u8]> = data.0.into();
+                index.insert(Path::new(&path), RamEntry::new(data));
+            }
+        }
+        Ok(ram)
+    }
+}
@@ -0,0 +1,183 @@
+use std::cell::RefCell;
+use std::fs;
+use std::io::{self, BufRead, Cursor, ErrorKind, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use sevenz_rust::{Archive, BlockDecoder, SevenZArchiveEntry};
+
+use crate::index::Index;
+use crate::store::Store;
+use crate::{Entries, Entry, EntryKind, Metadata};
+
+/// 7-Zip archive store.
+///
+/// The archive is indexed when the store is created, so its entries can be
+/// listed right away. Encrypted archives are not supported.
+///
+/// # Remarks
+///
+/// When used with a `std::fs::File`, the file will remain open for the lifetime
+/// of the SevenZ.
+pub struct SevenZ<T: Read + Seek> {
+    inner: RefCell<T>,
+    archive: Archive,
+    index: Index<SevenZIndexEntry>,
+}
+
+struct SevenZIndexEntry {
+    // position of the file in `Archive::files`
+    file: usize,
+    meta: Metadata,
+}
+
+/// Entry in the 7-Zip archive.
+pub struct SevenZEntry {
+    inner: Cursor<Box<[u8]>>,
+}
+
+impl SevenZEntry {
+    pub(crate) fn len(&self) -> u64 {
+        self.inner.get_ref().len() as u64
+    }
+}
+
+impl Read for SevenZEntry {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl BufRead for SevenZEntry {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+impl Seek for SevenZEntry {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl SevenZ<fs::File> {
+    /// Open a file from the native filesystem.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = fs::OpenOptions::new()
+            .read(true)
+            .write(false)
+            .create(false)
+            .open(path)?;
+        Self::new(file)
+    }
+}
+
+impl<T: Read + Seek> SevenZ<T> {
+    /// Read the headers of the archive and index its contents.
+    pub fn new(mut inner: T) -> io::Result<Self> {
+        let len = inner.seek(SeekFrom::End(0))?;
+        inner.seek(SeekFrom::Start(0))?;
+        let archive = Archive::read(&mut inner, len, &[]).map_err(io_error)?;
+
+        let mut index = Index::new();
+        for (file, entry) in archive.files.iter().enumerate() {
+            let path = entry_path(entry);
+            if entry.is_directory() {
+                index.insert_dir(path);
+            } else {
+                let meta = metadata(entry);
+                index.insert(path, SevenZIndexEntry { file, meta });
+            }
+        }
+
+        Ok(Self {
+            inner: RefCell::new(inner),
+            archive,
+            index,
+        })
+    }
+}
+
+impl<T: Read + Seek> Store for SevenZ<T> {
+    type File = SevenZEntry;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        let file = match self.index.get(path) {
+            Some(entry) => entry.file,
+            None => return Err(io::Error::from(ErrorKind::NotFound)),
+        };
+        let target = &self.archive.files[file];
+
+        // Files are compressed together in folders, which can only be decoded
+        // from the start, so everything before the file has to be skipped.
+        let mut data = Vec::new();
+        if let Some(folder) = self.archive.stream_map.file_folder_index[file] {
+            let mut source = self.inner.borrow_mut();
+            let decoder = BlockDecoder::new(folder, &self.archive, &[], &mut *source);
+            decoder
+                .for_each_entries(&mut |entry, reader| {
+                    if std::ptr::eq(entry, target) {
+                        reader.read_to_end(&mut data)?;
+                        Ok(false)
+                    } else {
+                        io::copy(reader, &mut io::sink())?;
+                        Ok(true)
+                    }
+                })
+                .map_err(io_error)?;
+        }
+        Ok(SevenZEntry {
+            inner: Cursor::new(data.into()),
+        })
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        Ok(Entries::new(self.index.entries(path).map(|ent| {
+            let name = ent.name.to_os_string();
+            let kind = ent.kind;
+            Ok(Entry { name, kind })
+        })))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        if let Some(entry) = self.index.get(path) {
+            Ok(entry.meta.clone())
+        } else if self.index.contains_dir(path) {
+            Ok(Metadata::dir())
+        } else {
+            Err(io::Error::from(ErrorKind::NotFound))
+        }
+    }
+}
+
+// Archives created on Windows may use backslashes.
+fn entry_path(entry: &SevenZArchiveEntry) -> PathBuf {
+    PathBuf::from(entry.name().replace('\\', "/"))
+}
+
+fn metadata(entry: &SevenZArchiveEntry) -> Metadata {
+    Metadata {
+        kind: EntryKind::File,
+        len: entry.size(),
+        modified: if entry.has_last_modified_date {
+            Some(SystemTime::from(entry.last_modified_date()))
+        } else {
+            None
+        },
+    }
+}
+
+fn io_error(err: sevenz_rust::Error) -> io::Error {
+    match err {
+        sevenz_rust::Error::Io(err, _) | sevenz_rust::Error::FileOpen(err, _) => err,
+        err => io::Error::new(ErrorKind::InvalidData, err),
+    }
+}
@@ -0,0 +1,319 @@
+use std::io::{self, BufRead, Cursor, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::rc::Rc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
+
+use crate::kv::{child_entries, key};
+use crate::store::{read_only, Store};
+use crate::{Entries, Entry, EntryKind, Metadata};
+
+/// Files stored in a SQLite database.
+///
+/// Files are the rows of a table with the following schema, where `path` is
+/// relative to the root of the store, with components separated by `/`, and
+/// `mtime` is the modification time in seconds since the Unix epoch:
+///
+/// ```sql
+/// CREATE TABLE files (
+///     path TEXT PRIMARY KEY NOT NULL,
+///     data BLOB NOT NULL,
+///     mtime INTEGER
+/// );
+/// ```
+///
+/// Directories aren't stored, they exist as long as they contain files.
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{MiniFs, Sqlite};
+/// use std::io::Write;
+///
+/// let db = Sqlite::open_writable("assets.db")?;
+/// db.create("textures/wall.png")?.write_all(b"...")?;
+///
+/// let fs = MiniFs::new().mount("/assets", db);
+/// let file = fs.open("/assets/textures/wall.png")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Remarks
+///
+/// Stores are read-only unless they are opened with
+/// [`open_writable`](#method.open_writable) or made
+/// [`writable`](#method.writable). Files being written are stored when they
+/// are flushed or dropped.
+pub struct Sqlite {
+    conn: Rc<Connection>,
+    writable: bool,
+}
+
+/// File from a Sqlite store.
+pub struct SqliteEntry {
+    inner: Cursor<Vec<u8>>,
+    // Row the contents are written back to.
+    write: Option<(String, Rc<Connection>)>,
+}
+
+impl SqliteEntry {
+    pub(crate) fn len(&self) -> u64 {
+        self.inner.get_ref().len() as u64
+    }
+}
+
+impl Read for SqliteEntry {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl BufRead for SqliteEntry {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+impl Seek for SqliteEntry {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl Write for SqliteEntry {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.write {
+            Some(_) => self.inner.write(buf),
+            None => Err(read_only()),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if let Some((ref path, ref conn)) = self.write {
+            insert(conn, path, self.inner.get_ref())?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for SqliteEntry {
+    fn drop(&mut self) {
+        let _ = Write::flush(self);
+    }
+}
+
+impl Sqlite {
+    /// Open a database for reading.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let flags = OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX;
+        let conn = Connection::open_with_flags(path, flags).map_err(sql_error)?;
+        Ok(Self::new(conn))
+    }
+
+    /// Open a database for reading and writing, creating it if it doesn't exist.
+    pub fn open_writable<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let conn = Connection::open(path).map_err(sql_error)?;
+        Self::new(conn).writable()
+    }
+
+    /// Read files from an open connection.
+    pub fn new(conn: Connection) -> Self {
+        Self {
+            conn: Rc::new(conn),
+            writable: false,
+        }
+    }
+
+    /// Allow writes to the store, creating the `files` table if it doesn't
+    /// exist.
+    pub fn writable(mut self) -> io::Result<Self> {
+        self.conn
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS files (
+                    path TEXT PRIMARY KEY NOT NULL,
+                    data BLOB NOT NULL,
+                    mtime INTEGER
+                )",
+            )
+            .map_err(sql_error)?;
+        self.writable = true;
+        Ok(self)
+    }
+
+    // Whether there are files under the directory `dir`.
+    fn contains_dir(&self, dir: &str) -> io::Result<bool> {
+        if dir.is_empty() {
+            return Ok(true);
+        }
+        let prefix = format!("{}/", dir);
+        self.conn
+            .query_row(
+                "SELECT 1 FROM files WHERE substr(path, 1, ?2) = ?1 LIMIT 1",
+                params![prefix, prefix.chars().count() as i64],
+                |_| Ok(()),
+            )
+            .optional()
+            .map(|row| row.is_some())
+            .map_err(sql_error)
+    }
+
+    fn check_writable(&self) -> io::Result<()> {
+        if self.writable {
+            Ok(())
+        } else {
+            Err(read_only())
+        }
+    }
+}
+
+impl Store for Sqlite {
+    type File = SqliteEntry;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        let data: Vec<u8> = self
+            .conn
+            .query_row(
+                "SELECT data FROM files WHERE path = ?1",
+                params![key(path)?],
+                |row| row.get(0),
+            )
+            .map_err(sql_error)?;
+        Ok(SqliteEntry {
+            inner: Cursor::new(data),
+            write: None,
+        })
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        let dir = key(path)?;
+        let prefix = if dir.is_empty() {
+            dir
+        } else {
+            format!("{}/", dir)
+        };
+        let mut stmt = self
+            .conn
+            .prepare("SELECT path FROM files WHERE substr(path, 1, ?2) = ?1")
+            .map_err(sql_error)?;
+        let rows = stmt
+            .query_map(params![prefix, prefix.chars().count() as i64], |row| {
+                row.get::<_, String>(0)
+            })
+            .map_err(sql_error)?;
+        let keys = rows.collect::<Result<Vec<_>, _>>().map_err(sql_error)?;
+        let entries = child_entries(keys.iter().map(|key| &key[prefix.len()..]));
+        Ok(Entries::new(entries.into_iter().map(|(name, kind)| {
+            Ok(Entry {
+                name: name.into(),
+                kind,
+            })
+        })))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        let key = key(path)?;
+        let file = self
+            .conn
+            .query_row(
+                "SELECT length(data), mtime FROM files WHERE path = ?1",
+                params![key],
+                |row| Ok((row.get::<_, i64>(0)?, row.get::<_, Option<i64>>(1)?)),
+            )
+            .optional()
+            .map_err(sql_error)?;
+        match file {
+            Some((len, mtime)) => Ok(Metadata {
+                kind: EntryKind::File,
+                len: len as u64,
+                modified: mtime
+                    .filter(|&secs| secs >= 0)
+                    .map(|secs| UNIX_EPOCH + Duration::from_secs(secs as u64)),
+            }),
+            None if self.contains_dir(&key)? => Ok(Metadata::dir()),
+            None => Err(io::Error::from(ErrorKind::NotFound)),
+        }
+    }
+
+    fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+        self.check_writable()?;
+        let key = key(path)?;
+        insert(&self.conn, &key, &[])?;
+        Ok(SqliteEntry {
+            inner: Cursor::new(Vec::new()),
+            write: Some((key, Rc::clone(&self.conn))),
+        })
+    }
+
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        self.check_writable()?;
+        let removed = self
+            .conn
+            .execute("DELETE FROM files WHERE path = ?1", params![key(path)?])
+            .map_err(sql_error)?;
+        if removed == 0 {
+            Err(io::Error::from(ErrorKind::NotFound))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.check_writable()?;
+        let (from, to) = (key(from)?, key(to)?);
+        let tx = self.conn.unchecked_transaction().map_err(sql_error)?;
+        if from != to {
+            tx.execute("DELETE FROM files WHERE path = ?1", params![to])
+                .map_err(sql_error)?;
+        }
+        let renamed = tx
+            .execute(
+                "UPDATE files SET path = ?2 WHERE path = ?1",
+                params![from, to],
+            )
+            .map_err(sql_error)?;
+        if renamed == 0 {
+            return Err(io::Error::from(ErrorKind::NotFound));
+        }
+        tx.commit().map_err(sql_error)
+    }
+
+    fn is_read_only(&self) -> bool {
+        !self.writable
+    }
+}
+
+fn insert(conn: &Connection, path: &str, data: &[u8]) -> io::Result<()> {
+    let mtime = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs() as i64);
+    conn.execute(
+        "INSERT OR REPLACE INTO files (path, data, mtime) VALUES (?1, ?2, ?3)",
+        params![path, data, mtime],
+    )
+    .map_err(sql_error)?;
+    Ok(())
+}
+
+fn sql_error(err: rusqlite::Error) -> io::Error {
+    let kind = match err {
+        rusqlite::Error::QueryReturnedNoRows => ErrorKind::NotFound,
+        rusqlite::Error::SqliteFailure(ref err, _) => match err.code {
+            rusqlite::ErrorCode::ReadOnly => ErrorKind::ReadOnlyFilesystem,
+            rusqlite::ErrorCode::PermissionDenied => ErrorKind::PermissionDenied,
+            rusqlite::ErrorCode::CannotOpen => ErrorKind::NotFound,
+            _ => ErrorKind::Other,
+        },
+        _ => ErrorKind::Other,
+    };
+    io::Error::new(kind, err)
+}
@@ -0,0 +1,155 @@
+use std::fs;
+use std::io::{self, BufRead, BufReader, Cursor, ErrorKind, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+use backhand::{FilesystemReader, InnerNode, Node, SquashfsFileReader};
+
+use crate::index::Index;
+use crate::store::Store;
+use crate::{Entries, Entry, EntryKind, Metadata};
+
+/// SquashFS image store.
+///
+/// The image is read in-process, without mounting it. The directory tree is
+/// indexed when the store is created, and only the blocks of a file are
+/// decompressed when it is opened. Symlinks and special files are skipped.
+///
+/// # Remarks
+///
+/// When used with a `std::fs::File`, the file will remain open for the lifetime
+/// of the SquashFs.
+pub struct SquashFs {
+    fs: FilesystemReader<'static>,
+    index: Index<SquashFsIndexEntry>,
+}
+
+struct SquashFsIndexEntry {
+    // position of the node in `FilesystemReader::root`
+    node: usize,
+    meta: Metadata,
+}
+
+/// Entry in the SquashFS image.
+pub struct SquashFsEntry {
+    inner: Cursor<Box<[u8]>>,
+}
+
+impl SquashFsEntry {
+    pub(crate) fn len(&self) -> u64 {
+        self.inner.get_ref().len() as u64
+    }
+}
+
+impl Read for SquashFsEntry {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl BufRead for SquashFsEntry {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+impl Seek for SquashFsEntry {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl SquashFs {
+    /// Open an image from the native filesystem.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = fs::OpenOptions::new()
+            .read(true)
+            .write(false)
+            .create(false)
+            .open(path)?;
+        Self::new(file)
+    }
+
+    /// Read the superblock and the directory tree of the image.
+    pub fn new<T: Read + Seek + Send + 'static>(inner: T) -> io::Result<Self> {
+        let fs = FilesystemReader::from_reader(BufReader::new(inner))?;
+
+        let mut index = Index::new();
+        for (node, entry) in fs.files().enumerate() {
+            let path = entry_path(entry);
+            match entry.inner {
+                InnerNode::File(ref file) => {
+                    let meta = metadata(entry, file);
+                    index.insert(path, SquashFsIndexEntry { node, meta });
+                }
+                InnerNode::Dir(_) => index.insert_dir(path),
+                _ => {}
+            }
+        }
+
+        Ok(Self { fs, index })
+    }
+}
+
+impl Store for SquashFs {
+    type File = SquashFsEntry;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        let node = match self.index.get(path) {
+            Some(entry) => &self.fs.root.nodes[entry.node],
+            None => return Err(io::Error::from(ErrorKind::NotFound)),
+        };
+        let file = match node.inner {
+            InnerNode::File(ref file) => file,
+            _ => return Err(io::Error::from(ErrorKind::NotFound)),
+        };
+
+        let mut data = Vec::new();
+        self.fs.file(file).reader().read_to_end(&mut data)?;
+        Ok(SquashFsEntry {
+            inner: Cursor::new(data.into()),
+        })
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        Ok(Entries::new(self.index.entries(path).map(|ent| {
+            let name = ent.name.to_os_string();
+            let kind = ent.kind;
+            Ok(Entry { name, kind })
+        })))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        if let Some(entry) = self.index.get(path) {
+            Ok(entry.meta.clone())
+        } else if self.index.contains_dir(path) {
+            Ok(Metadata::dir())
+        } else {
+            Err(io::Error::from(ErrorKind::NotFound))
+        }
+    }
+}
+
+// Nodes have absolute paths, with the root of the image at "/".
+fn entry_path<T>(node: &Node<T>) -> PathBuf {
+    node.fullpath
+        .strip_prefix("/")
+        .unwrap_or(&node.fullpath)
+        .to_path_buf()
+}
+
+fn metadata(node: &Node<SquashfsFileReader>, file: &SquashfsFileReader) -> Metadata {
+    Metadata {
+        kind: EntryKind::File,
+        len: file.file_len() as u64,
+        modified: Some(UNIX_EPOCH + Duration::from_secs(u64::from(node.header.mtime))),
+    }
+}
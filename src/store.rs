@@ -1,8 +1,12 @@
 use std::collections::btree_set::BTreeSet;
 use std::ffi::OsString;
-use std::io::{self, Read, Seek, Write};
-use std::marker::PhantomData;
+use std::io;
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use crate::glob::Glob;
 
 /// File or directory entry.
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -11,6 +15,27 @@ pub struct Entry {
     pub kind: EntryKind,
 }
 
+/// File or directory metadata.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Metadata {
+    pub kind: EntryKind,
+    /// Size of the file in bytes. Uncompressed size for archive entries, and
+    /// zero for directories.
+    pub len: u64,
+    /// Modification time, when the store keeps track of it.
+    pub modified: Option<SystemTime>,
+}
+
+impl Metadata {
+    pub(crate) fn dir() -> Self {
+        Self {
+            kind: EntryKind::Dir,
+            len: 0,
+            modified: None,
+        }
+    }
+}
+
 /// Type of file entry.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum EntryKind {
@@ -49,6 +74,63 @@ impl Iterator for Entries<'_> {
     }
 }
 
+/// Recursive iterator over the file paths of a Store.
+///
+/// Directories are visited depth first. Returned by [`StoreExt::walk`].
+///
+/// [`StoreExt::walk`]: ./trait.StoreExt.html#method.walk
+pub struct Walk<'a, S: ?Sized> {
+    store: &'a S,
+    stack: Vec<(PathBuf, Option<Entries<'a>>)>,
+}
+
+impl<'a, S: ?Sized> Walk<'a, S> {
+    fn new(store: &'a S, root: PathBuf) -> Self {
+        Self {
+            store,
+            stack: vec![(root, None)],
+        }
+    }
+}
+
+impl<'a, S: Store + ?Sized> Iterator for Walk<'a, S> {
+    type Item = io::Result<PathBuf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let store: &'a S = self.store;
+        loop {
+            let (dir, entries) = self.stack.last_mut()?;
+            let entries = match entries {
+                Some(entries) => entries,
+                None => match store.entries_path(dir) {
+                    Ok(new) => entries.insert(new),
+                    Err(err) => {
+                        self.stack.pop();
+                        return Some(Err(err));
+                    }
+                },
+            };
+            match entries.next() {
+                None => {
+                    self.stack.pop();
+                }
+                Some(Err(err)) => return Some(Err(err)),
+                Some(Ok(entry)) => {
+                    // Some stores return names relative to their root rather
+                    // than to the listed directory, so only the last component
+                    // is used.
+                    let name = Path::new(&entry.name);
+                    let path = dir.join(name.file_name().unwrap_or(name.as_os_str()));
+                    match entry.kind {
+                        EntryKind::File => return Some(Ok(path)),
+                        EntryKind::Dir => self.stack.push((path, None)),
+                    }
+                }
+            }
+        }
+    }
+}
+
 /// Generic file storage.
 pub trait Store {
     type File;
@@ -59,24 +141,283 @@ pub trait Store {
     ///
     /// Order is not defined, so it may be depth first, breadth first, or any
     /// arbitrary order.
-    fn entries_path(&self, path: &Path) -> io::Result<Entries> {
+    fn entries_path(&self, _path: &Path) -> io::Result<Entries<'_>> {
         Ok(Entries::empty())
     }
+
+    /// Query the metadata of a file or directory.
+    ///
+    /// Returns an error of kind `Unsupported` by default.
+    fn metadata_path(&self, _path: &Path) -> io::Result<Metadata> {
+        Err(io::Error::from(io::ErrorKind::Unsupported))
+    }
+
+    /// Create a file for writing, truncating it if it already exists.
+    ///
+    /// Stores are read-only by default, which is signaled with an error of
+    /// kind `ReadOnlyFilesystem`.
+    fn create_path(&self, _path: &Path) -> io::Result<Self::File> {
+        Err(read_only())
+    }
+
+    /// Remove a file.
+    ///
+    /// Like [`create_path`](#method.create_path), this fails with an error of
+    /// kind `ReadOnlyFilesystem` by default.
+    fn remove_path(&self, _path: &Path) -> io::Result<()> {
+        Err(read_only())
+    }
+
+    /// Rename a file, replacing `to` if it already exists.
+    ///
+    /// Fails with an error of kind `ReadOnlyFilesystem` by default.
+    fn rename_path(&self, _from: &Path, _to: &Path) -> io::Result<()> {
+        Err(read_only())
+    }
+
+    /// Whether every change fails with an error of kind `ReadOnlyFilesystem`.
+    ///
+    /// Returns `true` by default, so stores overriding
+    /// [`create_path`](#method.create_path) should override it too.
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
+    /// Type name of the store that would open the file at `path`, without
+    /// reading it.
+    ///
+    /// Tuples report the first of their stores with the file, and other stores
+    /// their own type name. Returns an error of kind `NotFound` if there is no
+    /// file at `path`.
+    fn resolve_path(&self, path: &Path) -> io::Result<&'static str> {
+        match kind(self, path) {
+            Some(EntryKind::File) => Ok(std::any::type_name::<Self>()),
+            _ => Err(io::Error::from(io::ErrorKind::NotFound)),
+        }
+    }
+}
+
+pub(crate) fn read_only() -> io::Error {
+    io::Error::new(io::ErrorKind::ReadOnlyFilesystem, "Read-only store.")
+}
+
+// Outcome of trying to modify a file (remove, rename) in several stores in
+// order. The first store that has the file wins. Read-only stores and stores
+// that don't have the file are skipped; if none of them could do it, a read-only
+// error takes precedence over a `NotFound` one.
+#[derive(Default)]
+pub(crate) struct TryEach {
+    read_only: bool,
+}
+
+impl TryEach {
+    /// Returns `Some` when the search is over.
+    pub(crate) fn next(&mut self, res: io::Result<()>) -> Option<io::Result<()>> {
+        match res {
+            Err(ref err) if err.kind() == io::ErrorKind::ReadOnlyFilesystem => {
+                self.read_only = true;
+                None
+            }
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => None,
+            res => Some(res),
+        }
+    }
+
+    pub(crate) fn finish(self) -> io::Result<()> {
+        if self.read_only {
+            Err(read_only())
+        } else {
+            Err(io::Error::from(io::ErrorKind::NotFound))
+        }
+    }
 }
 
 /// Convenient methods on top of Store.
 pub trait StoreExt: Store {
-    fn entries<P: AsRef<Path>>(&self, path: P) -> io::Result<Entries> {
+    fn entries<P: AsRef<Path>>(&self, path: P) -> io::Result<Entries<'_>> {
         <Self as Store>::entries_path(self, &crate::index::normalize_path(path.as_ref()))
     }
 
+    /// Open a file at any kind of path, like `&str`, `String` or `PathBuf`.
+    ///
+    /// Paths are normalized before they are passed to
+    /// [`open_path`](trait.Store.html#tymethod.open_path).
     fn open<P: AsRef<Path>>(&self, path: P) -> io::Result<Self::File> {
         <Self as Store>::open_path(self, &crate::index::normalize_path(path.as_ref()))
     }
+
+    /// Read the whole contents of a file, like `std::fs::read`.
+    fn read<P: AsRef<Path>>(&self, path: P) -> io::Result<Vec<u8>>
+    where
+        Self::File: io::Read,
+    {
+        let mut data = Vec::new();
+        io::Read::read_to_end(&mut self.open(path)?, &mut data)?;
+        Ok(data)
+    }
+
+    /// Read the whole contents of a file into a string, like
+    /// `std::fs::read_to_string`.
+    ///
+    /// Returns an error of kind `InvalidData` if the file isn't valid UTF-8.
+    fn read_to_string<P: AsRef<Path>>(&self, path: P) -> io::Result<String>
+    where
+        Self::File: io::Read,
+    {
+        let mut data = String::new();
+        io::Read::read_to_string(&mut self.open(path)?, &mut data)?;
+        Ok(data)
+    }
+
+    /// Deserialize a JSON file (`json` feature).
+    ///
+    /// Files that can't be deserialized return an error of kind `InvalidData`,
+    /// with their path in the message.
+    #[cfg(feature = "json")]
+    fn read_json<T, P>(&self, path: P) -> io::Result<T>
+    where
+        T: serde_::de::DeserializeOwned,
+        P: AsRef<Path>,
+        Self::File: io::Read,
+    {
+        let path = path.as_ref();
+        let data = self.read(path)?;
+        serde_json::from_slice(&data).map_err(|err| invalid_data(path, err))
+    }
+
+    /// Deserialize a TOML file (`toml` feature), like
+    /// [`read_json`](#method.read_json).
+    #[cfg(feature = "toml")]
+    fn read_toml<T, P>(&self, path: P) -> io::Result<T>
+    where
+        T: serde_::de::DeserializeOwned,
+        P: AsRef<Path>,
+        Self::File: io::Read,
+    {
+        let path = path.as_ref();
+        let data = self.read_to_string(path)?;
+        toml_::from_str(&data).map_err(|err| invalid_data(path, err))
+    }
+
+    /// Deserialize a RON file (`ron` feature), like
+    /// [`read_json`](#method.read_json).
+    #[cfg(feature = "ron")]
+    fn read_ron<T, P>(&self, path: P) -> io::Result<T>
+    where
+        T: serde_::de::DeserializeOwned,
+        P: AsRef<Path>,
+        Self::File: io::Read,
+    {
+        let path = path.as_ref();
+        let data = self.read(path)?;
+        ron_::de::from_bytes(&data).map_err(|err| invalid_data(path, err))
+    }
+
+    fn metadata<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
+        <Self as Store>::metadata_path(self, &crate::index::normalize_path(path.as_ref()))
+    }
+
+    /// Returns `true` if the path points to an existing file or directory.
+    fn exists<P: AsRef<Path>>(&self, path: P) -> bool {
+        kind(self, &crate::index::normalize_path(path.as_ref())).is_some()
+    }
+
+    fn is_file<P: AsRef<Path>>(&self, path: P) -> bool {
+        kind(self, &crate::index::normalize_path(path.as_ref())) == Some(EntryKind::File)
+    }
+
+    fn is_dir<P: AsRef<Path>>(&self, path: P) -> bool {
+        kind(self, &crate::index::normalize_path(path.as_ref())) == Some(EntryKind::Dir)
+    }
+
+    fn create<P: AsRef<Path>>(&self, path: P) -> io::Result<Self::File> {
+        <Self as Store>::create_path(self, &crate::index::normalize_path(path.as_ref()))
+    }
+
+    fn remove<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        <Self as Store>::remove_path(self, &crate::index::normalize_path(path.as_ref()))
+    }
+
+    fn rename<P: AsRef<Path>, Q: AsRef<Path>>(&self, from: P, to: Q) -> io::Result<()> {
+        <Self as Store>::rename_path(
+            self,
+            &crate::index::normalize_path(from.as_ref()),
+            &crate::index::normalize_path(to.as_ref()),
+        )
+    }
+
+    /// Recursively iterate over the paths of every file reachable from `path`.
+    ///
+    /// The returned paths include `path` as a prefix, so they can be passed
+    /// back to [`open`](#method.open) as they are. When entries are shadowed
+    /// (overlay tuples, or several mounts of a `MiniFs`), each path is yielded
+    /// only once.
+    fn walk<P: AsRef<Path>>(&self, path: P) -> Walk<'_, Self>
+    where
+        Self: Sized,
+    {
+        Walk::new(
+            self,
+            crate::index::normalize_path(path.as_ref()).into_owned(),
+        )
+    }
+
+    /// Iterate over the paths of every file matching a glob `pattern`.
+    ///
+    /// `?` matches any character and `*` any number of them within a name,
+    /// `[a-z]` and `[!a-z]` match characters in (or outside) a set, and `**`
+    /// matches any number of directories. The walk starts at the directory
+    /// before the first wildcard, so only the directories that can match are
+    /// listed:
+    ///
+    /// ```no_run
+    /// # fn main() -> std::io::Result<()> {
+    /// use mini_fs::prelude::*;
+    /// use mini_fs::{Local, MiniFs, Zip};
+    ///
+    /// let fs = MiniFs::new()
+    ///     .mount("/res", Local::new("./res"))
+    ///     .mount("/res", Zip::open("res.zip")?);
+    ///
+    /// for path in fs.glob("/res/**/*.png")? {
+    ///     let image = fs.open(path?)?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// Returns an error of kind `InvalidInput` when a `[` isn't closed or the
+    /// pattern isn't valid UTF-8.
+    fn glob(&self, pattern: &str) -> io::Result<Glob<'_, Self>>
+    where
+        Self: Sized,
+    {
+        Glob::new(self, pattern)
+    }
 }
 
 impl<T: Store> StoreExt for T {}
 
+#[cfg(any(feature = "json", feature = "toml", feature = "ron"))]
+fn invalid_data<E: std::fmt::Display>(path: &Path, err: E) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{}: {}", path.display(), err),
+    )
+}
+
+// Stores without metadata support can still tell whether a file exists by
+// opening it.
+fn kind<S: Store + ?Sized>(store: &S, path: &Path) -> Option<EntryKind> {
+    match store.metadata_path(path) {
+        Ok(meta) => Some(meta.kind),
+        Err(ref err) if err.kind() == io::ErrorKind::Unsupported => {
+            store.open_path(path).ok().map(|_| EntryKind::File)
+        }
+        Err(_) => None,
+    }
+}
+
 pub(crate) struct MapFile<S, F> {
     store: S,
     clo: F,
@@ -106,20 +447,208 @@ where
     }
 
     #[inline]
-    fn entries_path(&self, path: &Path) -> io::Result<Entries> {
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
         self.store.entries_path(path)
     }
+
+    #[inline]
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        self.store.metadata_path(path)
+    }
+
+    #[inline]
+    fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+        match self.store.create_path(path) {
+            Ok(file) => Ok((self.clo)(file)),
+            Err(err) => Err(err),
+        }
+    }
+
+    #[inline]
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        self.store.remove_path(path)
+    }
+
+    #[inline]
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.store.rename_path(from, to)
+    }
+
+    #[inline]
+    fn is_read_only(&self) -> bool {
+        self.store.is_read_only()
+    }
+
+    #[inline]
+    fn resolve_path(&self, path: &Path) -> io::Result<&'static str> {
+        self.store.resolve_path(path)
+    }
+}
+
+// Forwards every method to the store that `Self` dereferences or coerces to.
+macro_rules! forward {
+    ($target:ty) => {
+        #[inline]
+        fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+            <$target as Store>::open_path(self, path)
+        }
+
+        #[inline]
+        fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+            <$target as Store>::entries_path(self, path)
+        }
+
+        #[inline]
+        fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+            <$target as Store>::metadata_path(self, path)
+        }
+
+        #[inline]
+        fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+            <$target as Store>::create_path(self, path)
+        }
+
+        #[inline]
+        fn remove_path(&self, path: &Path) -> io::Result<()> {
+            <$target as Store>::remove_path(self, path)
+        }
+
+        #[inline]
+        fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+            <$target as Store>::rename_path(self, from, to)
+        }
+
+        #[inline]
+        fn is_read_only(&self) -> bool {
+            <$target as Store>::is_read_only(self)
+        }
+
+        #[inline]
+        fn resolve_path(&self, path: &Path) -> io::Result<&'static str> {
+            <$target as Store>::resolve_path(self, path)
+        }
+    };
+}
+
+// Stores behind references and smart pointers can be shared between several
+// mounts and tuples.
+macro_rules! pointers {
+    ($($ptr:ty),+) => {
+        $(
+        impl<S: Store + ?Sized> Store for $ptr {
+            type File = S::File;
+
+            forward!(S);
+        }
+        )+
+    };
+}
+
+pointers!(&S, Box<S>, Rc<S>, Arc<S>);
+
+/// Slices merge stores of the same type, like tuples do, but keep the type of
+/// their files.
+impl<S: Store> Store for [S] {
+    type File = S::File;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        for store in self {
+            match store.open_path(path) {
+                Err(ref err) if err.kind() == io::ErrorKind::NotFound => {}
+                res => return res,
+            }
+        }
+        Err(io::Error::from(io::ErrorKind::NotFound))
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        let mut lists = Vec::with_capacity(self.len());
+        for store in self {
+            lists.push(match store.entries_path(path) {
+                Ok(entries) => entries,
+                Err(ref err) if err.kind() == io::ErrorKind::NotFound => Entries::empty(),
+                Err(err) => return Err(err),
+            });
+        }
+        Ok(Entries::new(TupleEntries::new(lists.into_iter().flatten())))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        for store in self {
+            match store.metadata_path(path) {
+                Err(ref err) if err.kind() == io::ErrorKind::NotFound => {}
+                res => return res,
+            }
+        }
+        Err(io::Error::from(io::ErrorKind::NotFound))
+    }
+
+    fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+        for store in self {
+            match store.create_path(path) {
+                Err(ref err) if err.kind() == io::ErrorKind::ReadOnlyFilesystem => {}
+                res => return res,
+            }
+        }
+        Err(read_only())
+    }
+
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        let mut each = TryEach::default();
+        for store in self {
+            if let Some(res) = each.next(store.remove_path(path)) {
+                return res;
+            }
+        }
+        each.finish()
+    }
+
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let mut each = TryEach::default();
+        for store in self {
+            if let Some(res) = each.next(store.rename_path(from, to)) {
+                return res;
+            }
+        }
+        each.finish()
+    }
+
+    fn is_read_only(&self) -> bool {
+        self.iter().all(Store::is_read_only)
+    }
+
+    fn resolve_path(&self, path: &Path) -> io::Result<&'static str> {
+        for store in self {
+            match store.resolve_path(path) {
+                Err(ref err) if err.kind() == io::ErrorKind::NotFound => {}
+                res => return res,
+            }
+        }
+        Err(io::Error::from(io::ErrorKind::NotFound))
+    }
+}
+
+impl<S: Store> Store for Vec<S> {
+    type File = S::File;
+
+    forward!([S]);
+}
+
+impl<S: Store, const N: usize> Store for [S; N] {
+    type File = S::File;
+
+    forward!([S]);
 }
 
 // iterator + set to take care of repeating elements.
 // TODO consider other data structures for the set.
-struct TupleEntries<I> {
+pub(crate) struct TupleEntries<I> {
     inner: I,
     set: BTreeSet<OsString>,
 }
 
 impl<I> TupleEntries<I> {
-    fn new(inner: I) -> Self {
+    pub(crate) fn new(inner: I) -> Self {
         Self {
             inner,
             set: BTreeSet::new(),
@@ -147,10 +676,17 @@ where
     }
 }
 
+// Elements that don't contain the path are skipped, like in `open_path`.
 macro_rules! entries {
-    ($self:ident, $path:expr, $head:ident,) => { $self.0.entries_path($path)? };
-    ($self:ident, $path:expr, $head:ident, $($tail:ident,)+) => {
-        $self.0.entries_path($path)?.chain(entries!($self, $path, $($tail,)+) )
+    ($path:expr, $head:ident,) => {
+        match $head.entries_path($path) {
+            Ok(entries) => entries,
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => Entries::empty(),
+            Err(err) => return Err(err),
+        }
+    };
+    ($path:expr, $head:ident, $($tail:ident,)+) => {
+        entries!($path, $head,).chain(entries!($path, $($tail,)+))
     }
 }
 
@@ -184,15 +720,108 @@ macro_rules! tuples {
                 Err(io::Error::from(io::ErrorKind::NotFound))
             }
 
-            fn entries_path(&self, path: &Path) -> io::Result<Entries> {
+            #[allow(non_snake_case)]
+            fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
                 // chain all elements from the tuple
-                let raw = entries!(self, path, $head, $($tail,)+);
+                let ($head, $($tail,)+) = self;
+                let raw = entries!(path, $head, $($tail,)+);
                 Ok(Entries::new(TupleEntries::new(raw)))
             }
+
+            #[allow(non_snake_case)]
+            fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+                let ($head, $($tail,)+) = self;
+                match $head.metadata_path(path) {
+                    Err(ref err) if err.kind() == io::ErrorKind::NotFound => {},
+                    res => return res,
+                }
+                $(
+                match $tail.metadata_path(path) {
+                    Err(ref err) if err.kind() == io::ErrorKind::NotFound => {},
+                    res => return res,
+                }
+                )+
+
+                Err(io::Error::from(io::ErrorKind::NotFound))
+            }
+
+            // Files are created in the first element that isn't read-only.
+            #[allow(non_snake_case)]
+            fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+                let ($head, $($tail,)+) = self;
+                match $head.create_path(path) {
+                    Ok(file) => return Ok(file.into()),
+                    Err(ref err) if err.kind() == io::ErrorKind::ReadOnlyFilesystem => {},
+                    Err(err) => return Err(err),
+                }
+                $(
+                match $tail.create_path(path) {
+                    Ok(file) => return Ok(file.into()),
+                    Err(ref err) if err.kind() == io::ErrorKind::ReadOnlyFilesystem => {},
+                    Err(err) => return Err(err),
+                }
+                )+
+
+                Err(read_only())
+            }
+
+            #[allow(non_snake_case)]
+            fn remove_path(&self, path: &Path) -> io::Result<()> {
+                let ($head, $($tail,)+) = self;
+                let mut each = TryEach::default();
+                if let Some(res) = each.next($head.remove_path(path)) {
+                    return res;
+                }
+                $(
+                if let Some(res) = each.next($tail.remove_path(path)) {
+                    return res;
+                }
+                )+
+                each.finish()
+            }
+
+            #[allow(non_snake_case)]
+            fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+                let ($head, $($tail,)+) = self;
+                let mut each = TryEach::default();
+                if let Some(res) = each.next($head.rename_path(from, to)) {
+                    return res;
+                }
+                $(
+                if let Some(res) = each.next($tail.rename_path(from, to)) {
+                    return res;
+                }
+                )+
+                each.finish()
+            }
+
+            #[allow(non_snake_case)]
+            fn is_read_only(&self) -> bool {
+                let ($head, $($tail,)+) = self;
+                $head.is_read_only() $(&& $tail.is_read_only())+
+            }
+
+            #[allow(non_snake_case)]
+            fn resolve_path(&self, path: &Path) -> io::Result<&'static str> {
+                let ($head, $($tail,)+) = self;
+                match $head.resolve_path(path) {
+                    Err(ref err) if err.kind() == io::ErrorKind::NotFound => {},
+                    res => return res,
+                }
+                $(
+                match $tail.resolve_path(path) {
+                    Err(ref err) if err.kind() == io::ErrorKind::NotFound => {},
+                    res => return res,
+                }
+                )+
+
+                Err(io::Error::from(io::ErrorKind::NotFound))
+            }
         }
         tuples!($($tail,)+);
     };
 }
 
-// Implement tuples of up to 11 elements (12 or more looks bad on the rustdoc)
-tuples! { A, B, C, D, E, F, G, H, I, J, K, }
+// Implement tuples of up to 12 elements, like the standard library does for
+// its traits.
+tuples! { A, B, C, D, E, F, G, H, I, J, K, L, }
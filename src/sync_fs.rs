@@ -0,0 +1,218 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::mount::{MountTable, Order};
+use crate::store::{self, Store};
+use crate::{Entries, File, Metadata, Resolved};
+
+type SyncStore = dyn Store<File = File> + Send + Sync;
+
+/// Virtual filesystem that can be shared between threads.
+///
+/// Works like [`MiniFs`], resolving paths the same way, but only mounts stores
+/// that are `Send` and `Sync`, and mounting takes `&self`, so stores can be
+/// mounted and unmounted while other threads open files:
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{Local, SyncMiniFs};
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// let files = Arc::new(SyncMiniFs::new());
+/// files.mount("/", Local::new("./assets"));
+///
+/// let loader = {
+///     let files = Arc::clone(&files);
+///     thread::spawn(move || files.open("/textures/grass.png").is_ok())
+/// };
+/// files.mount("/", Local::new("./patches"));
+/// loader.join().unwrap();
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Remarks
+///
+/// Every operation holds a lock on the mount table, so mounting waits for the
+/// operations in progress to finish. Entries are collected before they are
+/// returned, for the same reason.
+///
+/// `Zip` and `Tar` archives can be mounted, so patch archives can be mounted
+/// while other threads load files. Stores that keep their state in a
+/// `RefCell`, like `Ram`, aren't `Sync` and can't be mounted.
+///
+/// [`MiniFs`]: ./struct.MiniFs.html
+pub struct SyncMiniFs {
+    mount: RwLock<MountTable<SyncStore>>,
+}
+
+impl Default for SyncMiniFs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SyncMiniFs {
+    pub fn new() -> Self {
+        Self {
+            mount: RwLock::new(MountTable::new()),
+        }
+    }
+
+    // The mount table is never left half updated, so a panic in another thread
+    // doesn't need to poison it.
+    fn read(&self) -> RwLockReadGuard<'_, MountTable<SyncStore>> {
+        self.mount.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, MountTable<SyncStore>> {
+        self.mount.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn mount<P, S, T>(&self, path: P, store: S) -> &Self
+    where
+        P: Into<PathBuf>,
+        S: Store<File = T> + Send + Sync + 'static,
+        T: Into<File>,
+    {
+        self.mount_with_priority(path, store, 0)
+    }
+
+    /// See [`MiniFs::mount_with_priority`].
+    ///
+    /// [`MiniFs::mount_with_priority`]: ./struct.MiniFs.html#method.mount_with_priority
+    pub fn mount_with_priority<P, S, T>(&self, path: P, store: S, priority: i32) -> &Self
+    where
+        P: Into<PathBuf>,
+        S: Store<File = T> + Send + Sync + 'static,
+        T: Into<File>,
+    {
+        let name = std::any::type_name::<S>();
+        self.write()
+            .insert(&path.into(), name, boxed(store), Order::Priority(priority));
+        self
+    }
+
+    /// See [`MiniFs::mount_before`].
+    ///
+    /// [`MiniFs::mount_before`]: ./struct.MiniFs.html#method.mount_before
+    pub fn mount_before<P, S, T>(&self, path: P, store: S) -> &Self
+    where
+        P: Into<PathBuf>,
+        S: Store<File = T> + Send + Sync + 'static,
+        T: Into<File>,
+    {
+        let name = std::any::type_name::<S>();
+        self.write()
+            .insert(&path.into(), name, boxed(store), Order::Before);
+        self
+    }
+
+    /// See [`MiniFs::mount_after`].
+    ///
+    /// [`MiniFs::mount_after`]: ./struct.MiniFs.html#method.mount_after
+    pub fn mount_after<P, S, T>(&self, path: P, store: S) -> &Self
+    where
+        P: Into<PathBuf>,
+        S: Store<File = T> + Send + Sync + 'static,
+        T: Into<File>,
+    {
+        let name = std::any::type_name::<S>();
+        self.write()
+            .insert(&path.into(), name, boxed(store), Order::After);
+        self
+    }
+
+    pub fn umount<P>(&self, path: P) -> Option<Box<SyncStore>>
+    where
+        P: AsRef<Path>,
+    {
+        self.write().umount(path.as_ref())
+    }
+
+    /// See [`MiniFs::remount`]. Threads opening files see either the old or the
+    /// new store, never an empty mount point.
+    ///
+    /// [`MiniFs::remount`]: ./struct.MiniFs.html#method.remount
+    pub fn remount<P, S, T>(&self, path: P, store: S) -> Option<Box<SyncStore>>
+    where
+        P: Into<PathBuf>,
+        S: Store<File = T> + Send + Sync + 'static,
+        T: Into<File>,
+    {
+        let name = std::any::type_name::<S>();
+        self.write().remount(&path.into(), name, boxed(store))
+    }
+
+    /// See [`MiniFs::resolve`].
+    ///
+    /// [`MiniFs::resolve`]: ./struct.MiniFs.html#method.resolve
+    pub fn resolve<P: AsRef<Path>>(&self, path: P) -> io::Result<Resolved> {
+        self.read().resolve(path.as_ref())
+    }
+
+    /// Same as [`MiniFs::mounts`], but collected.
+    ///
+    /// [`MiniFs::mounts`]: ./struct.MiniFs.html#method.mounts
+    pub fn mounts(&self) -> Vec<(PathBuf, &'static str, bool)> {
+        self.read()
+            .mounts()
+            .map(|(path, name, read_only)| (path.to_path_buf(), name, read_only))
+            .collect()
+    }
+}
+
+impl Store for SyncMiniFs {
+    type File = File;
+
+    #[inline]
+    fn open_path(&self, path: &Path) -> io::Result<File> {
+        self.read().open_path(path)
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        let entries: Vec<_> = self.read().entries_path(path)?.collect();
+        Ok(Entries::new(entries))
+    }
+
+    #[inline]
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        self.read().metadata_path(path)
+    }
+
+    #[inline]
+    fn create_path(&self, path: &Path) -> io::Result<File> {
+        self.read().create_path(path)
+    }
+
+    #[inline]
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        self.read().remove_path(path)
+    }
+
+    #[inline]
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.read().rename_path(from, to)
+    }
+
+    #[inline]
+    fn is_read_only(&self) -> bool {
+        self.read().is_read_only()
+    }
+
+    #[inline]
+    fn resolve_path(&self, path: &Path) -> io::Result<&'static str> {
+        self.read().resolve_path(path)
+    }
+}
+
+fn boxed<S, T>(store: S) -> Box<SyncStore>
+where
+    S: Store<File = T> + Send + Sync + 'static,
+    T: Into<File>,
+{
+    Box::new(store::MapFile::new(store, |file: T| file.into()))
+}
@@ -1,26 +1,60 @@
-use std::cell::{Cell, RefCell};
 use std::fs;
-use std::io::{self, Cursor, ErrorKind, Read, Seek, SeekFrom};
-use std::ops::DerefMut;
+use std::io::{self, BufRead, Cursor, ErrorKind, Read, Seek, SeekFrom};
 use std::path::Path;
+use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError};
+use std::time::{Duration, UNIX_EPOCH};
 
+#[cfg(feature = "bzip2")]
+use bzip2_::read::BzDecoder;
 use flate2::read::GzDecoder;
 use tar_::Archive;
+#[cfg(feature = "xz")]
+use xz2::read::XzDecoder;
+#[cfg(feature = "zstd")]
+use zstd_::stream::read::Decoder as ZstdDecoder;
 
-use crate::index::Index;
+use crate::index::{forward_slashes, Index};
+use crate::limits::Limits;
 use crate::store::Store;
-use crate::{Entries, Entry};
+use crate::{Entries, Entry, EntryKind, Metadata};
 
 /// Tar archive.
 ///
+/// Compressed archives are supported too, see [`Compression`]. The format is
+/// detected from the first bytes of the archive.
+///
+/// [`Compression`]: ./enum.Compression.html
+///
 /// # Remarks
 ///
 /// When used with a `std::fs::File`, the file will remain open for the lifetime
-/// of the Tar.
+/// of the Tar. Tars over readers that are `Send` can be shared between threads,
+/// but the reader is locked while the archive is read, so reads don't run in
+/// parallel.
+///
+/// Indexed archives record the position of every file, so uncompressed
+/// archives read the data of a file directly when it's opened. Otherwise the
+/// archive is read from the start, and its headers are parsed until the file
+/// is found.
+///
+/// Archives from untrusted sources should be read with [`limits`], since
+/// compressed archives can decompress to many times their size.
+///
+/// [`limits`]: #method.limits
 pub struct Tar<F: Read + Seek> {
-    gzip: Cell<bool>,
-    inner: RefCell<F>,
-    index: Option<Index<SeekFrom>>,
+    // Unset until the magic bytes have been checked.
+    compression: OnceLock<Compression>,
+    inner: Mutex<F>,
+    index: Option<Index<TarIndexEntry>>,
+    limits: Limits,
+}
+
+// Header data of an indexed file.
+struct TarIndexEntry {
+    // position of the file data within the uncompressed archive, `None` for
+    // sparse files, whose data isn't contiguous
+    pos: Option<u64>,
+    meta: Metadata,
 }
 
 /// Entry in the Tar archive.
@@ -28,12 +62,30 @@ pub struct TarEntry {
     inner: Cursor<Box<[u8]>>,
 }
 
+impl TarEntry {
+    pub(crate) fn len(&self) -> u64 {
+        self.inner.get_ref().len() as u64
+    }
+}
+
 impl Read for TarEntry {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         self.inner.read(buf)
     }
 }
 
+impl BufRead for TarEntry {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
 impl Seek for TarEntry {
     #[inline]
     fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
@@ -45,28 +97,35 @@ impl<T: Read + Seek> Store for Tar<T> {
     type File = TarEntry;
 
     fn open_path(&self, path: &Path) -> io::Result<Self::File> {
-        if self.gzip.get() {
-            let mut file = self.inner.borrow_mut();
-            file.seek(SeekFrom::Start(0))?;
-            self.open_read(path, GzDecoder::new(&mut *file))
-        } else {
-            let mut file = self.inner.borrow_mut();
-            file.seek(SeekFrom::Start(0))?;
-            match self.open_read(path, &mut *file) {
-                Ok(entry) => Ok(entry),
-                Err(ref e) if e.kind() == ErrorKind::NotFound => {
-                    Err(io::Error::from(ErrorKind::NotFound))
-                }
-                Err(e) => {
-                    self.gzip.set(true);
-                    drop(file);
-                    self.open_path(path)
-                }
+        if let Some(ref idx) = self.index {
+            match idx.get(path) {
+                Some(TarIndexEntry {
+                    pos: Some(pos),
+                    meta,
+                }) => return self.read_at(*pos, meta.len),
+                Some(_) => {}
+                None => return Err(io::Error::from(ErrorKind::NotFound)),
             }
         }
+        let path = forward_slashes(path);
+        let limits = self.limits;
+        self.read_archive(|mut archive| {
+            for entry in archive.entries()? {
+                let mut entry = entry?;
+                if path == forward_slashes(&entry.path()?) {
+                    limits.check_file_size(entry.size())?;
+                    let mut data = Vec::new();
+                    entry.read_to_end(&mut data)?;
+                    return Ok(TarEntry {
+                        inner: Cursor::new(data.into()),
+                    });
+                }
+            }
+            Err(io::Error::from(ErrorKind::NotFound))
+        })
     }
 
-    fn entries_path(&self, path: &Path) -> io::Result<Entries> {
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
         if let Some(ref idx) = self.index {
             Ok(Entries::new(idx.entries(path).map(|ent| {
                 let name = ent.name.to_os_string();
@@ -74,7 +133,55 @@ impl<T: Read + Seek> Store for Tar<T> {
                 Ok(Entry { name, kind })
             })))
         } else {
-            panic!("You have to call the `Zip::index` method on this zip archive before you can list its entries.")
+            Err(io::Error::other(
+                "You have to call the `Tar::index` method on this tar archive before you can list its entries.",
+            ))
+        }
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        if let Some(ref idx) = self.index {
+            if let Some(file) = idx.get(path) {
+                Ok(file.meta.clone())
+            } else if idx.contains_dir(path) {
+                Ok(Metadata::dir())
+            } else {
+                Err(io::Error::from(ErrorKind::NotFound))
+            }
+        } else if path.iter().next().is_none() {
+            Ok(Metadata::dir())
+        } else {
+            // Without an index, directories are only found if they have their own header.
+            let path = forward_slashes(path);
+            self.read_archive(|mut archive| {
+                for entry in archive.entries()? {
+                    let entry = entry?;
+                    if path == forward_slashes(&entry.path()?) {
+                        return Ok(metadata(&entry));
+                    }
+                }
+                Err(io::Error::from(ErrorKind::NotFound))
+            })
+        }
+    }
+}
+
+fn metadata<R: Read>(entry: &tar_::Entry<R>) -> Metadata {
+    let header = entry.header();
+    let modified = header
+        .mtime()
+        .ok()
+        .map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+    if header.entry_type().is_dir() {
+        Metadata {
+            modified,
+            ..Metadata::dir()
+        }
+    } else {
+        Metadata {
+            kind: EntryKind::File,
+            len: entry.size(),
+            modified,
         }
     }
 }
@@ -91,28 +198,196 @@ impl Tar<fs::File> {
     }
 }
 
+#[cfg(feature = "mmap")]
+impl Tar<crate::Mmap> {
+    /// Memory-map a file from the native filesystem.
+    pub fn mmap<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self::new(crate::Mmap::open(path)?))
+    }
+}
+
+/// Compression format of a Tar archive.
+///
+/// Formats other than gzip need their feature to be enabled.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Compression {
+    /// Uncompressed archive (tar).
+    None,
+    /// Gzip (tar.gz).
+    Gzip,
+    /// Xz (tar.xz).
+    #[cfg(feature = "xz")]
+    Xz,
+    /// Zstandard (tar.zst).
+    #[cfg(feature = "zstd")]
+    Zstd,
+    /// Bzip2 (tar.bz2).
+    #[cfg(feature = "bzip2")]
+    Bzip2,
+}
+
+// Magic numbers of the compression formats.
+const MAGIC: &[(&[u8], Compression)] = &[
+    (&[0x1f, 0x8b], Compression::Gzip),
+    #[cfg(feature = "xz")]
+    (&[0xfd, b'7', b'z', b'X', b'Z', 0x00], Compression::Xz),
+    #[cfg(feature = "zstd")]
+    (&[0x28, 0xb5, 0x2f, 0xfd], Compression::Zstd),
+    #[cfg(feature = "bzip2")]
+    (b"BZh", Compression::Bzip2),
+];
+
+impl Compression {
+    // Longest magic number of all compression formats.
+    const MAGIC_LEN: usize = 6;
+
+    fn detect(magic: &[u8]) -> Self {
+        MAGIC
+            .iter()
+            .find(|(m, _)| magic.starts_with(m))
+            .map(|&(_, compression)| compression)
+            .unwrap_or(Compression::None)
+    }
+
+    fn decoder<'a, R: Read + 'a>(self, inner: R) -> io::Result<Box<dyn Read + 'a>> {
+        Ok(match self {
+            Compression::None => Box::new(inner),
+            Compression::Gzip => Box::new(GzDecoder::new(inner)),
+            #[cfg(feature = "xz")]
+            Compression::Xz => Box::new(XzDecoder::new(inner)),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => Box::new(ZstdDecoder::new(inner)?),
+            #[cfg(feature = "bzip2")]
+            Compression::Bzip2 => Box::new(BzDecoder::new(inner)),
+        })
+    }
+}
+
 impl<T: Read + Seek> Tar<T> {
+    /// Create a Tar from a plain or compressed archive.
+    ///
+    /// Compression is detected from the first bytes of the archive.
     pub fn new(inner: T) -> Self {
         Self {
-            inner: RefCell::new(inner),
-            gzip: Cell::new(false),
+            inner: Mutex::new(inner),
+            compression: OnceLock::new(),
             index: None,
+            limits: Limits::new(),
         }
     }
 
-    fn open_read<R: Read>(&self, path: &Path, read: R) -> io::Result<TarEntry> {
-        let mut archive = Archive::new(read);
-        for entry in archive.entries()? {
-            let mut entry = entry?;
-            if path == entry.path()? {
-                let mut data = Vec::new();
-                entry.read_to_end(&mut data)?;
-                return Ok(TarEntry {
-                    inner: Cursor::new(data.into()),
-                });
+    /// Create a Tar from an archive in the given compression format.
+    pub fn with_compression(inner: T, compression: Compression) -> Self {
+        Self {
+            inner: Mutex::new(inner),
+            compression: OnceLock::from(compression),
+            index: None,
+            limits: Limits::new(),
+        }
+    }
+
+    /// Limit the size of the files read from the archive, the decompressed
+    /// size of the archive, and the number of entries indexed.
+    ///
+    /// Call it before indexing the archive, so the limits apply to the index.
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Create a Tar from a gzip compressed archive.
+    pub fn gz(inner: T) -> Self {
+        Self::with_compression(inner, Compression::Gzip)
+    }
+
+    /// Create a Tar from a xz compressed archive.
+    #[cfg(feature = "xz")]
+    pub fn xz(inner: T) -> Self {
+        Self::with_compression(inner, Compression::Xz)
+    }
+
+    /// Create a Tar from a zstd compressed archive.
+    #[cfg(feature = "zstd")]
+    pub fn zstd(inner: T) -> Self {
+        Self::with_compression(inner, Compression::Zstd)
+    }
+
+    /// Create a Tar from a bzip2 compressed archive.
+    #[cfg(feature = "bzip2")]
+    pub fn bz2(inner: T) -> Self {
+        Self::with_compression(inner, Compression::Bzip2)
+    }
+
+    // Compression of the archive, detected the first time it's read.
+    fn compression(&self, file: &mut T) -> io::Result<Compression> {
+        if let Some(&compression) = self.compression.get() {
+            return Ok(compression);
+        }
+        let mut magic = Vec::with_capacity(Compression::MAGIC_LEN);
+        file.seek(SeekFrom::Start(0))?;
+        file.take(Compression::MAGIC_LEN as u64)
+            .read_to_end(&mut magic)?;
+        let compression = Compression::detect(&magic);
+        let _ = self.compression.set(compression);
+        Ok(compression)
+    }
+
+    // The reader is always seeked before it's read, so a panic while reading
+    // doesn't need to poison the lock.
+    fn file(&self) -> MutexGuard<'_, T> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // Reads the archive from the start.
+    fn read_archive<U, F>(&self, read: F) -> io::Result<U>
+    where
+        F: FnOnce(Archive<&mut dyn Read>) -> io::Result<U>,
+    {
+        let mut file = self.file();
+        let compression = self.compression(&mut file)?;
+        let mut decoder = self.decoder(&mut file, compression)?;
+        read(Archive::new(&mut *decoder))
+    }
+
+    // Decompresses the archive from the start, up to the ratio limit.
+    fn decoder<'a>(
+        &self,
+        file: &'a mut T,
+        compression: Compression,
+    ) -> io::Result<Box<dyn Read + 'a>> {
+        if compression == Compression::None {
+            file.seek(SeekFrom::Start(0))?;
+            return compression.decoder(file);
+        }
+        let len = file.seek(SeekFrom::End(0))?;
+        file.seek(SeekFrom::Start(0))?;
+        let decoder = compression.decoder(file)?;
+        Ok(Box::new(self.limits.decompressed(decoder, len)))
+    }
+
+    // Reads the data of an indexed file. Compressed archives can't seek, so the
+    // data before the file is decompressed and skipped.
+    fn read_at(&self, pos: u64, len: u64) -> io::Result<TarEntry> {
+        self.limits.check_file_size(len)?;
+        let mut file = self.file();
+        let mut data = Vec::new();
+        match self.compression(&mut file)? {
+            Compression::None => {
+                file.seek(SeekFrom::Start(pos))?;
+                (&mut *file).take(len).read_to_end(&mut data)?;
+            }
+            compression => {
+                let mut decoder = self.decoder(&mut file, compression)?;
+                io::copy(&mut (&mut decoder).take(pos), &mut io::sink())?;
+                decoder.take(len).read_to_end(&mut data)?;
             }
         }
-        Err(io::Error::from(ErrorKind::NotFound))
+        if data.len() as u64 != len {
+            return Err(io::Error::from(ErrorKind::UnexpectedEof));
+        }
+        Ok(TarEntry {
+            inner: Cursor::new(data.into()),
+        })
     }
 
     /// Index the contents of the archive.
@@ -120,6 +395,57 @@ impl<T: Read + Seek> Tar<T> {
     /// Having an index allows you to list the contents of the archive using the
     /// entries_path and entries methods.
     pub fn index(self) -> io::Result<Self> {
+        self.index_with(Index::new())
+    }
+
+    /// Index the contents of the archive, so paths are looked up ignoring
+    /// ASCII case.
+    ///
+    /// Exact matches win, and entries are listed with the case of the archive.
+    pub fn index_case_insensitive(self) -> io::Result<Self> {
+        self.index_with(Index::new().case_insensitive())
+    }
+
+    /// Look up paths by their Unicode normalization form C, so names in
+    /// composed and decomposed forms find the same files.
+    ///
+    /// Archives created on macOS often have decomposed names. The archive is
+    /// indexed if it wasn't already.
+    #[cfg(feature = "unicode")]
+    pub fn unicode_normalized(self) -> io::Result<Self> {
+        let mut this = match self.index {
+            Some(_) => self,
+            None => self.index()?,
+        };
+        this.index = this.index.map(Index::unicode_normalized);
+        Ok(this)
+    }
+
+    fn index_with(mut self, mut index: Index<TarIndexEntry>) -> io::Result<Self> {
+        let limits = self.limits;
+        let index = self.read_archive(|mut archive| {
+            let mut total = 0u64;
+            for (i, entry) in archive.entries()?.enumerate() {
+                limits.check_entries(i + 1)?;
+                let entry = entry?;
+                let path = entry.path()?.into_owned();
+                if entry.header().entry_type().is_dir() {
+                    index.insert_dir(path);
+                } else {
+                    let pos = if entry.header().entry_type().is_gnu_sparse() {
+                        None
+                    } else {
+                        Some(entry.raw_file_position())
+                    };
+                    let meta = metadata(&entry);
+                    total = total.saturating_add(meta.len);
+                    limits.check_total_size(total)?;
+                    index.insert(path, TarIndexEntry { pos, meta });
+                }
+            }
+            Ok(index)
+        })?;
+        self.index = Some(index);
         Ok(self)
     }
 }
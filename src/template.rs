@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::env;
+use std::io::{self, Read};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::store::Store;
+use crate::{Entries, File, Metadata};
+
+/// Store expanding `${VAR}` placeholders in the text files of another store
+/// when they are opened.
+///
+/// Placeholders are replaced with the variables set with [`var`], or with
+/// environment variables once [`env`] is called, so config templates can be
+/// read directly:
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{Local, MiniFs, Templates};
+///
+/// let config = Templates::new(Local::new("./config"))
+///     .var("DATA_DIR", "/usr/share/game")
+///     .env();
+///
+/// let fs = MiniFs::new().mount("/config", config);
+/// // `data = "${DATA_DIR}/data"` reads as `data = "/usr/share/game/data"`
+/// let settings = fs.read_to_string("/config/settings.toml")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Remarks
+///
+/// Only files that are valid UTF-8 are expanded, other files are opened as
+/// they are. Opening a file with a placeholder for an undefined variable fails
+/// with an error of kind `InvalidData`, and `$${VAR}` is read as a literal
+/// `${VAR}`. Expanded files are kept in memory, and their metadata is the one
+/// of the template.
+///
+/// [`var`]: #method.var
+/// [`env`]: #method.env
+pub struct Templates<S> {
+    store: S,
+    vars: HashMap<String, String>,
+    env: bool,
+}
+
+impl<S> Templates<S> {
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            vars: HashMap::new(),
+            env: false,
+        }
+    }
+
+    /// Replace `${name}` with `value`, even if an environment variable has the
+    /// same name.
+    pub fn var<K, V>(mut self, name: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.vars.insert(name.into(), value.into());
+        self
+    }
+
+    /// Replace placeholders for variables that weren't set with `var` with
+    /// environment variables.
+    pub fn env(mut self) -> Self {
+        self.env = true;
+        self
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.store
+    }
+
+    pub fn into_inner(self) -> S {
+        self.store
+    }
+
+    fn lookup(&self, name: &str) -> Option<String> {
+        match self.vars.get(name) {
+            Some(value) => Some(value.clone()),
+            None if self.env => env::var(name).ok(),
+            None => None,
+        }
+    }
+
+    fn expand(&self, path: &Path, mut template: &str) -> io::Result<String> {
+        let mut out = String::with_capacity(template.len());
+        while let Some(start) = template.find('$') {
+            out.push_str(&template[..start]);
+            let rest = &template[start..];
+            if rest.starts_with("$${") {
+                out.push('$');
+                template = &rest[2..];
+                continue;
+            }
+            let end = match rest.strip_prefix("${").and_then(|s| s.find('}')) {
+                Some(end) => end + 2,
+                None => {
+                    out.push('$');
+                    template = &rest[1..];
+                    continue;
+                }
+            };
+            let name = &rest[2..end];
+            match self.lookup(name) {
+                Some(value) => out.push_str(&value),
+                None => {
+                    let msg = format!("{}: undefined variable `{}`", path.display(), name);
+                    return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                }
+            }
+            template = &rest[end + 1..];
+        }
+        out.push_str(template);
+        Ok(out)
+    }
+}
+
+impl<S> Store for Templates<S>
+where
+    S: Store,
+    S::File: Read + Into<File>,
+{
+    type File = File;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        let mut data = Vec::new();
+        self.store.open_path(path)?.read_to_end(&mut data)?;
+        let data: Arc<[u8]> = match String::from_utf8(data) {
+            Ok(text) => self.expand(path, &text)?.into_bytes().into(),
+            Err(err) => err.into_bytes().into(),
+        };
+        Ok(data.into())
+    }
+
+    #[inline]
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        self.store.entries_path(path)
+    }
+
+    #[inline]
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        self.store.metadata_path(path)
+    }
+
+    #[inline]
+    fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+        self.store.create_path(path).map(Into::into)
+    }
+
+    #[inline]
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        self.store.remove_path(path)
+    }
+
+    #[inline]
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.store.rename_path(from, to)
+    }
+
+    #[inline]
+    fn is_read_only(&self) -> bool {
+        self.store.is_read_only()
+    }
+}
@@ -0,0 +1,176 @@
+use std::io::{self, BufRead, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::store::Store;
+use crate::{Entries, File, Metadata};
+
+/// Store limiting the rate at which the files of another store are read.
+///
+/// All the files opened from the store share the same rate, in bytes per
+/// second, and reads sleep until they fit in it, so slow disks can be
+/// simulated, or background loading kept from competing with other IO:
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{Local, MiniFs, Throttle};
+///
+/// // at most 1 MiB/s, like a slow optical drive
+/// let assets = Throttle::new(Local::new("./assets"), 1 << 20);
+/// let fs = MiniFs::new().mount("/assets", assets);
+/// let music = fs.read("/assets/music.ogg")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Remarks
+///
+/// Reads are split in chunks of a tenth of the rate, so files read at the same
+/// time share it. Only reads are throttled: opening, listing and writing
+/// aren't.
+pub struct Throttle<S> {
+    store: S,
+    bucket: Arc<Bucket>,
+}
+
+struct Bucket {
+    rate: u64,
+    // When the bytes read so far have been served at the rate.
+    next: Mutex<Instant>,
+}
+
+impl Bucket {
+    fn chunk(&self) -> usize {
+        (self.rate / 10).clamp(1, usize::MAX as u64) as usize
+    }
+
+    // Sleeps until `n` more bytes can be served.
+    fn take(&self, n: usize) {
+        let wait = Duration::from_secs_f64(n as f64 / self.rate as f64);
+        let until = {
+            let mut next = self.next.lock().unwrap_or_else(PoisonError::into_inner);
+            *next = (*next).max(Instant::now()) + wait;
+            *next
+        };
+        let now = Instant::now();
+        if until > now {
+            thread::sleep(until - now);
+        }
+    }
+}
+
+impl<S> Throttle<S> {
+    /// Throttle the files of `store` to `rate` bytes per second.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rate` is zero.
+    pub fn new(store: S, rate: u64) -> Self {
+        assert!(rate > 0, "the rate of a Throttle can't be zero");
+        Self {
+            store,
+            bucket: Arc::new(Bucket {
+                rate,
+                next: Mutex::new(Instant::now()),
+            }),
+        }
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.store
+    }
+
+    pub fn into_inner(self) -> S {
+        self.store
+    }
+}
+
+/// File from a Throttle store.
+pub struct ThrottleFile {
+    inner: Box<File>,
+    bucket: Arc<Bucket>,
+}
+
+impl ThrottleFile {
+    pub(crate) fn len(&mut self) -> io::Result<u64> {
+        self.inner.len()
+    }
+}
+
+impl Read for ThrottleFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.bucket.chunk());
+        let n = self.inner.read(&mut buf[..len])?;
+        self.bucket.take(n);
+        Ok(n)
+    }
+}
+
+impl BufRead for ThrottleFile {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let chunk = self.bucket.chunk();
+        let data = self.inner.fill_buf()?;
+        Ok(&data[..data.len().min(chunk)])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.bucket.take(amt);
+        self.inner.consume(amt)
+    }
+}
+
+impl Seek for ThrottleFile {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl<S> Store for Throttle<S>
+where
+    S: Store,
+    S::File: Into<File>,
+{
+    type File = File;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        let inner = self.store.open_path(path)?.into();
+        Ok(File::Throttle(ThrottleFile {
+            inner: Box::new(inner),
+            bucket: Arc::clone(&self.bucket),
+        }))
+    }
+
+    #[inline]
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        self.store.entries_path(path)
+    }
+
+    #[inline]
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        self.store.metadata_path(path)
+    }
+
+    #[inline]
+    fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+        self.store.create_path(path).map(Into::into)
+    }
+
+    #[inline]
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        self.store.remove_path(path)
+    }
+
+    #[inline]
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.store.rename_path(from, to)
+    }
+
+    #[inline]
+    fn is_read_only(&self) -> bool {
+        self.store.is_read_only()
+    }
+}
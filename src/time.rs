@@ -0,0 +1,70 @@
+use std::convert::TryFrom;
+#[cfg(any(
+    feature = "zip",
+    feature = "rar",
+    feature = "iso",
+    feature = "webdav",
+    feature = "ftp"
+))]
+use std::time::Duration;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// MS-DOS timestamps are in local time, with no timezone information, so they
+// are interpreted as UTC.
+#[cfg(any(feature = "zip", feature = "rar"))]
+pub(crate) fn from_dos(date: u16, time: u16) -> Option<SystemTime> {
+    from_civil(
+        1980 + i64::from(date >> 9),
+        i64::from((date >> 5) & 0xf),
+        i64::from(date & 0x1f),
+        i64::from(time >> 11) * 3600
+            + i64::from((time >> 5) & 0x3f) * 60
+            + i64::from(time & 0x1f) * 2,
+    )
+}
+
+// Converts a UTC date, plus the seconds elapsed since midnight.
+#[cfg(any(
+    feature = "zip",
+    feature = "rar",
+    feature = "iso",
+    feature = "webdav",
+    feature = "ftp"
+))]
+pub(crate) fn from_civil(year: i64, month: i64, day: i64, secs: i64) -> Option<SystemTime> {
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    // days from civil algorithm (http://howardhinnant.github.io/date_algorithms.html)
+    let year = year - if month <= 2 { 1 } else { 0 };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    u64::try_from(days * 86_400 + secs)
+        .ok()
+        .map(|secs| UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+// Converts a time to a UTC date, plus the seconds elapsed since midnight.
+// Times before the Unix epoch are converted as the epoch.
+#[cfg(any(feature = "s3", feature = "zip"))]
+pub(crate) fn to_civil(time: SystemTime) -> (i64, i64, i64, i64) {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let days = i64::try_from(secs / 86_400).unwrap_or(0);
+
+    // civil from days algorithm (http://howardhinnant.github.io/date_algorithms.html)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day, (secs % 86_400) as i64)
+}
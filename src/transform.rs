@@ -0,0 +1,118 @@
+use std::io;
+use std::path::Path;
+
+use crate::store::Store;
+use crate::{Entries, File, Metadata};
+
+type Stage = Box<dyn Fn(&Path, File) -> io::Result<File>>;
+
+/// Store passing the files of another store through a chain of stages when
+/// they are opened.
+///
+/// Each stage is called with the path of the file, relative to the root of the
+/// wrapped store, and the file returned by the previous stage, so files can be
+/// decrypted, decompressed or preprocessed before the caller reads them:
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{File, Local, MiniFs, Transform};
+/// use std::io::Read;
+/// use std::sync::Arc;
+///
+/// let shaders = Transform::new(Local::new("./shaders")).stage(|path, mut file| {
+///     if path.extension() != Some("glsl".as_ref()) {
+///         return Ok(file);
+///     }
+///     let mut source = String::from("#version 330 core\n");
+///     file.read_to_string(&mut source)?;
+///     let source: Arc<[u8]> = source.into_bytes().into();
+///     Ok(File::from(source))
+/// });
+///
+/// let fs = MiniFs::new().mount("/shaders", shaders);
+/// let sprite = fs.read_to_string("/shaders/sprite.glsl")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Remarks
+///
+/// Stages only apply to opened files. Metadata is the one of the wrapped
+/// store, so the length of transformed files may not match, and created files
+/// are written as they are.
+pub struct Transform<S> {
+    store: S,
+    stages: Vec<Stage>,
+}
+
+impl<S> Transform<S> {
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            stages: Vec::new(),
+        }
+    }
+
+    /// Add a stage called with the files returned by the previous stages.
+    pub fn stage<F>(mut self, stage: F) -> Self
+    where
+        F: Fn(&Path, File) -> io::Result<File> + 'static,
+    {
+        self.stages.push(Box::new(stage));
+        self
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.store
+    }
+
+    pub fn into_inner(self) -> S {
+        self.store
+    }
+}
+
+impl<S> Store for Transform<S>
+where
+    S: Store,
+    S::File: Into<File>,
+{
+    type File = File;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        let file = self.store.open_path(path)?.into();
+        self.stages
+            .iter()
+            .try_fold(file, |file, stage| stage(path, file))
+    }
+
+    #[inline]
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        self.store.entries_path(path)
+    }
+
+    #[inline]
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        self.store.metadata_path(path)
+    }
+
+    #[inline]
+    fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+        self.store.create_path(path).map(Into::into)
+    }
+
+    #[inline]
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        self.store.remove_path(path)
+    }
+
+    #[inline]
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.store.rename_path(from, to)
+    }
+
+    #[inline]
+    fn is_read_only(&self) -> bool {
+        self.store.is_read_only()
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use sha2::{Digest, Sha256};
+
+use crate::checksum::{invalid_data, parse_manifest};
+use crate::index::relative_path;
+use crate::store::Store;
+use crate::{Entries, File, Metadata};
+
+/// Store serving only the files of another store listed in a signed manifest.
+///
+/// The manifest lists the SHA-256 hash of every file, in the format of
+/// `sha256sum`, with paths relative to the root of the store:
+///
+/// ```text
+/// 9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08  maps/e1m1.bsp
+/// ```
+///
+/// It must be signed with ed25519, and files are only served when their hash
+/// matches, so tampered content packs are detected:
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{MiniFs, Verified, Zip};
+///
+/// let public_key = [0x42; 32]; // the key content packs are signed with
+/// let pack = Zip::open("pack.zip")?;
+/// // reads `MANIFEST` and its signature, `MANIFEST.sig`
+/// let pack = Verified::load(pack, "MANIFEST", &public_key)?;
+///
+/// let fs = MiniFs::new().mount("/pack", pack);
+/// let map = fs.read("/pack/maps/e1m1.bsp")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Remarks
+///
+/// Files that aren't listed in the manifest can't be opened, and fail with an
+/// error of kind `PermissionDenied`. Files whose hash doesn't match fail with
+/// an error of kind `InvalidData`. Files are read and hashed before they are
+/// returned, and kept in memory.
+///
+/// Listing and metadata aren't verified, and the store is read-only, since
+/// writes would invalidate the manifest.
+pub struct Verified<S> {
+    store: S,
+    hashes: HashMap<PathBuf, [u8; 32]>,
+}
+
+impl<S> Verified<S> {
+    /// Verify the files of `store` with a `manifest`, signed with the ed25519
+    /// key `public_key`.
+    ///
+    /// Returns an error of kind `InvalidData` if the signature doesn't match,
+    /// or the manifest is malformed, and of kind `InvalidInput` if
+    /// `public_key` isn't a valid key.
+    pub fn new(
+        store: S,
+        manifest: &[u8],
+        signature: &[u8],
+        public_key: &[u8; 32],
+    ) -> io::Result<Self> {
+        let key = VerifyingKey::from_bytes(public_key)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+        let signature = Signature::from_slice(signature).map_err(invalid_data)?;
+        key.verify(manifest, &signature).map_err(invalid_data)?;
+        let hashes = parse_manifest(manifest)?;
+        Ok(Self { store, hashes })
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.store
+    }
+
+    pub fn into_inner(self) -> S {
+        self.store
+    }
+}
+
+impl<S> Verified<S>
+where
+    S: Store,
+    S::File: Read,
+{
+    /// Verify the files of `store` with a manifest read from the store at
+    /// `path`, and its signature, read from the same path with a `.sig` suffix.
+    pub fn load<P: AsRef<Path>>(store: S, path: P, public_key: &[u8; 32]) -> io::Result<Self> {
+        let path = path.as_ref();
+        let mut sig_path = OsString::from(path.as_os_str());
+        sig_path.push(".sig");
+        let manifest = read(&store, path)?;
+        let signature = read(&store, Path::new(&sig_path))?;
+        Self::new(store, &manifest, &signature, public_key)
+    }
+}
+
+fn read<S>(store: &S, path: &Path) -> io::Result<Vec<u8>>
+where
+    S: Store,
+    S::File: Read,
+{
+    let mut data = Vec::new();
+    store.open_path(path)?.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+impl<S> Store for Verified<S>
+where
+    S: Store,
+    S::File: Read,
+{
+    type File = File;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        let expected = match self.hashes.get(&relative_path(path)) {
+            Some(hash) => hash,
+            None => return Err(io::Error::from(io::ErrorKind::PermissionDenied)),
+        };
+        let data = read(&self.store, path)?;
+        if Sha256::digest(&data)[..] != expected[..] {
+            let msg = format!("{}: hash doesn't match the manifest", path.display());
+            return Err(invalid_data(msg));
+        }
+        let data: Arc<[u8]> = data.into();
+        Ok(data.into())
+    }
+
+    #[inline]
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        self.store.entries_path(path)
+    }
+
+    #[inline]
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        self.store.metadata_path(path)
+    }
+}
@@ -0,0 +1,189 @@
+use std::cell::RefCell;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use crate::index::{normalize_path, Index};
+use crate::store::{Entry, EntryKind, Store};
+use crate::{Entries, Metadata, RamFile};
+
+/// In-memory store keeping every revision of its files.
+///
+/// Each file created (or touched) at a path adds a new revision instead of
+/// replacing the previous one. Opening a path returns its latest revision,
+/// while [`open_version`](#method.open_version) gives access to older ones,
+/// numbered from zero:
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::Versioned;
+/// use std::io::{Read, Write};
+///
+/// let mut assets = Versioned::new();
+/// assets.touch("shader.glsl", &b"v1"[..]);
+/// assets.create("shader.glsl")?.write_all(b"v2")?;
+///
+/// let mut old = String::new();
+/// assets.open_version("shader.glsl", 0)?.read_to_string(&mut old)?;
+/// assert_eq!("v1", old);
+/// assert_eq!(2, assets.versions("shader.glsl"));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Remarks
+///
+/// Revisions are never dropped, except when their file is removed, which
+/// removes all of them. Renaming a file moves all its revisions.
+pub struct Versioned {
+    index: Rc<RefCell<Index<Vec<Revision>>>>,
+}
+
+#[derive(Clone)]
+struct Revision {
+    data: Arc<[u8]>,
+    modified: SystemTime,
+}
+
+impl Revision {
+    fn new(data: Arc<[u8]>) -> Self {
+        Self {
+            data,
+            modified: SystemTime::now(),
+        }
+    }
+}
+
+impl Default for Versioned {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Versioned {
+    pub fn new() -> Self {
+        Self {
+            index: Rc::new(RefCell::new(Index::new())),
+        }
+    }
+
+    /// Add a revision of a file.
+    pub fn touch<P, F>(&mut self, path: P, file: F)
+    where
+        P: Into<PathBuf>,
+        F: Into<Arc<[u8]>>,
+    {
+        let path = path.into();
+        let mut index = self.index.borrow_mut();
+        let mut revisions = index.remove(&path).unwrap_or_default();
+        revisions.push(Revision::new(file.into()));
+        index.insert(path, revisions);
+    }
+
+    /// Number of revisions of a file, or zero if it doesn't exist.
+    pub fn versions<P: AsRef<Path>>(&self, path: P) -> usize {
+        let path = normalize_path(path.as_ref());
+        self.index.borrow().get(path).map_or(0, Vec::len)
+    }
+
+    /// Open the revision `version` of a file, zero being the oldest one.
+    pub fn open_version<P: AsRef<Path>>(&self, path: P, version: usize) -> io::Result<RamFile> {
+        let path = normalize_path(path.as_ref());
+        match self
+            .index
+            .borrow()
+            .get(path)
+            .and_then(|revs| revs.get(version))
+        {
+            Some(rev) => Ok(RamFile::reader(Arc::clone(&rev.data))),
+            None => Err(io::Error::from(io::ErrorKind::NotFound)),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.index.borrow_mut().clear();
+    }
+}
+
+impl Store for Versioned {
+    type File = RamFile;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        match self.index.borrow().get(path).and_then(|revs| revs.last()) {
+            Some(rev) => Ok(RamFile::reader(Arc::clone(&rev.data))),
+            None => Err(io::Error::from(io::ErrorKind::NotFound)),
+        }
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        // Collected because the index can't stay borrowed.
+        let entries: Vec<_> = self
+            .index
+            .borrow()
+            .entries(path)
+            .map(|ent| {
+                Ok(Entry {
+                    name: ent.name.to_os_string(),
+                    kind: ent.kind,
+                })
+            })
+            .collect();
+        Ok(Entries::new(entries))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        let index = self.index.borrow();
+        if let Some(rev) = index.get(path).and_then(|revs| revs.last()) {
+            Ok(Metadata {
+                kind: EntryKind::File,
+                len: rev.data.len() as u64,
+                modified: Some(rev.modified),
+            })
+        } else if index.contains_dir(path) {
+            Ok(Metadata::dir())
+        } else {
+            Err(io::Error::from(io::ErrorKind::NotFound))
+        }
+    }
+
+    fn create_path(&self, path: &Path) -> io::Result<Self::File> {
+        let path = path.to_path_buf();
+        let version = self.versions(&path);
+        let index = Rc::clone(&self.index);
+        // Writes keep updating the revision added by this file.
+        Ok(RamFile::writer(move |data| {
+            let mut index = index.borrow_mut();
+            let mut revisions = index.remove(&path).unwrap_or_default();
+            match revisions.get_mut(version) {
+                Some(rev) => *rev = Revision::new(data),
+                None => revisions.push(Revision::new(data)),
+            }
+            index.insert(path.clone(), revisions);
+        }))
+    }
+
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        match self.index.borrow_mut().remove(path) {
+            Some(_) => Ok(()),
+            None => Err(io::Error::from(io::ErrorKind::NotFound)),
+        }
+    }
+
+    fn rename_path(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let mut index = self.index.borrow_mut();
+        match index.remove(from) {
+            Some(revisions) => {
+                index.insert(to.to_path_buf(), revisions);
+                Ok(())
+            }
+            None => Err(io::Error::from(io::ErrorKind::NotFound)),
+        }
+    }
+
+    fn is_read_only(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,235 @@
+use std::cell::RefCell;
+use std::fs;
+use std::io::{self, BufRead, Cursor, ErrorKind, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use crate::bytes::{le16, le32};
+use crate::index::Index;
+use crate::store::Store;
+use crate::{Entries, Entry, EntryKind, Metadata};
+
+const SIGNATURE: u32 = 0x55aa_1234;
+// Archive index of files stored in the directory file itself.
+const DIR_ARCHIVE: u16 = 0x7fff;
+
+/// Valve VPK store.
+///
+/// VPKs are made of a directory file (`pak01_dir.vpk`), which holds the tree
+/// of the package, and numbered data files next to it (`pak01_000.vpk`,
+/// `pak01_001.vpk`, ...). The tree is indexed when the store is created, and
+/// the data files are opened when their files are read. Versions 1 and 2 are
+/// supported.
+///
+/// # Remarks
+///
+/// The directory file remains open for the lifetime of the Vpk. Checksums are
+/// not verified.
+pub struct Vpk {
+    dir: RefCell<fs::File>,
+    // path of the directory file, without the "dir.vpk" suffix
+    prefix: PathBuf,
+    // position of the data stored in the directory file
+    data: u64,
+    index: Index<VpkIndexEntry>,
+}
+
+struct VpkIndexEntry {
+    preload: Box<[u8]>,
+    archive: u16,
+    pos: u64,
+    len: u64,
+    meta: Metadata,
+}
+
+/// Entry in the VPK.
+pub struct VpkEntry {
+    inner: Cursor<Box<[u8]>>,
+}
+
+impl VpkEntry {
+    pub(crate) fn len(&self) -> u64 {
+        self.inner.get_ref().len() as u64
+    }
+}
+
+impl Read for VpkEntry {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl BufRead for VpkEntry {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+impl Seek for VpkEntry {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl Vpk {
+    /// Open and index the directory file of a VPK from the native filesystem.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let prefix = path
+            .to_str()
+            .and_then(|path| path.strip_suffix("dir.vpk"))
+            .ok_or_else(|| invalid("Not a VPK directory file (*_dir.vpk)."))?;
+        let mut dir = fs::OpenOptions::new()
+            .read(true)
+            .write(false)
+            .create(false)
+            .open(path)?;
+
+        let mut header = [0; 12];
+        if dir.read_exact(&mut header).is_err() || le32(&header[..4])? != SIGNATURE {
+            return Err(invalid("Not a VPK file."));
+        }
+        let header_len = match le32(&header[4..8])? {
+            1 => 12,
+            2 => 28,
+            _ => return Err(invalid("Unsupported VPK version.")),
+        };
+        let tree_len = u64::from(le32(&header[8..12])?);
+        dir.seek(SeekFrom::Start(header_len))?;
+        let mut tree = Vec::new();
+        (&mut dir).take(tree_len).read_to_end(&mut tree)?;
+
+        let index = index(&tree).ok_or_else(|| invalid("Invalid VPK tree."))?;
+        Ok(Self {
+            dir: RefCell::new(dir),
+            prefix: PathBuf::from(prefix),
+            data: header_len + tree_len,
+            index,
+        })
+    }
+}
+
+impl Store for Vpk {
+    type File = VpkEntry;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        let entry = match self.index.get(path) {
+            Some(entry) => entry,
+            None => return Err(io::Error::from(ErrorKind::NotFound)),
+        };
+        let mut data = entry.preload.to_vec();
+        if entry.len > 0 {
+            if entry.archive == DIR_ARCHIVE {
+                let mut dir = self.dir.borrow_mut();
+                dir.seek(SeekFrom::Start(self.data + entry.pos))?;
+                (&mut *dir).take(entry.len).read_to_end(&mut data)?;
+            } else {
+                let mut path = self.prefix.clone().into_os_string();
+                path.push(format!("{:03}.vpk", entry.archive));
+                let mut file = fs::File::open(path)?;
+                file.seek(SeekFrom::Start(entry.pos))?;
+                file.take(entry.len).read_to_end(&mut data)?;
+            }
+            if data.len() as u64 != entry.meta.len {
+                return Err(io::Error::from(ErrorKind::UnexpectedEof));
+            }
+        }
+        Ok(VpkEntry {
+            inner: Cursor::new(data.into()),
+        })
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        Ok(Entries::new(self.index.entries(path).map(|ent| {
+            let name = ent.name.to_os_string();
+            let kind = ent.kind;
+            Ok(Entry { name, kind })
+        })))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        if let Some(entry) = self.index.get(path) {
+            Ok(entry.meta.clone())
+        } else if self.index.contains_dir(path) {
+            Ok(Metadata::dir())
+        } else {
+            Err(io::Error::from(ErrorKind::NotFound))
+        }
+    }
+}
+
+// The tree is grouped by extension, then by directory. Each level is a list of
+// strings ending with an empty one, and a single space stands for an empty
+// directory or extension.
+fn index(tree: &[u8]) -> Option<Index<VpkIndexEntry>> {
+    let mut index = Index::new();
+    let mut tree = tree;
+    loop {
+        let ext = cstr(&mut tree)?;
+        if ext.is_empty() {
+            return Some(index);
+        }
+        loop {
+            let dir = cstr(&mut tree)?;
+            if dir.is_empty() {
+                break;
+            }
+            loop {
+                let name = cstr(&mut tree)?;
+                if name.is_empty() {
+                    break;
+                }
+                if tree.len() < 18 || tree[16..18] != [0xff, 0xff] {
+                    return None;
+                }
+                let preload_len = usize::from(le16(&tree[4..6]).ok()?);
+                let archive = le16(&tree[6..8]).ok()?;
+                let pos = u64::from(le32(&tree[8..12]).ok()?);
+                let len = u64::from(le32(&tree[12..16]).ok()?);
+                let preload = tree.get(18..18 + preload_len)?.into();
+                tree = &tree[18 + preload_len..];
+
+                let mut path = PathBuf::new();
+                if dir != " " {
+                    path.push(&dir);
+                }
+                if ext == " " {
+                    path.push(&name);
+                } else {
+                    path.push(format!("{}.{}", name, ext));
+                }
+                let meta = Metadata {
+                    kind: EntryKind::File,
+                    len: preload_len as u64 + len,
+                    modified: None,
+                };
+                let entry = VpkIndexEntry {
+                    preload,
+                    archive,
+                    pos,
+                    len,
+                    meta,
+                };
+                index.insert(path, entry);
+            }
+        }
+    }
+}
+
+fn cstr(data: &mut &[u8]) -> Option<String> {
+    let len = data.iter().position(|b| *b == 0)?;
+    let s = String::from_utf8_lossy(&data[..len]).into_owned();
+    *data = &data[len + 1..];
+    Some(s)
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg)
+}
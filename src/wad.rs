@@ -0,0 +1,253 @@
+use std::cell::RefCell;
+use std::fs;
+use std::io::{self, BufRead, Cursor, ErrorKind, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use crate::bytes::le32;
+use crate::index::Index;
+use crate::store::Store;
+use crate::{Entries, Entry, EntryKind, Metadata};
+
+// Size of a directory entry: offset, size and an 8 character name.
+const LUMP: usize = 16;
+
+// Lumps that make up a map in the binary (Doom and Hexen) formats.
+const MAP_LUMPS: &[&str] = &[
+    "THINGS", "LINEDEFS", "SIDEDEFS", "VERTEXES", "SEGS", "SSECTORS", "NODES", "SECTORS", "REJECT",
+    "BLOCKMAP", "BEHAVIOR", "SCRIPTS",
+];
+
+/// Doom WAD store.
+///
+/// Lumps are mapped to paths by name:
+///
+/// - Map lumps are placed in a directory named after the map, e.g.
+///   `E1M1/THINGS`. UDMF maps include every lump up to `ENDMAP`.
+/// - Lumps between namespace markers are placed in a directory named after the
+///   namespace, e.g. `flats/FLOOR0_1` for the lumps between `F_START` and
+///   `F_END`. The `S`, `F`, `P`, `TX`, `C`, `A` and `HI` namespaces are
+///   recognized (doubled letters too), and markers nested in them are skipped.
+/// - Other lumps are placed at the root. When names are repeated, the last lump
+///   is used, like the engine does.
+///
+/// Both IWADs and PWADs are read. Stores can be layered with a tuple, so
+/// `(pwad, iwad)` gives the lumps of the PWAD priority over those of the IWAD.
+///
+/// # Remarks
+///
+/// When used with a `std::fs::File`, the file will remain open for the lifetime
+/// of the Wad.
+pub struct Wad<T: Read + Seek> {
+    inner: RefCell<T>,
+    index: Index<WadIndexEntry>,
+}
+
+struct WadIndexEntry {
+    // position of the lump data within the file
+    pos: u64,
+    meta: Metadata,
+}
+
+/// Lump in the WAD file.
+pub struct WadEntry {
+    inner: Cursor<Box<[u8]>>,
+}
+
+impl WadEntry {
+    pub(crate) fn len(&self) -> u64 {
+        self.inner.get_ref().len() as u64
+    }
+}
+
+impl Read for WadEntry {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl BufRead for WadEntry {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+impl Seek for WadEntry {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl Wad<fs::File> {
+    /// Open a file from the native filesystem.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = fs::OpenOptions::new()
+            .read(true)
+            .write(false)
+            .create(false)
+            .open(path)?;
+        Self::new(file)
+    }
+}
+
+impl<T: Read + Seek> Wad<T> {
+    /// Read the directory of the WAD and index its lumps.
+    pub fn new(mut inner: T) -> io::Result<Self> {
+        let mut header = [0; 12];
+        inner.seek(SeekFrom::Start(0))?;
+        if inner.read_exact(&mut header).is_err()
+            || (&header[..4] != b"IWAD" && &header[..4] != b"PWAD")
+        {
+            return Err(invalid("Not a WAD file."));
+        }
+        let count = le32(&header[4..8])? as usize;
+        inner.seek(SeekFrom::Start(u64::from(le32(&header[8..12])?)))?;
+        let mut directory = Vec::new();
+        (&mut inner)
+            .take((count * LUMP) as u64)
+            .read_to_end(&mut directory)?;
+        if directory.len() != count * LUMP {
+            return Err(invalid("Truncated WAD directory."));
+        }
+
+        let lumps: Vec<_> = directory
+            .chunks(LUMP)
+            .map(|lump| {
+                let name = &lump[8..];
+                let name = &name[..name.iter().position(|b| *b == 0).unwrap_or(8)];
+                let pos = u64::from(le32(&lump[..4])?);
+                let len = u64::from(le32(&lump[4..8])?);
+                Ok((String::from_utf8_lossy(name).into_owned(), pos, len))
+            })
+            .collect::<io::Result<_>>()?;
+
+        let mut index = Index::new();
+        let mut namespace = None;
+        let mut map = None;
+        for (i, (name, pos, len)) in lumps.iter().enumerate() {
+            let next = lumps.get(i + 1).map(|(name, _, _)| name.as_str());
+            // map lumps follow the map marker
+            match map {
+                Some(Map::Binary(_)) if MAP_LUMPS.contains(&name.as_str()) => {}
+                Some(Map::Udmf(_)) if name == "ENDMAP" => {
+                    map = None;
+                    continue;
+                }
+                Some(Map::Udmf(_)) => {}
+                _ => map = None,
+            }
+            if map.is_none() && namespace.is_none() {
+                match next {
+                    Some("THINGS") => map = Some(Map::Binary(name.clone())),
+                    Some("TEXTMAP") => map = Some(Map::Udmf(name.clone())),
+                    _ => {}
+                }
+                if map.is_some() {
+                    index.insert_dir(name.clone());
+                    continue;
+                }
+            }
+
+            if let Some(marker) = name.strip_suffix("_START") {
+                if namespace.is_none() {
+                    namespace = namespace_dir(marker);
+                }
+                if namespace.is_some() {
+                    continue;
+                }
+            } else if let Some(marker) = name.strip_suffix("_END") {
+                if namespace.is_some() {
+                    if namespace_dir(marker) == namespace {
+                        namespace = None;
+                    }
+                    continue;
+                }
+            }
+
+            let path = match (&map, namespace) {
+                (Some(Map::Binary(map)), _) | (Some(Map::Udmf(map)), _) => {
+                    PathBuf::from(map).join(name)
+                }
+                (None, Some(dir)) => PathBuf::from(dir).join(name),
+                (None, None) => PathBuf::from(name),
+            };
+            let meta = Metadata {
+                kind: EntryKind::File,
+                len: *len,
+                modified: None,
+            };
+            index.insert(path, WadIndexEntry { pos: *pos, meta });
+        }
+
+        Ok(Self {
+            inner: RefCell::new(inner),
+            index,
+        })
+    }
+}
+
+impl<T: Read + Seek> Store for Wad<T> {
+    type File = WadEntry;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        let entry = match self.index.get(path) {
+            Some(entry) => entry,
+            None => return Err(io::Error::from(ErrorKind::NotFound)),
+        };
+        let mut file = self.inner.borrow_mut();
+        file.seek(SeekFrom::Start(entry.pos))?;
+        let mut data = Vec::new();
+        (&mut *file).take(entry.meta.len).read_to_end(&mut data)?;
+        Ok(WadEntry {
+            inner: Cursor::new(data.into()),
+        })
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        Ok(Entries::new(self.index.entries(path).map(|ent| {
+            let name = ent.name.to_os_string();
+            let kind = ent.kind;
+            Ok(Entry { name, kind })
+        })))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        if let Some(entry) = self.index.get(path) {
+            Ok(entry.meta.clone())
+        } else if self.index.contains_dir(path) {
+            Ok(Metadata::dir())
+        } else {
+            Err(io::Error::from(ErrorKind::NotFound))
+        }
+    }
+}
+
+// Map being indexed, by name.
+enum Map {
+    Binary(String),
+    Udmf(String),
+}
+
+fn namespace_dir(marker: &str) -> Option<&'static str> {
+    match marker {
+        "S" | "SS" => Some("sprites"),
+        "F" | "FF" => Some("flats"),
+        "P" | "PP" => Some("patches"),
+        "TX" => Some("textures"),
+        "C" => Some("colormaps"),
+        "A" => Some("acs"),
+        "HI" => Some("hires"),
+        _ => None,
+    }
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg)
+}
@@ -0,0 +1,303 @@
+use std::io::{self, ErrorKind};
+use std::path::Path;
+use std::time::SystemTime;
+
+use roxmltree::{Document, Node};
+
+#[cfg(feature = "ureq")]
+use crate::http::Ureq;
+use crate::http::{Http, HttpClient, HttpEntry};
+use crate::store::{Entries, Entry, Store};
+use crate::time;
+use crate::{EntryKind, Metadata};
+
+const PROPFIND: &[u8] = br#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:">
+  <d:prop>
+    <d:resourcetype/>
+    <d:getcontentlength/>
+    <d:getlastmodified/>
+  </d:prop>
+</d:propfind>"#;
+
+/// Read-only WebDAV store.
+///
+/// Files are downloaded with GET requests and directories are listed with
+/// PROPFIND requests, so shares of servers like Nextcloud can be mounted:
+///
+/// ```no_run
+/// # #[cfg(feature = "ureq")]
+/// # fn main() -> std::io::Result<()> {
+/// use mini_fs::prelude::*;
+/// use mini_fs::{MiniFs, WebDav};
+///
+/// let share = WebDav::new("https://cloud.example.com/public.php/webdav")
+///     .basic_auth("share-token", "");
+/// let fs = MiniFs::new().mount("/assets", share);
+///
+/// for entry in fs.entries("/assets/textures")? {
+///     println!("{:?}", entry?.name);
+/// }
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "ureq"))]
+/// # fn main() {}
+/// ```
+///
+/// Requests are sent by an [`HttpClient`], which must support the PROPFIND
+/// method through [`HttpClient::request`].
+///
+/// [`HttpClient`]: ../http/trait.HttpClient.html
+/// [`HttpClient::request`]: ../http/trait.HttpClient.html#method.request
+pub struct WebDav<C> {
+    http: Http<C>,
+}
+
+#[cfg(feature = "ureq")]
+impl WebDav<Ureq> {
+    /// Create a store for the files under the `base` URL.
+    pub fn new<B: Into<String>>(base: B) -> Self {
+        Self::with_client(base, Ureq::default())
+    }
+}
+
+impl<C> WebDav<C> {
+    /// Create a store sending its requests with `client`.
+    pub fn with_client<B: Into<String>>(base: B, client: C) -> Self {
+        Self {
+            http: Http::with_client(base, client),
+        }
+    }
+
+    /// Add a header to every request.
+    pub fn header<N, V>(mut self, name: N, value: V) -> Self
+    where
+        N: Into<String>,
+        V: Into<String>,
+    {
+        self.http = self.http.header(name, value);
+        self
+    }
+
+    /// Authenticate every request with HTTP basic authentication.
+    pub fn basic_auth(self, user: &str, password: &str) -> Self {
+        let credentials = base64(format!("{}:{}", user, password).as_bytes());
+        self.header("Authorization", format!("Basic {}", credentials))
+    }
+}
+
+impl<C: HttpClient> WebDav<C> {
+    // Responses of a PROPFIND request, including the one of the requested
+    // resource itself.
+    fn propfind(&self, path: &Path, depth: &str) -> io::Result<Vec<Response>> {
+        let mut url = self.http.url(path)?;
+        if depth != "0" {
+            url.push('/');
+        }
+        let mut headers = self.http.headers.clone();
+        headers.push(("Depth".to_string(), depth.to_string()));
+        headers.push((
+            "Content-Type".to_string(),
+            "application/xml; charset=utf-8".to_string(),
+        ));
+        let body = self
+            .http
+            .client
+            .request("PROPFIND", &url, &headers, PROPFIND)?;
+        let body = String::from_utf8(body).map_err(|_| invalid("Invalid PROPFIND response."))?;
+        let doc = Document::parse(&body).map_err(|_| invalid("Invalid PROPFIND response."))?;
+        doc.root_element()
+            .children()
+            .filter(|node| is_dav(node, "response"))
+            .map(Response::parse)
+            .collect()
+    }
+}
+
+impl<C: HttpClient> Store for WebDav<C> {
+    type File = HttpEntry;
+
+    fn open_path(&self, path: &Path) -> io::Result<Self::File> {
+        self.http.open_path(path)
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        let target = href_path(&decode(&self.http.url(path)?)).to_string();
+        let (dirs, responses): (Vec<_>, Vec<_>) = self
+            .propfind(path, "1")?
+            .into_iter()
+            .partition(|response| href_path(&response.href) == target);
+        if dirs.iter().all(|dir| dir.kind != EntryKind::Dir) {
+            return Err(io::Error::from(ErrorKind::NotFound));
+        }
+        Ok(Entries::new(responses.into_iter().filter_map(|response| {
+            let name = response.name()?;
+            Some(Ok(Entry {
+                name: name.into(),
+                kind: response.kind,
+            }))
+        })))
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        let response = self
+            .propfind(path, "0")?
+            .into_iter()
+            .next()
+            .ok_or_else(|| invalid("Empty PROPFIND response."))?;
+        Ok(Metadata {
+            kind: response.kind,
+            len: if response.kind == EntryKind::File {
+                response.len
+            } else {
+                0
+            },
+            modified: response.modified,
+        })
+    }
+}
+
+// Properties of a resource in a PROPFIND response.
+struct Response {
+    href: String,
+    kind: EntryKind,
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl Response {
+    fn parse(node: Node) -> io::Result<Self> {
+        let href = child(node, "href")
+            .and_then(|href| href.text())
+            .ok_or_else(|| invalid("Missing href in PROPFIND response."))?;
+        let mut response = Response {
+            href: decode(href.trim()),
+            kind: EntryKind::File,
+            len: 0,
+            modified: None,
+        };
+        let props = node
+            .children()
+            .filter(|node| is_dav(node, "propstat"))
+            .filter(|propstat| {
+                child(*propstat, "status")
+                    .and_then(|status| status.text())
+                    .is_some_and(|status| status.split(' ').nth(1) == Some("200"))
+            })
+            .filter_map(|propstat| child(propstat, "prop"));
+        for prop in props {
+            if let Some(kind) = child(prop, "resourcetype") {
+                if child(kind, "collection").is_some() {
+                    response.kind = EntryKind::Dir;
+                }
+            }
+            if let Some(len) = child(prop, "getcontentlength").and_then(|len| len.text()) {
+                response.len = len.trim().parse().unwrap_or(0);
+            }
+            if let Some(date) = child(prop, "getlastmodified").and_then(|date| date.text()) {
+                response.modified = parse_date(date.trim());
+            }
+        }
+        Ok(response)
+    }
+
+    fn name(&self) -> Option<&str> {
+        let name = self.href.trim_end_matches('/').rsplit('/').next()?;
+        if name.is_empty() {
+            None
+        } else {
+            Some(name)
+        }
+    }
+}
+
+fn is_dav(node: &Node, name: &str) -> bool {
+    node.is_element()
+        && node.tag_name().namespace() == Some("DAV:")
+        && node.tag_name().name() == name
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| is_dav(child, name))
+}
+
+// Path of an href without the scheme, the host and the trailing slash.
+fn href_path(href: &str) -> &str {
+    let path = match href.find("://") {
+        Some(i) => href[i + 3..].find('/').map_or("", |j| &href[i + 3 + j..]),
+        None => href,
+    };
+    path.trim_end_matches('/')
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg)
+}
+
+// Percent-decodes an href.
+fn decode(href: &str) -> String {
+    let bytes = href.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+// Parses an HTTP date such as `Sun, 06 Nov 1994 08:49:37 GMT`.
+fn parse_date(date: &str) -> Option<SystemTime> {
+    let mut parts = date.split_whitespace().skip(1);
+    let day: i64 = parts.next()?.parse().ok()?;
+    let month = match parts.next()? {
+        "Jan" => 1,
+        "Feb" => 2,
+        "Mar" => 3,
+        "Apr" => 4,
+        "May" => 5,
+        "Jun" => 6,
+        "Jul" => 7,
+        "Aug" => 8,
+        "Sep" => 9,
+        "Oct" => 10,
+        "Nov" => 11,
+        "Dec" => 12,
+        _ => return None,
+    };
+    let year: i64 = parts.next()?.parse().ok()?;
+    let mut hms = parts.next()?.split(':').map(|n| n.parse::<i64>().ok());
+    let (hour, min, sec) = (hms.next()??, hms.next()??, hms.next()??);
+    time::from_civil(year, month, day, hour * 3600 + min * 60 + sec)
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
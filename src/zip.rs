@@ -1,43 +1,179 @@
-use std::cell::RefCell;
+use std::convert::TryInto;
 use std::fs;
-use std::io::{self, Cursor, ErrorKind, Read, Seek, SeekFrom, Write};
-use std::ops::DerefMut;
+use std::io::{self, BufRead, BufReader, Cursor, ErrorKind, Read, Seek, SeekFrom};
 use std::path::Path;
+use std::sync::{Mutex, MutexGuard, PoisonError};
 
+use flate2::bufread::DeflateDecoder;
+use flate2::Crc;
+use zip_::read::ZipFile;
 use zip_::ZipArchive;
 
+use crate::bytes::le16;
 use crate::index::Index;
+use crate::limits::Limits;
 use crate::store::Store;
-use crate::{Entries, Entry};
-use std::rc::Rc;
+use crate::{Entries, Entry, EntryKind, Metadata};
+
+const LOCAL_HEADER: usize = 30;
+const STORED: u16 = 0;
+const DEFLATED: u16 = 8;
 
 /// Zip archive store.
 ///
+/// Zip64 archives are supported, so archives can be larger than 4 GiB and hold
+/// more than 65535 entries.
+///
+/// Archives encrypted with the legacy ZipCrypto method can be read by creating
+/// the store with [`Zip::with_password`].
+///
+/// Deflated files are decompressed as they are read, so only the compressed
+/// data of an open file is kept in memory. Encrypted files and other
+/// compression methods are decompressed when they are opened.
+///
 /// # Remarks
 ///
 /// When used with a `std::fs::File`, the file will remain open for the lifetime
-/// of the Zip.
+/// of the Zip. Zips over readers that are `Send` can be shared between threads,
+/// but the reader is locked while the archive is read, so reads don't run in
+/// parallel.
+///
+/// Stores that haven't been indexed read the central directory of the archive
+/// every time a file is opened.
+///
+/// Archives from untrusted sources should be read with [`limits`], since
+/// files can decompress to many times their compressed size. Deflated files
+/// can never be read past the size recorded in the archive.
+///
+/// [`Zip::with_password`]: #method.with_password
+/// [`limits`]: #method.limits
 pub struct Zip<T: Read + Seek> {
-    inner: RefCell<T>,
-    index: Option<Index<()>>,
+    inner: Mutex<T>,
+    index: Option<Index<ZipIndexEntry>>,
+    password: Option<Box<[u8]>>,
+    limits: Limits,
+}
+
+struct ZipIndexEntry {
+    // name of the file in the archive
+    name: String,
+    // position of the local file header
+    header: u64,
+    compressed: u64,
+    crc: u32,
+    meta: Metadata,
 }
 
 /// Entry in the Zip archive.
 pub struct ZipEntry {
-    inner: Cursor<Box<[u8]>>,
+    inner: ZipEntryInner,
+    len: u64,
+}
+
+enum ZipEntryInner {
+    Memory(Cursor<Box<[u8]>>),
+    Deflated(Box<Inflate>),
+}
+
+// Deflated file, decompressed from the start again when seeking backwards.
+struct Inflate {
+    inner: BufReader<DeflateDecoder<Cursor<Box<[u8]>>>>,
+    pos: u64,
+    // checksum of the data read so far
+    crc: Crc,
+    expected: u32,
+}
+
+impl ZipEntry {
+    pub(crate) fn len(&self) -> u64 {
+        self.len
+    }
 }
 
 impl Read for ZipEntry {
-    #[inline]
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        self.inner.read(buf)
+        let data = self.fill_buf()?;
+        let n = data.len().min(buf.len());
+        buf[..n].copy_from_slice(&data[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl BufRead for ZipEntry {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        match &mut self.inner {
+            ZipEntryInner::Memory(inner) => inner.fill_buf(),
+            ZipEntryInner::Deflated(inflate) => {
+                let data = inflate.inner.fill_buf()?;
+                if !data.is_empty() && inflate.pos >= self.len {
+                    return Err(invalid("Zip file is larger than its declared size."));
+                }
+                if data.is_empty() {
+                    if inflate.pos < self.len {
+                        return Err(io::Error::from(ErrorKind::UnexpectedEof));
+                    }
+                    if inflate.pos == self.len && inflate.crc.sum() != inflate.expected {
+                        return Err(invalid("Invalid zip checksum."));
+                    }
+                }
+                Ok(data)
+            }
+        }
+    }
+
+    fn consume(&mut self, amt: usize) {
+        match &mut self.inner {
+            ZipEntryInner::Memory(inner) => inner.consume(amt),
+            ZipEntryInner::Deflated(inflate) => {
+                inflate.crc.update(&inflate.inner.buffer()[..amt]);
+                inflate.pos += amt as u64;
+                inflate.inner.consume(amt)
+            }
+        }
     }
 }
 
 impl Seek for ZipEntry {
-    #[inline]
     fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
-        self.inner.seek(pos)
+        let inflate = match &mut self.inner {
+            ZipEntryInner::Memory(inner) => return inner.seek(pos),
+            ZipEntryInner::Deflated(inflate) => inflate,
+        };
+        let target = match pos {
+            SeekFrom::Start(pos) => Some(pos),
+            SeekFrom::End(off) => self.len.checked_add_signed(off),
+            SeekFrom::Current(off) => inflate.pos.checked_add_signed(off),
+        };
+        let target = target.ok_or_else(|| {
+            io::Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        if target < inflate.pos {
+            let mut data = inflate.inner.get_mut().reset(Cursor::new(Box::default()));
+            data.set_position(0);
+            inflate.inner = BufReader::new(DeflateDecoder::new(data));
+            inflate.pos = 0;
+            inflate.crc.reset();
+        }
+        while self.stream_position()? < target {
+            let left = target - self.stream_position()?;
+            let n = self.fill_buf()?.len();
+            if n == 0 {
+                break;
+            }
+            self.consume(n.min(left.try_into().unwrap_or(usize::MAX)));
+        }
+        self.stream_position()
+    }
+
+    fn stream_position(&mut self) -> io::Result<u64> {
+        match &mut self.inner {
+            ZipEntryInner::Memory(inner) => inner.stream_position(),
+            ZipEntryInner::Deflated(inflate) => Ok(inflate.pos),
+        }
     }
 }
 
@@ -52,56 +188,187 @@ impl Zip<fs::File> {
     }
 }
 
+#[cfg(feature = "mmap")]
+impl Zip<crate::Mmap> {
+    /// Memory-map a file from the native filesystem.
+    pub fn mmap<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self::new(crate::Mmap::open(path)?))
+    }
+}
+
 impl<T: Read + Seek> Zip<T> {
     pub fn new(inner: T) -> Self {
         Self {
-            inner: RefCell::new(inner),
+            inner: Mutex::new(inner),
             index: None,
+            password: None,
+            limits: Limits::new(),
+        }
+    }
+
+    /// Limit the size of the files read from the archive, and the number of
+    /// entries indexed.
+    ///
+    /// Call it before indexing the archive, so the limits apply to the index.
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Create a store that decrypts encrypted files with `password`.
+    ///
+    /// Files that aren't encrypted are read as usual. Reading an encrypted file
+    /// with the wrong password fails with `ErrorKind::PermissionDenied`.
+    pub fn with_password<P: AsRef<[u8]>>(inner: T, password: P) -> Self {
+        Self {
+            password: Some(password.as_ref().into()),
+            ..Self::new(inner)
         }
     }
 
     /// Index the contents of the archive.
     ///
     /// Having an index allows you to list the contents of the archive using the
-    /// entries_path and entries methods.
-    pub fn index(mut self) -> io::Result<Self> {
-        let mut index = Index::new();
-        let mut file = self.inner.borrow_mut();
+    /// entries_path and entries methods. Indexed stores read the central
+    /// directory only once, here.
+    pub fn index(self) -> io::Result<Self> {
+        self.index_with(Index::new())
+    }
+
+    /// Index the contents of the archive, so paths are looked up ignoring
+    /// ASCII case.
+    ///
+    /// Exact matches win, and entries are listed with the case of the archive.
+    pub fn index_case_insensitive(self) -> io::Result<Self> {
+        self.index_with(Index::new().case_insensitive())
+    }
+
+    /// Look up paths by their Unicode normalization form C, so names in
+    /// composed and decomposed forms find the same files.
+    ///
+    /// Archives created on macOS often have decomposed names. The archive is
+    /// indexed if it wasn't already.
+    #[cfg(feature = "unicode")]
+    pub fn unicode_normalized(self) -> io::Result<Self> {
+        let mut this = match self.index {
+            Some(_) => self,
+            None => self.index()?,
+        };
+        this.index = this.index.map(Index::unicode_normalized);
+        Ok(this)
+    }
+
+    // The reader is always seeked before it's read, so a panic while reading
+    // doesn't need to poison the lock.
+    fn file(&self) -> MutexGuard<'_, T> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn index_with(mut self, mut index: Index<ZipIndexEntry>) -> io::Result<Self> {
+        let mut file = self.file();
         file.seek(SeekFrom::Start(0))?;
         let mut archive = ZipArchive::new(&mut *file)?;
+        self.limits.check_entries(archive.len())?;
+        let mut total = 0u64;
         for i in 0..archive.len() {
-            let file = archive.by_index(i)?;
-            let path = file.sanitized_name();
+            // Only the metadata is needed, so files aren't decrypted.
+            let file = archive.by_index_raw(i)?;
+            let path = file.mangled_name();
 
-            index.insert(path, ());
+            if file.is_dir() {
+                index.insert_dir(path);
+            } else {
+                let entry = index_entry(&file);
+                total = total.saturating_add(entry.meta.len);
+                self.limits.check_total_size(total)?;
+                index.insert(path, entry);
+            }
         }
-        self.index = Some(index);
         drop(file);
+        self.index = Some(index);
         Ok(self)
     }
+
+    // Reads the local header of the file, and the compressed data if it can be
+    // decompressed as it's read.
+    fn open_entry(&self, entry: &ZipIndexEntry) -> io::Result<ZipEntry> {
+        let mut file = self.file();
+        let mut header = [0; LOCAL_HEADER];
+        file.seek(SeekFrom::Start(entry.header))?;
+        file.read_exact(&mut header)?;
+        if header[..4] != [0x50, 0x4b, 0x03, 0x04] {
+            return Err(invalid("Invalid local file header."));
+        }
+        let encrypted = le16(&header[6..8])? & 1 != 0;
+        let method = le16(&header[8..10])?;
+        let len = entry.meta.len;
+        self.limits.check_file_size(len)?;
+        self.limits.check_ratio(len, entry.compressed)?;
+
+        if !encrypted && (method == STORED || method == DEFLATED) {
+            // the data follows the name and the extra field
+            let skip = i64::from(le16(&header[26..28])?) + i64::from(le16(&header[28..30])?);
+            file.seek(SeekFrom::Current(skip))?;
+            let mut data = Vec::new();
+            (&mut *file).take(entry.compressed).read_to_end(&mut data)?;
+            if data.len() as u64 != entry.compressed {
+                return Err(io::Error::from(ErrorKind::UnexpectedEof));
+            }
+            let data = Cursor::new(data.into_boxed_slice());
+            let inner = if method == STORED {
+                let mut crc = Crc::new();
+                crc.update(data.get_ref());
+                if data.get_ref().len() as u64 != len || crc.sum() != entry.crc {
+                    return Err(invalid("Invalid zip checksum."));
+                }
+                ZipEntryInner::Memory(data)
+            } else {
+                ZipEntryInner::Deflated(Box::new(Inflate {
+                    inner: BufReader::new(DeflateDecoder::new(data)),
+                    pos: 0,
+                    crc: Crc::new(),
+                    expected: entry.crc,
+                }))
+            };
+            return Ok(ZipEntry { inner, len });
+        }
+
+        file.seek(SeekFrom::Start(0))?;
+        let mut archive = ZipArchive::new(&mut *file)?;
+        let file = by_name(&mut archive, &entry.name, self.password.as_deref())?;
+        let mut data = Vec::new();
+        file.take(len.saturating_add(1)).read_to_end(&mut data)?;
+        if data.len() as u64 > len {
+            return Err(invalid("Zip file is larger than its declared size."));
+        }
+        Ok(ZipEntry {
+            len: data.len() as u64,
+            inner: ZipEntryInner::Memory(Cursor::new(data.into())),
+        })
+    }
 }
 
 impl<T: Read + Seek> Store for Zip<T> {
     type File = ZipEntry;
     fn open_path(&self, path: &Path) -> io::Result<Self::File> {
-        let mut file = self.inner.borrow_mut();
-        file.seek(SeekFrom::Start(0))?;
+        if let Some(ref idx) = self.index {
+            return match idx.get(path) {
+                Some(entry) => self.open_entry(entry),
+                None => Err(io::Error::from(ErrorKind::NotFound)),
+            };
+        }
 
-        let mut archive = ZipArchive::new(&mut *file)?;
-        let name = path.to_str().ok_or(io::Error::new(
-            ErrorKind::Other,
-            "Utf8 path conversion error.",
-        ));
-        let mut file = archive.by_name(name?)?;
-
-        let mut v = Vec::new();
-        file.read_to_end(&mut v)?;
-        Ok(ZipEntry {
-            inner: Cursor::new(v.into()),
-        })
+        let entry = {
+            let mut file = self.file();
+            file.seek(SeekFrom::Start(0))?;
+            let mut archive = ZipArchive::new(&mut *file)?;
+            let file = by_name(&mut archive, &utf8(path)?, self.password.as_deref())?;
+            index_entry(&file)
+        };
+        self.open_entry(&entry)
     }
 
-    fn entries_path(&self, path: &Path) -> io::Result<Entries> {
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
         if let Some(ref idx) = self.index {
             Ok(Entries::new(idx.entries(path).map(|ent| {
                 let name = ent.name.to_os_string();
@@ -109,7 +376,82 @@ impl<T: Read + Seek> Store for Zip<T> {
                 Ok(Entry { name, kind })
             })))
         } else {
-            panic!("You have to call the `Zip::index` method on this zip archive before you can list its entries.")
+            Err(io::Error::other(
+                "You have to call the `Zip::index` method on this zip archive before you can list its entries.",
+            ))
         }
     }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        if let Some(ref idx) = self.index {
+            return if let Some(entry) = idx.get(path) {
+                Ok(entry.meta.clone())
+            } else if idx.contains_dir(path) {
+                Ok(Metadata::dir())
+            } else {
+                Err(io::Error::from(ErrorKind::NotFound))
+            };
+        }
+        if path.iter().next().is_none() {
+            return Ok(Metadata::dir());
+        }
+
+        let mut file = self.file();
+        file.seek(SeekFrom::Start(0))?;
+        let mut archive = ZipArchive::new(&mut *file)?;
+        let name = utf8(path)?;
+        if let Ok(file) = by_name(&mut archive, &name, self.password.as_deref()) {
+            return Ok(metadata(&file));
+        }
+        // Without an index, directories are only found if they have their own
+        // entry.
+        let meta = match archive.by_name(&format!("{}/", name)) {
+            Ok(_) => Ok(Metadata::dir()),
+            Err(_) => Err(io::Error::from(ErrorKind::NotFound)),
+        };
+        meta
+    }
+}
+
+fn by_name<'a, R: Read + Seek>(
+    archive: &'a mut ZipArchive<R>,
+    name: &str,
+    password: Option<&[u8]>,
+) -> io::Result<ZipFile<'a>> {
+    match password {
+        Some(password) => archive
+            .by_name_decrypt(name, password)?
+            .map_err(|_| io::Error::new(ErrorKind::PermissionDenied, "Invalid zip password.")),
+        None => Ok(archive.by_name(name)?),
+    }
+}
+
+// Name of the file in the archive, where separators are always slashes.
+fn utf8(path: &Path) -> io::Result<String> {
+    path.to_str()
+        .map(|name| name.replace('\\', "/"))
+        .ok_or_else(|| io::Error::other("Utf8 path conversion error."))
+}
+
+fn index_entry(file: &ZipFile) -> ZipIndexEntry {
+    ZipIndexEntry {
+        name: file.name().to_owned(),
+        header: file.header_start(),
+        compressed: file.compressed_size(),
+        crc: file.crc32(),
+        meta: metadata(file),
+    }
+}
+
+fn metadata(file: &ZipFile) -> Metadata {
+    let time = file.last_modified();
+    Metadata {
+        kind: EntryKind::File,
+        len: file.size(),
+        modified: crate::time::from_dos(time.datepart(), time.timepart()),
+    }
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg)
 }
@@ -0,0 +1,64 @@
+use std::io::{ErrorKind, Result};
+use std::path::PathBuf;
+
+use mini_fs::prelude::*;
+use mini_fs::{Aliases, MiniFs};
+
+mod common;
+
+const FILES: &[(&str, &str)] = &[
+    ("sfx/explosion.wav", "boom"),
+    ("textures/legacy/brick.png", "brick"),
+];
+
+#[test]
+fn aliases_open() {
+    let store = Aliases::new(common::ram(FILES))
+        .alias("sfx/boom.wav", "sfx/kaboom.wav")
+        .alias("/sfx/kaboom.wav", "sfx/explosion.wav")
+        .alias("textures/old", "textures/legacy")
+        .alias("a", "b")
+        .alias("b", "a");
+    assert_eq!("boom", store.read_to_string("sfx/boom.wav").unwrap());
+    assert_eq!("boom", store.read_to_string("./sfx/kaboom.wav").unwrap());
+    assert_eq!("boom", store.read_to_string("sfx/explosion.wav").unwrap());
+    assert_eq!(
+        "brick",
+        store.read_to_string("textures/old/brick.png").unwrap()
+    );
+    assert_eq!(4, store.metadata("sfx/boom.wav").unwrap().len);
+    assert!(store.is_dir("textures/old"));
+    let names = store
+        .walk("textures/old")
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(vec![PathBuf::from("textures/old/brick.png")], names);
+    assert_eq!(ErrorKind::NotFound, store.open("a").err().unwrap().kind());
+}
+
+#[test]
+fn aliases_manifest() {
+    let manifest =
+        b"# renamed in 1.2\n\nsfx/boom.wav = sfx/explosion.wav\n textures/old=textures/legacy \n";
+    let store = Aliases::new(common::ram(FILES))
+        .manifest(&manifest[..])
+        .unwrap();
+    assert_eq!("boom", store.read_to_string("sfx/boom.wav").unwrap());
+    assert_eq!(
+        "brick",
+        store.read_to_string("textures/old/brick.png").unwrap()
+    );
+
+    let err = Aliases::new(common::ram(FILES))
+        .manifest(&b"a = b\nc\n"[..])
+        .err()
+        .unwrap();
+    assert_eq!(ErrorKind::InvalidData, err.kind());
+}
+
+#[test]
+fn aliases_mount() {
+    let store = Aliases::new(common::ram(FILES)).alias("sfx/boom.wav", "sfx/explosion.wav");
+    let fs = MiniFs::new().mount("/assets", store);
+    assert_eq!("boom", fs.read_to_string("/assets/sfx/boom.wav").unwrap());
+}
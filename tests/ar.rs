@@ -0,0 +1,59 @@
+#![cfg(feature = "ar")]
+
+use std::io::{Cursor, ErrorKind};
+use std::time::{Duration, UNIX_EPOCH};
+
+use mini_fs::prelude::*;
+use mini_fs::{Ar, EntryKind};
+
+mod common;
+
+#[test]
+fn ar() {
+    let ar = Ar::new(Cursor::new(&include_bytes!("archive.a")[..])).unwrap();
+    assert_eq!(
+        vec!["a_very_long_file_name.txt", "b.txt", "c.txt"],
+        common::names(&ar, ".")
+    );
+    assert_eq!(
+        "hello\n",
+        ar.read_to_string("a_very_long_file_name.txt").unwrap()
+    );
+    assert_eq!("world!\n", ar.read_to_string("b.txt").unwrap());
+    assert_eq!("bsd\n", ar.read_to_string("c.txt").unwrap());
+    assert_eq!(ErrorKind::NotFound, ar.open("nope").err().unwrap().kind());
+    assert!(Ar::new(Cursor::new(&b"not an archive"[..])).is_err());
+}
+
+#[test]
+fn ar_metadata() {
+    let ar = Ar::new(Cursor::new(&include_bytes!("archive.a")[..])).unwrap();
+
+    let meta = ar.metadata("c.txt").unwrap();
+    assert_eq!(EntryKind::File, meta.kind);
+    assert_eq!(4, meta.len);
+    assert_eq!(
+        Some(UNIX_EPOCH + Duration::from_secs(1_556_377_860)),
+        meta.modified
+    );
+
+    assert_eq!(EntryKind::Dir, ar.metadata(".").unwrap().kind);
+    assert!(ar.metadata("nope").is_err());
+}
+
+#[test]
+#[cfg(feature = "tar")]
+fn ar_deb() {
+    let deb = Ar::new(Cursor::new(&include_bytes!("archive.deb")[..])).unwrap();
+    assert_eq!(
+        vec!["control.tar.gz", "data.tar.gz", "debian-binary"],
+        common::names(&deb, ".")
+    );
+    assert_eq!("2.0\n", deb.read_to_string("debian-binary").unwrap());
+
+    let data = deb.deb_data().unwrap();
+    assert_eq!(vec!["a.txt", "b.txt"], common::names(&data, "."));
+    assert_eq!("hello\n", data.read_to_string("a.txt").unwrap());
+    assert!(deb.tar("control.tar.gz").is_ok());
+    assert!(deb.tar("nope").is_err());
+}
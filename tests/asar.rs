@@ -0,0 +1,108 @@
+#![cfg(feature = "asar")]
+
+use std::ffi::OsStr;
+use std::io::{Cursor, ErrorKind, Read};
+
+use mini_fs::prelude::*;
+use mini_fs::{Asar, EntryKind};
+
+fn archive() -> Asar<Cursor<&'static [u8]>> {
+    Asar::new(Cursor::new(&include_bytes!("archive.asar")[..])).unwrap()
+}
+
+#[test]
+fn asar() {
+    let asar = archive();
+    for _ in 0..4 {
+        let mut a_content = String::new();
+        let mut b_content = String::new();
+        asar.open("a.txt")
+            .unwrap()
+            .read_to_string(&mut a_content)
+            .unwrap();
+        asar.open("dir/b.txt")
+            .unwrap()
+            .read_to_string(&mut b_content)
+            .unwrap();
+        assert_eq!("hello\n", a_content);
+        assert_eq!("world!\n", b_content);
+    }
+    assert_eq!(ErrorKind::NotFound, asar.open("nope").err().unwrap().kind());
+    assert_eq!(ErrorKind::NotFound, asar.open("link").err().unwrap().kind());
+    assert_eq!(
+        ErrorKind::Unsupported,
+        asar.open("native.node").err().unwrap().kind()
+    );
+    assert!(Asar::new(Cursor::new(&b"not an archive"[..])).is_err());
+}
+
+#[test]
+fn asar_unpacked() {
+    let dir = std::env::temp_dir().join("mini-fs-asar-unpacked");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("app.asar");
+    std::fs::write(&path, &include_bytes!("archive.asar")[..]).unwrap();
+    std::fs::create_dir_all(dir.join("app.asar.unpacked")).unwrap();
+    std::fs::write(dir.join("app.asar.unpacked/native.node"), b"abc").unwrap();
+
+    let asar = Asar::open(&path).unwrap();
+    let mut content = String::new();
+    asar.open("native.node")
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    assert_eq!("abc", content);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn asar_entries() {
+    let asar = archive();
+
+    let mut entries = asar
+        .entries(".")
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    entries.sort_by_key(|e| e.name.clone());
+    let names: Vec<_> = entries.iter().map(|e| e.name.as_os_str()).collect();
+    assert_eq!(vec!["a.txt", "dir", "empty", "native.node"], names);
+    assert_eq!(EntryKind::File, entries[0].kind);
+    assert_eq!(EntryKind::Dir, entries[2].kind);
+
+    let entries = asar.entries("dir").unwrap().collect::<Vec<_>>();
+    assert_eq!(1, entries.len());
+    assert_eq!(OsStr::new("b.txt"), entries[0].as_ref().unwrap().name);
+}
+
+#[test]
+fn asar_metadata() {
+    let asar = archive();
+
+    let meta = asar.metadata("a.txt").unwrap();
+    assert_eq!(EntryKind::File, meta.kind);
+    assert_eq!(6, meta.len);
+    assert_eq!(None, meta.modified);
+
+    assert_eq!(3, asar.metadata("native.node").unwrap().len);
+    assert_eq!(EntryKind::Dir, asar.metadata("empty").unwrap().kind);
+    assert!(asar.metadata("nope").is_err());
+}
+
+#[test]
+fn asar_invalid_offset() {
+    let json = br#"{"files":{"a.txt":{"size":1,"offset":"18446744073709551615"}}}"#;
+    let mut data = Vec::new();
+    for n in &[
+        4,
+        json.len() as u32 + 8,
+        json.len() as u32 + 4,
+        json.len() as u32,
+    ] {
+        data.extend_from_slice(&n.to_le_bytes());
+    }
+    data.extend_from_slice(json);
+    let err = Asar::new(Cursor::new(&data[..])).err().unwrap();
+    assert_eq!(ErrorKind::InvalidData, err.kind());
+}
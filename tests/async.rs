@@ -0,0 +1,159 @@
+#![cfg(feature = "async")]
+
+use futures_core::Stream;
+use futures_io::AsyncRead;
+use mini_fs::{AsyncEntries, AsyncFile, AsyncStoreExt, MiniFs, Ram};
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+
+// In-memory stores are always ready, so they can be polled without a runtime.
+fn poll_ready<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    match future
+        .as_mut()
+        .poll(&mut Context::from_waker(Waker::noop()))
+    {
+        Poll::Ready(out) => out,
+        Poll::Pending => panic!("future is not ready"),
+    }
+}
+
+fn read_ready(file: &mut AsyncFile) -> String {
+    let mut buf = [0; 64];
+    let mut cx = Context::from_waker(Waker::noop());
+    match Pin::new(file).poll_read(&mut cx, &mut buf) {
+        Poll::Ready(Ok(n)) => String::from_utf8(buf[..n].to_vec()).unwrap(),
+        _ => panic!("read is not ready"),
+    }
+}
+
+async fn collect(mut entries: AsyncEntries<'_>) -> Vec<String> {
+    let mut names = Vec::new();
+    while let Some(entry) = poll_fn(|cx| Pin::new(&mut entries).poll_next(cx)).await {
+        names.push(entry.unwrap().name.into_string().unwrap());
+    }
+    names.sort();
+    names
+}
+
+#[test]
+fn ram_async() {
+    let mut ram = Ram::new();
+    ram.touch("a.txt", &b"hello"[..]);
+
+    let mut file = poll_ready(ram.open("a.txt")).unwrap();
+    assert_eq!("hello", read_ready(&mut file));
+    assert!(poll_ready(ram.open("nope")).is_err());
+
+    let fs = MiniFs::new().mount("/ram", ram);
+    let mut file = poll_ready(fs.open("/ram/a.txt")).unwrap();
+    assert_eq!("hello", read_ready(&mut file));
+}
+
+#[test]
+fn ram_async_entries() {
+    let mut ram = Ram::new();
+    ram.touch("a.txt", &b"hello"[..]);
+    ram.touch("b/c.txt", &b"world"[..]);
+
+    let entries = poll_ready(ram.entries(".")).unwrap();
+    assert_eq!(vec!["a.txt", "b"], poll_ready(collect(entries)));
+
+    let fs = MiniFs::new().mount("/ram", ram);
+    let entries = poll_ready(fs.entries("/ram/b")).unwrap();
+    assert_eq!(vec!["c.txt"], poll_ready(collect(entries)));
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use mini_fs::{AsyncStoreExt, Local, MiniFs, Ram};
+    use tokio_::io::AsyncReadExt;
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio_::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn local_async() {
+        block_on(async {
+            let local = Local::new("./");
+
+            let mut content = String::new();
+            let mut file = local.open("Cargo.toml").await.unwrap();
+            file.read_to_string(&mut content).await.unwrap();
+            assert!(content.starts_with("[package]"));
+
+            assert!(local.open("nope").await.is_err());
+        });
+    }
+
+    #[test]
+    fn local_async_entries() {
+        block_on(async {
+            let local = Local::new("./");
+
+            let names = super::collect(local.entries("tests").await.unwrap()).await;
+            assert!(names.contains(&"tests/async.rs".to_string()));
+            assert_eq!(std::fs::read_dir("tests").unwrap().count(), names.len());
+
+            assert!(local.entries("nope").await.is_err());
+        });
+    }
+
+    #[test]
+    fn mini_fs_async() {
+        block_on(async {
+            let mut ram = Ram::new();
+            ram.touch("a.txt", &b"hello"[..]);
+
+            let fs = MiniFs::new()
+                .mount("/ram", ram)
+                .mount("/local", Local::new("./"));
+
+            let mut content = String::new();
+            let mut file = fs.open("/ram/a.txt").await.unwrap();
+            file.read_to_string(&mut content).await.unwrap();
+            assert_eq!("hello", content);
+
+            let mut content = String::new();
+            let mut file = fs.open("/local/Cargo.toml").await.unwrap();
+            file.read_to_string(&mut content).await.unwrap();
+            assert!(content.starts_with("[package]"));
+        });
+    }
+}
+
+// Native files are handed to tokio when both runtime features are enabled.
+#[cfg(all(feature = "async-std", not(feature = "tokio")))]
+mod async_std {
+    use async_std_::io::ReadExt;
+    use async_std_::task::block_on;
+    use mini_fs::{AsyncStoreExt, Local, MiniFs};
+
+    #[test]
+    fn mini_fs_async_std() {
+        block_on(async {
+            let fs = MiniFs::new().mount("/local", Local::new("./"));
+
+            let mut content = String::new();
+            let mut file = fs.open("/local/Cargo.toml").await.unwrap();
+            file.read_to_string(&mut content).await.unwrap();
+            assert!(content.starts_with("[package]"));
+
+            let local = Local::new("./");
+            let mut content = String::new();
+            let mut file = local.open("Cargo.toml").await.unwrap();
+            file.read_to_string(&mut content).await.unwrap();
+            assert!(content.starts_with("[package]"));
+
+            let names = super::collect(local.entries("tests").await.unwrap()).await;
+            assert!(names.contains(&"tests/async.rs".to_string()));
+            assert_eq!(std::fs::read_dir("tests").unwrap().count(), names.len());
+        });
+    }
+}
@@ -0,0 +1,39 @@
+use mini_fs::prelude::*;
+use mini_fs::{Local, MiniFs, Ram};
+use std::io::{BufRead, Result};
+
+#[test]
+fn local_lines() {
+    let fs = MiniFs::new().mount("/", Local::new("./"));
+
+    let file = fs.open("/Cargo.toml").unwrap();
+    let first = file.lines().next().unwrap().unwrap();
+    assert_eq!("[package]", first);
+}
+
+#[test]
+fn ram_lines() {
+    let mut ram = Ram::new();
+    ram.touch("script.txt", &b"first\nsecond\nthird"[..]);
+    let fs = MiniFs::new().mount("/ram", ram);
+
+    let lines = fs
+        .open("/ram/script.txt")
+        .unwrap()
+        .lines()
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(vec!["first", "second", "third"], lines);
+}
+
+#[test]
+#[cfg(feature = "tar")]
+fn tar_lines() {
+    use mini_fs::Tar;
+
+    let fs = MiniFs::new().mount("/tar", Tar::open("tests/archive.tar").unwrap());
+
+    let mut line = String::new();
+    fs.open("/tar/a.txt").unwrap().read_line(&mut line).unwrap();
+    assert_eq!("hello\n", line);
+}
@@ -0,0 +1,149 @@
+use std::cell::Cell;
+use std::io::{self, Write};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use mini_fs::prelude::*;
+use mini_fs::{Cache, Local, MiniFs, Ram, RamFile};
+
+mod common;
+
+// Ram store counting the files opened.
+struct Counting {
+    ram: Ram,
+    opens: Cell<usize>,
+}
+
+impl Counting {
+    fn new(files: &[(&str, &str)]) -> Self {
+        let mut ram = Ram::new();
+        for (path, data) in files {
+            ram.touch(*path, data.as_bytes().to_vec());
+        }
+        Self {
+            ram,
+            opens: Cell::new(0),
+        }
+    }
+}
+
+impl Store for Counting {
+    type File = RamFile;
+
+    fn open_path(&self, path: &Path) -> io::Result<RamFile> {
+        self.opens.set(self.opens.get() + 1);
+        self.ram.open_path(path)
+    }
+
+    fn create_path(&self, path: &Path) -> io::Result<RamFile> {
+        self.ram.create_path(path)
+    }
+
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        self.ram.remove_path(path)
+    }
+}
+
+#[test]
+fn cache_open() {
+    let cache = Cache::new(Counting::new(&[("a.txt", "hello")]), 1024);
+    assert_eq!("hello", cache.read_to_string("a.txt").unwrap());
+    assert_eq!("hello", cache.read_to_string("/a.txt").unwrap());
+    assert_eq!("hello", cache.read_to_string("./b/../a.txt").unwrap());
+    assert_eq!(1, cache.get_ref().opens.get());
+    assert_eq!(5, cache.size());
+
+    // cached files are shared
+    let file = cache.open("a.txt").unwrap();
+    assert_eq!(Some(&b"hello"[..]), file.as_slice());
+
+    assert!(cache.open("missing").is_err());
+    assert!(cache.open("missing").is_err());
+    assert_eq!(3, cache.get_ref().opens.get());
+
+    cache.clear();
+    assert_eq!(0, cache.size());
+    assert_eq!("hello", cache.read_to_string("a.txt").unwrap());
+    assert_eq!(4, cache.get_ref().opens.get());
+}
+
+#[test]
+fn cache_evict() {
+    let files = &[
+        ("a", "aaaa"),
+        ("b", "bbbb"),
+        ("c", "cccc"),
+        ("big", "0123456789"),
+    ];
+    let cache = Cache::new(Counting::new(files), 8);
+    cache.read_to_string("a").unwrap();
+    cache.read_to_string("b").unwrap();
+    // `a` is now the most recently used
+    cache.read_to_string("a").unwrap();
+    cache.read_to_string("c").unwrap();
+    assert_eq!(8, cache.size());
+    assert_eq!(3, cache.get_ref().opens.get());
+
+    // `b` was evicted
+    cache.read_to_string("a").unwrap();
+    cache.read_to_string("c").unwrap();
+    assert_eq!(3, cache.get_ref().opens.get());
+    cache.read_to_string("b").unwrap();
+    assert_eq!(4, cache.get_ref().opens.get());
+
+    // files over the budget aren't cached
+    assert_eq!("0123456789", cache.read_to_string("big").unwrap());
+    assert_eq!("0123456789", cache.read_to_string("big").unwrap());
+    assert_eq!(6, cache.get_ref().opens.get());
+    assert_eq!(8, cache.size());
+}
+
+#[test]
+fn cache_ttl() {
+    let cache =
+        Cache::new(Counting::new(&[("a.txt", "hello")]), 1024).ttl(Duration::from_millis(100));
+    cache.read_to_string("a.txt").unwrap();
+    cache.read_to_string("a.txt").unwrap();
+    assert_eq!(1, cache.get_ref().opens.get());
+
+    thread::sleep(Duration::from_millis(150));
+    cache.read_to_string("a.txt").unwrap();
+    cache.read_to_string("a.txt").unwrap();
+    assert_eq!(2, cache.get_ref().opens.get());
+    assert_eq!(5, cache.size());
+}
+
+#[test]
+fn cache_write() {
+    let cache = Cache::new(Counting::new(&[("a.txt", "hello")]), 1024);
+    assert_eq!("hello", cache.read_to_string("a.txt").unwrap());
+    cache.create("a.txt").unwrap().write_all(b"world").unwrap();
+    assert_eq!("world", cache.read_to_string("a.txt").unwrap());
+    cache.remove("a.txt").unwrap();
+    assert!(cache.open("a.txt").is_err());
+    assert_eq!(0, cache.size());
+}
+
+#[test]
+fn cache_mount() {
+    let cache = Cache::new(Counting::new(&[("a.txt", "hello")]), 1024);
+    let fs = MiniFs::new().mount("/assets", cache);
+    assert_eq!("hello", fs.read_to_string("/assets/a.txt").unwrap());
+    assert_eq!("hello", fs.read_to_string("/assets/a.txt").unwrap());
+}
+
+#[test]
+fn cache_rename_dir() {
+    let root = common::tmp_dir("cache_rename_dir");
+    std::fs::create_dir(root.join("maps")).unwrap();
+    std::fs::write(root.join("maps/a.map"), "a").unwrap();
+    let cache = Cache::new(Local::new(&root), 1024);
+    assert_eq!("a", cache.read_to_string("maps/a.map").unwrap());
+
+    // the files of renamed directories aren't served anymore
+    cache.rename("maps", "old").unwrap();
+    assert!(cache.open("maps/a.map").is_err());
+    assert_eq!("a", cache.read_to_string("old/a.map").unwrap());
+    assert_eq!(1, cache.size());
+}
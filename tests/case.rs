@@ -0,0 +1,53 @@
+use std::io::{ErrorKind, Write};
+
+use mini_fs::prelude::*;
+use mini_fs::{CaseInsensitive, Local, MiniFs};
+
+mod common;
+
+const FILES: &[(&str, &str)] = &[
+    ("Textures/Wall.PNG", "wall"),
+    ("Textures/wall.png", "lower"),
+    ("README.txt", "readme"),
+];
+
+#[test]
+fn case_open() {
+    let store = CaseInsensitive::new(common::ram(FILES));
+    assert_eq!("wall", store.read_to_string("textures/WALL.png").unwrap());
+    assert_eq!("readme", store.read_to_string("readme.TXT").unwrap());
+    // exact matches win
+    assert_eq!("wall", store.read_to_string("textures/Wall.PNG").unwrap());
+    assert_eq!("lower", store.read_to_string("TEXTURES/wall.png").unwrap());
+    assert!(store.is_dir("textures"));
+    assert_eq!(6, store.metadata("Readme.txt").unwrap().len);
+    assert_eq!(2, store.entries("tEXTURES").unwrap().count());
+    assert_eq!(
+        ErrorKind::NotFound,
+        store.open("textures/floor.png").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn case_write() {
+    let store = CaseInsensitive::new(common::ram(FILES));
+    store
+        .create("textures/Floor.png")
+        .unwrap()
+        .write_all(b"floor")
+        .unwrap();
+    assert!(store.get_ref().is_file("Textures/Floor.png"));
+    assert_eq!("floor", store.read_to_string("TEXTURES/FLOOR.PNG").unwrap());
+
+    store.rename("readme.txt", "docs/Readme.md").unwrap();
+    assert_eq!("readme", store.read_to_string("DOCS/README.MD").unwrap());
+    store.remove("docs/readme.md").unwrap();
+    assert!(!store.exists("docs/readme.md"));
+}
+
+#[test]
+fn case_local() {
+    let fs = MiniFs::new().mount("/", CaseInsensitive::new(Local::new("./tests/local")));
+    assert!(fs.is_file("/BAZ/FooBar"));
+    assert!(fs.is_file("/Bar"));
+}
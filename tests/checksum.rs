@@ -0,0 +1,84 @@
+#![cfg(feature = "checksums")]
+use mini_fs::prelude::*;
+use mini_fs::{Checksummed, IntegrityError, MiniFs, Ram};
+use sha2::{Digest, Sha256};
+use std::io::{BufRead, BufReader, ErrorKind, Read, Seek, SeekFrom};
+
+fn hash(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+fn store() -> Checksummed<Ram> {
+    let mut ram = Ram::new();
+    ram.touch("good.txt", &b"line 1\nline 2\n"[..]);
+    ram.touch("bad.txt", &b"corrupted"[..]);
+    ram.touch("extra.txt", &b"extra"[..]);
+    Checksummed::new(ram)
+        .checksum("good.txt", hash(b"line 1\nline 2\n"))
+        .checksum("bad.txt", hash(b"original"))
+}
+
+#[test]
+fn checksum_read() {
+    let files = MiniFs::new().mount("/content", store());
+    assert_eq!(
+        b"line 1\nline 2\n",
+        &files.read("/content/good.txt").unwrap()[..]
+    );
+
+    let err = files.read("/content/bad.txt").unwrap_err();
+    assert_eq!(ErrorKind::InvalidData, err.kind());
+    let integrity = IntegrityError::from_io(&err).unwrap();
+    assert_eq!(hash(b"original"), integrity.expected);
+    assert_eq!(hash(b"corrupted"), integrity.actual);
+
+    let err = files.open("/content/extra.txt").err().unwrap();
+    assert_eq!(ErrorKind::PermissionDenied, err.kind());
+    assert!(IntegrityError::from_io(&err).is_none());
+}
+
+#[test]
+fn checksum_streaming() {
+    let store = store();
+    let mut file = store.open("bad.txt").unwrap();
+    let mut buf = [0; 4];
+    file.read_exact(&mut buf).unwrap();
+    assert_eq!(b"corr", &buf);
+
+    file.seek(SeekFrom::Start(0)).unwrap();
+    let mut data = Vec::new();
+    assert!(file.read_to_end(&mut data).is_err());
+    assert_eq!(b"corrupted", &data[..]);
+
+    let lines: Vec<_> = BufReader::new(store.open("good.txt").unwrap())
+        .lines()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(vec!["line 1", "line 2"], lines);
+    let file = store.open("bad.txt").unwrap();
+    assert!(file.lines().any(|line| line.is_err()));
+}
+
+#[test]
+fn checksum_manifest() {
+    let manifest = format!(
+        "{}  good.txt\n",
+        hash(b"line 1\nline 2\n")
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>()
+    );
+    let store = Checksummed::with_manifest(store().into_inner(), manifest.as_bytes()).unwrap();
+    assert!(store.read("good.txt").is_ok());
+    assert!(store.open("bad.txt").is_err());
+
+    let err = Checksummed::with_manifest(Ram::new(), b"nope good.txt")
+        .err()
+        .unwrap();
+    assert_eq!(ErrorKind::InvalidData, err.kind());
+    let signed = format!("{}  good.txt\n", "+f".repeat(32));
+    let err = Checksummed::with_manifest(Ram::new(), signed.as_bytes())
+        .err()
+        .unwrap();
+    assert_eq!(ErrorKind::InvalidData, err.kind());
+}
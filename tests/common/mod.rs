@@ -0,0 +1,45 @@
+// Each test crate uses only some of the helpers.
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+
+use mini_fs::{EntryKind, Ram, Store, StoreExt};
+
+/// In-memory store holding `files`, given as paths and contents.
+pub fn ram(files: &[(&str, &str)]) -> Ram {
+    let mut ram = Ram::new();
+    for (path, data) in files {
+        ram.touch(path, data.as_bytes().to_vec());
+    }
+    ram
+}
+
+/// Sorted names of the entries of the directory at `path`.
+pub fn names<S: Store>(store: &S, path: &str) -> Vec<String> {
+    let mut names: Vec<_> = entries(store, path)
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    names.sort();
+    names
+}
+
+/// Names and kinds of the entries of the directory at `path`, sorted by name.
+pub fn entries<S: Store>(store: &S, path: &str) -> Vec<(String, EntryKind)> {
+    let mut entries: Vec<_> = store
+        .entries(path)
+        .unwrap()
+        .map(|e| e.unwrap())
+        .map(|e| (e.name.into_string().unwrap(), e.kind))
+        .collect();
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    entries
+}
+
+/// Empty directory for the files of a test, removed from previous runs.
+pub fn tmp_dir(name: &str) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = std::fs::remove_dir_all(&path);
+    std::fs::create_dir_all(&path).unwrap();
+    path
+}
@@ -0,0 +1,69 @@
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use mini_fs::prelude::*;
+use mini_fs::{copy, CopyOptions, MiniFs, Overwrite, Ram, Rewrite};
+
+fn src() -> Ram {
+    let mut ram = Ram::new();
+    ram.touch("a.txt", &b"new a"[..]);
+    ram.touch("dir/b.txt", &b"new b"[..]);
+    ram
+}
+
+#[test]
+fn copy_overwrite() {
+    let dst = Ram::new();
+    let copied = copy(&src(), &dst, &CopyOptions::new()).unwrap();
+    assert_eq!(
+        vec![PathBuf::from("a.txt"), PathBuf::from("dir/b.txt")],
+        copied
+    );
+    assert_eq!("new b", dst.read_to_string("dir/b.txt").unwrap());
+
+    let mut dst = Ram::new();
+    dst.touch("a.txt", &b"old"[..]);
+    let options = CopyOptions::new().overwrite(Overwrite::Never);
+    let copied = copy(&src(), &dst, &options).unwrap();
+    assert_eq!(vec![PathBuf::from("dir/b.txt")], copied);
+    assert_eq!("old", dst.read_to_string("a.txt").unwrap());
+}
+
+#[test]
+fn copy_if_newer() {
+    let mut dst = Ram::new();
+    dst.touch("a.txt", &b"old"[..]);
+    thread::sleep(Duration::from_millis(10));
+    let src = src();
+    thread::sleep(Duration::from_millis(10));
+    dst.touch("dir/b.txt", &b"newest"[..]);
+
+    let options = CopyOptions::new().overwrite(Overwrite::IfNewer);
+    let copied = copy(&src, &dst, &options).unwrap();
+    assert_eq!(vec![PathBuf::from("a.txt")], copied);
+    assert_eq!("new a", dst.read_to_string("a.txt").unwrap());
+    assert_eq!("newest", dst.read_to_string("dir/b.txt").unwrap());
+}
+
+#[test]
+fn copy_dry_run() {
+    let dst = Ram::new();
+    let options = CopyOptions::new().dry_run(true);
+    let copied = copy(&src(), &dst, &options).unwrap();
+    assert_eq!(2, copied.len());
+    assert!(!dst.exists("a.txt"));
+}
+
+#[test]
+fn copy_subtree() {
+    let fs = MiniFs::new().mount("/res", src());
+    let src = Rewrite::new(fs).add_prefix("/res/dir");
+    let dst = Rewrite::new(Ram::new()).add_prefix("backup");
+    let copied = copy(&src, &dst, &CopyOptions::new()).unwrap();
+    assert_eq!(vec![PathBuf::from("b.txt")], copied);
+    assert_eq!(
+        "new b",
+        dst.get_ref().read_to_string("backup/b.txt").unwrap()
+    );
+}
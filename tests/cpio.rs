@@ -0,0 +1,67 @@
+#![cfg(feature = "cpio")]
+
+use std::io::{Cursor, ErrorKind};
+use std::time::{Duration, UNIX_EPOCH};
+
+use mini_fs::prelude::*;
+use mini_fs::{Cpio, EntryKind};
+
+mod common;
+
+fn archive() -> Cpio<Cursor<&'static [u8]>> {
+    Cpio::new(Cursor::new(&include_bytes!("archive.cpio")[..])).unwrap()
+}
+
+#[test]
+fn cpio() {
+    let cpio = archive();
+    for _ in 0..4 {
+        assert_eq!("hello\n", cpio.read_to_string("a.txt").unwrap());
+        assert_eq!("world!\n", cpio.read_to_string("dir/b.txt").unwrap());
+    }
+    assert_eq!(ErrorKind::NotFound, cpio.open("nope").err().unwrap().kind());
+    // symlinks are skipped
+    assert_eq!(ErrorKind::NotFound, cpio.open("link").err().unwrap().kind());
+    assert!(Cpio::new(Cursor::new(&b"not an archive"[..])).is_err());
+}
+
+#[test]
+fn cpio_hard_link() {
+    let cpio = archive();
+    // The data is stored with the last link.
+    assert_eq!("hello\n", cpio.read_to_string("dir/c.txt").unwrap());
+    assert_eq!(6, cpio.metadata("dir/c.txt").unwrap().len);
+}
+
+#[test]
+fn cpio_concatenated() {
+    let mut data = include_bytes!("archive.cpio").to_vec();
+    data.extend_from_slice(include_bytes!("archive.cpio"));
+    let cpio = Cpio::new(Cursor::new(&data[..])).unwrap();
+    assert_eq!(vec!["a.txt", "dir", "empty"], common::names(&cpio, "."));
+    assert_eq!("hello\n", cpio.read_to_string("a.txt").unwrap());
+}
+
+#[test]
+fn cpio_entries() {
+    let cpio = archive();
+    assert_eq!(vec!["a.txt", "dir", "empty"], common::names(&cpio, "."));
+    assert_eq!(vec!["b.txt", "c.txt"], common::names(&cpio, "dir"));
+    assert!(common::names(&cpio, "empty").is_empty());
+}
+
+#[test]
+fn cpio_metadata() {
+    let cpio = archive();
+
+    let meta = cpio.metadata("a.txt").unwrap();
+    assert_eq!(EntryKind::File, meta.kind);
+    assert_eq!(6, meta.len);
+    assert_eq!(
+        Some(UNIX_EPOCH + Duration::from_secs(1_556_377_860)),
+        meta.modified
+    );
+
+    assert_eq!(EntryKind::Dir, cpio.metadata("empty").unwrap().kind);
+    assert!(cpio.metadata("nope").is_err());
+}
@@ -0,0 +1,36 @@
+use mini_fs::prelude::*;
+use mini_fs::Delay;
+use std::io::ErrorKind;
+use std::time::{Duration, Instant};
+
+mod common;
+
+const FILES: &[(&str, &str)] = &[("a.txt", "a")];
+
+#[test]
+fn delay_fixed() {
+    let store = Delay::new(common::ram(FILES), Duration::from_millis(30));
+    let start = Instant::now();
+    assert_eq!(b"a", &store.read("a.txt").unwrap()[..]);
+    let err = store.open("missing.txt").err().unwrap();
+    assert_eq!(ErrorKind::NotFound, err.kind());
+    assert!(start.elapsed() >= Duration::from_millis(60));
+
+    // only opens are delayed
+    let start = Instant::now();
+    store.metadata("a.txt").unwrap();
+    store.entries("").unwrap().count();
+    assert!(start.elapsed() < Duration::from_millis(30));
+}
+
+#[test]
+fn delay_jitter() {
+    let store = Delay::new(common::ram(FILES), Duration::from_millis(5))
+        .jitter(Duration::from_millis(20), 7);
+    let start = Instant::now();
+    for _ in 0..5 {
+        store.open("a.txt").unwrap();
+    }
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(25));
+}
@@ -0,0 +1,41 @@
+use std::path::PathBuf;
+
+use mini_fs::{diff, Diff, MiniFs, Ram};
+
+#[test]
+fn diff_stores() {
+    let mut old = Ram::new();
+    old.touch("same.txt", &b"same"[..]);
+    old.touch("removed.txt", &b"removed"[..]);
+    old.touch("dir/len.txt", &b"short"[..]);
+    old.touch("dir/content.txt", &b"aaaa"[..]);
+    let mut new = Ram::new();
+    new.touch("same.txt", &b"same"[..]);
+    new.touch("added.txt", &b"added"[..]);
+    new.touch("dir/len.txt", &b"longer"[..]);
+    new.touch("dir/content.txt", &b"bbbb"[..]);
+
+    assert_eq!(
+        Diff {
+            added: vec![PathBuf::from("added.txt")],
+            removed: vec![PathBuf::from("removed.txt")],
+            changed: vec![
+                PathBuf::from("dir/content.txt"),
+                PathBuf::from("dir/len.txt")
+            ],
+        },
+        diff(&old, &new).unwrap()
+    );
+    assert!(diff(&old, &old.clone()).unwrap().is_empty());
+}
+
+#[test]
+fn diff_mounts() {
+    let mut a = Ram::new();
+    a.touch("a.txt", &b"a"[..]);
+    let old = MiniFs::new().mount("/res", a.clone());
+    let new = MiniFs::new()
+        .mount("/res", a)
+        .mount("/res/more", Ram::new());
+    assert!(diff(&old, &new).unwrap().is_empty());
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "encryption")]
+use mini_fs::prelude::*;
+use mini_fs::{Encrypted, MiniFs, Ram};
+use std::io::{ErrorKind, Write};
+
+const KEY: [u8; 32] = [7; 32];
+
+#[test]
+fn encrypted_roundtrip() {
+    let files = MiniFs::new().mount("/assets", Encrypted::new(Ram::new(), &KEY));
+    {
+        let mut file = files.create("/assets/a.dat").unwrap();
+        file.write_all(b"licensed").unwrap();
+        file.flush().unwrap();
+        file.write_all(b" content").unwrap();
+    }
+    files.create("/assets/empty.dat").unwrap();
+
+    assert_eq!(
+        b"licensed content",
+        &files.read("/assets/a.dat").unwrap()[..]
+    );
+    assert_eq!(16, files.metadata("/assets/a.dat").unwrap().len);
+    assert!(files.read("/assets/empty.dat").unwrap().is_empty());
+}
+
+#[test]
+fn encrypted_at_rest() {
+    let store = Encrypted::new(Ram::new(), &KEY);
+    store
+        .create("a.dat")
+        .unwrap()
+        .write_all(b"licensed content")
+        .unwrap();
+    let ram = store.into_inner();
+    let raw = ram.read("a.dat").unwrap();
+    assert!(raw.starts_with(b"MFSE"));
+    assert!(!raw.windows(8).any(|w| w == b"licensed"));
+
+    let store = Encrypted::new(ram, &[8; 32]);
+    let err = store.open("a.dat").err().unwrap();
+    assert_eq!(ErrorKind::InvalidData, err.kind());
+
+    let mut ram = store.into_inner();
+    ram.touch("plain.txt", &b"not encrypted at all"[..]);
+    let store = Encrypted::new(ram, &KEY);
+    let err = store.open("plain.txt").err().unwrap();
+    assert_eq!(ErrorKind::InvalidData, err.kind());
+    assert_eq!(b"licensed content", &store.read("a.dat").unwrap()[..]);
+}
@@ -1,7 +1,7 @@
 use mini_fs::prelude::*;
-use mini_fs::{EntryKind, Local, MiniFs};
+use mini_fs::{EntryKind, Local, MiniFs, Ram};
 use std::ffi::OsStr;
-use std::io::Result;
+use std::io::{Read, Result};
 
 #[test]
 fn mini_fs_entries() {
@@ -27,6 +27,81 @@ fn mini_fs_entries() {
     assert_eq!(OsStr::new("foo"), entries[2].name);
 }
 
+#[test]
+fn mini_fs_entries_mount_points() {
+    let mut ram = Ram::new();
+    ram.touch("a.txt", &b"a"[..]);
+
+    let files = MiniFs::new()
+        .mount("/files/local", Local::new("./tests/local"))
+        .mount("/files/ram", ram)
+        .mount("/other", Ram::new());
+
+    let mut entries = files
+        .entries("/files")
+        .unwrap()
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+
+    entries.sort_by_key(|e| e.name.clone());
+
+    assert_eq!(2, entries.len());
+    assert_eq!(OsStr::new("local"), entries[0].name);
+    assert_eq!(OsStr::new("ram"), entries[1].name);
+    assert_eq!(EntryKind::Dir, entries[0].kind);
+    assert_eq!(EntryKind::Dir, entries[1].kind);
+
+    let entries = files
+        .entries("/files/ram")
+        .unwrap()
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+
+    assert_eq!(1, entries.len());
+    assert_eq!(OsStr::new("a.txt"), entries[0].name);
+}
+
+#[test]
+fn mini_fs_entries_shadowed() {
+    let mut a = Ram::new();
+    let mut b = Ram::new();
+    a.touch("a.txt", &b"a"[..]);
+    a.touch("b.txt", &b"b"[..]);
+    b.touch("a.txt", &b"a"[..]);
+
+    // mounting `b` on the same path shadows `a`, just like `open` does.
+    let files = MiniFs::new().mount("/files", a).mount("/files", b);
+    let entries = files
+        .entries("/files")
+        .unwrap()
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+
+    assert_eq!(1, entries.len());
+    assert_eq!(OsStr::new("a.txt"), entries[0].name);
+}
+
+#[test]
+fn ram_entries() {
+    let mut ram = Ram::new();
+    ram.touch("a.txt", &b"a"[..]);
+    ram.touch("dir/b.txt", &b"b"[..]);
+
+    let mut entries = ram
+        .entries(".")
+        .unwrap()
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+
+    entries.sort_by_key(|e| e.name.clone());
+
+    assert_eq!(2, entries.len());
+    assert_eq!(OsStr::new("a.txt"), entries[0].name);
+    assert_eq!(OsStr::new("dir"), entries[1].name);
+    assert_eq!(EntryKind::File, entries[0].kind);
+    assert_eq!(EntryKind::Dir, entries[1].kind);
+}
+
 #[test]
 fn tuple_no_repeats() {
     let a = Local::new("./tests/local");
@@ -44,7 +119,6 @@ fn tuple_no_repeats() {
 
 #[test]
 fn local_trait_object_entries() {
-    use mini_fs::prelude::*;
     use mini_fs::{Local, Store};
     use std::path::Path;
 
@@ -99,3 +173,31 @@ fn local_entries() {
         entries.next().unwrap().map(|e| e.name).unwrap()
     );
 }
+
+#[test]
+fn mini_fs_longest_prefix() {
+    let mut res = Ram::new();
+    let mut root = Ram::new();
+    res.touch("a.txt", &b"res"[..]);
+    root.touch("res/a.txt", &b"root"[..]);
+    root.touch("b.txt", &b"b"[..]);
+
+    // `/` is mounted last, but `/res` is the longest mount point of its files.
+    let files = MiniFs::new().mount("/res", res).mount("/", root);
+    let mut contents = Vec::new();
+    files
+        .open("/res/a.txt")
+        .unwrap()
+        .read_to_end(&mut contents)
+        .unwrap();
+    assert_eq!(b"res", &contents[..]);
+    assert!(files.open("/b.txt").is_ok());
+
+    let mut names = files
+        .entries("/")
+        .unwrap()
+        .map(|ent| ent.unwrap().name)
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(vec![OsStr::new("b.txt"), OsStr::new("res")], names);
+}
@@ -0,0 +1,75 @@
+#![cfg(any(feature = "tar", feature = "zip"))]
+use std::io::{Cursor, Read};
+
+use mini_fs::prelude::*;
+use mini_fs::{export, MiniFs, Ram};
+
+fn fs() -> MiniFs {
+    let mut res = Ram::new();
+    res.touch("a.txt", &b"hello"[..]);
+    res.touch("nested/b.txt", &b"world"[..]);
+    let mut other = Ram::new();
+    other.touch("c.txt", &b"other"[..]);
+    MiniFs::new().mount("/res", res).mount("/other", other)
+}
+
+#[test]
+#[cfg(feature = "tar")]
+fn export_tar() {
+    use mini_fs::Tar;
+
+    let fs = fs();
+    let tar = export::to_tar(&fs, "/res", Cursor::new(Vec::new())).unwrap();
+    let tar = Tar::new(Cursor::new(tar.into_inner())).index().unwrap();
+    let mut content = String::new();
+    tar.open("nested/b.txt")
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    assert_eq!("world", content);
+    assert!(tar.is_file("a.txt"));
+    assert!(!tar.is_file("c.txt"));
+
+    let tar = export::to_tar(&fs, "/", Vec::new()).unwrap();
+    let tar = Tar::new(Cursor::new(tar)).index().unwrap();
+    assert!(tar.is_file("res/a.txt"));
+    assert!(tar.is_file("other/c.txt"));
+}
+
+#[test]
+#[cfg(feature = "zip")]
+fn export_zip() {
+    use mini_fs::export::ZipOptions;
+    use mini_fs::Zip;
+
+    let mut fs = fs();
+    let mut images = Ram::new();
+    images.touch("logo.PNG", &b"png data"[..]);
+    fs = fs.mount("/res/images", images);
+
+    let options = ZipOptions::new().stored(&[".png"]);
+    let zip = export::to_zip(&fs, "/res", Cursor::new(Vec::new()), &options).unwrap();
+    let data = zip.into_inner();
+
+    let mut archive = zip_::ZipArchive::new(Cursor::new(&data[..])).unwrap();
+    let method =
+        |archive: &mut zip_::ZipArchive<_>, name| archive.by_name(name).unwrap().compression();
+    assert_eq!(
+        zip_::CompressionMethod::Stored,
+        method(&mut archive, "images/logo.PNG")
+    );
+    assert_eq!(
+        zip_::CompressionMethod::Deflated,
+        method(&mut archive, "nested/b.txt")
+    );
+
+    let zip = Zip::new(Cursor::new(data)).index().unwrap();
+    let mut content = String::new();
+    zip.open("nested/b.txt")
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    assert_eq!("world", content);
+    assert!(zip.is_file("a.txt"));
+    assert!(!zip.is_file("c.txt"));
+}
@@ -0,0 +1,37 @@
+use std::io::ErrorKind;
+
+use mini_fs::prelude::*;
+use mini_fs::{Extensions, MiniFs};
+
+mod common;
+
+const FILES: &[(&str, &str)] = &[
+    ("tex/wall.png", "png"),
+    ("tex/wall.jpg", "jpg"),
+    ("tex/floor.jpg", "jpg"),
+    ("tex/sky.dds", "dds"),
+];
+
+#[test]
+fn extensions_open() {
+    let store = Extensions::new(common::ram(FILES), &[".ktx2", ".png", ".jpg"]);
+    assert_eq!("png", store.read_to_string("tex/wall").unwrap());
+    assert_eq!("png", store.read_to_string("tex/wall.dds").unwrap());
+    assert_eq!("jpg", store.read_to_string("tex/wall.jpg").unwrap());
+    assert_eq!("jpg", store.read_to_string("tex/floor").unwrap());
+    assert_eq!("dds", store.read_to_string("tex/sky.dds").unwrap());
+    assert_eq!(3, store.metadata("tex/wall").unwrap().len);
+    assert!(store.is_file("tex/floor.png"));
+    assert_eq!(
+        ErrorKind::NotFound,
+        store.open("tex/sky").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn extensions_mount() {
+    let store = Extensions::new(common::ram(FILES), vec!["png".to_string()]);
+    let fs = MiniFs::new().mount("/assets", store);
+    assert_eq!("png", fs.read_to_string("/assets/tex/wall").unwrap());
+    assert!(fs.open("/assets/tex/floor").is_err());
+}
@@ -0,0 +1,150 @@
+use std::cell::Cell;
+use std::io::{ErrorKind, Result, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use mini_fs::prelude::*;
+#[cfg(feature = "regex")]
+use mini_fs::Filtered;
+use mini_fs::{FilterFn, MiniFs};
+
+mod common;
+
+const FILES: &[(&str, &str)] = &[
+    ("main.lua", "print()"),
+    ("payload.exe", "MZ"),
+    ("lib/util.lua", ""),
+    ("debug/trace.lua", ""),
+];
+
+#[test]
+#[cfg(feature = "regex")]
+fn filtered_include() {
+    let store = Filtered::new(common::ram(FILES))
+        .include(r"\.lua$")
+        .unwrap();
+    assert!(store.open("main.lua").is_ok());
+    assert!(store.open("lib/util.lua").is_ok());
+    assert_eq!(
+        ErrorKind::NotFound,
+        store.open("payload.exe").err().unwrap().kind()
+    );
+    assert!(store.metadata("payload.exe").is_err());
+    assert!(store.is_dir("lib"));
+
+    let mut paths = store.walk("").collect::<Result<Vec<_>>>().unwrap();
+    paths.sort();
+    assert_eq!(
+        vec![
+            PathBuf::from("debug/trace.lua"),
+            PathBuf::from("lib/util.lua"),
+            PathBuf::from("main.lua"),
+        ],
+        paths
+    );
+}
+
+#[test]
+#[cfg(feature = "regex")]
+fn filtered_exclude() {
+    let store = Filtered::new(common::ram(FILES))
+        .include(r"\.lua$")
+        .unwrap()
+        .exclude(r"^debug/")
+        .unwrap();
+    assert!(store.open("debug/trace.lua").is_err());
+    assert!(store.entries("debug").is_err());
+    assert!(!store.is_dir("debug"));
+
+    let mut paths = store.walk("").collect::<Result<Vec<_>>>().unwrap();
+    paths.sort();
+    assert_eq!(
+        vec![PathBuf::from("lib/util.lua"), PathBuf::from("main.lua")],
+        paths
+    );
+
+    let err = Filtered::new(common::ram(FILES))
+        .exclude("(")
+        .err()
+        .unwrap();
+    assert_eq!(ErrorKind::InvalidInput, err.kind());
+}
+
+#[test]
+#[cfg(feature = "regex")]
+fn filtered_write() {
+    let store = Filtered::new(common::ram(FILES))
+        .include(r"\.lua$")
+        .unwrap();
+    store.create("new.lua").unwrap().write_all(b"").unwrap();
+    assert_eq!(
+        ErrorKind::PermissionDenied,
+        store.create("new.exe").err().unwrap().kind()
+    );
+    assert!(store.remove("payload.exe").is_err());
+    assert!(store.rename("main.lua", "main.exe").is_err());
+    store.rename("main.lua", "init.lua").unwrap();
+    assert!(store.get_ref().is_file("payload.exe"));
+}
+
+#[test]
+#[cfg(feature = "regex")]
+fn filtered_mount() {
+    let store = Filtered::new(common::ram(FILES))
+        .include(r"\.lua$")
+        .unwrap();
+    let fs = MiniFs::new().mount("/scripts", store);
+    assert!(fs.open("/scripts/main.lua").is_ok());
+    assert!(fs.open("/scripts/payload.exe").is_err());
+}
+
+#[test]
+fn filter_fn() {
+    let owned = Rc::new(Cell::new(false));
+    let dlc = Rc::clone(&owned);
+    let store = FilterFn::new(common::ram(FILES), move |path: &Path| {
+        !path.starts_with("debug") || dlc.get()
+    });
+    assert!(store.open("main.lua").is_ok());
+    assert_eq!(
+        ErrorKind::NotFound,
+        store
+            .open("/debug/../debug/trace.lua")
+            .err()
+            .unwrap()
+            .kind()
+    );
+    assert!(store.is_dir("debug"));
+    assert_eq!(0, store.entries("debug").unwrap().count());
+    assert_eq!(
+        ErrorKind::PermissionDenied,
+        store.create("debug/new.lua").err().unwrap().kind()
+    );
+
+    store.create("new.lua").unwrap().write_all(b"").unwrap();
+
+    owned.set(true);
+    assert!(store.open("debug/trace.lua").is_ok());
+    assert_eq!(1, store.entries("debug").unwrap().count());
+}
+
+#[test]
+fn filter_fn_mount() {
+    let store = FilterFn::new(common::ram(FILES), |path: &Path| {
+        path.extension().is_some_and(|ext| ext == "lua")
+    });
+    let fs = MiniFs::new().mount("/scripts", store);
+    assert!(fs.open("/scripts/lib/util.lua").is_ok());
+    assert!(fs.open("/scripts/payload.exe").is_err());
+
+    let mut paths = fs.walk("/scripts").collect::<Result<Vec<_>>>().unwrap();
+    paths.sort();
+    assert_eq!(
+        vec![
+            PathBuf::from("/scripts/debug/trace.lua"),
+            PathBuf::from("/scripts/lib/util.lua"),
+            PathBuf::from("/scripts/main.lua"),
+        ],
+        paths
+    );
+}
@@ -0,0 +1,40 @@
+use mini_fs::prelude::*;
+use mini_fs::Flaky;
+use std::io::ErrorKind;
+
+mod common;
+
+const FILES: &[(&str, &str)] = &[("a.txt", "a"), ("dir/b.txt", "b")];
+
+#[test]
+fn flaky_schedule() {
+    let store = Flaky::new(common::ram(FILES))
+        .fail_first(2, ErrorKind::Interrupted)
+        .fail_every(4, ErrorKind::TimedOut);
+    let kinds: Vec<_> = (0..8)
+        .map(|_| store.open("a.txt").err().map(|err| err.kind()))
+        .collect();
+    assert_eq!(
+        vec![
+            Some(ErrorKind::Interrupted),
+            Some(ErrorKind::Interrupted),
+            None,
+            Some(ErrorKind::TimedOut),
+            None,
+            None,
+            None,
+            Some(ErrorKind::TimedOut),
+        ],
+        kinds
+    );
+    assert_eq!(8, store.calls());
+}
+
+#[test]
+fn flaky_path() {
+    let store = Flaky::new(common::ram(FILES)).fail_path("/dir/./b.txt", ErrorKind::NotFound);
+    let err = store.open("dir/b.txt").err().unwrap();
+    assert_eq!(ErrorKind::NotFound, err.kind());
+    assert!(store.open("a.txt").is_ok());
+    assert!(store.metadata("dir/b.txt").is_ok());
+}
@@ -0,0 +1,51 @@
+#![cfg(any(feature = "json", feature = "toml", feature = "ron"))]
+use mini_fs::prelude::*;
+use mini_fs::{MiniFs, Ram};
+use std::collections::HashMap;
+use std::io::ErrorKind;
+
+fn files(name: &str, contents: &str) -> MiniFs {
+    let mut ram = Ram::new();
+    ram.touch(name, contents.as_bytes().to_vec());
+    MiniFs::new().mount("/config", ram)
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn read_json() {
+    let files = files("game.json", r#"{ "width": 800, "height": 600 }"#);
+    let config = files
+        .read_json::<HashMap<String, u32>, _>("/config/game.json")
+        .unwrap();
+    assert_eq!(800, config["width"]);
+
+    let err = files
+        .read_json::<Vec<u32>, _>("/config/game.json")
+        .unwrap_err();
+    assert_eq!(ErrorKind::InvalidData, err.kind());
+    assert!(err.to_string().starts_with("/config/game.json: "));
+}
+
+#[test]
+#[cfg(feature = "toml")]
+fn read_toml() {
+    let files = files("game.toml", "width = 800\nheight = 600\n");
+    let config: HashMap<String, u32> = files.read_toml("/config/game.toml").unwrap();
+    assert_eq!(600, config["height"]);
+
+    let err = files
+        .read_toml::<HashMap<String, String>, _>("/config/game.toml")
+        .unwrap_err();
+    assert_eq!(ErrorKind::InvalidData, err.kind());
+}
+
+#[test]
+#[cfg(feature = "ron")]
+fn read_ron() {
+    let files = files("game.ron", r#"{ "width": 800, "height": 600 }"#);
+    let config: HashMap<String, u32> = files.read_ron("/config/game.ron").unwrap();
+    assert_eq!(800, config["width"]);
+
+    let err = files.read_ron::<u32, _>("/config/missing.ron").unwrap_err();
+    assert_eq!(ErrorKind::NotFound, err.kind());
+}
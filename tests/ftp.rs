@@ -0,0 +1,214 @@
+#![cfg(feature = "ftp")]
+
+use std::io::{BufRead, BufReader, ErrorKind, Result, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, UNIX_EPOCH};
+
+use mini_fs::prelude::*;
+use mini_fs::{EntryKind, Ftp, MiniFs};
+
+mod common;
+
+const FILES: &[(&str, &str)] = &[("/pub/a.txt", "hello"), ("/pub/dir/b c.txt", "spaced")];
+
+fn dir_listing(path: &str) -> Option<&'static str> {
+    match path {
+        "/pub" => Some(concat!(
+            "drwxr-xr-x   2 ftp      ftp          4096 Jan 01 12:00 .\r\n",
+            "-rw-r--r--   1 ftp      ftp             5 Jan 01 12:00 a.txt\r\n",
+            "drwxr-xr-x   2 ftp      ftp          4096 Jan 01 12:00 dir\r\n",
+            "lrwxrwxrwx   1 ftp      ftp             5 Jan 01 12:00 link -> a.txt\r\n",
+        )),
+        "/pub/dir" => Some("01-01-20  12:00PM                    6 b c.txt\r\n"),
+        _ => None,
+    }
+}
+
+fn file(path: &str) -> Option<&'static str> {
+    FILES
+        .iter()
+        .find(|(p, _)| *p == path)
+        .map(|(_, data)| *data)
+}
+
+// Serves the files above to any number of sessions, counting them.
+fn serve() -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let sessions = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&sessions);
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            counter.fetch_add(1, Ordering::SeqCst);
+            let stream = stream.unwrap();
+            thread::spawn(move || session(stream));
+        }
+    });
+    (addr, sessions)
+}
+
+fn session(stream: TcpStream) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut out = stream;
+    let mut passive: Option<TcpListener> = None;
+    out.write_all(b"220-Welcome\r\n220 Ready\r\n").unwrap();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).unwrap_or(0) == 0 {
+            return;
+        }
+        let line = line.trim_end();
+        let (command, arg) = line.split_once(' ').unwrap_or((line, ""));
+        let reply = match command {
+            "USER" => "331 Password required".to_string(),
+            "PASS" if arg == "secret" => "230 Logged in".to_string(),
+            "PASS" => "530 Login incorrect".to_string(),
+            "TYPE" => "200 Type set".to_string(),
+            "PASV" => {
+                let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+                let port = listener.local_addr().unwrap().port();
+                passive = Some(listener);
+                // the host is private, so the client must use the one of the
+                // control connection
+                format!(
+                    "227 Entering Passive Mode (10,0,0,1,{},{})",
+                    port >> 8,
+                    port & 0xff
+                )
+            }
+            "CWD" if dir_listing(arg).is_some() => "250 Okay".to_string(),
+            "SIZE" if file(arg).is_some() => format!("213 {}", file(arg).unwrap().len()),
+            "MDTM" if file(arg).is_some() => "213 19941106084937".to_string(),
+            "LIST" | "RETR" => {
+                let data = match command {
+                    "LIST" => dir_listing(arg),
+                    _ => file(arg),
+                };
+                match (data, passive.take()) {
+                    (Some(data), Some(listener)) => {
+                        out.write_all(b"150 Opening data connection\r\n").unwrap();
+                        let (mut conn, _) = listener.accept().unwrap();
+                        conn.write_all(data.as_bytes()).unwrap();
+                        drop(conn);
+                        "226 Transfer complete".to_string()
+                    }
+                    _ => "550 No such file".to_string(),
+                }
+            }
+            _ => "550 No such file".to_string(),
+        };
+        out.write_all(format!("{}\r\n", reply).as_bytes()).unwrap();
+    }
+}
+
+fn ftp(addr: &str) -> Ftp {
+    Ftp::new(addr).login("user", "secret").root("/pub/")
+}
+
+#[test]
+fn ftp_read() {
+    let (addr, sessions) = serve();
+    let ftp = ftp(&addr);
+    assert_eq!("hello", ftp.read_to_string("a.txt").unwrap());
+    assert_eq!("spaced", ftp.read_to_string("/dir/b c.txt").unwrap());
+    assert_eq!(
+        ErrorKind::NotFound,
+        ftp.open("missing.txt").err().unwrap().kind()
+    );
+    // the control connection is reused
+    assert_eq!("hello", ftp.read_to_string("./a.txt").unwrap());
+    assert_eq!(1, sessions.load(Ordering::SeqCst));
+}
+
+#[test]
+fn ftp_entries() {
+    let (addr, _) = serve();
+    let ftp = ftp(&addr);
+    assert_eq!(
+        vec![
+            ("a.txt".to_string(), EntryKind::File),
+            ("dir".to_string(), EntryKind::Dir),
+            ("link".to_string(), EntryKind::File),
+        ],
+        common::entries(&ftp, "/"),
+    );
+    assert_eq!(
+        vec![("b c.txt".to_string(), EntryKind::File)],
+        common::entries(&ftp, "dir"),
+    );
+    assert_eq!(
+        ErrorKind::NotFound,
+        ftp.entries("missing").err().unwrap().kind()
+    );
+
+    let mut paths = ftp.walk("/dir").collect::<Result<Vec<_>>>().unwrap();
+    paths.sort();
+    assert_eq!(vec![PathBuf::from("/dir/b c.txt")], paths);
+}
+
+#[test]
+fn ftp_metadata() {
+    let (addr, _) = serve();
+    let ftp = ftp(&addr);
+    let meta = ftp.metadata("a.txt").unwrap();
+    assert_eq!(EntryKind::File, meta.kind);
+    assert_eq!(5, meta.len);
+    assert_eq!(
+        Some(UNIX_EPOCH + Duration::from_secs(784111777)),
+        meta.modified
+    );
+    assert_eq!(EntryKind::Dir, ftp.metadata("dir").unwrap().kind);
+    assert_eq!(EntryKind::Dir, ftp.metadata("/").unwrap().kind);
+    assert_eq!(
+        ErrorKind::NotFound,
+        ftp.metadata("missing").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn ftp_login() {
+    let (addr, _) = serve();
+    let ftp = Ftp::new(addr).login("user", "wrong");
+    assert_eq!(
+        ErrorKind::PermissionDenied,
+        ftp.open("pub/a.txt").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn ftp_mount() {
+    let (addr, _) = serve();
+    let fs = MiniFs::new().mount("/assets", ftp(&addr));
+    assert_eq!("spaced", fs.read_to_string("/assets/dir/b c.txt").unwrap());
+    assert!(fs.is_dir("/assets/dir"));
+}
+
+#[test]
+fn ftp_invalid_pasv() {
+    for reply in &["227 ) (", "227 (10,0,0,1,256,80)"] {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut out = stream;
+            out.write_all(b"220 Ready\r\n").unwrap();
+            for reply in &[
+                "331 Password required",
+                "230 Logged in",
+                "200 Type set",
+                reply,
+            ] {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                out.write_all(format!("{}\r\n", reply).as_bytes()).unwrap();
+            }
+        });
+        let err = ftp(&addr).open("a.txt").err().unwrap();
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+    }
+}
@@ -0,0 +1,170 @@
+#![cfg(feature = "git")]
+
+use std::io::{ErrorKind, Result};
+use std::path::PathBuf;
+use std::time::{Duration, UNIX_EPOCH};
+
+use gix::actor::SignatureRef;
+use gix::objs::{tree, Kind, Tree};
+use gix::refs::transaction::PreviousValue;
+use gix::{ObjectId, Repository};
+use mini_fs::prelude::*;
+use mini_fs::{EntryKind, GitStore, Local, MiniFs};
+
+mod common;
+
+fn signature(time: &str) -> SignatureRef<'_> {
+    SignatureRef {
+        name: "mini-fs".into(),
+        email: "mini-fs@example.com".into(),
+        time,
+    }
+}
+
+// Writes a tree with the files, which are blobs or trees.
+fn tree(repo: &Repository, files: &[(&str, ObjectId)]) -> ObjectId {
+    let mut entries: Vec<_> = files
+        .iter()
+        .map(|&(name, oid)| {
+            let kind = repo.find_header(oid).unwrap().kind();
+            tree::Entry {
+                mode: match kind {
+                    Kind::Tree => tree::EntryKind::Tree.into(),
+                    _ => tree::EntryKind::Blob.into(),
+                },
+                filename: name.into(),
+                oid,
+            }
+        })
+        .collect();
+    entries.sort();
+    repo.write_object(Tree { entries }).unwrap().detach()
+}
+
+fn blob(repo: &Repository, data: &str) -> ObjectId {
+    repo.write_blob(data).unwrap().detach()
+}
+
+// Repository with a `v1.2.0` tag and a newer commit on top.
+fn repo(name: &str) -> PathBuf {
+    let path = common::tmp_dir(name);
+    let repo = gix::init_bare(&path).unwrap();
+
+    let certs = tree(&repo, &[("ca.pem", blob(&repo, "ca"))]);
+    let files = [("server.toml", blob(&repo, "port = 80")), ("certs", certs)];
+    let first = tree(&repo, &files);
+    let sig = signature("784111777 +0000");
+    let first = repo
+        .commit_as(sig, sig, "HEAD", "first", first, None::<ObjectId>)
+        .unwrap();
+    repo.tag(
+        "v1.2.0",
+        first,
+        Kind::Commit,
+        Some(sig),
+        "release",
+        PreviousValue::MustNotExist,
+    )
+    .unwrap();
+
+    let files = [
+        ("server.toml", blob(&repo, "port = 8080")),
+        ("certs", certs),
+        ("new.txt", blob(&repo, "new")),
+    ];
+    let second = tree(&repo, &files);
+    let sig = signature("784111778 +0000");
+    repo.commit_as(sig, sig, "HEAD", "second", second, Some(first))
+        .unwrap();
+    path
+}
+
+#[test]
+fn git_revisions() {
+    let path = repo("git-revisions");
+    let tag = GitStore::open(&path, "v1.2.0").unwrap();
+    let head = GitStore::open(&path, "HEAD").unwrap();
+    let parent = GitStore::open(&path, "HEAD~1").unwrap();
+    assert_eq!("port = 80", tag.read_to_string("/server.toml").unwrap());
+    assert_eq!("port = 80", parent.read_to_string("server.toml").unwrap());
+    assert_eq!("port = 8080", head.read_to_string("server.toml").unwrap());
+    assert_eq!("new", head.read_to_string("./new.txt").unwrap());
+    assert_eq!(
+        ErrorKind::NotFound,
+        tag.open("new.txt").err().unwrap().kind()
+    );
+    assert_eq!(ErrorKind::NotFound, tag.open("certs").err().unwrap().kind());
+    assert_eq!(
+        ErrorKind::NotFound,
+        GitStore::open(&path, "v2.0.0").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn git_entries() {
+    let path = repo("git-entries");
+    let git = GitStore::open(&path, "v1.2.0").unwrap();
+    assert_eq!(
+        vec![
+            ("certs".to_string(), EntryKind::Dir),
+            ("server.toml".to_string(), EntryKind::File),
+        ],
+        common::entries(&git, "/"),
+    );
+    assert_eq!(
+        vec![("ca.pem".to_string(), EntryKind::File)],
+        common::entries(&git, "certs"),
+    );
+    assert_eq!(
+        ErrorKind::NotFound,
+        git.entries("server.toml").err().unwrap().kind()
+    );
+
+    let mut paths = git.walk("/").collect::<Result<Vec<_>>>().unwrap();
+    paths.sort();
+    assert_eq!(
+        vec![
+            PathBuf::from("/certs/ca.pem"),
+            PathBuf::from("/server.toml"),
+        ],
+        paths
+    );
+}
+
+#[test]
+fn git_metadata() {
+    let path = repo("git-metadata");
+    let git = GitStore::open(&path, "v1.2.0").unwrap();
+    let meta = git.metadata("server.toml").unwrap();
+    assert_eq!(EntryKind::File, meta.kind);
+    assert_eq!(9, meta.len);
+    assert_eq!(
+        Some(UNIX_EPOCH + Duration::from_secs(784111777)),
+        meta.modified
+    );
+    assert_eq!(EntryKind::Dir, git.metadata("certs").unwrap().kind);
+    assert_eq!(EntryKind::Dir, git.metadata("/").unwrap().kind);
+    assert_eq!(
+        ErrorKind::NotFound,
+        git.metadata("missing").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn git_overlay() {
+    let path = repo("git-overlay");
+    let work = common::tmp_dir("git-overlay-work");
+    std::fs::create_dir_all(&work).unwrap();
+    std::fs::write(work.join("server.toml"), "port = 8000").unwrap();
+
+    let release = GitStore::open(&path, "v1.2.0").unwrap();
+    let fs = MiniFs::new().mount("/config", (Local::new(&work), release));
+    assert_eq!(
+        "port = 8000",
+        fs.read_to_string("/config/server.toml").unwrap()
+    );
+    assert_eq!("ca", fs.read_to_string("/config/certs/ca.pem").unwrap());
+
+    let git = GitStore::new(gix::open(&path).unwrap(), "v1.2.0").unwrap();
+    assert_eq!("ca", git.read_to_string("certs/ca.pem").unwrap());
+}
@@ -0,0 +1,80 @@
+use mini_fs::prelude::*;
+use mini_fs::{Local, MiniFs, Ram};
+use std::io::{ErrorKind, Result};
+use std::path::PathBuf;
+
+fn glob<S: Store>(store: &S, pattern: &str) -> Vec<PathBuf> {
+    let mut paths = store
+        .glob(pattern)
+        .unwrap()
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn mini_fs_glob() {
+    let mut a = Ram::new();
+    let mut b = Ram::new();
+    a.touch("logo.png", &b"a"[..]);
+    a.touch("ui/button.png", &b"b"[..]);
+    a.touch("ui/button.svg", &b"c"[..]);
+    b.touch("grass/1.png", &b"d"[..]);
+    b.touch("water/2.png", &b"e"[..]);
+
+    let fs = MiniFs::new()
+        .mount("/res", a)
+        .mount("/res/tiles", b)
+        .mount("/res/local", Local::new("./tests/local"));
+
+    assert_eq!(
+        vec![
+            PathBuf::from("/res/logo.png"),
+            PathBuf::from("/res/tiles/grass/1.png"),
+            PathBuf::from("/res/tiles/water/2.png"),
+            PathBuf::from("/res/ui/button.png"),
+        ],
+        glob(&fs, "/res/**/*.png")
+    );
+    assert_eq!(
+        vec![PathBuf::from("/res/ui/button.svg")],
+        glob(&fs, "/res/*/*.svg")
+    );
+    assert_eq!(
+        vec![PathBuf::from("/res/local/bar")],
+        glob(&fs, "/res/local/**/[!f]*")
+    );
+    assert_eq!(
+        vec![PathBuf::from("/res/tiles/water/2.png")],
+        glob(&fs, "/res/tiles/[u-z]*/?.png")
+    );
+    assert!(glob(&fs, "/missing/**").is_empty());
+}
+
+#[test]
+fn glob_patterns() {
+    let mut ram = Ram::new();
+    ram.touch("a.txt", &b""[..]);
+    ram.touch("b.txt", &b""[..]);
+    ram.touch("dir/a.txt", &b""[..]);
+    ram.touch("dir/[x].txt", &b""[..]);
+
+    assert_eq!(
+        vec![PathBuf::from("a.txt"), PathBuf::from("b.txt")],
+        glob(&ram, "*.txt")
+    );
+    assert_eq!(
+        vec![PathBuf::from("a.txt"), PathBuf::from("dir/a.txt")],
+        glob(&ram, "**/a.txt")
+    );
+    assert_eq!(vec![PathBuf::from("dir/a.txt")], glob(&ram, "dir/a.txt"));
+    assert_eq!(
+        vec![PathBuf::from("dir/[x].txt")],
+        glob(&ram, "dir/[[]x[]].txt")
+    );
+    assert_eq!(
+        ErrorKind::InvalidInput,
+        ram.glob("dir/[a.txt").err().unwrap().kind()
+    );
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "grf")]
+
+use std::io::{Cursor, ErrorKind};
+
+use mini_fs::prelude::*;
+use mini_fs::{EntryKind, Grf, Ram};
+
+mod common;
+
+fn archive() -> Grf<Cursor<&'static [u8]>> {
+    Grf::new(Cursor::new(&include_bytes!("archive.grf")[..])).unwrap()
+}
+
+#[test]
+fn grf() {
+    let grf = archive();
+    for _ in 0..4 {
+        assert_eq!(b"hello\n", &grf.read("data/a.txt").unwrap()[..]);
+        // header encrypted
+        assert_eq!(b"world!\n", &grf.read("data/dir/b.txt").unwrap()[..]);
+    }
+    // fully encrypted
+    let big: Vec<u8> = (0..20_000u32).map(|i| (i * 7919 % 256) as u8).collect();
+    assert_eq!(big, grf.read("data/big.bin").unwrap());
+    assert_eq!(ErrorKind::NotFound, grf.open("nope").err().unwrap().kind());
+    assert!(Grf::new(Cursor::new(&b"not an archive"[..])).is_err());
+}
+
+#[test]
+fn grf_names() {
+    let grf = archive();
+    assert_eq!(b"korean\n", &grf.read("data/dir/한글.txt").unwrap()[..]);
+    assert_eq!(
+        vec!["a.txt", "big.bin", "dir", "empty"],
+        common::names(&grf, "data")
+    );
+    assert_eq!(vec!["b.txt", "한글.txt"], common::names(&grf, "data/dir"));
+}
+
+#[test]
+fn grf_patched() {
+    let mut patch = Ram::new();
+    patch.touch("data/a.txt", b"patched\n".to_vec());
+    let fs = (patch, archive());
+    assert_eq!(b"patched\n", &fs.read("data/a.txt").unwrap()[..]);
+    assert_eq!(b"world!\n", &fs.read("data/dir/b.txt").unwrap()[..]);
+}
+
+#[test]
+fn grf_metadata() {
+    let grf = archive();
+
+    let meta = grf.metadata("data/big.bin").unwrap();
+    assert_eq!(EntryKind::File, meta.kind);
+    assert_eq!(20_000, meta.len);
+    assert_eq!(None, meta.modified);
+
+    assert_eq!(EntryKind::Dir, grf.metadata("data/empty").unwrap().kind);
+    assert!(grf.metadata("nope").is_err());
+}
@@ -0,0 +1,39 @@
+#![cfg(feature = "gzip")]
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use mini_fs::prelude::*;
+use mini_fs::{Gzipped, MiniFs, Ram};
+use std::io::{ErrorKind, Write};
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[test]
+fn gzipped_open() {
+    let mut ram = Ram::new();
+    ram.touch("plain.txt", &b"plain"[..]);
+    ram.touch("packed.txt.gz", gzip(b"packed"));
+    ram.touch("both.txt", &b"plain"[..]);
+    ram.touch("both.txt.gz", gzip(b"packed"));
+    let files = MiniFs::new().mount("/data", Gzipped::new(ram));
+
+    assert_eq!(b"plain", &files.read("/data/plain.txt").unwrap()[..]);
+    assert_eq!(b"packed", &files.read("/data/packed.txt").unwrap()[..]);
+    assert_eq!(b"plain", &files.read("/data/both.txt").unwrap()[..]);
+    let packed = gzip(b"packed").len() as u64;
+    assert_eq!(packed, files.metadata("/data/packed.txt").unwrap().len);
+
+    let err = files.open("/data/missing.txt").err().unwrap();
+    assert_eq!(ErrorKind::NotFound, err.kind());
+}
+
+#[test]
+fn gzipped_invalid() {
+    let mut ram = Ram::new();
+    ram.touch("broken.txt.gz", &b"not gzip"[..]);
+    let files = Gzipped::new(ram);
+    assert!(files.open("broken.txt").is_err());
+}
@@ -0,0 +1,175 @@
+#![cfg(feature = "http")]
+
+use std::cell::RefCell;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+use std::rc::Rc;
+
+use mini_fs::prelude::*;
+use mini_fs::{EntryKind, Http, MiniFs, Ram};
+
+type Requests = Rc<RefCell<Vec<(String, Vec<(String, String)>)>>>;
+
+// Serves `/cdn/a.txt` and `/cdn/dir/b c.txt`, recording every request.
+fn cdn(requests: Requests) -> impl Fn(&str, &[(String, String)]) -> io::Result<Vec<u8>> {
+    move |url: &str, headers: &[(String, String)]| {
+        requests
+            .borrow_mut()
+            .push((url.to_string(), headers.to_vec()));
+        match url {
+            "https://example.com/cdn/a.txt" => Ok(b"remote a".to_vec()),
+            "https://example.com/cdn/dir/b%20c.txt" => Ok(b"remote b".to_vec()),
+            _ => Err(io::Error::from(ErrorKind::NotFound)),
+        }
+    }
+}
+
+#[test]
+fn http_url() {
+    let http = Http::with_client("https://example.com/cdn/", ());
+    assert_eq!(
+        "https://example.com/cdn/dir/b%20c.txt",
+        http.url(Path::new("/dir/./b c.txt")).unwrap()
+    );
+    assert_eq!(
+        "https://example.com/cdn/%C3%A1%3F%23.txt",
+        http.url(Path::new("á?#.txt")).unwrap()
+    );
+    assert_eq!(
+        "https://example.com/cdn/a.txt",
+        http.url(Path::new("../a.txt")).unwrap()
+    );
+}
+
+#[test]
+fn http_open() {
+    let requests = Requests::default();
+    let http = Http::with_client("https://example.com/cdn", cdn(requests.clone()))
+        .header("Authorization", "Bearer token");
+
+    assert_eq!("remote a", http.read_to_string("a.txt").unwrap());
+    assert_eq!("remote b", http.read_to_string("/dir/b c.txt").unwrap());
+    assert_eq!(
+        ErrorKind::NotFound,
+        http.open("missing.txt").err().unwrap().kind()
+    );
+
+    let requests = requests.borrow();
+    assert_eq!(3, requests.len());
+    assert_eq!("https://example.com/cdn/a.txt", requests[0].0);
+    assert_eq!(
+        vec![("Authorization".to_string(), "Bearer token".to_string())],
+        requests[0].1
+    );
+}
+
+#[test]
+fn http_metadata() {
+    let http = Http::with_client("https://example.com/cdn", cdn(Requests::default()));
+    let meta = http.metadata("a.txt").unwrap();
+    assert_eq!(EntryKind::File, meta.kind);
+    assert_eq!(8, meta.len);
+    assert_eq!(
+        ErrorKind::NotFound,
+        http.metadata("missing.txt").err().unwrap().kind()
+    );
+    assert_eq!(0, http.entries("/").unwrap().count());
+}
+
+#[test]
+fn http_overlay() {
+    let http = Http::with_client("https://example.com/cdn", cdn(Requests::default()));
+    let mut local = Ram::new();
+    local.touch("a.txt", &b"local a"[..]);
+    local.touch("c.txt", &b"local c"[..]);
+
+    let fs = MiniFs::new().mount("/assets", (http, local));
+    assert_eq!("remote a", fs.read_to_string("/assets/a.txt").unwrap());
+    assert_eq!(
+        "remote b",
+        fs.read_to_string("/assets/dir/b c.txt").unwrap()
+    );
+    assert_eq!("local c", fs.read_to_string("/assets/c.txt").unwrap());
+    assert_eq!(
+        ErrorKind::NotFound,
+        fs.open("/assets/d.txt").err().unwrap().kind()
+    );
+}
+
+#[cfg(feature = "ureq")]
+mod ureq {
+    use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    use mini_fs::prelude::*;
+    use mini_fs::Http;
+
+    // Serves `requests` connections, answering `/a.txt` and 404 otherwise.
+    fn serve(requests: usize) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            for stream in listener.incoming().take(requests) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = String::new();
+                reader.read_line(&mut request).unwrap();
+                let mut authorized = false;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    authorized |= line.trim() == "X-Token: secret";
+                }
+                let mut parts = request.split(' ');
+                let method = parts.next().unwrap();
+                let response = match parts.next().unwrap() {
+                    "/files/a.txt" if authorized => "HTTP/1.1 200 OK",
+                    "/files/a.txt" => "HTTP/1.1 403 Forbidden",
+                    _ => "HTTP/1.1 404 Not Found",
+                };
+                let body = if response.ends_with("OK") {
+                    "hello"
+                } else {
+                    ""
+                };
+                write!(
+                    stream,
+                    "{}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    response,
+                    body.len()
+                )
+                .unwrap();
+                if method == "GET" {
+                    stream.write_all(body.as_bytes()).unwrap();
+                }
+            }
+        });
+        format!("http://{}/files", addr)
+    }
+
+    #[test]
+    fn ureq_client() {
+        let http = Http::new(serve(3)).header("X-Token", "secret");
+        let mut content = String::new();
+        http.open("a.txt")
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!("hello", content);
+        assert_eq!(5, http.metadata("a.txt").unwrap().len);
+        assert_eq!(
+            ErrorKind::NotFound,
+            http.open("missing.txt").err().unwrap().kind()
+        );
+
+        let http = Http::new(serve(1));
+        assert_eq!(
+            ErrorKind::PermissionDenied,
+            http.open("a.txt").err().unwrap().kind()
+        );
+    }
+}
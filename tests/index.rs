@@ -87,6 +87,23 @@ fn index_entries() {
     assert_eq!(2, index.entries("a/b/").collect::<Vec<_>>().len());
 }
 
+#[test]
+fn index_remove() {
+    let mut index = Index::new();
+
+    index.insert("a/b/c.txt", 1);
+    index.insert("a/d.txt", 2);
+
+    assert_eq!(None, index.remove("a/b"));
+    assert_eq!(Some(1), index.remove("a/b/c.txt"));
+    assert_eq!(None, index.get("a/b/c.txt"));
+
+    // `a/b` is left empty, so it goes away too.
+    assert_eq!(1, index.entries("a").collect::<Vec<_>>().len());
+    assert_eq!(Some(2), index.remove("./a/d.txt"));
+    assert_eq!(0, index.entries(".").collect::<Vec<_>>().len());
+}
+
 #[test]
 fn test_normal_path() {
     assert_eq!(
@@ -103,3 +120,26 @@ fn test_normal_path() {
         normalize_path(Path::new("/a/b/c/.././../../"))
     );
 }
+
+#[test]
+fn index_case_insensitive() {
+    let mut index = Index::new().case_insensitive();
+    index.insert("Textures/Wall.PNG", 1);
+    index.insert("Textures/wall.png", 2);
+    index.insert_dir("Textures/Empty");
+
+    // exact matches win, then the first inserted entry
+    assert_eq!(Some(&1), index.get("textures/WALL.png"));
+    assert_eq!(Some(&1), index.get("TEXTURES/Wall.PNG"));
+    assert_eq!(Some(&2), index.get("textures/wall.png"));
+    assert!(index.contains_dir("textures/empty"));
+    let names: Vec<_> = index.entries("textures").map(|e| e.name).collect();
+    assert_eq!(vec!["Wall.PNG", "wall.png", "Empty"], names);
+
+    assert_eq!(Some(1), index.remove("textures/WALL.PNG"));
+    assert_eq!(Some(&2), index.get("textures/WALL.png"));
+
+    let mut index = Index::new();
+    index.insert("Textures/Wall.PNG", 1);
+    assert_eq!(None, index.get("textures/wall.png"));
+}
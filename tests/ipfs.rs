@@ -0,0 +1,56 @@
+#![cfg(feature = "ipfs")]
+
+use std::io::{self, ErrorKind, Read};
+use std::path::Path;
+
+use mini_fs::prelude::*;
+use mini_fs::{Ipfs, MiniFs, Ram};
+
+const CID: &str = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+
+fn gateway(url: &str, _headers: &[(String, String)]) -> io::Result<Vec<u8>> {
+    match url.strip_prefix("https://gw.example.com/ipfs/") {
+        Some(path) if path == format!("{}/textures/a%20b.png", CID) => Ok(b"png".to_vec()),
+        _ => Err(io::Error::from(ErrorKind::NotFound)),
+    }
+}
+
+#[test]
+fn ipfs_url() {
+    let ipfs = Ipfs::with_client(CID, ());
+    assert_eq!(
+        format!("https://ipfs.io/ipfs/{}/a.txt", CID),
+        ipfs.url(Path::new("/a.txt")).unwrap()
+    );
+    let ipfs = ipfs.gateway("http://127.0.0.1:8080/");
+    assert_eq!(
+        format!("http://127.0.0.1:8080/ipfs/{}/dir/a.txt", CID),
+        ipfs.url(Path::new("dir/./a.txt")).unwrap()
+    );
+}
+
+#[test]
+fn ipfs_open() {
+    let ipfs = Ipfs::with_client(CID, gateway).gateway("https://gw.example.com");
+    let mut content = String::new();
+    ipfs.open("textures/a b.png")
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    assert_eq!("png", content);
+    assert_eq!(3, ipfs.metadata("textures/a b.png").unwrap().len);
+    assert_eq!(
+        ErrorKind::NotFound,
+        ipfs.open("missing.png").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn ipfs_overlay() {
+    let ipfs = Ipfs::with_client(CID, gateway).gateway("https://gw.example.com");
+    let mut local = Ram::new();
+    local.touch("textures/b.png", &b"local"[..]);
+    let fs = MiniFs::new().mount("/assets", (ipfs, local));
+    assert!(fs.is_file("/assets/textures/a b.png"));
+    assert!(fs.is_file("/assets/textures/b.png"));
+}
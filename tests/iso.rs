@@ -0,0 +1,62 @@
+#![cfg(feature = "iso")]
+
+use std::io::{Cursor, ErrorKind};
+use std::time::{Duration, UNIX_EPOCH};
+
+use mini_fs::prelude::*;
+use mini_fs::{EntryKind, Iso};
+
+mod common;
+
+#[test]
+fn iso_joliet() {
+    let iso = Iso::new(Cursor::new(&include_bytes!("archive.iso")[..])).unwrap();
+
+    assert_eq!("hello\n", iso.read_to_string("a.txt").unwrap());
+    assert_eq!(
+        "world!\n",
+        iso.read_to_string("directory/long file name.txt").unwrap()
+    );
+    assert_eq!(ErrorKind::NotFound, iso.open("A.TXT").err().unwrap().kind());
+
+    assert_eq!(vec!["a.txt", "directory"], common::names(&iso, "."));
+    assert_eq!(vec!["long file name.txt"], common::names(&iso, "directory"));
+}
+
+#[test]
+fn iso_rock_ridge() {
+    let iso = Iso::new(Cursor::new(&include_bytes!("archive-rr.iso")[..])).unwrap();
+
+    assert_eq!("hello\n", iso.read_to_string("a.txt").unwrap());
+    assert_eq!(
+        "world!\n",
+        iso.read_to_string("directory/long file name.txt").unwrap()
+    );
+
+    assert_eq!(vec!["a.txt", "directory"], common::names(&iso, "."));
+    assert_eq!(vec!["long file name.txt"], common::names(&iso, "directory"));
+}
+
+#[test]
+fn iso_metadata() {
+    let iso = Iso::new(Cursor::new(&include_bytes!("archive.iso")[..])).unwrap();
+
+    let meta = iso.metadata("directory/long file name.txt").unwrap();
+    assert_eq!(EntryKind::File, meta.kind);
+    assert_eq!(7, meta.len);
+    assert_eq!(
+        Some(UNIX_EPOCH + Duration::from_secs(1_556_377_860)),
+        meta.modified
+    );
+
+    assert_eq!(EntryKind::Dir, iso.metadata("directory").unwrap().kind);
+    assert!(iso.metadata("nope").is_err());
+}
+
+#[test]
+fn iso_invalid() {
+    let data = vec![0; 20 * 2048];
+    let err = Iso::new(Cursor::new(&data[..])).err().unwrap();
+    assert_eq!(ErrorKind::InvalidData, err.kind());
+    assert!(Iso::new(Cursor::new(&b"short"[..])).is_err());
+}
@@ -0,0 +1,179 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{self, ErrorKind, Result, Write};
+use std::path::PathBuf;
+
+use mini_fs::prelude::*;
+use mini_fs::{EntryKind, KvBackend, KvStore, MiniFs};
+
+mod common;
+
+#[derive(Default)]
+struct Map(RefCell<HashMap<String, Vec<u8>>>);
+
+impl KvBackend for Map {
+    fn get(&self, key: &str) -> io::Result<Option<Vec<u8>>> {
+        Ok(self.0.borrow().get(key).cloned())
+    }
+
+    fn put(&self, key: &str, value: &[u8]) -> io::Result<()> {
+        self.0.borrow_mut().insert(key.to_string(), value.to_vec());
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) -> io::Result<bool> {
+        Ok(self.0.borrow_mut().remove(key).is_some())
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> io::Result<Vec<String>> {
+        let map = self.0.borrow();
+        let keys = map.keys().filter(|key| key.starts_with(prefix));
+        Ok(keys.cloned().collect())
+    }
+}
+
+// Backend without write support.
+struct Fixed(&'static [(&'static str, &'static str)]);
+
+impl KvBackend for Fixed {
+    fn get(&self, key: &str) -> io::Result<Option<Vec<u8>>> {
+        let file = self.0.iter().find(|(path, _)| *path == key);
+        Ok(file.map(|(_, data)| data.as_bytes().to_vec()))
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> io::Result<Vec<String>> {
+        let keys = self.0.iter().map(|(path, _)| path.to_string());
+        Ok(keys.filter(|key| key.starts_with(prefix)).collect())
+    }
+}
+
+const FILES: &[(&str, &str)] = &[
+    ("a.txt", "hello"),
+    ("textures/wall.png", "png"),
+    ("textures/ui/button.png", "button"),
+    ("textures_old.txt", "old"),
+];
+
+#[test]
+fn kv_open() {
+    let kv = KvStore::new(Fixed(FILES));
+    assert_eq!("hello", kv.read_to_string("/a.txt").unwrap());
+    assert_eq!(
+        "button",
+        kv.read_to_string("./textures/ui/button.png").unwrap()
+    );
+    assert_eq!(
+        ErrorKind::NotFound,
+        kv.open("missing").err().unwrap().kind()
+    );
+    assert_eq!(
+        ErrorKind::NotFound,
+        kv.open("textures").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn kv_entries() {
+    let kv = KvStore::new(Fixed(FILES));
+    assert_eq!(
+        vec![
+            ("a.txt".to_string(), EntryKind::File),
+            ("textures".to_string(), EntryKind::Dir),
+            ("textures_old.txt".to_string(), EntryKind::File),
+        ],
+        common::entries(&kv, "/"),
+    );
+    assert_eq!(
+        vec![
+            ("ui".to_string(), EntryKind::Dir),
+            ("wall.png".to_string(), EntryKind::File),
+        ],
+        common::entries(&kv, "textures"),
+    );
+
+    let mut paths = kv.walk("/").collect::<Result<Vec<_>>>().unwrap();
+    paths.sort();
+    assert_eq!(
+        vec![
+            PathBuf::from("/a.txt"),
+            PathBuf::from("/textures/ui/button.png"),
+            PathBuf::from("/textures/wall.png"),
+            PathBuf::from("/textures_old.txt"),
+        ],
+        paths
+    );
+}
+
+#[test]
+fn kv_metadata() {
+    let kv = KvStore::new(Fixed(FILES));
+    let meta = kv.metadata("a.txt").unwrap();
+    assert_eq!(EntryKind::File, meta.kind);
+    assert_eq!(5, meta.len);
+    assert_eq!(None, meta.modified);
+    assert_eq!(EntryKind::Dir, kv.metadata("textures/ui").unwrap().kind);
+    assert_eq!(EntryKind::Dir, kv.metadata("/").unwrap().kind);
+    assert_eq!(
+        ErrorKind::NotFound,
+        kv.metadata("texture").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn kv_read_only() {
+    let kv = KvStore::new(Fixed(FILES));
+    assert_eq!(
+        ErrorKind::ReadOnlyFilesystem,
+        kv.create("b.txt").err().unwrap().kind()
+    );
+    assert_eq!(
+        ErrorKind::ReadOnlyFilesystem,
+        kv.remove("a.txt").err().unwrap().kind()
+    );
+    assert!(kv.open("a.txt").unwrap().write(b"x").is_err());
+}
+
+#[test]
+fn kv_write() {
+    let kv = KvStore::new(Map::default());
+    let mut file = kv.create("a/b.txt").unwrap();
+    assert!(kv.is_file("a/b.txt"));
+    file.write_all(b"hello").unwrap();
+    drop(file);
+    assert_eq!("hello", kv.read_to_string("a/b.txt").unwrap());
+    assert_eq!(
+        Some(&b"hello"[..]),
+        kv.backend().0.borrow().get("a/b.txt").map(|v| &v[..])
+    );
+
+    kv.rename("a/b.txt", "c.txt").unwrap();
+    assert!(!kv.is_dir("a"));
+    assert_eq!("hello", kv.read_to_string("c.txt").unwrap());
+    assert_eq!(
+        ErrorKind::NotFound,
+        kv.rename("a/b.txt", "d.txt").err().unwrap().kind()
+    );
+
+    kv.remove("c.txt").unwrap();
+    assert_eq!(
+        ErrorKind::NotFound,
+        kv.remove("c.txt").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn kv_mount() {
+    let fs = MiniFs::new().mount(
+        "/assets",
+        (KvStore::new(Map::default()), KvStore::new(Fixed(FILES))),
+    );
+    assert_eq!(
+        "png",
+        fs.read_to_string("/assets/textures/wall.png").unwrap()
+    );
+    fs.create("/assets/a.txt")
+        .unwrap()
+        .write_all(b"new")
+        .unwrap();
+    assert_eq!("new", fs.read_to_string("/assets/a.txt").unwrap());
+}
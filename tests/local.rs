@@ -0,0 +1,104 @@
+use mini_fs::prelude::*;
+use mini_fs::{Local, MiniFs, Ram};
+use std::io::ErrorKind;
+use std::path::Path;
+
+mod common;
+
+#[test]
+fn local_root() {
+    let local = Local::new("./tests/local/baz");
+    assert!(local.is_file("foobar"));
+    assert!(local.is_file("/foobar"));
+    assert!(local.is_file("../foobar"));
+    assert!(local.open_path(Path::new("/foobar")).is_ok());
+    assert!(local.open_path(Path::new("a/../foobar")).is_ok());
+}
+
+#[test]
+fn local_traversal() {
+    let local = Local::new("./tests/local/baz");
+    assert_eq!(
+        ErrorKind::PermissionDenied,
+        local.open_path(Path::new("../bar")).err().unwrap().kind()
+    );
+    assert_eq!(
+        ErrorKind::PermissionDenied,
+        local
+            .metadata_path(Path::new("a/../../bar"))
+            .err()
+            .unwrap()
+            .kind()
+    );
+    assert_eq!(
+        ErrorKind::PermissionDenied,
+        local.create_path(Path::new("../new")).err().unwrap().kind()
+    );
+
+    let fs = MiniFs::new().mount("/mods", Local::new("./tests/local/baz"));
+    assert!(fs.open_path(Path::new("/mods/../bar")).is_err());
+
+    let local = Local::new("./tests/local/baz").allow_traversal();
+    assert!(local.open_path(Path::new("../bar")).is_ok());
+}
+
+#[cfg(unix)]
+#[test]
+fn local_symlinks() {
+    use std::io::Write;
+    use std::os::unix::fs::symlink;
+
+    let dir = common::tmp_dir("local_symlinks");
+    let root = dir.join("root");
+    std::fs::create_dir_all(root.join("textures")).unwrap();
+    std::fs::write(dir.join("secret"), b"secret").unwrap();
+    std::fs::write(root.join("textures/wall.png"), b"png").unwrap();
+    symlink(dir.join("secret"), root.join("secret")).unwrap();
+    symlink(&dir, root.join("parent")).unwrap();
+    symlink("textures/wall.png", root.join("wall.png")).unwrap();
+    symlink(dir.join("missing"), root.join("dangling")).unwrap();
+
+    let local = Local::new(&root);
+    assert!(local.is_file("secret"));
+
+    let local = Local::new(&root).contain_symlinks();
+    assert!(local.is_file("wall.png"));
+    assert!(local.is_file("textures/wall.png"));
+    for path in &["secret", "parent/secret", "dangling"] {
+        assert_eq!(
+            ErrorKind::PermissionDenied,
+            local.open(path).err().unwrap().kind()
+        );
+    }
+    assert_eq!(
+        ErrorKind::PermissionDenied,
+        local.create("parent/new").err().unwrap().kind()
+    );
+    assert_eq!(
+        ErrorKind::PermissionDenied,
+        local.create("dangling").err().unwrap().kind()
+    );
+    assert!(!dir.join("missing").exists());
+
+    local
+        .create("textures/new/floor.png")
+        .unwrap()
+        .write_all(b"png")
+        .unwrap();
+    assert!(local.is_file("textures/new/floor.png"));
+}
+
+#[test]
+fn ram_from_dir() {
+    let ram = Ram::from_dir("./tests/local").unwrap();
+    assert!(ram.is_file("foo"));
+    assert!(ram.is_file("bar"));
+    assert!(ram.is_file("baz/foobar"));
+
+    let ram = Ram::from_dir_glob("./tests/local", "**/foo*").unwrap();
+    assert!(ram.is_file("foo"));
+    assert!(!ram.is_file("bar"));
+    assert!(ram.is_file("baz/foobar"));
+
+    assert!(Ram::from_dir("./tests/missing").is_err());
+}
@@ -0,0 +1,42 @@
+use std::io::ErrorKind;
+
+use mini_fs::prelude::*;
+use mini_fs::{Localized, MiniFs};
+
+mod common;
+
+const FILES: &[(&str, &str)] = &[
+    ("dialog/intro.txt", "hello"),
+    ("dialog/pt/intro.txt", "ola"),
+    ("dialog/pt-BR/outro.txt", "tchau"),
+    ("dialog/en/outro.txt", "bye"),
+    ("dialog/en/credits.txt", "credits"),
+    ("dialog/pt/credits.txt", "creditos"),
+];
+
+#[test]
+fn localized_open() {
+    let store = Localized::new(common::ram(FILES), &["pt-BR", "pt", "en"]);
+    assert_eq!("ola", store.read_to_string("dialog/intro.txt").unwrap());
+    assert_eq!("tchau", store.read_to_string("dialog/outro.txt").unwrap());
+    assert_eq!(
+        "creditos",
+        store.read_to_string("dialog/credits.txt").unwrap()
+    );
+    assert_eq!(5, store.metadata("dialog/outro.txt").unwrap().len);
+    assert_eq!(
+        ErrorKind::NotFound,
+        store.open("dialog/missing.txt").err().unwrap().kind()
+    );
+
+    let store = Localized::new(common::ram(FILES), &["fr"]);
+    assert_eq!("hello", store.read_to_string("dialog/intro.txt").unwrap());
+    assert!(store.open("dialog/outro.txt").is_err());
+}
+
+#[test]
+fn localized_mount() {
+    let fs = MiniFs::new().mount("/res", Localized::new(common::ram(FILES), vec!["en"]));
+    assert_eq!("bye", fs.read_to_string("/res/dialog/outro.txt").unwrap());
+    assert_eq!("hello", fs.read_to_string("/res/dialog/intro.txt").unwrap());
+}
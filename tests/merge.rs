@@ -1,11 +1,9 @@
-use std::io::{Cursor, Read};
-use std::path::Path;
-use std::rc::Rc;
+use std::io::Read;
 
-#[cfg(nope)]
 #[test]
 fn merge() {
-    use mini_fs::{MiniFs, Ram, Store};
+    use mini_fs::prelude::*;
+    use mini_fs::{MiniFs, Ram};
 
     let mut a = Ram::new();
     let mut b = Ram::new();
@@ -28,3 +26,270 @@ fn merge() {
 
     assert_eq!("overriden", atxt);
 }
+
+#[test]
+fn mount_priority() {
+    use mini_fs::prelude::*;
+    use mini_fs::{MiniFs, Ram};
+
+    fn ram(contents: &str) -> Ram {
+        let mut ram = Ram::new();
+        ram.touch("a.txt", contents.as_bytes().to_vec());
+        ram
+    }
+
+    fn read(fs: &MiniFs) -> String {
+        let mut contents = String::new();
+        let mut file = fs.open("/files/a.txt").unwrap();
+        file.read_to_string(&mut contents).unwrap();
+        contents
+    }
+
+    let fs = MiniFs::new()
+        .mount_with_priority("/files", ram("high"), 1)
+        .mount("/files", ram("default"));
+    assert_eq!("high", read(&fs));
+
+    let fs = fs.mount_before("/files", ram("before"));
+    assert_eq!("before", read(&fs));
+
+    let fs = MiniFs::new()
+        .mount("/files", ram("first"))
+        .mount_with_priority("/files", ram("low"), -1)
+        .mount_after("/files", ram("after"));
+    assert_eq!("first", read(&fs));
+
+    let mut fs = fs;
+    fs.umount("/files").unwrap();
+    assert_eq!("low", read(&fs));
+    fs.umount("/files").unwrap();
+    assert_eq!("after", read(&fs));
+}
+
+#[test]
+fn mounts() {
+    use mini_fs::{Local, MiniFs, Ram, ReadOnly};
+    use std::path::Path;
+
+    let fs = MiniFs::new()
+        .mount("/", Local::new("./tests/local"))
+        .mount("/res", ReadOnly::new(Ram::new()))
+        .mount("/res", (Ram::new(), ReadOnly::new(Ram::new())));
+    let mounts = fs.mounts().collect::<Vec<_>>();
+
+    assert_eq!(3, mounts.len());
+    assert_eq!((Path::new("/res"), false), (mounts[0].0, mounts[0].2));
+    assert_eq!((Path::new("/res"), true), (mounts[1].0, mounts[1].2));
+    assert_eq!((Path::new("/"), false), (mounts[2].0, mounts[2].2));
+    assert!(mounts[1].1.ends_with("ReadOnly<mini_fs::Ram>"));
+    assert_eq!("mini_fs::Local", mounts[2].1);
+}
+
+#[test]
+fn resolve() {
+    use mini_fs::{MiniFs, Ram, ReadOnly};
+    use std::io::ErrorKind;
+    use std::path::Path;
+
+    let mut a = Ram::new();
+    let mut b = Ram::new();
+    a.touch("a.txt", &b"a"[..]);
+    b.touch("a.txt", &b"b"[..]);
+    b.touch("b.txt", &b"b"[..]);
+
+    let fs = MiniFs::new().mount("/files", (a, ReadOnly::new(b)));
+
+    let resolved = fs.resolve("/files/a.txt").unwrap();
+    assert_eq!(Path::new("/files"), resolved.mount);
+    assert_eq!(Path::new("a.txt"), resolved.path);
+    assert_eq!("mini_fs::Ram", resolved.store);
+
+    let resolved = fs.resolve("/files/b.txt").unwrap();
+    assert!(resolved.store.ends_with("ReadOnly<mini_fs::Ram>"));
+
+    let err = fs.resolve("/files/c.txt").unwrap_err();
+    assert_eq!(ErrorKind::NotFound, err.kind());
+    assert!(fs.resolve("/files").is_err());
+}
+
+#[test]
+fn mount_mut() {
+    use mini_fs::prelude::*;
+    use mini_fs::{MiniFs, Ram};
+
+    struct Game {
+        files: MiniFs,
+    }
+
+    let mut a = Ram::new();
+    let mut b = Ram::new();
+    a.touch("a.txt", &b"a"[..]);
+    b.touch("b.txt", &b"b"[..]);
+
+    let mut game = Game {
+        files: MiniFs::new(),
+    };
+    game.files.mount_mut("/a", a).mount_mut("/b", b);
+    assert!(game.files.open("/a/a.txt").is_ok());
+    assert!(game.files.open("/b/b.txt").is_ok());
+
+    assert!(game.files.umount("/a").is_some());
+    assert!(game.files.open("/a/a.txt").is_err());
+    assert!(game.files.open("/b/b.txt").is_ok());
+}
+
+#[test]
+fn remount() {
+    use mini_fs::prelude::*;
+    use mini_fs::{MiniFs, Ram};
+
+    let mut old = Ram::new();
+    let mut new = Ram::new();
+    let mut top = Ram::new();
+    old.touch("a.txt", &b"old"[..]);
+    new.touch("a.txt", &b"new"[..]);
+    top.touch("b.txt", &b"top"[..]);
+
+    let mut fs = MiniFs::new()
+        .mount_with_priority("/files", old, -1)
+        .mount("/files", top);
+
+    let replaced = fs.remount("/files", Ram::new()).unwrap();
+    assert!(replaced.open_path("b.txt".as_ref()).is_ok());
+
+    fs.remount("/files", new).unwrap();
+    let mut contents = String::new();
+    let mut file = fs.open("/files/a.txt").unwrap();
+    file.read_to_string(&mut contents).unwrap();
+    assert_eq!("new", contents);
+    assert!(!fs.is_file("/files/b.txt"));
+    assert_eq!(2, fs.mounts().count());
+
+    assert!(fs.remount("/other", Ram::new()).is_none());
+    assert_eq!(3, fs.mounts().count());
+}
+
+#[test]
+fn shared_stores() {
+    use mini_fs::prelude::*;
+    use mini_fs::{Local, MiniFs, Ram, RamFile};
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    let mut ram = Ram::new();
+    ram.touch("a.txt", &b"a"[..]);
+    let ram = Rc::new(ram);
+    let local = Arc::new(Local::new("./tests/local"));
+    let boxed: Box<dyn Store<File = RamFile>> = Box::new(Ram::new());
+
+    let fs = MiniFs::new()
+        .mount("/a", Rc::clone(&ram))
+        .mount("/b", (boxed, Rc::clone(&ram)))
+        .mount("/c", Arc::clone(&local));
+    assert!(fs.open("/a/a.txt").is_ok());
+    assert!(fs.open("/b/a.txt").is_ok());
+    assert!(fs.open("/c/baz/foobar").is_ok());
+
+    let both = (&*ram, &*local);
+    assert!(both.is_file("a.txt"));
+    assert!(both.is_file("baz/foobar"));
+    assert_eq!(3, Rc::strong_count(&ram));
+}
+
+#[test]
+fn merge_vec() {
+    use mini_fs::prelude::*;
+    use mini_fs::{MiniFs, Ram};
+
+    let mods = (0..3)
+        .map(|i| {
+            let mut ram = Ram::new();
+            ram.touch("shared.txt", format!("mod{}", i).into_bytes());
+            ram.touch(format!("mod{}.txt", i), Vec::new());
+            ram
+        })
+        .collect::<Vec<_>>();
+
+    // The first store shadows the rest, and the file type is kept.
+    let mut contents = String::new();
+    let mut file: mini_fs::RamFile = mods.open("shared.txt").unwrap();
+    file.read_to_string(&mut contents).unwrap();
+    assert_eq!("mod0", contents);
+    assert!(mods.is_file("mod2.txt"));
+    assert_eq!(4, mods.entries("").unwrap().count());
+
+    let fs = MiniFs::new().mount("/mods", mods);
+    assert!(fs.open("/mods/mod1.txt").is_ok());
+}
+
+#[test]
+fn merge_array() {
+    use mini_fs::prelude::*;
+    use mini_fs::Ram;
+
+    let mut a = Ram::new();
+    let mut b = Ram::new();
+    a.touch("a.txt", &b"a"[..]);
+    b.touch("b.txt", &b"b"[..]);
+
+    let merged = [a, b];
+    assert!(merged.is_file("a.txt"));
+    assert!(merged.is_file("b.txt"));
+    assert!(!merged.is_read_only());
+    assert!(merged.create("c.txt").is_ok());
+    assert!(merged[0].is_file("c.txt"));
+}
+
+#[test]
+fn merge_tuple_12() {
+    use mini_fs::prelude::*;
+    use mini_fs::Ram;
+
+    let mut last = Ram::new();
+    last.touch("last.txt", &b"last"[..]);
+    let merged = (
+        Ram::new(),
+        Ram::new(),
+        Ram::new(),
+        Ram::new(),
+        Ram::new(),
+        Ram::new(),
+        Ram::new(),
+        Ram::new(),
+        Ram::new(),
+        Ram::new(),
+        Ram::new(),
+        last,
+    );
+    assert!(merged.is_file("last.txt"));
+}
+
+#[test]
+fn merge_dyn() {
+    use mini_fs::prelude::*;
+    use mini_fs::{Local, MergeDyn, Ram, ReadOnly};
+
+    let mut top = Ram::new();
+    top.touch("bar", &b"top"[..]);
+    let mut middle = Ram::new();
+    middle.touch("middle.txt", &b"middle"[..]);
+
+    let mut merged = MergeDyn::new();
+    assert!(merged.is_empty());
+    merged.push_back(ReadOnly::new(Local::new("./tests/local")));
+    merged.push_front(top);
+    merged.insert(1, middle);
+    assert_eq!(3, merged.len());
+
+    let mut contents = String::new();
+    let mut file = merged.open("bar").unwrap();
+    file.read_to_string(&mut contents).unwrap();
+    assert_eq!("top", contents);
+    assert!(merged.is_file("middle.txt"));
+    assert!(merged.is_file("baz/foobar"));
+
+    // Files are created in the first writable store.
+    assert!(merged.create("new.txt").is_ok());
+    let top = merged.remove_store(0);
+    assert!(top.open_path("new.txt".as_ref()).is_ok());
+}
@@ -0,0 +1,145 @@
+use mini_fs::prelude::*;
+use mini_fs::{EntryKind, Local, MiniFs, Ram};
+use std::io::ErrorKind;
+
+#[test]
+fn local_metadata() {
+    let local = Local::new("./tests/local");
+
+    let foo = local.metadata("foo").unwrap();
+    assert_eq!(EntryKind::File, foo.kind);
+    assert_eq!(0, foo.len);
+    assert!(foo.modified.is_some());
+
+    assert_eq!(EntryKind::Dir, local.metadata("baz").unwrap().kind);
+    assert_eq!(
+        ErrorKind::NotFound,
+        local.metadata("nope").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn ram_metadata() {
+    let mut ram = Ram::new();
+    ram.touch("a/b.txt", &b"hello"[..]);
+
+    let file = ram.metadata("a/b.txt").unwrap();
+    assert_eq!(EntryKind::File, file.kind);
+    assert_eq!(5, file.len);
+    assert!(file.modified.is_some());
+
+    assert_eq!(EntryKind::Dir, ram.metadata("a").unwrap().kind);
+    assert_eq!(EntryKind::Dir, ram.metadata(".").unwrap().kind);
+    assert!(ram.metadata("a/nope.txt").is_err());
+}
+
+#[test]
+#[cfg(feature = "tar")]
+fn tar_metadata() {
+    use mini_fs::Tar;
+    use std::io::Cursor;
+    use std::time::UNIX_EPOCH;
+
+    let file = include_bytes!("archive.tar.gz");
+    let tar = Tar::new(Cursor::new(&file[..]));
+    let indexed = Tar::new(Cursor::new(&file[..])).index().unwrap();
+
+    for tar in &[tar, indexed] {
+        let meta = tar.metadata("b.txt").unwrap();
+        assert_eq!(EntryKind::File, meta.kind);
+        assert_eq!(7, meta.len);
+        // 2019-04-26
+        let secs = meta
+            .modified
+            .unwrap()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        assert_eq!(18012, secs / 86_400);
+
+        assert!(tar.metadata("nope").is_err());
+    }
+}
+
+#[test]
+#[cfg(feature = "zip")]
+fn zip_metadata() {
+    use mini_fs::Zip;
+    use std::io::Cursor;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    let file = include_bytes!("archive2.zip");
+    let zip = Zip::new(Cursor::new(&file[..]));
+    let indexed = Zip::new(Cursor::new(&file[..])).index().unwrap();
+
+    for zip in &[zip, indexed] {
+        let meta = zip.metadata("nested/world.txt").unwrap();
+        assert_eq!(EntryKind::File, meta.kind);
+        assert_eq!(7, meta.len);
+        // 2019-04-27 15:11:00
+        assert_eq!(
+            UNIX_EPOCH + Duration::from_secs(1_556_377_860),
+            meta.modified.unwrap()
+        );
+
+        assert_eq!(EntryKind::Dir, zip.metadata("nested").unwrap().kind);
+        assert!(zip.metadata("nope").is_err());
+    }
+}
+
+#[test]
+fn mini_fs_metadata() {
+    let mut ram = Ram::new();
+    ram.touch("a.txt", &b"a"[..]);
+
+    let fs = MiniFs::new()
+        .mount("/files/local", Local::new("./tests/local"))
+        .mount("/files/ram", ram);
+
+    assert_eq!(EntryKind::Dir, fs.metadata("/files").unwrap().kind);
+    assert_eq!(EntryKind::Dir, fs.metadata("/files/ram").unwrap().kind);
+    assert_eq!(1, fs.metadata("/files/ram/a.txt").unwrap().len);
+    assert_eq!(
+        EntryKind::Dir,
+        fs.metadata("/files/local/baz").unwrap().kind
+    );
+    assert!(fs.metadata("/nope").is_err());
+}
+
+#[test]
+fn exists() {
+    let mut ram = Ram::new();
+    ram.touch("a/b.txt", &b"hello"[..]);
+
+    let fs = MiniFs::new()
+        .mount("/ram", ram)
+        .mount("/local", Local::new("./tests/local"));
+
+    assert!(fs.exists("/ram/a/b.txt"));
+    assert!(fs.is_file("/ram/a/b.txt"));
+    assert!(!fs.is_dir("/ram/a/b.txt"));
+
+    assert!(fs.exists("/ram/a"));
+    assert!(fs.is_dir("/ram/a"));
+    assert!(!fs.is_file("/ram/a"));
+
+    assert!(fs.is_dir("/local/baz"));
+    assert!(fs.is_file("/local/baz/foobar"));
+
+    assert!(!fs.exists("/ram/nope"));
+    assert!(!fs.exists("/nope"));
+}
+
+#[test]
+#[cfg(feature = "zip")]
+fn zip_exists() {
+    use mini_fs::Zip;
+    use std::io::Cursor;
+
+    let file = include_bytes!("archive2.zip");
+    let zip = Zip::new(Cursor::new(&file[..])).index().unwrap();
+
+    assert!(zip.is_file("nested/hello.txt"));
+    assert!(zip.is_dir("nested"));
+    assert!(!zip.exists("nested/nope.txt"));
+}
@@ -0,0 +1,56 @@
+use mini_fs::prelude::*;
+use mini_fs::{mime, MiniFs, Ram};
+use std::io::Read;
+
+fn files() -> MiniFs {
+    let mut ram = Ram::new();
+    ram.touch("logo.png", &b"\x89PNG\r\n\x1a\n...."[..]);
+    ram.touch("wrong.png", &b"\xff\xd8\xff\xe0...."[..]);
+    ram.touch("mod.jar", &b"PK\x03\x04...."[..]);
+    ram.touch("mod.bin", &b"PK\x03\x04...."[..]);
+    ram.touch("app.js", &b"console.log(1)"[..]);
+    ram.touch("README", "héllo".as_bytes().to_vec());
+    ram.touch("blob", &b"\x00\x01\x02"[..]);
+    MiniFs::new().mount("/res", ram)
+}
+
+fn guess(files: &MiniFs, path: &str) -> &'static str {
+    let mut file = files.open(path).unwrap();
+    mime::guess(path, &mut file).unwrap()
+}
+
+#[test]
+fn mime_guess() {
+    let files = files();
+    assert_eq!("image/png", guess(&files, "/res/logo.png"));
+    // Signatures win over extensions.
+    assert_eq!("image/jpeg", guess(&files, "/res/wrong.png"));
+    // Except for formats based on zip archives.
+    assert_eq!("application/java-archive", guess(&files, "/res/mod.jar"));
+    assert_eq!("application/zip", guess(&files, "/res/mod.bin"));
+    assert_eq!("text/javascript", guess(&files, "/res/app.js"));
+    assert_eq!("text/plain", guess(&files, "/res/README"));
+    assert_eq!("application/octet-stream", guess(&files, "/res/blob"));
+}
+
+#[test]
+fn mime_guess_rewinds() {
+    let files = files();
+    let mut file = files.open("/res/logo.png").unwrap();
+    let mut header = [0; 4];
+    file.read_exact(&mut header).unwrap();
+
+    assert_eq!(
+        "image/png",
+        mime::guess("/res/logo.png", &mut file).unwrap()
+    );
+    let mut rest = Vec::new();
+    file.read_to_end(&mut rest).unwrap();
+    assert_eq!(b"\r\n\x1a\n....", &rest[..]);
+}
+
+#[test]
+fn mime_from_extension() {
+    assert_eq!(Some("image/jpeg"), mime::from_extension("a/B.JPG"));
+    assert_eq!(None, mime::from_extension("Makefile"));
+}
@@ -0,0 +1,176 @@
+#![cfg(feature = "minipak")]
+
+use std::io::{Cursor, ErrorKind, Result};
+use std::path::PathBuf;
+
+use mini_fs::prelude::*;
+use mini_fs::{EntryKind, Local, MiniPak, MiniPakWriter, Ram};
+
+mod common;
+
+fn pak() -> MiniPak<Cursor<Vec<u8>>> {
+    let mut ram = Ram::new();
+    ram.touch("a.txt", &b"a"[..]);
+    ram.touch("dir/b.txt", &b"b"[..]);
+    ram.touch("dir/sub/c.txt", &b"c"[..]);
+    ram.touch("dir.txt", &b"not a directory"[..]);
+    ram.touch("big.txt", "mini-fs ".repeat(1000).into_bytes());
+
+    let mut writer = MiniPakWriter::new(Cursor::new(Vec::new())).unwrap();
+    writer.add_store(&ram, "./").unwrap();
+    writer.add("extra/d.txt", &b"d"[..]).unwrap();
+    MiniPak::new(writer.finish().unwrap()).unwrap()
+}
+
+#[test]
+fn minipak_read() {
+    let pak = pak();
+    assert_eq!("a", pak.read_to_string("a.txt").unwrap());
+    assert_eq!("b", pak.read_to_string("/dir/b.txt").unwrap());
+    assert_eq!("c", pak.read_to_string("dir/sub/../sub/c.txt").unwrap());
+    assert_eq!("d", pak.read_to_string("extra/d.txt").unwrap());
+    assert_eq!(
+        "mini-fs ".repeat(1000),
+        pak.read_to_string("big.txt").unwrap()
+    );
+    assert_eq!(ErrorKind::NotFound, pak.open("dir").err().unwrap().kind());
+    assert_eq!(
+        ErrorKind::NotFound,
+        pak.open("missing.txt").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn minipak_entries() {
+    let pak = pak();
+    assert_eq!(
+        vec![
+            ("a.txt".to_string(), EntryKind::File),
+            ("big.txt".to_string(), EntryKind::File),
+            ("dir".to_string(), EntryKind::Dir),
+            ("dir.txt".to_string(), EntryKind::File),
+            ("extra".to_string(), EntryKind::Dir),
+        ],
+        common::entries(&pak, "."),
+    );
+    assert_eq!(
+        vec![
+            ("b.txt".to_string(), EntryKind::File),
+            ("sub".to_string(), EntryKind::Dir),
+        ],
+        common::entries(&pak, "dir"),
+    );
+
+    let mut paths = pak.walk("/").collect::<Result<Vec<_>>>().unwrap();
+    paths.sort();
+    assert_eq!(
+        vec![
+            PathBuf::from("/a.txt"),
+            PathBuf::from("/big.txt"),
+            PathBuf::from("/dir/b.txt"),
+            PathBuf::from("/dir/sub/c.txt"),
+            PathBuf::from("/dir.txt"),
+            PathBuf::from("/extra/d.txt"),
+        ],
+        paths
+    );
+}
+
+#[test]
+fn minipak_metadata() {
+    let pak = pak();
+    let meta = pak.metadata("big.txt").unwrap();
+    assert_eq!(EntryKind::File, meta.kind);
+    assert_eq!(8000, meta.len);
+    assert_eq!(EntryKind::Dir, pak.metadata("dir/sub").unwrap().kind);
+    assert_eq!(EntryKind::Dir, pak.metadata("/").unwrap().kind);
+    assert_eq!(
+        ErrorKind::NotFound,
+        pak.metadata("di").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn minipak_compression() {
+    let mut writer = MiniPakWriter::new(Cursor::new(Vec::new())).unwrap();
+    writer
+        .add("big.txt", "mini-fs ".repeat(1000).as_bytes())
+        .unwrap();
+    writer.add("small.txt", &b"a"[..]).unwrap();
+    let data = writer.finish().unwrap().into_inner();
+    // the repeated text is deflated
+    assert!(data.len() < 8000);
+    let pak = MiniPak::new(Cursor::new(data)).unwrap();
+    assert_eq!(
+        "mini-fs ".repeat(1000),
+        pak.read_to_string("big.txt").unwrap()
+    );
+    assert_eq!("a", pak.read_to_string("small.txt").unwrap());
+}
+
+#[test]
+fn minipak_checksum() {
+    let mut writer = MiniPakWriter::new(Cursor::new(Vec::new())).unwrap();
+    writer.add("a.txt", &b"abc"[..]).unwrap();
+    let mut data = writer.finish().unwrap().into_inner();
+    // the data of the only file follows the header
+    data[24] = b'x';
+    let pak = MiniPak::new(Cursor::new(data)).unwrap();
+    assert_eq!(
+        ErrorKind::InvalidData,
+        pak.open("a.txt").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn minipak_writer_errors() {
+    let mut writer = MiniPakWriter::new(Cursor::new(Vec::new())).unwrap();
+    writer.add("a.txt", &b"a"[..]).unwrap();
+    assert_eq!(
+        ErrorKind::AlreadyExists,
+        writer.add("/a.txt", &b"a"[..]).err().unwrap().kind()
+    );
+    assert_eq!(
+        ErrorKind::InvalidInput,
+        writer.add("/", &b"a"[..]).err().unwrap().kind()
+    );
+}
+
+#[test]
+fn minipak_local() {
+    let mut writer = MiniPakWriter::new(Cursor::new(Vec::new())).unwrap();
+    writer.add_store(&Local::new("./tests"), "local").unwrap();
+    let pak = MiniPak::new(writer.finish().unwrap()).unwrap();
+    assert!(pak.is_file("bar"));
+    assert!(pak.is_file("baz/foobar"));
+    assert!(pak.is_file("foo"));
+    assert!(!pak.exists("local"));
+}
+
+#[test]
+fn minipak_invalid() {
+    let err = MiniPak::new(Cursor::new(&b"MINIPAK"[..])).err().unwrap();
+    assert_eq!(ErrorKind::InvalidData, err.kind());
+}
+
+#[test]
+fn minipak_invalid_len() {
+    // "a", deflated to an empty final block, claiming u64::MAX bytes
+    let mut file = b"MINIPAK\0".to_vec();
+    file.extend(&1u32.to_le_bytes());
+    file.extend(&1u32.to_le_bytes());
+    file.extend(&26u64.to_le_bytes());
+    file.extend(&[0x03, 0x00]);
+    file.extend(&1u16.to_le_bytes());
+    file.extend(b"a");
+    file.push(1);
+    file.extend(&0u32.to_le_bytes());
+    file.extend(&24u64.to_le_bytes());
+    file.extend(&2u64.to_le_bytes());
+    file.extend(&u64::MAX.to_le_bytes());
+
+    let pak = MiniPak::new(Cursor::new(file)).unwrap();
+    assert_eq!(u64::MAX, pak.metadata("a").unwrap().len);
+    let err = pak.open("a").err().unwrap();
+    assert_eq!(ErrorKind::InvalidData, err.kind());
+}
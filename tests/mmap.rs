@@ -0,0 +1,38 @@
+#![cfg(feature = "mmap")]
+
+use std::io::{Read, Seek, SeekFrom};
+
+use mini_fs::Mmap;
+
+#[test]
+#[cfg(all(feature = "tar", feature = "zip"))]
+fn mmap_archives() {
+    use mini_fs::prelude::*;
+    use mini_fs::{MiniFs, Tar, Zip};
+
+    let zip = Zip::mmap("tests/archive.zip").unwrap();
+    let tar = Tar::mmap("tests/archive.tar.gz").unwrap().index().unwrap();
+    assert_eq!("hello\n", zip.read_to_string("hello.txt").unwrap());
+    assert_eq!("world!\n", tar.read_to_string("b.txt").unwrap());
+
+    let fs = MiniFs::new().mount("/zip", zip).mount("/tar", tar);
+    assert_eq!("world!\n", fs.read_to_string("/zip/world.txt").unwrap());
+    assert_eq!(2, fs.entries("/tar").unwrap().count());
+}
+
+#[test]
+fn mmap_file() {
+    let mut map = Mmap::open("tests/archive.tar").unwrap();
+    assert_eq!(&include_bytes!("archive.tar")[..], map.as_ref());
+    assert_eq!(10240, map.len());
+
+    // header of the second file
+    let mut name = [0; 5];
+    map.seek(SeekFrom::Start(1024)).unwrap();
+    map.read_exact(&mut name).unwrap();
+    assert_eq!(b"b.txt", &name);
+
+    let empty = Mmap::open("tests/local/foo").unwrap();
+    assert!(empty.is_empty());
+    assert!(Mmap::open("tests/nope").is_err());
+}
@@ -0,0 +1,62 @@
+#![cfg(feature = "mpq")]
+
+use std::io::{Cursor, ErrorKind};
+
+use mini_fs::prelude::*;
+use mini_fs::{EntryKind, Mpq};
+
+mod common;
+
+fn archive() -> Mpq<Cursor<&'static [u8]>> {
+    Mpq::new(Cursor::new(&include_bytes!("archive.mpq")[..])).unwrap()
+}
+
+#[test]
+fn mpq() {
+    let mpq = archive();
+    for _ in 0..4 {
+        assert_eq!(b"hello\n", &mpq.read("a.txt").unwrap()[..]);
+        // compressed and encrypted
+        assert_eq!(b"world!\n", &mpq.read("dir/b.txt").unwrap()[..]);
+    }
+    // compressed in several sectors
+    let big: Vec<u8> = (0..150_000u32).map(|i| (i % 251) as u8).collect();
+    assert_eq!(big, mpq.read("dir/sub/big.bin").unwrap());
+    assert_eq!(ErrorKind::NotFound, mpq.open("nope").err().unwrap().kind());
+    assert!(Mpq::new(Cursor::new(&b"not an archive"[..])).is_err());
+}
+
+#[test]
+fn mpq_case_insensitive() {
+    let mpq = archive();
+    assert_eq!(b"world!\n", &mpq.read("DIR/B.TXT").unwrap()[..]);
+}
+
+#[test]
+fn mpq_appended() {
+    // archives can be appended to other files, at a 512 byte boundary
+    let mut data = vec![0; 1024];
+    data.extend_from_slice(include_bytes!("archive.mpq"));
+    let mpq = Mpq::new(Cursor::new(&data[..])).unwrap();
+    assert_eq!(b"world!\n", &mpq.read("dir/b.txt").unwrap()[..]);
+}
+
+#[test]
+fn mpq_entries() {
+    let mpq = archive();
+    assert_eq!(vec!["a.txt", "dir"], common::names(&mpq, "."));
+    assert_eq!(vec!["b.txt", "sub"], common::names(&mpq, "dir"));
+}
+
+#[test]
+fn mpq_metadata() {
+    let mpq = archive();
+
+    let meta = mpq.metadata("dir/sub/big.bin").unwrap();
+    assert_eq!(EntryKind::File, meta.kind);
+    assert_eq!(150_000, meta.len);
+    assert_eq!(None, meta.modified);
+
+    assert_eq!(EntryKind::Dir, mpq.metadata("dir/sub").unwrap().kind);
+    assert!(mpq.metadata("nope").is_err());
+}
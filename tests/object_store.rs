@@ -0,0 +1,132 @@
+#![cfg(feature = "object_store")]
+
+use std::future::Future;
+use std::io::{ErrorKind, Result};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use mini_fs::prelude::*;
+use mini_fs::{EntryKind, MiniFs, ObjectStore};
+use object_store_::memory::InMemory;
+use object_store_::path::Path as ObjectPath;
+use object_store_::ObjectStore as _;
+
+mod common;
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    tokio_::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+fn memory() -> Arc<InMemory> {
+    let memory = Arc::new(InMemory::new());
+    block_on(async {
+        for (key, data) in [
+            ("a.txt", "a"),
+            ("maps/b.txt", "bb"),
+            ("maps/sub/c.txt", "ccc"),
+        ] {
+            memory
+                .put(&ObjectPath::from(key), data.as_bytes().to_vec().into())
+                .await
+                .unwrap();
+        }
+    });
+    memory
+}
+
+#[test]
+fn object_store_read() {
+    let store = ObjectStore::from_arc(memory());
+    assert_eq!("a", store.read_to_string("/a.txt").unwrap());
+    assert_eq!("ccc", store.read_to_string("maps/./sub/c.txt").unwrap());
+    assert_eq!(
+        ErrorKind::NotFound,
+        store.open("missing.txt").err().unwrap().kind()
+    );
+    assert_eq!(
+        ErrorKind::NotFound,
+        store.open("maps").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn object_store_entries() {
+    let store = ObjectStore::from_arc(memory());
+    assert_eq!(
+        vec![
+            ("a.txt".to_string(), EntryKind::File),
+            ("maps".to_string(), EntryKind::Dir),
+        ],
+        common::entries(&store, "/"),
+    );
+    assert_eq!(
+        vec![
+            ("b.txt".to_string(), EntryKind::File),
+            ("sub".to_string(), EntryKind::Dir),
+        ],
+        common::entries(&store, "maps"),
+    );
+
+    let mut paths = store.walk("/").collect::<Result<Vec<_>>>().unwrap();
+    paths.sort();
+    assert_eq!(
+        vec![
+            PathBuf::from("/a.txt"),
+            PathBuf::from("/maps/b.txt"),
+            PathBuf::from("/maps/sub/c.txt"),
+        ],
+        paths
+    );
+}
+
+#[test]
+fn object_store_metadata() {
+    let store = ObjectStore::from_arc(memory());
+    let meta = store.metadata("maps/b.txt").unwrap();
+    assert_eq!(EntryKind::File, meta.kind);
+    assert_eq!(2, meta.len);
+    assert!(meta.modified.is_some());
+    assert_eq!(EntryKind::Dir, store.metadata("maps/sub").unwrap().kind);
+    assert_eq!(EntryKind::Dir, store.metadata("/").unwrap().kind);
+    assert_eq!(
+        ErrorKind::NotFound,
+        store.metadata("map").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn object_store_mount() {
+    let fs = MiniFs::new().mount("/data", ObjectStore::from_arc(memory()));
+    assert_eq!("bb", fs.read_to_string("/data/maps/b.txt").unwrap());
+    assert!(fs.is_dir("/data/maps"));
+}
+
+#[test]
+fn object_store_async() {
+    use futures_io::AsyncRead;
+    use std::future::poll_fn;
+    use std::pin::Pin;
+
+    use mini_fs::AsyncStoreExt;
+
+    let store = ObjectStore::from_arc(memory());
+    block_on(async {
+        let mut file = AsyncStoreExt::open(&store, "maps/b.txt").await.unwrap();
+        let mut buf = [0; 8];
+        let n = poll_fn(|cx| Pin::new(&mut file).poll_read(cx, &mut buf))
+            .await
+            .unwrap();
+        assert_eq!(b"bb", &buf[..n]);
+        assert_eq!(
+            ErrorKind::NotFound,
+            AsyncStoreExt::open(&store, "missing.txt")
+                .await
+                .err()
+                .unwrap()
+                .kind()
+        );
+    });
+}
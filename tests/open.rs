@@ -0,0 +1,33 @@
+use mini_fs::prelude::*;
+use mini_fs::{MiniFs, Ram};
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+#[test]
+fn mini_fs_open_any_path() {
+    let mut ram = Ram::new();
+    ram.touch("ui/main.png", b"png".to_vec());
+    let files = MiniFs::new().mount("/res", ram);
+
+    assert!(files.open("/res/ui/main.png").is_ok());
+    assert!(files.open(String::from("/res/ui/main.png")).is_ok());
+    assert!(files.open(PathBuf::from("/res/ui/main.png")).is_ok());
+    assert!(files.open("/res/ui/missing.png").is_err());
+}
+
+#[test]
+fn mini_fs_read() {
+    let mut ram = Ram::new();
+    ram.touch("a.txt", b"hello".to_vec());
+    ram.touch("b.bin", vec![0xff, 0xfe]);
+    let files = MiniFs::new().mount("/res", ram);
+
+    assert_eq!(b"hello".to_vec(), files.read("/res/a.txt").unwrap());
+    assert_eq!("hello", files.read_to_string("/res/a.txt").unwrap());
+    assert_eq!(vec![0xff, 0xfe], files.read("/res/b.bin").unwrap());
+
+    let err = files.read_to_string("/res/b.bin").unwrap_err();
+    assert_eq!(ErrorKind::InvalidData, err.kind());
+    let err = files.read("/res/c.txt").unwrap_err();
+    assert_eq!(ErrorKind::NotFound, err.kind());
+}
@@ -0,0 +1,114 @@
+use std::io::{ErrorKind, Write};
+
+use mini_fs::prelude::*;
+use mini_fs::{EntryKind, MiniFs, Overlay, Ram, ReadOnly};
+
+mod common;
+
+fn base() -> ReadOnly<Ram> {
+    let mut ram = Ram::new();
+    ram.touch("a.txt", b"hello".to_vec());
+    ram.touch("dir/b.txt", b"world".to_vec());
+    ReadOnly::new(ram)
+}
+
+#[test]
+fn overlay_create() {
+    let fs = Overlay::new(base(), Ram::new());
+    fs.create("a.txt").unwrap().write_all(b"new").unwrap();
+    fs.create("c.txt").unwrap().write_all(b"more").unwrap();
+    assert_eq!("new", fs.read_to_string("a.txt").unwrap());
+    assert_eq!("more", fs.read_to_string("c.txt").unwrap());
+    assert_eq!("world", fs.read_to_string("dir/b.txt").unwrap());
+    assert_eq!(3, fs.metadata("a.txt").unwrap().len);
+    assert_eq!("hello", fs.base().read_to_string("a.txt").unwrap());
+    assert!(!fs.base().exists("c.txt"));
+    assert_eq!(
+        vec![
+            ("a.txt".to_string(), EntryKind::File),
+            ("c.txt".to_string(), EntryKind::File),
+            ("dir".to_string(), EntryKind::Dir),
+        ],
+        common::entries(&fs, ""),
+    );
+}
+
+#[test]
+fn overlay_remove() {
+    let fs = Overlay::new(base(), Ram::new());
+    fs.create("a.txt").unwrap().write_all(b"new").unwrap();
+    fs.remove("a.txt").unwrap();
+    fs.remove("dir/b.txt").unwrap();
+    assert!(!fs.exists("a.txt"));
+    assert!(!fs.exists("/dir/b.txt"));
+    assert!(fs.base().is_file("a.txt"));
+    assert!(common::entries(&fs, "dir").is_empty());
+    assert_eq!(
+        ErrorKind::NotFound,
+        fs.remove("a.txt").err().unwrap().kind()
+    );
+    assert_eq!(
+        ErrorKind::NotFound,
+        fs.remove("c.txt").err().unwrap().kind()
+    );
+
+    // created again
+    fs.create("a.txt").unwrap().write_all(b"again").unwrap();
+    assert_eq!("again", fs.read_to_string("a.txt").unwrap());
+}
+
+#[test]
+fn overlay_whiteout() {
+    let fs = Overlay::new(base(), Ram::new());
+    fs.remove("dir/b.txt").unwrap();
+    assert!(fs.upper().is_file("dir/.wh.b.txt"));
+    assert!(!fs.exists("dir/.wh.b.txt"));
+    assert_eq!(
+        ErrorKind::InvalidInput,
+        fs.create("dir/.wh.a.txt").err().unwrap().kind()
+    );
+    assert_eq!(
+        vec![
+            ("a.txt".to_string(), EntryKind::File),
+            ("dir".to_string(), EntryKind::Dir),
+        ],
+        common::entries(&fs, ""),
+    );
+
+    // removals are kept by the upper store
+    let (base, upper) = fs.into_inner();
+    let fs = Overlay::new(base, upper);
+    assert!(!fs.exists("dir/b.txt"));
+    fs.create("dir/b.txt").unwrap();
+    assert!(!fs.upper().exists("dir/.wh.b.txt"));
+    assert!(fs.is_file("dir/b.txt"));
+}
+
+#[test]
+fn overlay_rename() {
+    let fs = Overlay::new(base(), Ram::new());
+    fs.rename("a.txt", "dir/a.txt").unwrap();
+    assert!(!fs.exists("a.txt"));
+    assert_eq!("hello", fs.read_to_string("dir/a.txt").unwrap());
+    assert!(fs.upper().is_file("dir/a.txt"));
+
+    fs.rename("dir/a.txt", "a.txt").unwrap();
+    assert_eq!("hello", fs.read_to_string("a.txt").unwrap());
+    assert!(!fs.exists("dir/a.txt"));
+    assert_eq!(
+        ErrorKind::NotFound,
+        fs.rename("missing", "b.txt").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn overlay_mount() {
+    let fs = MiniFs::new().mount("/assets", Overlay::new(base(), Ram::new()));
+    fs.create("/assets/a.txt")
+        .unwrap()
+        .write_all(b"new")
+        .unwrap();
+    assert_eq!("new", fs.read_to_string("/assets/a.txt").unwrap());
+    fs.remove("/assets/dir/b.txt").unwrap();
+    assert!(!fs.exists("/assets/dir/b.txt"));
+}
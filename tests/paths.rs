@@ -0,0 +1,98 @@
+use std::io::Write;
+use std::path::Path;
+
+use mini_fs::prelude::*;
+use mini_fs::{MiniFs, Ram};
+
+mod common;
+
+const FILES: &[(&str, &str)] = &[("ui/main.png", "png")];
+
+#[test]
+fn mini_fs_normalize() {
+    let fs = MiniFs::new().mount("/res", common::ram(FILES));
+    for path in &[
+        "/res/ui/main.png",
+        "/res/./ui//main.png",
+        "/res/ui/main.png/",
+        "//res/ui/../ui/main.png",
+        "/res/../res/ui/main.png",
+        "res/ui/main.png",
+    ] {
+        assert!(fs.is_file(path), "{}", path);
+        assert!(fs.open_path(Path::new(path)).is_ok(), "{}", path);
+    }
+    assert!(fs.is_dir("/res/ui/"));
+    assert_eq!(1, fs.entries("res/./ui").unwrap().count());
+}
+
+#[test]
+fn mini_fs_normalize_mount() {
+    let mut fs = MiniFs::new()
+        .mount("res/./", common::ram(FILES))
+        .mount("/saves/", Ram::new());
+    assert!(fs.is_file("/res/ui/main.png"));
+    fs.create_path(Path::new("/saves/../saves//a.sav"))
+        .unwrap()
+        .write_all(b"save")
+        .unwrap();
+    assert!(fs.is_file("/saves/a.sav"));
+    fs.rename_path(Path::new("saves/a.sav"), Path::new("/saves/./b.sav"))
+        .unwrap();
+    fs.remove_path(Path::new("/saves//b.sav")).unwrap();
+    assert!(fs.umount("res/").is_some());
+    assert!(!fs.exists("/res/ui/main.png"));
+}
+
+#[test]
+fn backslashes() {
+    let mut ram = Ram::new();
+    ram.touch("ui\\icons\\save.png", b"png".to_vec());
+    assert!(ram.is_file("ui/icons/save.png"));
+    assert!(ram.is_file("ui\\icons/save.png"));
+    assert!(ram.is_dir("ui\\icons"));
+
+    let fs = MiniFs::new().mount("\\res", ram);
+    assert!(fs.is_file("\\res\\ui\\icons\\save.png"));
+    assert!(fs.is_file("/res/ui/icons/save.png"));
+    fs.create("res\\ui\\new.png").unwrap();
+    assert!(fs.is_file("/res/ui/new.png"));
+}
+
+#[test]
+#[cfg(feature = "zip")]
+fn zip_backslashes() {
+    use mini_fs::Zip;
+    use std::io::Cursor;
+
+    let file = include_bytes!("archive2.zip");
+    let zip = Zip::new(Cursor::new(&file[..]));
+    assert!(zip.open("nested\\hello.txt").is_ok());
+    assert!(zip.is_file("nested\\world.txt"));
+    let zip = zip.index().unwrap();
+    assert!(zip.open("nested\\hello.txt").is_ok());
+    assert_eq!(2, zip.entries("nested\\").unwrap().count());
+}
+
+#[test]
+#[cfg(feature = "tar")]
+fn tar_backslashes() {
+    use mini_fs::Tar;
+    use std::io::Cursor;
+
+    let mut builder = tar_::Builder::new(Vec::new());
+    let mut header = tar_::Header::new_gnu();
+    header.set_size(3);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, "dir\\a.txt", &b"abc"[..])
+        .unwrap();
+    let file = builder.into_inner().unwrap();
+
+    let tar = Tar::new(Cursor::new(&file[..]));
+    assert!(tar.open("dir/a.txt").is_ok());
+    assert!(tar.open("dir\\a.txt").is_ok());
+    let tar = tar.index().unwrap();
+    assert!(tar.is_dir("dir"));
+    assert!(tar.open("dir\\a.txt").is_ok());
+}
@@ -0,0 +1,111 @@
+use mini_fs::prelude::*;
+use mini_fs::{Entries, EntryKind, Metadata, MiniFs, Quota, Ram, RamFile};
+use std::io::{self, ErrorKind, Seek, SeekFrom, Write};
+use std::path::Path;
+
+// Ram removing directories with the files in them.
+struct Tree(Ram);
+
+impl Store for Tree {
+    type File = RamFile;
+
+    fn open_path(&self, path: &Path) -> io::Result<RamFile> {
+        self.0.open_path(path)
+    }
+
+    fn entries_path(&self, path: &Path) -> io::Result<Entries<'_>> {
+        self.0.entries_path(path)
+    }
+
+    fn metadata_path(&self, path: &Path) -> io::Result<Metadata> {
+        self.0.metadata_path(path)
+    }
+
+    fn create_path(&self, path: &Path) -> io::Result<RamFile> {
+        self.0.create_path(path)
+    }
+
+    fn remove_path(&self, path: &Path) -> io::Result<()> {
+        if self.0.metadata_path(path)?.kind != EntryKind::Dir {
+            return self.0.remove_path(path);
+        }
+        let files = self.0.walk(path).collect::<io::Result<Vec<_>>>()?;
+        for file in files {
+            self.0.remove_path(&file)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn quota_bytes() {
+    let quota = Quota::new(Ram::new()).unwrap().max_bytes(10);
+    {
+        let mut file = quota.create("a").unwrap();
+        file.write_all(b"12345678").unwrap();
+        // rewriting doesn't use more of the quota
+        file.seek(SeekFrom::Start(0)).unwrap();
+        file.write_all(b"abcd").unwrap();
+        let err = file.write_all(b"efghijk").unwrap_err();
+        assert_eq!(ErrorKind::QuotaExceeded, err.kind());
+    }
+    assert_eq!(8, quota.bytes_used());
+    assert_eq!(b"abcd5678", &quota.read("a").unwrap()[..]);
+
+    // overwriting and removing give the size back
+    quota.create("a").unwrap().write_all(b"123").unwrap();
+    assert_eq!(3, quota.bytes_used());
+    quota.create("b").unwrap().write_all(b"1234567").unwrap();
+    assert!(quota.create("c").unwrap().write_all(b"1").is_err());
+    quota.remove("b").unwrap();
+    assert_eq!(3, quota.bytes_used());
+    quota.create("c").unwrap().write_all(b"1").unwrap();
+    assert_eq!(4, quota.bytes_used());
+}
+
+#[test]
+fn quota_files() {
+    let mut ram = Ram::new();
+    ram.touch("saves/old.sav", &b"old"[..]);
+    let quota = Quota::new(ram).unwrap().max_files(2);
+    assert_eq!(1, quota.files_used());
+    assert_eq!(3, quota.bytes_used());
+
+    let files = MiniFs::new().mount("/data", quota);
+    files.create("/data/saves/a.sav").unwrap();
+    let err = files.create("/data/saves/b.sav").err().unwrap();
+    assert_eq!(ErrorKind::QuotaExceeded, err.kind());
+    // existing files can still be overwritten
+    files.create("/data/saves/a.sav").unwrap();
+
+    files
+        .rename("/data/saves/old.sav", "/data/saves/a.sav")
+        .unwrap();
+    files.create("/data/saves/b.sav").unwrap();
+
+    // renaming a file to itself keeps it counted
+    for _ in 0..2 {
+        files
+            .rename("/data/saves/a.sav", "/data/saves/./a.sav")
+            .unwrap();
+    }
+    let err = files.create("/data/saves/c.sav").err().unwrap();
+    assert_eq!(ErrorKind::QuotaExceeded, err.kind());
+}
+
+#[test]
+fn quota_remove_dir() {
+    let mut ram = Ram::new();
+    ram.touch("keep.sav", &b"1"[..]);
+    ram.touch("saves/a.sav", &b"12"[..]);
+    ram.touch("saves/old/b.sav", &b"345"[..]);
+    let quota = Quota::new(Tree(ram)).unwrap().max_files(3);
+    assert_eq!(3, quota.files_used());
+    assert_eq!(6, quota.bytes_used());
+
+    quota.remove("saves").unwrap();
+    assert_eq!(1, quota.files_used());
+    assert_eq!(1, quota.bytes_used());
+    quota.create("c.sav").unwrap();
+    quota.create("d.sav").unwrap();
+}
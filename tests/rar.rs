@@ -0,0 +1,73 @@
+#![cfg(feature = "rar")]
+
+use std::ffi::OsStr;
+use std::io::{ErrorKind, Read};
+use std::time::{Duration, UNIX_EPOCH};
+
+use mini_fs::prelude::*;
+use mini_fs::{EntryKind, Rar};
+
+fn archive() -> Rar {
+    Rar::open(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/archive.rar")).unwrap()
+}
+
+#[test]
+fn rar() {
+    let rar = archive();
+    for _ in 0..4 {
+        let mut a_content = String::new();
+        let mut b_content = String::new();
+        rar.open("a.txt")
+            .unwrap()
+            .read_to_string(&mut a_content)
+            .unwrap();
+        rar.open("dir/b.txt")
+            .unwrap()
+            .read_to_string(&mut b_content)
+            .unwrap();
+        assert_eq!("hello\n", a_content);
+        assert_eq!("world!\n", b_content);
+    }
+    assert_eq!(ErrorKind::NotFound, rar.open("nope").err().unwrap().kind());
+    assert_eq!(ErrorKind::NotFound, rar.open("dir").err().unwrap().kind());
+    assert_eq!(
+        ErrorKind::NotFound,
+        Rar::open("nope.rar").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn rar_entries() {
+    let rar = archive();
+
+    let mut entries = rar
+        .entries(".")
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    entries.sort_by_key(|e| e.name.clone());
+    let names: Vec<_> = entries.iter().map(|e| e.name.as_os_str()).collect();
+    assert_eq!(vec!["a.txt", "dir", "empty"], names);
+    assert_eq!(EntryKind::File, entries[0].kind);
+    assert_eq!(EntryKind::Dir, entries[1].kind);
+
+    let entries = rar.entries("dir").unwrap().collect::<Vec<_>>();
+    assert_eq!(1, entries.len());
+    assert_eq!(OsStr::new("b.txt"), entries[0].as_ref().unwrap().name);
+}
+
+#[test]
+fn rar_metadata() {
+    let rar = archive();
+
+    let meta = rar.metadata("dir/b.txt").unwrap();
+    assert_eq!(EntryKind::File, meta.kind);
+    assert_eq!(7, meta.len);
+    assert_eq!(
+        Some(UNIX_EPOCH + Duration::from_secs(1_556_377_860)),
+        meta.modified
+    );
+
+    assert_eq!(EntryKind::Dir, rar.metadata("empty").unwrap().kind);
+    assert!(rar.metadata("nope").is_err());
+}
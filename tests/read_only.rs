@@ -0,0 +1,61 @@
+use std::io::{ErrorKind, Read, Write};
+
+use mini_fs::prelude::*;
+use mini_fs::{EntryKind, MiniFs, Ram, ReadOnly};
+
+mod common;
+
+const FILES: &[(&str, &str)] = &[("a.txt", "hello"), ("dir/b.txt", "world")];
+
+#[test]
+fn read_only_reads() {
+    let store = ReadOnly::new(common::ram(FILES));
+    let mut content = String::new();
+    store
+        .open("a.txt")
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    assert_eq!("hello", content);
+    assert!(store.is_dir("dir"));
+    assert_eq!(5, store.metadata("dir/b.txt").unwrap().len);
+    assert_eq!(
+        EntryKind::File,
+        store.entries("dir").unwrap().next().unwrap().unwrap().kind
+    );
+}
+
+#[test]
+fn read_only_writes() {
+    let store = ReadOnly::new(common::ram(FILES));
+    assert_eq!(
+        ErrorKind::ReadOnlyFilesystem,
+        store.create("c.txt").err().unwrap().kind()
+    );
+    assert_eq!(
+        ErrorKind::ReadOnlyFilesystem,
+        store.remove("a.txt").err().unwrap().kind()
+    );
+    assert_eq!(
+        ErrorKind::ReadOnlyFilesystem,
+        store.rename("a.txt", "c.txt").err().unwrap().kind()
+    );
+    assert!(store.open("a.txt").unwrap().write(b"x").is_err());
+
+    let ram = store.into_inner();
+    assert!(ram.is_file("a.txt"));
+    assert!(!ram.exists("c.txt"));
+}
+
+#[test]
+fn read_only_overlay() {
+    // files are created in the first writable element
+    let fs = MiniFs::new().mount("/", (ReadOnly::new(common::ram(FILES)), Ram::new()));
+    fs.create("/a.txt").unwrap().write_all(b"new").unwrap();
+    assert_eq!(
+        ErrorKind::ReadOnlyFilesystem,
+        fs.remove("/dir/b.txt").err().unwrap().kind()
+    );
+    fs.remove("/a.txt").unwrap();
+    assert!(fs.is_file("/a.txt"));
+}
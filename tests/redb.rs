@@ -0,0 +1,134 @@
+#![cfg(feature = "redb")]
+
+use std::io::{ErrorKind, Result, Write};
+use std::path::PathBuf;
+
+use mini_fs::prelude::*;
+use mini_fs::{EntryKind, MiniFs, Ram, Redb};
+
+mod common;
+
+fn redb(name: &str) -> Redb {
+    let db = Redb::open(common::tmp_dir(name).join("files.redb")).unwrap();
+    for (path, data) in &[
+        ("a.txt", "hello"),
+        ("textures/wall.png", "png"),
+        ("textures/ui/button.png", "button"),
+        ("textures/ui/cursor.png", "cursor"),
+        ("textures_old.txt", "old"),
+    ] {
+        db.create(path).unwrap().write_all(data.as_bytes()).unwrap();
+    }
+    db
+}
+
+#[test]
+fn redb_open() {
+    let db = redb("redb-open");
+    assert_eq!("hello", db.read_to_string("/a.txt").unwrap());
+    assert_eq!(
+        "button",
+        db.read_to_string("textures/./ui/button.png").unwrap()
+    );
+    assert_eq!(
+        ErrorKind::NotFound,
+        db.open("missing").err().unwrap().kind()
+    );
+    assert_eq!(
+        ErrorKind::NotFound,
+        db.open("textures").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn redb_entries() {
+    let db = redb("redb-entries");
+    assert_eq!(
+        vec![
+            ("a.txt".to_string(), EntryKind::File),
+            ("textures".to_string(), EntryKind::Dir),
+            ("textures_old.txt".to_string(), EntryKind::File),
+        ],
+        common::entries(&db, "/"),
+    );
+    assert_eq!(
+        vec![
+            ("ui".to_string(), EntryKind::Dir),
+            ("wall.png".to_string(), EntryKind::File),
+        ],
+        common::entries(&db, "textures"),
+    );
+
+    let mut paths = db.walk("/textures").collect::<Result<Vec<_>>>().unwrap();
+    paths.sort();
+    assert_eq!(
+        vec![
+            PathBuf::from("/textures/ui/button.png"),
+            PathBuf::from("/textures/ui/cursor.png"),
+            PathBuf::from("/textures/wall.png"),
+        ],
+        paths
+    );
+}
+
+#[test]
+fn redb_metadata() {
+    let db = redb("redb-metadata");
+    let meta = db.metadata("a.txt").unwrap();
+    assert_eq!(EntryKind::File, meta.kind);
+    assert_eq!(5, meta.len);
+    assert!(meta.modified.is_some());
+    assert_eq!(EntryKind::Dir, db.metadata("textures/ui").unwrap().kind);
+    assert_eq!(EntryKind::Dir, db.metadata("/").unwrap().kind);
+    assert_eq!(
+        ErrorKind::NotFound,
+        db.metadata("texture").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn redb_write() {
+    let path = common::tmp_dir("redb-write").join("files.redb");
+    let db = Redb::open(&path).unwrap();
+
+    let mut file = db.create("a/b.txt").unwrap();
+    assert!(db.is_file("a/b.txt"));
+    file.write_all(b"hello").unwrap();
+    drop(file);
+    assert_eq!("hello", db.read_to_string("a/b.txt").unwrap());
+
+    db.rename("a/b.txt", "c.txt").unwrap();
+    assert!(!db.is_dir("a"));
+    assert_eq!(
+        ErrorKind::NotFound,
+        db.rename("a/b.txt", "d.txt").err().unwrap().kind()
+    );
+    assert!(db.open("c.txt").unwrap().write(b"x").is_err());
+    drop(db);
+
+    // changes are persisted
+    let db = Redb::open(&path).unwrap();
+    assert_eq!("hello", db.read_to_string("c.txt").unwrap());
+    db.remove("c.txt").unwrap();
+    assert_eq!(
+        ErrorKind::NotFound,
+        db.remove("c.txt").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn redb_cache() {
+    let db = redb("redb-cache");
+    let mut origin = Ram::new();
+    origin.touch("a.txt", &b"stale"[..]);
+    origin.touch("b.txt", &b"origin"[..]);
+
+    let fs = MiniFs::new().mount("/assets", (db, origin));
+    assert_eq!("hello", fs.read_to_string("/assets/a.txt").unwrap());
+    assert_eq!("origin", fs.read_to_string("/assets/b.txt").unwrap());
+    fs.create("/assets/b.txt")
+        .unwrap()
+        .write_all(b"cached")
+        .unwrap();
+    assert_eq!("cached", fs.read_to_string("/assets/b.txt").unwrap());
+}
@@ -0,0 +1,73 @@
+use mini_fs::prelude::*;
+use mini_fs::{Flaky, Journal, Recorder, Replayer};
+use std::io::ErrorKind;
+use std::path::Path;
+
+mod common;
+
+const FILES: &[(&str, &str)] = &[("a.txt", "a"), ("dir/b.txt", "bb")];
+
+#[test]
+fn replay_session() {
+    let recorder = Recorder::new(Flaky::new(common::ram(FILES)).fail_first(1, ErrorKind::TimedOut));
+    assert!(recorder.open("a.txt").is_err());
+    assert_eq!(b"a", &recorder.read("a.txt").unwrap()[..]);
+    assert_eq!(b"bb", &recorder.read("dir/b.txt").unwrap()[..]);
+    let err = recorder.open("missing.txt").err().unwrap();
+    assert_eq!(ErrorKind::NotFound, err.kind());
+    assert_eq!(4, recorder.journal().len());
+
+    let replayer = Replayer::new(recorder.journal());
+    let err = replayer.open("a.txt").err().unwrap();
+    assert_eq!(ErrorKind::TimedOut, err.kind());
+    assert_eq!(b"a", &replayer.read("a.txt").unwrap()[..]);
+    // The last response is repeated.
+    assert_eq!(b"a", &replayer.read("a.txt").unwrap()[..]);
+    assert_eq!(b"bb", &replayer.read("dir/b.txt").unwrap()[..]);
+    assert_eq!(2, replayer.metadata("dir/b.txt").unwrap().len);
+    let err = replayer.open("missing.txt").err().unwrap();
+    assert_eq!(ErrorKind::NotFound, err.kind());
+    let err = replayer.open("never.txt").err().unwrap();
+    assert_eq!(ErrorKind::NotFound, err.kind());
+}
+
+#[test]
+fn replay_journal_bytes() {
+    let recorder = Recorder::new(
+        Flaky::new(common::ram(FILES)).fail_path("a.txt", ErrorKind::PermissionDenied),
+    );
+    assert!(recorder.open("a.txt").is_err());
+    assert!(recorder.open("dir/b.txt").is_ok());
+
+    let mut data = Vec::new();
+    recorder.journal().write_to(&mut data).unwrap();
+    let journal = Journal::read_from(&data[..]).unwrap();
+    assert_eq!(2, journal.len());
+
+    let replayer = Replayer::new(journal);
+    let err = replayer.open("a.txt").err().unwrap();
+    assert_eq!(ErrorKind::PermissionDenied, err.kind());
+    assert_eq!(b"bb", &replayer.read("dir/b.txt").unwrap()[..]);
+
+    assert!(Journal::read_from(&data[..data.len() - 1]).is_err());
+    assert!(Journal::read_from(&b"not a journal"[..]).is_err());
+}
+
+#[test]
+fn replay_normalized_paths() {
+    let recorder = Recorder::new(common::ram(FILES));
+    assert!(recorder.open_path(Path::new("./dir/b.txt")).is_ok());
+
+    let replayer = Replayer::new(recorder.journal());
+    assert_eq!(b"bb", &replayer.read("dir/b.txt").unwrap()[..]);
+    assert!(replayer.open_path(Path::new("dir/./b.txt")).is_ok());
+    assert!(replayer.metadata_path(Path::new("./dir/b.txt")).is_ok());
+}
+
+#[test]
+fn replay_corrupt_length() {
+    let mut data = b"MFSJ\x01\x00".to_vec();
+    data.extend_from_slice(&u32::MAX.to_le_bytes());
+    let err = Journal::read_from(&data[..]).err().unwrap();
+    assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+}
@@ -0,0 +1,52 @@
+use std::io::{ErrorKind, Result, Write};
+use std::path::PathBuf;
+
+use mini_fs::prelude::*;
+use mini_fs::{MiniFs, Rewrite};
+
+mod common;
+
+const FILES: &[(&str, &str)] = &[("data/logo.png", "logo"), ("data/ui/font.ttf", "font")];
+
+#[test]
+fn rewrite_add() {
+    let store = Rewrite::new(common::ram(FILES)).add_prefix("/data");
+    assert_eq!("logo", store.read_to_string("logo.png").unwrap());
+    assert_eq!("font", store.read_to_string("/ui/./font.ttf").unwrap());
+    assert!(store.open("data/logo.png").is_err());
+
+    let mut paths = store.walk("").collect::<Result<Vec<_>>>().unwrap();
+    paths.sort();
+    assert_eq!(
+        vec![PathBuf::from("logo.png"), PathBuf::from("ui/font.ttf")],
+        paths
+    );
+
+    store.create("new.txt").unwrap().write_all(b"new").unwrap();
+    store.rename("new.txt", "ui/new.txt").unwrap();
+    assert!(store.get_ref().is_file("data/ui/new.txt"));
+    store.remove("ui/new.txt").unwrap();
+    assert!(!store.get_ref().is_file("data/ui/new.txt"));
+}
+
+#[test]
+fn rewrite_strip() {
+    let store = Rewrite::new(common::ram(FILES))
+        .strip_prefix("v1")
+        .add_prefix("data/ui");
+    assert_eq!("font", store.read_to_string("v1/font.ttf").unwrap());
+    assert_eq!(
+        ErrorKind::NotFound,
+        store.open("font.ttf").err().unwrap().kind()
+    );
+    assert!(store.create("v2/font.ttf").is_err());
+}
+
+#[test]
+fn rewrite_mount() {
+    let fs = MiniFs::new().mount(
+        "/assets",
+        Rewrite::new(common::ram(FILES)).add_prefix("data"),
+    );
+    assert_eq!("logo", fs.read_to_string("/assets/logo.png").unwrap());
+}
@@ -0,0 +1,206 @@
+#![cfg(feature = "s3")]
+
+use std::io::{self, ErrorKind, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use mini_fs::prelude::*;
+use mini_fs::s3::Credentials;
+use mini_fs::{EntryKind, MiniFs, S3};
+
+type Requests = Arc<Mutex<Vec<(String, Vec<(String, String)>)>>>;
+
+fn object() -> Vec<u8> {
+    (0..3 << 20).map(|i| (i % 251) as u8).collect()
+}
+
+// Serves `maps/big.bin` from the `game-data` bucket, honouring ranges.
+fn bucket(requests: Requests) -> impl Fn(&str, &[(String, String)]) -> io::Result<Vec<u8>> {
+    let object = object();
+    move |url: &str, headers: &[(String, String)]| {
+        requests
+            .lock()
+            .unwrap()
+            .push((url.to_string(), headers.to_vec()));
+        match url {
+            "https://game-data.s3.eu-west-1.amazonaws.com/release/maps/big.bin" => {}
+            "https://game-data.s3.eu-west-1.amazonaws.com/release/a%20b.txt" => {
+                return Ok(b"hello".to_vec())
+            }
+            _ => return Err(io::Error::from(ErrorKind::NotFound)),
+        }
+        let range = headers.iter().find(|(name, _)| name == "range");
+        match range {
+            Some((_, range)) => {
+                let range = range.trim_start_matches("bytes=");
+                let mut bounds = range.split('-').map(|n| n.parse::<usize>().unwrap());
+                let start = bounds.next().unwrap();
+                let end = bounds.next().unwrap();
+                Ok(object[start..=end].to_vec())
+            }
+            None => Ok(object.clone()),
+        }
+    }
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v.as_str())
+}
+
+#[test]
+fn s3_url() {
+    let s3 = S3::with_client("game-data", ()).region("eu-west-1");
+    assert_eq!(
+        "https://game-data.s3.eu-west-1.amazonaws.com/maps/a%20b.bsp",
+        s3.url(Path::new("/maps/./a b.bsp")).unwrap()
+    );
+    assert_eq!(
+        ErrorKind::NotFound,
+        s3.url(Path::new("/")).err().unwrap().kind()
+    );
+
+    let s3 = S3::with_client("game-data", ())
+        .endpoint("http://localhost:9000/")
+        .prefix("release");
+    assert_eq!(
+        "http://localhost:9000/game-data/release/maps/a.bsp",
+        s3.url(Path::new("maps/a.bsp")).unwrap()
+    );
+}
+
+#[test]
+fn s3_ranged_read() {
+    let requests = Requests::default();
+    let s3 = S3::with_client("game-data", bucket(requests.clone()))
+        .region("eu-west-1")
+        .prefix("release/");
+
+    let mut file = s3.open("maps/big.bin").unwrap();
+    let mut buf = [0; 4];
+    file.seek(SeekFrom::Start(2 << 20)).unwrap();
+    file.read_exact(&mut buf).unwrap();
+    assert_eq!(&object()[2 << 20..(2 << 20) + 4], &buf);
+    file.read_exact(&mut buf).unwrap();
+    assert_eq!(&object()[(2 << 20) + 4..(2 << 20) + 8], &buf);
+
+    // the length, then a single chunk from the seek position
+    let ranges: Vec<_> = requests
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(_, headers)| header(headers, "range").map(String::from))
+        .collect();
+    assert_eq!(
+        vec![None, Some(format!("bytes={}-{}", 2 << 20, (3 << 20) - 1))],
+        ranges
+    );
+
+    let mut data = Vec::new();
+    file.seek(SeekFrom::End(-10)).unwrap();
+    file.read_to_end(&mut data).unwrap();
+    assert_eq!(&object()[(3 << 20) - 10..], &data[..]);
+
+    let mut data = Vec::new();
+    s3.open("maps/big.bin")
+        .unwrap()
+        .read_to_end(&mut data)
+        .unwrap();
+    assert_eq!(object(), data);
+}
+
+#[test]
+fn s3_metadata() {
+    let s3 = S3::with_client("game-data", bucket(Requests::default()))
+        .region("eu-west-1")
+        .prefix("release/");
+    let meta = s3.metadata("a b.txt").unwrap();
+    assert_eq!(EntryKind::File, meta.kind);
+    assert_eq!(5, meta.len);
+    assert_eq!(
+        ErrorKind::NotFound,
+        s3.open("missing.bin").err().unwrap().kind()
+    );
+    assert_eq!(0, s3.entries("/").unwrap().count());
+}
+
+#[test]
+fn s3_signed() {
+    let requests = Requests::default();
+    let credentials = Credentials::new("AKIDEXAMPLE", "secret").session_token("token");
+    let s3 = S3::with_client("game-data", bucket(requests.clone()))
+        .region("eu-west-1")
+        .prefix("release/")
+        .credentials(credentials);
+    let mut content = String::new();
+    s3.open("a b.txt")
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    assert_eq!("hello", content);
+
+    for (_, headers) in requests.lock().unwrap().iter() {
+        let date = header(headers, "x-amz-date").unwrap();
+        assert_eq!(16, date.len());
+        assert_eq!(Some("token"), header(headers, "x-amz-security-token"));
+        let auth = header(headers, "authorization").unwrap();
+        let credential = format!(
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/{}/eu-west-1/s3/aws4_request, ",
+            &date[..8]
+        );
+        assert!(auth.starts_with(&credential), "{}", auth);
+        assert!(auth.contains("x-amz-date;x-amz-security-token, Signature="));
+    }
+
+    // anonymous requests aren't signed
+    let requests = Requests::default();
+    let s3 = S3::with_client("game-data", bucket(requests.clone()))
+        .region("eu-west-1")
+        .prefix("release/");
+    s3.open("a b.txt").unwrap();
+    assert_eq!(
+        None,
+        header(&requests.lock().unwrap()[0].1, "authorization")
+    );
+}
+
+#[test]
+fn s3_overlay() {
+    let s3 = S3::with_client("game-data", bucket(Requests::default()))
+        .region("eu-west-1")
+        .prefix("release/");
+    let fs = MiniFs::new().mount("/data", s3);
+    let mut content = String::new();
+    fs.open("/data/a b.txt")
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    assert_eq!("hello", content);
+    assert_eq!(3 << 20, fs.metadata("/data/maps/big.bin").unwrap().len);
+}
+
+#[test]
+fn s3_short_response() {
+    // the ranges are ignored, and the object is shorter than its length
+    let client = |_: &str, headers: &[(String, String)]| {
+        let ranged = headers.iter().any(|(name, _)| name == "range");
+        Ok(vec![0; if ranged { 20 } else { 1000 }])
+    };
+    let s3 = S3::with_client("game-data", client).region("eu-west-1");
+    let mut file = s3.open("a.bin").unwrap();
+    file.seek(SeekFrom::Start(995)).unwrap();
+    let err = file.read(&mut [0; 5]).err().unwrap();
+    assert_eq!(ErrorKind::InvalidData, err.kind());
+}
+
+#[test]
+fn s3_send() {
+    fn assert_send<T: Send>(_: T) {}
+    let s3 = S3::with_client("game-data", bucket(Requests::default()))
+        .region("eu-west-1")
+        .prefix("release/");
+    assert_send(s3.open("a b.txt").unwrap());
+    assert_send(s3);
+}
@@ -0,0 +1,114 @@
+use mini_fs::prelude::*;
+use mini_fs::{File, MiniFs, Ram};
+use std::io::{Read, Seek, SeekFrom};
+
+fn read_at(file: &mut File, pos: SeekFrom, len: usize) -> String {
+    file.seek(pos).unwrap();
+    let mut buf = vec![0; len];
+    file.read_exact(&mut buf).unwrap();
+    String::from_utf8(buf).unwrap()
+}
+
+#[test]
+fn ram_seek() {
+    let mut ram = Ram::new();
+    ram.touch("a.txt", &b"header|table"[..]);
+    let fs = MiniFs::new().mount("/ram", ram);
+
+    let mut file = fs.open("/ram/a.txt").unwrap();
+    assert_eq!("table", read_at(&mut file, SeekFrom::Start(7), 5));
+    assert_eq!("header", read_at(&mut file, SeekFrom::Start(0), 6));
+    assert_eq!("able", read_at(&mut file, SeekFrom::End(-4), 4));
+    assert_eq!("ta", read_at(&mut file, SeekFrom::Current(-5), 2));
+}
+
+#[test]
+#[cfg(feature = "tar")]
+fn tar_seek() {
+    use mini_fs::Tar;
+
+    let fs = MiniFs::new().mount("/tar", Tar::open("tests/archive.tar.gz").unwrap());
+
+    let mut file = fs.open("/tar/b.txt").unwrap();
+    assert_eq!("rld", read_at(&mut file, SeekFrom::Start(2), 3));
+    assert_eq!("wo", read_at(&mut file, SeekFrom::Start(0), 2));
+}
+
+#[test]
+#[cfg(feature = "zip")]
+fn zip_seek() {
+    use mini_fs::Zip;
+
+    let fs = MiniFs::new().mount("/zip", Zip::open("tests/archive.zip").unwrap());
+
+    let mut file = fs.open("/zip/world.txt").unwrap();
+    assert_eq!("!", read_at(&mut file, SeekFrom::End(-2), 1));
+    assert_eq!("world", read_at(&mut file, SeekFrom::Start(0), 5));
+}
+
+#[test]
+fn len_remaining() {
+    use mini_fs::Local;
+
+    let mut ram = Ram::new();
+    ram.touch("a.txt", &b"header|table"[..]);
+    let fs = MiniFs::new()
+        .mount("/ram", ram)
+        .mount("/local", Local::new("./tests/local"));
+
+    let mut file = fs.open("/ram/a.txt").unwrap();
+    assert_eq!(12, file.len().unwrap());
+    assert_eq!(12, file.remaining().unwrap());
+    file.seek(SeekFrom::Start(7)).unwrap();
+    assert_eq!(5, file.remaining().unwrap());
+
+    let mut file = fs.open("/local/foo").unwrap();
+    assert_eq!(0, file.len().unwrap());
+    assert!(file.is_empty().unwrap());
+}
+
+#[test]
+#[cfg(feature = "zip")]
+fn zip_len() {
+    use mini_fs::Zip;
+
+    let fs = MiniFs::new().mount("/zip", Zip::open("tests/archive.zip").unwrap());
+
+    let mut file = fs.open("/zip/world.txt").unwrap();
+    assert_eq!(7, file.len().unwrap());
+    let mut buf = [0; 3];
+    file.read_exact(&mut buf).unwrap();
+    assert_eq!(4, file.remaining().unwrap());
+}
+
+#[test]
+fn bytes_file() {
+    use mini_fs::Local;
+    use std::sync::Arc;
+
+    let data: Arc<[u8]> = Arc::from(&b"header|table"[..]);
+    let mut file = File::from(Arc::clone(&data));
+    assert_eq!(12, file.len().unwrap());
+    assert_eq!("table", read_at(&mut file, SeekFrom::Start(7), 5));
+    assert_eq!(Some(&data[..]), file.as_slice());
+    assert!(Arc::ptr_eq(&data, &file.into_bytes().unwrap()));
+
+    // files from a Ram store share its buffer too
+    let mut ram = Ram::new();
+    ram.touch("a.txt", Arc::clone(&data));
+    let fs = MiniFs::new()
+        .mount("/ram", ram)
+        .mount("/local", Local::new("./tests"));
+    let file = fs.open("/ram/a.txt").unwrap();
+    assert_eq!(data.as_ptr(), file.as_slice().unwrap().as_ptr());
+    assert!(Arc::ptr_eq(&data, &file.into_bytes().unwrap()));
+
+    // other files are read
+    let mut file = fs.open("/local/archive.tar").unwrap();
+    assert!(file.as_slice().is_none());
+    file.seek(SeekFrom::End(0)).unwrap();
+    assert_eq!(
+        &include_bytes!("archive.tar")[..],
+        &file.into_bytes().unwrap()[..]
+    );
+}
@@ -0,0 +1,32 @@
+#![cfg(feature = "serde")]
+use std::io::Read;
+
+use mini_fs::prelude::*;
+use mini_fs::Ram;
+
+#[test]
+fn ram_serde() {
+    let mut ram = Ram::new();
+    ram.touch("a.txt", &b"hello"[..]);
+    ram.touch("dir/b.bin", &[0u8, 255, 10][..]);
+
+    let json = serde_json::to_string(&ram).unwrap();
+    assert_eq!(
+        r#"{"a.txt":[104,101,108,108,111],"dir/b.bin":[0,255,10]}"#,
+        json
+    );
+
+    let ram: Ram = serde_json::from_str(&json).unwrap();
+    let mut content = Vec::new();
+    ram.open("dir/b.bin")
+        .unwrap()
+        .read_to_end(&mut content)
+        .unwrap();
+    assert_eq!(vec![0, 255, 10], content);
+    assert!(ram.is_file("a.txt"));
+    assert!(ram.is_dir("dir"));
+
+    let ram: Ram = serde_json::from_str(r#"{"c.txt":"text"}"#).unwrap();
+    assert_eq!(4, ram.metadata("c.txt").unwrap().len);
+    assert!(serde_json::from_str::<Ram>("[]").is_err());
+}
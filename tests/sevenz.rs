@@ -0,0 +1,74 @@
+#![cfg(feature = "7z")]
+
+use std::ffi::OsStr;
+use std::io::{Cursor, ErrorKind, Read};
+use std::time::{Duration, UNIX_EPOCH};
+
+use mini_fs::prelude::*;
+use mini_fs::{EntryKind, SevenZ};
+
+fn archive() -> SevenZ<Cursor<&'static [u8]>> {
+    SevenZ::new(Cursor::new(&include_bytes!("archive.7z")[..])).unwrap()
+}
+
+#[test]
+fn sevenz() {
+    let sevenz = archive();
+    for _ in 0..4 {
+        let mut a_content = String::new();
+        let mut b_content = String::new();
+        sevenz
+            .open("a.txt")
+            .unwrap()
+            .read_to_string(&mut a_content)
+            .unwrap();
+        sevenz
+            .open("dir/b.txt")
+            .unwrap()
+            .read_to_string(&mut b_content)
+            .unwrap();
+        assert_eq!("hello\n", a_content);
+        assert_eq!("world!\n", b_content);
+    }
+    assert_eq!(
+        ErrorKind::NotFound,
+        sevenz.open("nope").err().unwrap().kind()
+    );
+    assert!(SevenZ::new(Cursor::new(&b"not an archive"[..])).is_err());
+}
+
+#[test]
+fn sevenz_entries() {
+    let sevenz = archive();
+
+    let mut entries = sevenz
+        .entries(".")
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    entries.sort_by_key(|e| e.name.clone());
+    let names: Vec<_> = entries.iter().map(|e| e.name.as_os_str()).collect();
+    assert_eq!(vec!["a.txt", "dir", "empty"], names);
+    assert_eq!(EntryKind::File, entries[0].kind);
+    assert_eq!(EntryKind::Dir, entries[2].kind);
+
+    let entries = sevenz.entries("dir").unwrap().collect::<Vec<_>>();
+    assert_eq!(1, entries.len());
+    assert_eq!(OsStr::new("b.txt"), entries[0].as_ref().unwrap().name);
+}
+
+#[test]
+fn sevenz_metadata() {
+    let sevenz = archive();
+
+    let meta = sevenz.metadata("a.txt").unwrap();
+    assert_eq!(EntryKind::File, meta.kind);
+    assert_eq!(6, meta.len);
+    assert_eq!(
+        Some(UNIX_EPOCH + Duration::from_secs(1_556_377_860)),
+        meta.modified
+    );
+
+    assert_eq!(EntryKind::Dir, sevenz.metadata("empty").unwrap().kind);
+    assert!(sevenz.metadata("nope").is_err());
+}
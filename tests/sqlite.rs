@@ -0,0 +1,160 @@
+#![cfg(feature = "sqlite")]
+
+use std::io::{ErrorKind, Result, Write};
+use std::path::PathBuf;
+use std::time::{Duration, UNIX_EPOCH};
+
+use mini_fs::prelude::*;
+use mini_fs::{EntryKind, MiniFs, Sqlite};
+use rusqlite::Connection;
+
+mod common;
+
+fn connection() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(
+        "CREATE TABLE files (path TEXT PRIMARY KEY NOT NULL, data BLOB NOT NULL, mtime INTEGER);
+         INSERT INTO files VALUES ('a.txt', CAST('hello' AS BLOB), 784111777);
+         INSERT INTO files VALUES ('textures/wall.png', CAST('png' AS BLOB), NULL);
+         INSERT INTO files VALUES ('textures/ui/button.png', CAST('button' AS BLOB), NULL);
+         INSERT INTO files VALUES ('textures_old.txt', CAST('old' AS BLOB), NULL);",
+    )
+    .unwrap();
+    conn
+}
+
+#[test]
+fn sqlite_open() {
+    let db = Sqlite::new(connection());
+    assert_eq!("hello", db.read_to_string("/a.txt").unwrap());
+    assert_eq!(
+        "button",
+        db.read_to_string("textures/ui/../ui/button.png").unwrap()
+    );
+    assert_eq!(
+        ErrorKind::NotFound,
+        db.open("missing").err().unwrap().kind()
+    );
+    assert_eq!(
+        ErrorKind::NotFound,
+        db.open("textures").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn sqlite_entries() {
+    let db = Sqlite::new(connection());
+    assert_eq!(
+        vec![
+            ("a.txt".to_string(), EntryKind::File),
+            ("textures".to_string(), EntryKind::Dir),
+            ("textures_old.txt".to_string(), EntryKind::File),
+        ],
+        common::entries(&db, "/"),
+    );
+    assert_eq!(
+        vec![
+            ("ui".to_string(), EntryKind::Dir),
+            ("wall.png".to_string(), EntryKind::File),
+        ],
+        common::entries(&db, "textures"),
+    );
+
+    let mut paths = db.walk("/textures").collect::<Result<Vec<_>>>().unwrap();
+    paths.sort();
+    assert_eq!(
+        vec![
+            PathBuf::from("/textures/ui/button.png"),
+            PathBuf::from("/textures/wall.png"),
+        ],
+        paths
+    );
+}
+
+#[test]
+fn sqlite_metadata() {
+    let db = Sqlite::new(connection());
+    let meta = db.metadata("a.txt").unwrap();
+    assert_eq!(EntryKind::File, meta.kind);
+    assert_eq!(5, meta.len);
+    assert_eq!(
+        Some(UNIX_EPOCH + Duration::from_secs(784111777)),
+        meta.modified
+    );
+    assert_eq!(None, db.metadata("textures/wall.png").unwrap().modified);
+    assert_eq!(EntryKind::Dir, db.metadata("textures/ui").unwrap().kind);
+    assert_eq!(EntryKind::Dir, db.metadata("/").unwrap().kind);
+    assert_eq!(
+        ErrorKind::NotFound,
+        db.metadata("texture").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn sqlite_read_only() {
+    let db = Sqlite::new(connection());
+    assert_eq!(
+        ErrorKind::ReadOnlyFilesystem,
+        db.create("b.txt").err().unwrap().kind()
+    );
+    assert_eq!(
+        ErrorKind::ReadOnlyFilesystem,
+        db.remove("a.txt").err().unwrap().kind()
+    );
+
+    // opened without write access
+    let path = common::tmp_dir("sqlite-read-only").join("files.db");
+    Sqlite::open_writable(&path).unwrap();
+    let db = Sqlite::open(&path).unwrap().writable().unwrap();
+    assert_eq!(
+        ErrorKind::ReadOnlyFilesystem,
+        db.create("b.txt").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn sqlite_write() {
+    let path = common::tmp_dir("sqlite-write").join("files.db");
+    let db = Sqlite::open_writable(&path).unwrap();
+
+    let mut file = db.create("a/b.txt").unwrap();
+    assert!(db.is_file("a/b.txt"));
+    file.write_all(b"hello").unwrap();
+    drop(file);
+    assert_eq!("hello", db.read_to_string("a/b.txt").unwrap());
+
+    db.rename("a/b.txt", "c.txt").unwrap();
+    assert!(!db.is_dir("a"));
+    assert_eq!(
+        ErrorKind::NotFound,
+        db.rename("a/b.txt", "d.txt").err().unwrap().kind()
+    );
+    drop(db);
+
+    let db = Sqlite::open(&path).unwrap();
+    assert_eq!("hello", db.read_to_string("c.txt").unwrap());
+    assert!(db.metadata("c.txt").unwrap().modified.is_some());
+    assert!(db.open("c.txt").unwrap().write(b"x").is_err());
+
+    let db = Sqlite::open_writable(&path).unwrap();
+    db.remove("c.txt").unwrap();
+    assert_eq!(
+        ErrorKind::NotFound,
+        db.remove("c.txt").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn sqlite_mount() {
+    let db = Sqlite::new(connection()).writable().unwrap();
+    let fs = MiniFs::new().mount("/assets", db);
+    assert_eq!(
+        "png",
+        fs.read_to_string("/assets/textures/wall.png").unwrap()
+    );
+    fs.create("/assets/new.txt")
+        .unwrap()
+        .write_all(b"new")
+        .unwrap();
+    assert_eq!("new", fs.read_to_string("/assets/new.txt").unwrap());
+}
@@ -0,0 +1,74 @@
+#![cfg(feature = "squashfs")]
+
+use std::ffi::OsStr;
+use std::io::{Cursor, ErrorKind, Read};
+use std::time::{Duration, UNIX_EPOCH};
+
+use mini_fs::prelude::*;
+use mini_fs::{EntryKind, SquashFs};
+
+fn archive() -> SquashFs {
+    SquashFs::new(Cursor::new(&include_bytes!("archive.squashfs")[..])).unwrap()
+}
+
+#[test]
+fn squashfs() {
+    let squashfs = archive();
+    for _ in 0..4 {
+        let mut a_content = String::new();
+        let mut b_content = String::new();
+        squashfs
+            .open("a.txt")
+            .unwrap()
+            .read_to_string(&mut a_content)
+            .unwrap();
+        squashfs
+            .open("dir/b.txt")
+            .unwrap()
+            .read_to_string(&mut b_content)
+            .unwrap();
+        assert_eq!("hello\n", a_content);
+        assert_eq!("world!\n", b_content);
+    }
+    assert_eq!(
+        ErrorKind::NotFound,
+        squashfs.open("nope").err().unwrap().kind()
+    );
+    assert!(SquashFs::new(Cursor::new(&b"not an image"[..])).is_err());
+}
+
+#[test]
+fn squashfs_entries() {
+    let squashfs = archive();
+
+    let mut entries = squashfs
+        .entries(".")
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    entries.sort_by_key(|e| e.name.clone());
+    let names: Vec<_> = entries.iter().map(|e| e.name.as_os_str()).collect();
+    assert_eq!(vec!["a.txt", "dir", "empty"], names);
+    assert_eq!(EntryKind::File, entries[0].kind);
+    assert_eq!(EntryKind::Dir, entries[2].kind);
+
+    let entries = squashfs.entries("dir").unwrap().collect::<Vec<_>>();
+    assert_eq!(1, entries.len());
+    assert_eq!(OsStr::new("b.txt"), entries[0].as_ref().unwrap().name);
+}
+
+#[test]
+fn squashfs_metadata() {
+    let squashfs = archive();
+
+    let meta = squashfs.metadata("a.txt").unwrap();
+    assert_eq!(EntryKind::File, meta.kind);
+    assert_eq!(6, meta.len);
+    assert_eq!(
+        Some(UNIX_EPOCH + Duration::from_secs(1_556_377_860)),
+        meta.modified
+    );
+
+    assert_eq!(EntryKind::Dir, squashfs.metadata("empty").unwrap().kind);
+    assert!(squashfs.metadata("nope").is_err());
+}
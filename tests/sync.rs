@@ -0,0 +1,86 @@
+use mini_fs::prelude::*;
+use mini_fs::{Local, ReadOnly, SyncMiniFs};
+use std::path::Path;
+use std::sync::Arc;
+use std::thread;
+
+#[test]
+fn sync_mini_fs_threads() {
+    let files = Arc::new(SyncMiniFs::new());
+    files.mount("/files", Local::new("./tests/local"));
+
+    let readers: Vec<_> = (0..4)
+        .map(|_| {
+            let files = Arc::clone(&files);
+            thread::spawn(move || {
+                for _ in 0..100 {
+                    assert!(files.is_file("/files/baz/foobar"));
+                }
+            })
+        })
+        .collect();
+    for _ in 0..100 {
+        files.remount("/files", ReadOnly::new(Local::new("./tests/local")));
+    }
+    for reader in readers {
+        reader.join().unwrap();
+    }
+
+    let mounts = files.mounts();
+    assert_eq!(1, mounts.len());
+    assert_eq!(Path::new("/files"), mounts[0].0);
+    assert!(mounts[0].2);
+}
+
+#[test]
+fn sync_mini_fs_mount() {
+    let files = SyncMiniFs::new();
+    files
+        .mount("/a", Local::new("./tests/local"))
+        .mount_after("/a", Local::new("./tests"));
+
+    // Mounted after, so shadowed until the first store is unmounted.
+    assert!(files.is_file("/a/baz/foobar"));
+    assert!(!files.is_file("/a/local/baz/foobar"));
+    assert!(files.umount("/a").is_some());
+    assert!(!files.is_file("/a/baz/foobar"));
+    assert!(files.is_file("/a/local/baz/foobar"));
+
+    let entries = files.entries("/").unwrap().collect::<Vec<_>>();
+    assert_eq!(1, entries.len());
+    assert_eq!("a", entries[0].as_ref().unwrap().name);
+}
+
+#[test]
+#[cfg(all(feature = "tar", feature = "zip"))]
+fn sync_mini_fs_archives() {
+    use mini_fs::{Tar, Zip};
+    use std::io::Cursor;
+
+    let tar = || Tar::new(Cursor::new(&include_bytes!("archive.tar")[..]));
+    let files = Arc::new(SyncMiniFs::new());
+    files.mount("/data", tar().index().unwrap());
+
+    let readers: Vec<_> = (0..4)
+        .map(|_| {
+            let files = Arc::clone(&files);
+            thread::spawn(move || {
+                for _ in 0..100 {
+                    assert_eq!("hello\n", files.read_to_string("/data/a.txt").unwrap());
+                }
+            })
+        })
+        .collect();
+    let gz = include_bytes!("archive.tar.gz");
+    let zip = include_bytes!("archive.zip");
+    for _ in 0..20 {
+        files.remount("/data", Tar::new(Cursor::new(&gz[..])));
+        files.mount("/patch", Zip::new(Cursor::new(&zip[..])));
+        assert_eq!("hello\n", files.read_to_string("/patch/hello.txt").unwrap());
+        assert!(files.umount("/patch").is_some());
+        files.remount("/data", tar());
+    }
+    for reader in readers {
+        reader.join().unwrap();
+    }
+}
@@ -1,11 +1,11 @@
+#[cfg(feature = "tar")]
 use std::io::{Cursor, Read};
-use std::path::Path;
 
 #[test]
 #[cfg(feature = "tar")]
 fn tar() {
     use mini_fs::prelude::*;
-    use mini_fs::{Store, Tar};
+    use mini_fs::Tar;
 
     let file = include_bytes!("archive.tar");
     let tar = Tar::new(Cursor::new(&file[..]));
@@ -47,12 +47,297 @@ fn tar_gz() {
     }
 }
 
+#[test]
+#[cfg(feature = "tar")]
+fn tar_gz_explicit() {
+    use mini_fs::prelude::*;
+    use mini_fs::Tar;
+
+    let file = include_bytes!("archive.tar.gz");
+    let tar = Tar::gz(Cursor::new(&file[..]));
+    let mut a_content = String::new();
+    tar.open("a.txt")
+        .unwrap()
+        .read_to_string(&mut a_content)
+        .unwrap();
+    assert_eq!("hello\n", a_content);
+    assert_eq!(
+        std::io::ErrorKind::NotFound,
+        tar.open("nope").err().unwrap().kind()
+    );
+
+    // a plain archive can't be read as gzip
+    let file = include_bytes!("archive.tar");
+    let tar = Tar::gz(Cursor::new(&file[..]));
+    assert!(tar.open("a.txt").is_err());
+}
+
+#[test]
+#[cfg(feature = "xz")]
+fn tar_xz() {
+    use mini_fs::prelude::*;
+    use mini_fs::Tar;
+
+    let file = include_bytes!("archive.tar.xz");
+    for tar in [
+        Tar::new(Cursor::new(&file[..])),
+        Tar::xz(Cursor::new(&file[..])),
+    ] {
+        let tar = tar.index().unwrap();
+        assert_eq!(2, tar.entries(".").unwrap().count());
+
+        let mut b_content = String::new();
+        tar.open("b.txt")
+            .unwrap()
+            .read_to_string(&mut b_content)
+            .unwrap();
+        assert_eq!("world!\n", b_content);
+        assert!(tar.open("nope").is_err());
+    }
+}
+
+#[test]
+#[cfg(feature = "zstd")]
+fn tar_zstd() {
+    use mini_fs::prelude::*;
+    use mini_fs::Tar;
+
+    let file = include_bytes!("archive.tar.zst");
+    for tar in [
+        Tar::new(Cursor::new(&file[..])),
+        Tar::zstd(Cursor::new(&file[..])),
+    ] {
+        let tar = tar.index().unwrap();
+        assert_eq!(2, tar.entries(".").unwrap().count());
+
+        let mut b_content = String::new();
+        tar.open("b.txt")
+            .unwrap()
+            .read_to_string(&mut b_content)
+            .unwrap();
+        assert_eq!("world!\n", b_content);
+        assert!(tar.open("nope").is_err());
+    }
+}
+
+#[test]
+#[cfg(feature = "bzip2")]
+fn tar_bzip2() {
+    use mini_fs::prelude::*;
+    use mini_fs::Tar;
+
+    let file = include_bytes!("archive.tar.bz2");
+    for tar in [
+        Tar::new(Cursor::new(&file[..])),
+        Tar::bz2(Cursor::new(&file[..])),
+    ] {
+        let tar = tar.index().unwrap();
+        assert_eq!(2, tar.entries(".").unwrap().count());
+
+        let mut b_content = String::new();
+        tar.open("b.txt")
+            .unwrap()
+            .read_to_string(&mut b_content)
+            .unwrap();
+        assert_eq!("world!\n", b_content);
+        assert!(tar.open("nope").is_err());
+    }
+}
+
+#[test]
+#[cfg(feature = "tar")]
+fn tar_with_compression() {
+    use mini_fs::prelude::*;
+    use mini_fs::tar::Compression;
+    use mini_fs::Tar;
+
+    let file = include_bytes!("archive.tar.gz");
+    let tar = Tar::with_compression(Cursor::new(&file[..]), Compression::Gzip);
+    assert!(tar.open("a.txt").is_ok());
+
+    let file = include_bytes!("archive.tar");
+    let tar = Tar::with_compression(Cursor::new(&file[..]), Compression::None);
+    assert!(tar.open("a.txt").is_ok());
+}
+
 #[test]
 #[cfg(feature = "tar")]
 fn tar_entries() {
     use mini_fs::prelude::*;
     use mini_fs::Tar;
 
+    let file = include_bytes!("archive.tar");
+    let tar = Tar::new(Cursor::new(&file[..])).index().unwrap();
+    assert_eq!(2, tar.entries(".").unwrap().collect::<Vec<_>>().len());
+
+    let file = include_bytes!("archive.tar.gz");
+    let tar = Tar::new(Cursor::new(&file[..])).index().unwrap();
+    assert_eq!(2, tar.entries(".").unwrap().collect::<Vec<_>>().len());
+
+    // the archive is still readable after building the index
+    let mut a_content = String::new();
+    tar.open("a.txt")
+        .unwrap()
+        .read_to_string(&mut a_content)
+        .unwrap();
+    assert_eq!("hello\n", a_content);
+}
+
+#[test]
+#[cfg(feature = "tar")]
+fn tar_indexed_open() {
+    use mini_fs::prelude::*;
+    use mini_fs::Tar;
+    use std::cell::Cell;
+    use std::io::{Seek, SeekFrom};
+    use std::rc::Rc;
+
+    // Counts the bytes read from the archive.
+    struct Counting<R>(R, Rc<Cell<usize>>);
+
+    impl<R: Read> Read for Counting<R> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = self.0.read(buf)?;
+            self.1.set(self.1.get() + n);
+            Ok(n)
+        }
+    }
+
+    impl<R: Seek> Seek for Counting<R> {
+        fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+            self.0.seek(pos)
+        }
+    }
+
+    let file = include_bytes!("archive.tar");
+    let count = Rc::new(Cell::new(0));
+    let tar = Tar::new(Counting(Cursor::new(&file[..]), count.clone()))
+        .index()
+        .unwrap();
+
+    count.set(0);
+    let mut b_content = String::new();
+    tar.open("./b.txt")
+        .unwrap()
+        .read_to_string(&mut b_content)
+        .unwrap();
+    assert_eq!("world!\n", b_content);
+    // only the data of the file is read
+    assert_eq!(7, count.get());
+
+    assert_eq!(
+        std::io::ErrorKind::NotFound,
+        tar.open("nope").err().unwrap().kind()
+    );
+
+    // compressed archives are decompressed up to the file
     let file = include_bytes!("archive.tar.gz");
     let tar = Tar::new(Cursor::new(&file[..])).index().unwrap();
+    for _ in 0..2 {
+        let mut b_content = String::new();
+        tar.open("b.txt")
+            .unwrap()
+            .read_to_string(&mut b_content)
+            .unwrap();
+        assert_eq!("world!\n", b_content);
+    }
+}
+
+#[test]
+#[cfg(feature = "tar")]
+fn tar_case_insensitive() {
+    use mini_fs::prelude::*;
+    use mini_fs::Tar;
+
+    let file = include_bytes!("archive.tar");
+    let tar = Tar::new(Cursor::new(&file[..]))
+        .index_case_insensitive()
+        .unwrap();
+    let mut content = String::new();
+    tar.open("A.TXT")
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    assert_eq!("hello\n", content);
+    assert!(tar.is_file("B.txt"));
+    let names: Vec<_> = tar.entries(".").unwrap().map(|e| e.unwrap().name).collect();
+    assert!(names.contains(&"a.txt".into()));
+
+    let tar = Tar::new(Cursor::new(&file[..])).index().unwrap();
+    assert!(!tar.exists("A.TXT"));
+}
+
+#[test]
+#[cfg(feature = "tar")]
+fn ram_from_tar() {
+    use mini_fs::prelude::*;
+    use mini_fs::{Ram, Tar};
+
+    let file = include_bytes!("archive.tar");
+    let tar = Tar::new(Cursor::new(&file[..])).index().unwrap();
+    let ram = Ram::from_store(&tar).unwrap();
+    let mut a = String::new();
+    ram.open("a.txt").unwrap().read_to_string(&mut a).unwrap();
+    assert_eq!("hello\n", a);
+    assert!(ram.is_file("b.txt"));
+
+    let ram = Ram::from_tar("tests/archive.tar").unwrap();
+    assert!(ram.is_file("a.txt"));
+    assert!(Ram::from_tar("tests/missing.tar").is_err());
+}
+
+#[test]
+#[cfg(feature = "tar")]
+fn tar_limits() {
+    use flate2::write::GzEncoder;
+    use mini_fs::prelude::*;
+    use mini_fs::{Limits, Tar};
+    use std::io::ErrorKind;
+
+    let mut builder = tar_::Builder::new(GzEncoder::new(Vec::new(), Default::default()));
+    for (name, data) in &[
+        ("bomb.bin", vec![0; 1 << 20]),
+        ("small.txt", b"small".to_vec()),
+    ] {
+        let mut header = tar_::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, name, &data[..]).unwrap();
+    }
+    let file = builder.into_inner().unwrap().finish().unwrap();
+    let tar = |limits| Tar::new(Cursor::new(file.clone())).limits(limits);
+
+    assert!(tar(Limits::new()).index().unwrap().open("bomb.bin").is_ok());
+
+    let tar_size = tar(Limits::new().max_file_size(1000)).index().unwrap();
+    let err = tar_size.open("bomb.bin").err().unwrap();
+    assert_eq!(ErrorKind::FileTooLarge, err.kind());
+    assert!(tar_size.open("small.txt").is_ok());
+    let err = tar(Limits::new().max_file_size(1000))
+        .open("bomb.bin")
+        .err()
+        .unwrap();
+    assert_eq!(ErrorKind::FileTooLarge, err.kind());
+
+    let err = tar(Limits::new().max_ratio(100)).index().err().unwrap();
+    assert_eq!(ErrorKind::FileTooLarge, err.kind());
+    let err = tar(Limits::new().max_ratio(100))
+        .open("small.txt")
+        .err()
+        .unwrap();
+    assert_eq!(ErrorKind::FileTooLarge, err.kind());
+
+    let err = tar(Limits::new().max_entries(1)).index().err().unwrap();
+    assert_eq!(ErrorKind::FileTooLarge, err.kind());
+    assert!(tar(Limits::new().max_entries(2)).index().is_ok());
+
+    let err = tar(Limits::new().max_total_size(1 << 20))
+        .index()
+        .err()
+        .unwrap();
+    assert_eq!(ErrorKind::FileTooLarge, err.kind());
+    assert!(tar(Limits::new().max_total_size((1 << 20) + 5))
+        .index()
+        .is_ok());
 }
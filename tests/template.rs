@@ -0,0 +1,47 @@
+use mini_fs::prelude::*;
+use mini_fs::{MiniFs, Ram, Templates};
+use std::io::ErrorKind;
+
+#[test]
+fn templates_vars() {
+    let mut ram = Ram::new();
+    ram.touch("a.toml", &b"dir = \"${DIR}/data\"\nname = \"${NAME}\""[..]);
+    ram.touch("b.txt", &b"$5, $${DIR}, ${DIR"[..]);
+    ram.touch("c.txt", &b"${MISSING}"[..]);
+    ram.touch("d.bin", &b"\xff${DIR}"[..]);
+    let store = Templates::new(ram)
+        .var("DIR", "/usr/share")
+        .var("NAME", "game");
+    let files = MiniFs::new().mount("/config", store);
+
+    assert_eq!(
+        "dir = \"/usr/share/data\"\nname = \"game\"",
+        files.read_to_string("/config/a.toml").unwrap()
+    );
+    assert_eq!(
+        "$5, ${DIR}, ${DIR",
+        files.read_to_string("/config/b.txt").unwrap()
+    );
+    assert_eq!(b"\xff${DIR}", &files.read("/config/d.bin").unwrap()[..]);
+
+    let err = files.open("/config/c.txt").err().unwrap();
+    assert_eq!(ErrorKind::InvalidData, err.kind());
+    assert!(err.to_string().contains("MISSING"));
+}
+
+#[test]
+fn templates_env() {
+    std::env::set_var("MINI_FS_TEMPLATE_TEST", "from env");
+    let mut ram = Ram::new();
+    ram.touch("a.txt", &b"${MINI_FS_TEMPLATE_TEST}"[..]);
+    let store = Templates::new(ram);
+    assert!(store.open("a.txt").is_err());
+
+    let store = Templates::new(store.into_inner()).env();
+    assert_eq!("from env", store.read_to_string("a.txt").unwrap());
+
+    let store = Templates::new(store.into_inner())
+        .var("MINI_FS_TEMPLATE_TEST", "from var")
+        .env();
+    assert_eq!("from var", store.read_to_string("a.txt").unwrap());
+}
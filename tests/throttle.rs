@@ -0,0 +1,33 @@
+use mini_fs::prelude::*;
+use mini_fs::{MiniFs, Ram, Throttle};
+use std::io::{BufRead, BufReader, Read};
+use std::time::{Duration, Instant};
+
+fn store() -> Throttle<Ram> {
+    let mut ram = Ram::new();
+    ram.touch("a.bin", vec![1; 2000]);
+    ram.touch("b.txt", "line\n".repeat(400).into_bytes());
+    Throttle::new(ram, 10_000)
+}
+
+#[test]
+fn throttle_read() {
+    let files = MiniFs::new().mount("/slow", store());
+    let start = Instant::now();
+    assert_eq!(vec![1; 2000], files.read("/slow/a.bin").unwrap());
+    assert!(start.elapsed() >= Duration::from_millis(150));
+    assert_eq!(2000, files.metadata("/slow/a.bin").unwrap().len);
+}
+
+#[test]
+fn throttle_shared() {
+    let store = store();
+    let start = Instant::now();
+    let mut a = store.open("a.bin").unwrap();
+    let b = BufReader::new(store.open("b.txt").unwrap());
+    let mut data = Vec::new();
+    a.read_to_end(&mut data).unwrap();
+    assert_eq!(400, b.lines().count());
+    // both files are served at the same rate
+    assert!(start.elapsed() >= Duration::from_millis(350));
+}
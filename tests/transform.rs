@@ -0,0 +1,43 @@
+use mini_fs::prelude::*;
+use mini_fs::{File, MiniFs, Ram, Transform};
+use std::io::{Error, ErrorKind, Read};
+use std::sync::Arc;
+
+fn upper(_: &std::path::Path, mut file: File) -> std::io::Result<File> {
+    let mut data = String::new();
+    file.read_to_string(&mut data)?;
+    let data: Arc<[u8]> = data.to_uppercase().into_bytes().into();
+    Ok(data.into())
+}
+
+#[test]
+fn transform_stages() {
+    let mut ram = Ram::new();
+    ram.touch("a.txt", &b"hello"[..]);
+    ram.touch("b.bin", &b"hello"[..]);
+    let store = Transform::new(ram).stage(upper).stage(|path, mut file| {
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)?;
+        if path.extension() == Some("txt".as_ref()) {
+            data.push(b'!');
+        }
+        let data: Arc<[u8]> = data.into();
+        Ok(data.into())
+    });
+    let files = MiniFs::new().mount("/data", store);
+
+    assert_eq!("HELLO!", files.read_to_string("/data/a.txt").unwrap());
+    assert_eq!("HELLO", files.read_to_string("/data/b.bin").unwrap());
+    assert_eq!(5, files.metadata("/data/a.txt").unwrap().len);
+}
+
+#[test]
+fn transform_error() {
+    let mut ram = Ram::new();
+    ram.touch("a.txt", &b"hello"[..]);
+    let store = Transform::new(ram)
+        .stage(|_, _| Err(Error::new(ErrorKind::InvalidData, "bad key")))
+        .stage(|_, _| panic!("stage called after an error"));
+    let err = store.open("a.txt").err().unwrap();
+    assert_eq!(ErrorKind::InvalidData, err.kind());
+}
@@ -0,0 +1,74 @@
+#![cfg(feature = "unicode")]
+use std::io::Read;
+
+use mini_fs::index::Index;
+use mini_fs::prelude::*;
+use mini_fs::{MiniFs, Ram};
+
+// "café" with a combining accent, like macOS writes it.
+const DECOMPOSED: &str = "cafe\u{301}/me\u{301}nu.txt";
+const COMPOSED: &str = "caf\u{e9}/m\u{e9}nu.txt";
+
+#[test]
+fn index_unicode() {
+    let mut index = Index::new().unicode_normalized();
+    index.insert(DECOMPOSED, 1);
+    assert_eq!(Some(&1), index.get(COMPOSED));
+    assert_eq!(Some(&1), index.get(DECOMPOSED));
+    assert!(index.contains_dir("caf\u{e9}"));
+    let names: Vec<_> = index.entries("caf\u{e9}").map(|e| e.name).collect();
+    assert_eq!(vec!["me\u{301}nu.txt"], names);
+
+    // other foldings are kept, also when they're set afterwards
+    let mut index = Index::new();
+    index.insert(DECOMPOSED, 1);
+    assert_eq!(None, index.get(COMPOSED));
+    let index = index.case_insensitive().unicode_normalized();
+    assert_eq!(Some(&1), index.get("CAF\u{e9}/M\u{e9}NU.TXT"));
+}
+
+#[test]
+fn ram_unicode() {
+    let mut ram = Ram::new();
+    ram.touch(DECOMPOSED, b"menu".to_vec());
+    assert!(!ram.is_file(COMPOSED));
+    let ram = ram.unicode_normalized();
+    assert!(ram.is_file(COMPOSED));
+
+    let fs = MiniFs::new().mount("/", ram);
+    let mut content = String::new();
+    fs.open(COMPOSED)
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    assert_eq!("menu", content);
+}
+
+#[test]
+#[cfg(feature = "tar")]
+fn tar_unicode() {
+    use mini_fs::Tar;
+    use std::io::Cursor;
+
+    let mut builder = tar_::Builder::new(Vec::new());
+    let mut header = tar_::Header::new_gnu();
+    header.set_size(4);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, DECOMPOSED, &b"menu"[..])
+        .unwrap();
+    let file = builder.into_inner().unwrap();
+
+    let tar = Tar::new(Cursor::new(&file[..]))
+        .unicode_normalized()
+        .unwrap();
+    assert!(tar.is_file(COMPOSED));
+    assert!(tar.is_file(DECOMPOSED));
+
+    let tar = Tar::new(Cursor::new(&file[..]))
+        .index_case_insensitive()
+        .unwrap()
+        .unicode_normalized()
+        .unwrap();
+    assert!(tar.is_file("CAF\u{e9}/m\u{e9}nu.TXT"));
+}
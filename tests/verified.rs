@@ -0,0 +1,61 @@
+#![cfg(feature = "signatures")]
+use ed25519_dalek::{Signer, SigningKey};
+use mini_fs::prelude::*;
+use mini_fs::{MiniFs, Ram, Verified};
+use sha2::{Digest, Sha256};
+use std::io::ErrorKind;
+
+fn hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn pack() -> (Ram, [u8; 32]) {
+    let key = SigningKey::from_bytes(&[3; 32]);
+    let manifest = format!(
+        "{}  maps/e1m1.bsp\n{} *readme.txt\n",
+        hex(b"map"),
+        hex(b"readme")
+    );
+    let mut ram = Ram::new();
+    ram.touch("maps/e1m1.bsp", &b"map"[..]);
+    ram.touch("readme.txt", &b"tampered"[..]);
+    ram.touch("extra.txt", &b"extra"[..]);
+    ram.touch("MANIFEST.sig", key.sign(manifest.as_bytes()).to_vec());
+    ram.touch("MANIFEST", manifest.into_bytes());
+    (ram, key.verifying_key().to_bytes())
+}
+
+#[test]
+fn verified_open() {
+    let (ram, public_key) = pack();
+    let pack = Verified::load(ram, "MANIFEST", &public_key).unwrap();
+    let files = MiniFs::new().mount("/pack", pack);
+
+    assert_eq!(b"map", &files.read("/pack/maps/e1m1.bsp").unwrap()[..]);
+    let err = files.open("/pack/readme.txt").err().unwrap();
+    assert_eq!(ErrorKind::InvalidData, err.kind());
+    let err = files.open("/pack/extra.txt").err().unwrap();
+    assert_eq!(ErrorKind::PermissionDenied, err.kind());
+    assert!(files.create("/pack/maps/e1m1.bsp").is_err());
+}
+
+#[test]
+fn verified_bad_signature() {
+    let (mut ram, _) = pack();
+    let other = SigningKey::from_bytes(&[4; 32]).verifying_key().to_bytes();
+    ram.rm("MANIFEST.sig");
+    let err = Verified::load(ram, "MANIFEST", &other).err().unwrap();
+    assert_eq!(ErrorKind::NotFound, err.kind());
+
+    let (ram, _) = pack();
+    let err = Verified::load(ram, "MANIFEST", &other).err().unwrap();
+    assert_eq!(ErrorKind::InvalidData, err.kind());
+
+    let (mut ram, public_key) = pack();
+    ram.touch("MANIFEST", &b"tampered manifest"[..]);
+    let err = Verified::load(ram, "MANIFEST", &public_key).err().unwrap();
+    assert_eq!(ErrorKind::InvalidData, err.kind());
+}
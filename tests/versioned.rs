@@ -0,0 +1,71 @@
+use std::io::{self, ErrorKind, Write};
+
+use mini_fs::prelude::*;
+use mini_fs::{MiniFs, Versioned};
+
+#[test]
+fn versioned_open() {
+    let mut store = Versioned::new();
+    store.touch("a.txt", &b"v1"[..]);
+    store.touch("a.txt", &b"v2"[..]);
+    {
+        let mut file = store.create("a.txt").unwrap();
+        file.write_all(b"v").unwrap();
+        file.flush().unwrap();
+        file.write_all(b"3").unwrap();
+    }
+    assert_eq!(3, store.versions("a.txt"));
+    assert_eq!(3, store.versions("./b/../a.txt"));
+    assert_eq!(
+        "v3",
+        io::read_to_string(store.open("a.txt").unwrap()).unwrap()
+    );
+    assert_eq!(
+        "v1",
+        io::read_to_string(store.open_version("a.txt", 0).unwrap()).unwrap()
+    );
+    assert_eq!(
+        "v2",
+        io::read_to_string(store.open_version("a.txt", 1).unwrap()).unwrap()
+    );
+    assert_eq!(
+        "v3",
+        io::read_to_string(store.open_version("a.txt", 2).unwrap()).unwrap()
+    );
+    assert_eq!(
+        ErrorKind::NotFound,
+        store.open_version("a.txt", 3).err().unwrap().kind()
+    );
+    assert_eq!(2, store.metadata("a.txt").unwrap().len);
+    assert_eq!(0, store.versions("b.txt"));
+}
+
+#[test]
+fn versioned_write() {
+    let mut store = Versioned::new();
+    store.touch("dir/a.txt", &b"v1"[..]);
+    store.touch("dir/a.txt", &b"v2"[..]);
+    store.rename("dir/a.txt", "b.txt").unwrap();
+    assert!(!store.is_dir("dir"));
+    assert_eq!(2, store.versions("b.txt"));
+    assert_eq!(
+        "v1",
+        io::read_to_string(store.open_version("b.txt", 0).unwrap()).unwrap()
+    );
+
+    store.remove("b.txt").unwrap();
+    assert_eq!(0, store.versions("b.txt"));
+    assert!(store.open("b.txt").is_err());
+}
+
+#[test]
+fn versioned_mount() {
+    let mut store = Versioned::new();
+    store.touch("a.txt", &b"v1"[..]);
+    store.touch("a.txt", &b"v2"[..]);
+    let fs = MiniFs::new().mount("/assets", store);
+    assert_eq!(
+        "v2",
+        io::read_to_string(fs.open("/assets/a.txt").unwrap()).unwrap()
+    );
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "vpk")]
+
+use std::io::ErrorKind;
+
+use mini_fs::prelude::*;
+use mini_fs::{EntryKind, Vpk};
+
+mod common;
+
+fn vpk() -> Vpk {
+    Vpk::open("tests/vpk/pak01_dir.vpk").unwrap()
+}
+
+#[test]
+fn vpk_read() {
+    let vpk = vpk();
+    for _ in 0..4 {
+        // stored in the directory file
+        assert_eq!("hello\n", vpk.read_to_string("a.txt").unwrap());
+        // stored in pak01_000.vpk
+        assert_eq!("world!\n", vpk.read_to_string("dir/b.txt").unwrap());
+    }
+    // preload data comes first
+    assert_eq!(
+        "preload\n",
+        vpk.read_to_string("materials/brick/wall.vmt").unwrap()
+    );
+    assert_eq!("x\n", vpk.read_to_string("dir/noext").unwrap());
+    assert_eq!(ErrorKind::NotFound, vpk.open("nope").err().unwrap().kind());
+}
+
+#[test]
+fn vpk_invalid() {
+    assert_eq!(
+        ErrorKind::InvalidData,
+        Vpk::open("tests/vpk/pak01_000.vpk").err().unwrap().kind()
+    );
+    assert_eq!(
+        ErrorKind::NotFound,
+        Vpk::open("tests/vpk/nope_dir.vpk").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn vpk_entries() {
+    let vpk = vpk();
+    assert_eq!(vec!["a.txt", "dir", "materials"], common::names(&vpk, "."));
+    assert_eq!(vec!["b.txt", "noext"], common::names(&vpk, "dir"));
+    assert_eq!(vec!["wall.vmt"], common::names(&vpk, "materials/brick"));
+}
+
+#[test]
+fn vpk_metadata() {
+    let vpk = vpk();
+
+    let meta = vpk.metadata("materials/brick/wall.vmt").unwrap();
+    assert_eq!(EntryKind::File, meta.kind);
+    assert_eq!(8, meta.len);
+    assert_eq!(None, meta.modified);
+
+    assert_eq!(EntryKind::Dir, vpk.metadata("materials").unwrap().kind);
+    assert!(vpk.metadata("nope").is_err());
+}
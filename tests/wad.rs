@@ -0,0 +1,53 @@
+#![cfg(feature = "wad")]
+
+use std::io::{Cursor, ErrorKind};
+
+use mini_fs::prelude::*;
+use mini_fs::{EntryKind, Ram, Wad};
+
+mod common;
+
+fn wad() -> Wad<Cursor<&'static [u8]>> {
+    Wad::new(Cursor::new(&include_bytes!("archive.wad")[..])).unwrap()
+}
+
+#[test]
+fn wad_lumps() {
+    let wad = wad();
+    assert_eq!("pal", wad.read_to_string("PLAYPAL").unwrap());
+    // the last lump with a name is used
+    assert_eq!("new", wad.read_to_string("ENDOOM").unwrap());
+    assert_eq!(ErrorKind::NotFound, wad.open("nope").err().unwrap().kind());
+    assert!(Wad::new(Cursor::new(&b"not a wad"[..])).is_err());
+}
+
+#[test]
+fn wad_maps() {
+    let wad = wad();
+    assert_eq!(vec!["LINEDEFS", "THINGS"], common::names(&wad, "E1M1"));
+    assert_eq!("things", wad.read_to_string("E1M1/THINGS").unwrap());
+    assert_eq!(vec!["TEXTMAP", "ZNODES"], common::names(&wad, "MAP01"));
+    assert_eq!(EntryKind::Dir, wad.metadata("MAP01").unwrap().kind);
+}
+
+#[test]
+fn wad_namespaces() {
+    let wad = wad();
+    assert_eq!(
+        vec!["E1M1", "ENDOOM", "MAP01", "PLAYPAL", "flats", "sprites"],
+        common::names(&wad, ".")
+    );
+    assert_eq!("floor", wad.read_to_string("flats/FLOOR1").unwrap());
+    // nested markers are skipped
+    assert_eq!(vec!["TROOA1"], common::names(&wad, "sprites"));
+    assert_eq!(3, wad.metadata("sprites/TROOA1").unwrap().len);
+}
+
+#[test]
+fn wad_layered() {
+    let mut pwad = Ram::new();
+    pwad.touch("flats/FLOOR1", b"custom".to_vec());
+    let fs = (pwad, wad());
+    assert_eq!("custom", fs.read_to_string("flats/FLOOR1").unwrap());
+    assert_eq!("pal", fs.read_to_string("PLAYPAL").unwrap());
+}
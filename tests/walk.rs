@@ -0,0 +1,67 @@
+use mini_fs::prelude::*;
+use mini_fs::{Local, MiniFs, Ram};
+use std::io::{Read, Result};
+use std::path::PathBuf;
+
+#[test]
+fn local_walk() {
+    let local = Local::new("./tests/local");
+
+    let mut paths = local.walk("./").collect::<Result<Vec<_>>>().unwrap();
+    paths.sort();
+
+    assert_eq!(
+        vec![
+            PathBuf::from("bar"),
+            PathBuf::from("baz/foobar"),
+            PathBuf::from("foo"),
+        ],
+        paths
+    );
+}
+
+#[test]
+fn mini_fs_walk() {
+    let mut a = Ram::new();
+    let mut b = Ram::new();
+    a.touch("a.txt", &b"a"[..]);
+    a.touch("nested/b.txt", &b"b"[..]);
+    b.touch("a.txt", &b"overriden"[..]);
+    b.touch("nested/deeper/c.txt", &b"c"[..]);
+
+    // `b` and `a` are merged, and `/res/local` is nested inside of them.
+    let fs = MiniFs::new()
+        .mount("/res", (b, a))
+        .mount("/res/local", Local::new("./tests/local"));
+
+    let mut paths = fs.walk("/res").collect::<Result<Vec<_>>>().unwrap();
+    paths.sort();
+
+    assert_eq!(
+        vec![
+            PathBuf::from("/res/a.txt"),
+            PathBuf::from("/res/local/bar"),
+            PathBuf::from("/res/local/baz/foobar"),
+            PathBuf::from("/res/local/foo"),
+            PathBuf::from("/res/nested/b.txt"),
+            PathBuf::from("/res/nested/deeper/c.txt"),
+        ],
+        paths
+    );
+
+    // every walked path can be opened
+    for path in paths {
+        let mut content = Vec::new();
+        fs.open(&path).unwrap().read_to_end(&mut content).unwrap();
+    }
+
+    let mut a_content = String::new();
+    fs.open("/res/a.txt")
+        .unwrap()
+        .read_to_string(&mut a_content)
+        .unwrap();
+    assert_eq!("overriden", a_content);
+
+    assert_eq!(6, fs.walk("/").count());
+    assert_eq!(0, fs.walk("/nope").count());
+}
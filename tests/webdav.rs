@@ -0,0 +1,217 @@
+#![cfg(feature = "webdav")]
+
+use std::cell::RefCell;
+use std::io::{self, ErrorKind, Read, Result};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::{Duration, UNIX_EPOCH};
+
+use mini_fs::http::HttpClient;
+use mini_fs::prelude::*;
+use mini_fs::{EntryKind, MiniFs, WebDav};
+
+mod common;
+
+const ROOT: &str = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:" xmlns:oc="http://owncloud.org/ns">
+  <d:response>
+    <d:href>/dav/</d:href>
+    <d:propstat>
+      <d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/dav/a%20b.txt</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:resourcetype/>
+        <d:getcontentlength>5</d:getcontentlength>
+        <d:getlastmodified>Sun, 06 Nov 1994 08:49:37 GMT</d:getlastmodified>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>https://cloud.example.com/dav/textures/</d:href>
+    <d:propstat>
+      <d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+    <d:propstat>
+      <d:prop><d:getcontentlength/></d:prop>
+      <d:status>HTTP/1.1 404 Not Found</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+
+const TEXTURES: &str = r#"<?xml version="1.0"?>
+<multistatus xmlns="DAV:">
+  <response>
+    <href>/dav/textures/wall.png</href>
+    <propstat>
+      <prop><resourcetype/><getcontentlength>3</getcontentlength></prop>
+      <status>HTTP/1.1 200 OK</status>
+    </propstat>
+  </response>
+  <response>
+    <href>/dav/textures/</href>
+    <propstat>
+      <prop><resourcetype><collection/></resourcetype></prop>
+      <status>HTTP/1.1 200 OK</status>
+    </propstat>
+  </response>
+</multistatus>"#;
+
+// Nextcloud-like share under `https://cloud.example.com/dav`.
+#[derive(Default)]
+struct Share {
+    requests: Rc<Requests>,
+}
+
+type Requests = RefCell<Vec<(String, String, Vec<(String, String)>)>>;
+
+impl HttpClient for Share {
+    fn get(&self, url: &str, headers: &[(String, String)]) -> io::Result<Vec<u8>> {
+        self.request("GET", url, headers, &[])
+    }
+
+    fn request(
+        &self,
+        method: &str,
+        url: &str,
+        headers: &[(String, String)],
+        _body: &[u8],
+    ) -> io::Result<Vec<u8>> {
+        self.requests
+            .borrow_mut()
+            .push((method.to_string(), url.to_string(), headers.to_vec()));
+        let depth = headers
+            .iter()
+            .find(|(name, _)| name == "Depth")
+            .map(|(_, depth)| depth.as_str());
+        let body = match (method, url, depth) {
+            ("GET", "https://cloud.example.com/dav/a%20b.txt", _) => "hello".to_string(),
+            ("GET", "https://cloud.example.com/dav/textures/wall.png", _) => "png".to_string(),
+            ("PROPFIND", "https://cloud.example.com/dav/", Some("1")) => ROOT.to_string(),
+            ("PROPFIND", "https://cloud.example.com/dav/textures/", Some("1")) => {
+                TEXTURES.to_string()
+            }
+            // the file is the only resource in the response
+            ("PROPFIND", "https://cloud.example.com/dav/a%20b.txt", Some("0")) => {
+                let start = ROOT.find("<d:response>\n    <d:href>/dav/a").unwrap();
+                let end = ROOT.find("<d:response>\n    <d:href>https").unwrap();
+                format!(
+                    "<d:multistatus xmlns:d=\"DAV:\">{}</d:multistatus>",
+                    &ROOT[start..end]
+                )
+            }
+            ("PROPFIND", "https://cloud.example.com/dav/textures", Some("0")) => {
+                let start = TEXTURES.rfind("<response>").unwrap();
+                let end = TEXTURES.rfind("</multistatus>").unwrap();
+                format!(
+                    "<multistatus xmlns=\"DAV:\">{}</multistatus>",
+                    &TEXTURES[start..end]
+                )
+            }
+            _ => return Err(io::Error::from(ErrorKind::NotFound)),
+        };
+        Ok(body.as_bytes().to_vec())
+    }
+}
+
+fn share() -> WebDav<Share> {
+    WebDav::with_client("https://cloud.example.com/dav", Share::default())
+}
+
+#[test]
+fn webdav_open() {
+    let dav = share();
+    let mut content = String::new();
+    dav.open("/a b.txt")
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    assert_eq!("hello", content);
+    assert_eq!(
+        ErrorKind::NotFound,
+        dav.open("missing.txt").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn webdav_entries() {
+    let dav = share();
+    assert_eq!(
+        vec![
+            ("a b.txt".to_string(), EntryKind::File),
+            ("textures".to_string(), EntryKind::Dir),
+        ],
+        common::entries(&dav, "/"),
+    );
+    assert_eq!(
+        vec![("wall.png".to_string(), EntryKind::File)],
+        common::entries(&dav, "textures"),
+    );
+    assert_eq!(
+        ErrorKind::NotFound,
+        dav.entries("missing").err().unwrap().kind()
+    );
+
+    let mut paths = dav.walk("/").collect::<Result<Vec<_>>>().unwrap();
+    paths.sort();
+    assert_eq!(
+        vec![
+            PathBuf::from("/a b.txt"),
+            PathBuf::from("/textures/wall.png"),
+        ],
+        paths
+    );
+}
+
+#[test]
+fn webdav_metadata() {
+    let dav = share();
+    let meta = dav.metadata("a b.txt").unwrap();
+    assert_eq!(EntryKind::File, meta.kind);
+    assert_eq!(5, meta.len);
+    assert_eq!(
+        Some(UNIX_EPOCH + Duration::from_secs(784111777)),
+        meta.modified
+    );
+    assert_eq!(EntryKind::Dir, dav.metadata("textures").unwrap().kind);
+    assert_eq!(
+        ErrorKind::NotFound,
+        dav.metadata("missing").err().unwrap().kind()
+    );
+}
+
+#[test]
+fn webdav_auth() {
+    let share = Share::default();
+    let requests = Rc::clone(&share.requests);
+    let dav = WebDav::with_client("https://cloud.example.com/dav", share)
+        .basic_auth("Aladdin", "open sesame");
+    dav.open("a b.txt").unwrap();
+    dav.entries("/").unwrap();
+    let requests = requests.borrow();
+    assert_eq!(2, requests.len());
+    for (_, _, headers) in requests.iter() {
+        assert!(headers.contains(&(
+            "Authorization".to_string(),
+            "Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ==".to_string()
+        )));
+    }
+}
+
+#[test]
+fn webdav_mount() {
+    let fs = MiniFs::new().mount("/assets", share());
+    let mut content = String::new();
+    fs.open("/assets/textures/wall.png")
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    assert_eq!("png", content);
+    assert!(fs.is_dir("/assets/textures"));
+}
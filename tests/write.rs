@@ -0,0 +1,281 @@
+use mini_fs::prelude::*;
+use mini_fs::{Local, MiniFs, Ram};
+use std::io::{ErrorKind, Read, Write};
+
+mod common;
+
+#[test]
+fn ram_create() {
+    let ram = Ram::new();
+
+    let mut file = ram.create("a/b.txt").unwrap();
+    file.write_all(b"hello").unwrap();
+    drop(file);
+
+    let mut content = String::new();
+    ram.open("a/b.txt")
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    assert_eq!("hello", content);
+
+    // flushed contents are visible while the file is still open.
+    let mut file = ram.create("a/b.txt").unwrap();
+    assert_eq!(0, ram.open("a/b.txt").unwrap().read(&mut [0; 4]).unwrap());
+    file.write_all(b"world").unwrap();
+    file.flush().unwrap();
+
+    let mut content = String::new();
+    ram.open("a/b.txt")
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    assert_eq!("world", content);
+
+    // directories, and files in place of directories, aren't replaced
+    let err = ram.create("a").err().unwrap();
+    assert_eq!(ErrorKind::IsADirectory, err.kind());
+    let err = ram.create("a/b.txt/c.txt").err().unwrap();
+    assert_eq!(ErrorKind::NotADirectory, err.kind());
+    assert!(ram.open("a/b.txt").is_ok());
+}
+
+#[test]
+fn ram_clone() {
+    use std::io::BufRead;
+    use std::sync::Arc;
+
+    let data: Arc<[u8]> = Arc::from(&b"shared"[..]);
+    let mut ram = Ram::new();
+    ram.touch("a.txt", Arc::clone(&data));
+    let copy = ram.clone();
+
+    // opening a file, in either store, doesn't copy its contents
+    for ram in &[&ram, &copy] {
+        let mut file = ram.open("a.txt").unwrap();
+        assert_eq!(data.as_ptr(), file.fill_buf().unwrap().as_ptr());
+    }
+
+    // but the stores are independent
+    copy.create("b.txt").unwrap().write_all(b"b").unwrap();
+    assert!(copy.exists("b.txt"));
+    assert!(!ram.exists("b.txt"));
+}
+
+#[test]
+fn ram_snapshot() {
+    use std::io::BufRead;
+    use std::sync::Arc;
+
+    let data: Arc<[u8]> = Arc::from(&b"shared"[..]);
+    let mut ram = Ram::new();
+    ram.touch("a.txt", Arc::clone(&data));
+    let snapshot = ram.snapshot();
+
+    ram.create("a.txt").unwrap().write_all(b"changed").unwrap();
+    ram.create("b.txt").unwrap().write_all(b"b").unwrap();
+    ram.restore(snapshot.clone());
+    assert!(!ram.exists("b.txt"));
+
+    // the snapshot doesn't copy the contents of the files
+    let mut file = ram.open("a.txt").unwrap();
+    assert_eq!(data.as_ptr(), file.fill_buf().unwrap().as_ptr());
+
+    // and can be restored again
+    ram.remove("a.txt").unwrap();
+    ram.restore(snapshot);
+    assert!(ram.exists("a.txt"));
+}
+
+#[test]
+fn local_create() {
+    let root = common::tmp_dir("local_create");
+    let local = Local::new(&root);
+
+    local
+        .create("nested/hello.txt")
+        .unwrap()
+        .write_all(b"hello")
+        .unwrap();
+
+    assert_eq!(
+        "hello",
+        std::fs::read_to_string(root.join("nested/hello.txt")).unwrap()
+    );
+}
+
+#[test]
+#[cfg(feature = "tar")]
+fn mini_fs_create() {
+    use mini_fs::Tar;
+
+    let tar = Tar::open("tests/archive.tar").unwrap();
+    let mut fs = MiniFs::new()
+        .mount("/files", Ram::new())
+        .mount("/files", tar);
+
+    // the tar archive is read-only, so the file ends up in the Ram store.
+    fs.create("/files/c.txt")
+        .unwrap()
+        .write_all(b"hello")
+        .unwrap();
+
+    let tar = fs.umount("/files").unwrap();
+    assert!(tar.open_path("c.txt".as_ref()).is_err());
+    let ram = fs.umount("/files").unwrap();
+    assert!(ram.open_path("c.txt".as_ref()).is_ok());
+
+    let err = MiniFs::new()
+        .mount("/files", Tar::open("tests/archive.tar").unwrap())
+        .create("/files/c.txt")
+        .err()
+        .unwrap();
+    assert_eq!(ErrorKind::ReadOnlyFilesystem, err.kind());
+    assert!(MiniFs::new().create("/nope.txt").is_err());
+}
+
+#[test]
+fn tuple_create() {
+    let mut a = Ram::new();
+    a.touch("a.txt", &b"a"[..]);
+    let files = (a, Ram::new());
+
+    files.create("b.txt").unwrap().write_all(b"b").unwrap();
+
+    let (a, b) = files;
+    assert!(a.open("b.txt").is_ok());
+    assert!(b.open("b.txt").is_err());
+}
+
+#[test]
+fn ram_remove() {
+    let mut ram = Ram::new();
+    ram.touch("a.txt", &b"a"[..]);
+    ram.touch("nested/b.txt", &b"b"[..]);
+
+    ram.remove("nested/b.txt").unwrap();
+    assert!(ram.open("nested/b.txt").is_err());
+    assert_eq!(1, ram.entries(".").unwrap().count());
+
+    let err = ram.remove("nested/b.txt").err().unwrap();
+    assert_eq!(ErrorKind::NotFound, err.kind());
+
+    ram.rm("a.txt");
+    assert!(ram.open("a.txt").is_err());
+}
+
+#[test]
+fn local_remove() {
+    let root = common::tmp_dir("local_remove");
+    let local = Local::new(&root);
+
+    local.create("hello.txt").unwrap();
+    assert!(root.join("hello.txt").exists());
+
+    local.remove("hello.txt").unwrap();
+    assert!(!root.join("hello.txt").exists());
+}
+
+#[test]
+#[cfg(feature = "tar")]
+fn mini_fs_remove() {
+    use mini_fs::Tar;
+
+    let tar = Tar::open("tests/archive.tar").unwrap();
+    let fs = MiniFs::new().mount("/files", tar);
+
+    let err = fs.remove("/files/a.txt").err().unwrap();
+    assert_eq!(ErrorKind::ReadOnlyFilesystem, err.kind());
+
+    let mut ram = Ram::new();
+    ram.touch("c.txt", &b"c"[..]);
+    let fs = MiniFs::new()
+        .mount("/files", ram)
+        .mount("/files", Tar::open("tests/archive.tar").unwrap());
+
+    fs.remove("/files/c.txt").unwrap();
+    let err = MiniFs::new()
+        .mount("/files", Ram::new())
+        .remove("/files/c.txt")
+        .err()
+        .unwrap();
+    assert_eq!(ErrorKind::NotFound, err.kind());
+}
+
+#[test]
+fn ram_rename() {
+    let mut ram = Ram::new();
+    ram.touch("slot0.sav", &b"0"[..]);
+
+    ram.rename("slot0.sav", "backup/slot1.sav").unwrap();
+    assert!(ram.open("slot0.sav").is_err());
+    assert!(ram.open("backup/slot1.sav").is_ok());
+
+    let err = ram.rename("slot0.sav", "slot2.sav").err().unwrap();
+    assert_eq!(ErrorKind::NotFound, err.kind());
+
+    // renaming over a directory, or below a file, keeps both files
+    ram.touch("slot2.sav", &b"2"[..]);
+    let err = ram.rename("slot2.sav", "backup").err().unwrap();
+    assert_eq!(ErrorKind::IsADirectory, err.kind());
+    let err = ram.rename("slot2.sav", "backup/slot1.sav/a").err().unwrap();
+    assert_eq!(ErrorKind::NotADirectory, err.kind());
+    assert!(ram.open("slot2.sav").is_ok());
+    assert!(ram.open("backup/slot1.sav").is_ok());
+}
+
+#[test]
+fn local_rename() {
+    let root = common::tmp_dir("local_rename");
+    let local = Local::new(&root);
+
+    local.create("a.txt").unwrap().write_all(b"a").unwrap();
+    local.rename("a.txt", "nested/b.txt").unwrap();
+
+    assert!(!root.join("a.txt").exists());
+    assert_eq!(
+        "a",
+        std::fs::read_to_string(root.join("nested/b.txt")).unwrap()
+    );
+}
+
+#[test]
+fn mini_fs_rename() {
+    let mut ram = Ram::new();
+    ram.touch("a.txt", &b"hello"[..]);
+
+    let fs = MiniFs::new().mount("/a", ram).mount("/b", Ram::new());
+
+    // same mount
+    fs.rename("/a/a.txt", "/a/b.txt").unwrap();
+    assert!(fs.open("/a/a.txt").is_err());
+
+    // different mounts
+    fs.rename("/a/b.txt", "/b/c.txt").unwrap();
+    assert!(fs.open("/a/b.txt").is_err());
+
+    let mut content = String::new();
+    fs.open("/b/c.txt")
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    assert_eq!("hello", content);
+
+    let err = fs.rename("/a/nope.txt", "/b/nope.txt").err().unwrap();
+    assert_eq!(ErrorKind::NotFound, err.kind());
+}
+
+#[test]
+#[cfg(feature = "tar")]
+fn mini_fs_rename_read_only() {
+    use mini_fs::Tar;
+
+    let fs = MiniFs::new()
+        .mount("/tar", Tar::open("tests/archive.tar").unwrap())
+        .mount("/ram", Ram::new());
+
+    let err = fs.rename("/tar/a.txt", "/ram/a.txt").err().unwrap();
+    assert_eq!(ErrorKind::ReadOnlyFilesystem, err.kind());
+    assert!(fs.open("/tar/a.txt").is_ok());
+    assert!(fs.open("/ram/a.txt").is_err());
+}
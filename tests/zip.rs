@@ -1,5 +1,5 @@
+#[cfg(feature = "zip")]
 use std::io::{Cursor, Read};
-use std::path::Path;
 
 #[test]
 #[cfg(feature = "zip")]
@@ -34,3 +34,262 @@ fn zip_entries() {
     assert_eq!(2, zip.entries("nested").unwrap().collect::<Vec<_>>().len());
     assert_eq!(3, zip.entries(".").unwrap().collect::<Vec<_>>().len());
 }
+
+#[test]
+#[cfg(feature = "zip")]
+fn zip_password() {
+    use mini_fs::prelude::*;
+    use mini_fs::Zip;
+    use std::io::ErrorKind;
+
+    let file = include_bytes!("archive-zipcrypto.zip");
+    let zip = Zip::with_password(Cursor::new(&file[..]), "hunter2")
+        .index()
+        .unwrap();
+
+    let mut a = String::new();
+    zip.open("a.txt").unwrap().read_to_string(&mut a).unwrap();
+    assert_eq!("secret", a);
+    // deflated and encrypted
+    let mut b = String::new();
+    zip.open("dir/b.txt")
+        .unwrap()
+        .read_to_string(&mut b)
+        .unwrap();
+    assert_eq!("nested secret\n".repeat(20), b);
+    assert_eq!(280, zip.metadata("dir/b.txt").unwrap().len);
+    assert_eq!(2, zip.entries(".").unwrap().count());
+
+    let zip = Zip::with_password(Cursor::new(&file[..]), "wrong");
+    let err = zip.open("a.txt").err().unwrap();
+    assert_eq!(ErrorKind::PermissionDenied, err.kind());
+
+    // encrypted files can be listed without the password, but not read
+    let zip = Zip::new(Cursor::new(&file[..])).index().unwrap();
+    assert_eq!(6, zip.metadata("a.txt").unwrap().len);
+    assert!(zip.open("a.txt").is_err());
+}
+
+// Archive of `count` empty files, with a Zip64 end of central directory record.
+#[cfg(feature = "zip")]
+fn zip64(count: u32) -> Vec<u8> {
+    let mut data = Vec::new();
+    let mut central = Vec::new();
+    for i in 0..count {
+        let name = format!("files/{}.txt", i);
+        let pos = data.len() as u32;
+        // local header, version 4.5, stored, 1980-01-01, empty
+        data.extend_from_slice(&[0x50, 0x4b, 0x03, 0x04, 45, 0, 0, 0, 0, 0, 0, 0, 0x21, 0]);
+        data.extend_from_slice(&[0; 12]);
+        data.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        data.extend_from_slice(&[0, 0]);
+        data.extend_from_slice(name.as_bytes());
+        // central directory header
+        central.extend_from_slice(&[0x50, 0x4b, 0x01, 0x02, 45, 0, 45, 0, 0, 0, 0, 0, 0, 0]);
+        central.extend_from_slice(&[0x21, 0]);
+        central.extend_from_slice(&[0; 12]);
+        central.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        central.extend_from_slice(&[0; 12]);
+        central.extend_from_slice(&pos.to_le_bytes());
+        central.extend_from_slice(name.as_bytes());
+    }
+    let central_pos = data.len() as u64;
+    data.extend_from_slice(&central);
+
+    let end_pos = data.len() as u64;
+    data.extend_from_slice(&[0x50, 0x4b, 0x06, 0x06]);
+    data.extend_from_slice(&44u64.to_le_bytes());
+    data.extend_from_slice(&[45, 0, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    data.extend_from_slice(&u64::from(count).to_le_bytes());
+    data.extend_from_slice(&u64::from(count).to_le_bytes());
+    data.extend_from_slice(&(central.len() as u64).to_le_bytes());
+    data.extend_from_slice(&central_pos.to_le_bytes());
+    // locator
+    data.extend_from_slice(&[0x50, 0x4b, 0x06, 0x07, 0, 0, 0, 0]);
+    data.extend_from_slice(&end_pos.to_le_bytes());
+    data.extend_from_slice(&[1, 0, 0, 0]);
+    // classic record, with every field saturated
+    data.extend_from_slice(&[0x50, 0x4b, 0x05, 0x06, 0, 0, 0, 0]);
+    data.extend_from_slice(&[0xff; 12]);
+    data.extend_from_slice(&[0, 0]);
+    data
+}
+
+#[test]
+#[cfg(feature = "zip")]
+fn zip64_entries() {
+    use mini_fs::prelude::*;
+    use mini_fs::{MiniFs, Zip};
+
+    // more entries than the classic end of central directory can count
+    let count = 70_000;
+    let zip = Zip::new(Cursor::new(zip64(count))).index().unwrap();
+    assert_eq!(count as usize, zip.entries("files").unwrap().count());
+    assert!(zip.is_file("files/0.txt"));
+    assert!(zip.is_file("files/69999.txt"));
+
+    let fs = MiniFs::new().mount("/textures", zip);
+    let mut content = Vec::new();
+    fs.open("/textures/files/65536.txt")
+        .unwrap()
+        .read_to_end(&mut content)
+        .unwrap();
+    assert!(content.is_empty());
+}
+
+#[test]
+#[cfg(feature = "zip")]
+fn zip_deflate_stream() {
+    use mini_fs::prelude::*;
+    use mini_fs::Zip;
+    use std::io::{BufRead, Seek, SeekFrom};
+
+    let file = include_bytes!("archive-deflate.zip");
+    let line = |i: u64| format!("line {:05}\n", i);
+
+    for zip in [
+        Zip::new(Cursor::new(&file[..])),
+        Zip::new(Cursor::new(&file[..])).index().unwrap(),
+    ] {
+        let mut lines = zip.open("lines.txt").unwrap();
+        assert_eq!(44_000, zip.metadata("lines.txt").unwrap().len);
+        let mut first = String::new();
+        lines.read_line(&mut first).unwrap();
+        assert_eq!(line(0), first);
+
+        // forwards, then backwards past the start of the last read
+        for i in [3999, 1234, 5, 3000] {
+            let mut content = String::new();
+            assert_eq!(i * 11, lines.seek(SeekFrom::Start(i * 11)).unwrap());
+            lines.read_line(&mut content).unwrap();
+            assert_eq!(line(i), content);
+        }
+        assert_eq!(43_989, lines.seek(SeekFrom::End(-11)).unwrap());
+        assert_eq!(43_967, lines.seek(SeekFrom::Current(-22)).unwrap());
+
+        lines.seek(SeekFrom::Start(0)).unwrap();
+        let mut content = String::new();
+        lines.read_to_string(&mut content).unwrap();
+        assert_eq!((0..4000).map(line).collect::<String>(), content);
+
+        let mut stored = String::new();
+        zip.open("stored.txt")
+            .unwrap()
+            .read_to_string(&mut stored)
+            .unwrap();
+        assert_eq!("stored\n", stored);
+    }
+}
+
+#[test]
+#[cfg(feature = "zip")]
+fn zip_deflate_checksum() {
+    use mini_fs::prelude::*;
+    use mini_fs::Zip;
+
+    // break the checksum in the central directory header
+    let mut file = include_bytes!("archive-deflate.zip").to_vec();
+    let crc = file
+        .windows(9)
+        .rposition(|w| w == b"lines.txt")
+        .map(|pos| pos - 30)
+        .unwrap();
+    file[crc] ^= 0xff;
+
+    let zip = Zip::new(Cursor::new(&file[..]));
+    let mut content = Vec::new();
+    let err = zip
+        .open("lines.txt")
+        .unwrap()
+        .read_to_end(&mut content)
+        .err()
+        .unwrap();
+    assert_eq!(std::io::ErrorKind::InvalidData, err.kind());
+}
+
+#[test]
+#[cfg(feature = "zip")]
+fn zip_case_insensitive() {
+    use mini_fs::prelude::*;
+    use mini_fs::Zip;
+
+    let file = include_bytes!("archive2.zip");
+    let zip = Zip::new(Cursor::new(&file[..]))
+        .index_case_insensitive()
+        .unwrap();
+    assert!(zip.is_dir("NESTED"));
+    assert_eq!(2, zip.entries("Nested").unwrap().count());
+
+    let file = include_bytes!("archive.zip");
+    let zip = Zip::new(Cursor::new(&file[..]))
+        .index_case_insensitive()
+        .unwrap();
+    let mut content = String::new();
+    zip.open("Hello.TXT")
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    assert_eq!("hello\n", content);
+}
+
+#[test]
+#[cfg(feature = "zip")]
+fn ram_from_zip() {
+    use mini_fs::prelude::*;
+    use mini_fs::Ram;
+
+    let ram = Ram::from_zip("tests/archive2.zip").unwrap();
+    assert_eq!(2, ram.entries("nested").unwrap().count());
+    assert_eq!(3, ram.entries("").unwrap().count());
+    let mut hello = String::new();
+    let zip = Ram::from_zip("tests/archive.zip").unwrap();
+    zip.open("hello.txt")
+        .unwrap()
+        .read_to_string(&mut hello)
+        .unwrap();
+    assert!(!hello.is_empty());
+}
+
+#[test]
+#[cfg(feature = "zip")]
+fn zip_limits() {
+    use mini_fs::prelude::*;
+    use mini_fs::{Limits, Zip};
+    use std::io::{ErrorKind, Write};
+    use zip_::write::FileOptions;
+    use zip_::CompressionMethod;
+
+    let mut writer = zip_::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    writer.start_file("bomb.bin", options).unwrap();
+    writer.write_all(&vec![0; 1 << 20]).unwrap();
+    writer.start_file("small.txt", options).unwrap();
+    writer.write_all(b"small").unwrap();
+    let file = writer.finish().unwrap().into_inner();
+    let zip = |limits| Zip::new(Cursor::new(file.clone())).limits(limits);
+
+    assert!(zip(Limits::new()).index().unwrap().open("bomb.bin").is_ok());
+
+    let zip_size = zip(Limits::new().max_file_size(1000)).index().unwrap();
+    let err = zip_size.open("bomb.bin").err().unwrap();
+    assert_eq!(ErrorKind::FileTooLarge, err.kind());
+    assert!(zip_size.open("small.txt").is_ok());
+
+    let zip_ratio = zip(Limits::new().max_ratio(100));
+    let err = zip_ratio.open("bomb.bin").err().unwrap();
+    assert_eq!(ErrorKind::FileTooLarge, err.kind());
+    assert!(zip_ratio.open("small.txt").is_ok());
+
+    let err = zip(Limits::new().max_entries(1)).index().err().unwrap();
+    assert_eq!(ErrorKind::FileTooLarge, err.kind());
+    assert!(zip(Limits::new().max_entries(2)).index().is_ok());
+
+    let err = zip(Limits::new().max_total_size(1 << 20))
+        .index()
+        .err()
+        .unwrap();
+    assert_eq!(ErrorKind::FileTooLarge, err.kind());
+    assert!(zip(Limits::new().max_total_size((1 << 20) + 5))
+        .index()
+        .is_ok());
+}